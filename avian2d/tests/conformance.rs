@@ -0,0 +1,64 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_tnua_avian2d::TnuaAvian2dPlugin;
+use bevy_tnua_physics_integration_layer::conformance::TnuaConformanceSetup;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector2, Vector3};
+
+struct Avian2dSetup;
+
+impl TnuaConformanceSetup for Avian2dSetup {
+    fn add_physics(&self, app: &mut App) {
+        app.add_plugins((AssetPlugin::default(), bevy::scene::ScenePlugin));
+        app.init_asset::<Mesh>();
+        app.add_plugins((PhysicsPlugins::default(), TnuaAvian2dPlugin::default()));
+        app.insert_resource(Gravity(Vector2::new(0.0, -9.81)));
+    }
+
+    fn spawn_static_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+    ) -> Entity {
+        let size = 2.0 * half_extents;
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Static,
+                Collider::rectangle(size.x, size.y),
+            ))
+            .id()
+    }
+
+    fn spawn_kinematic_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+        linear_velocity: Vector3,
+        angular_velocity: Vector3,
+    ) -> Entity {
+        let size = 2.0 * half_extents;
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Kinematic,
+                LinearVelocity(linear_velocity.truncate()),
+                AngularVelocity(angular_velocity.z),
+                Collider::rectangle(size.x, size.y),
+            ))
+            .id()
+    }
+
+    fn spawn_dynamic_ball(&self, app: &mut App, transform: Transform, radius: Float) -> Entity {
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Dynamic,
+                Collider::circle(radius),
+            ))
+            .id()
+    }
+}
+
+bevy_tnua_physics_integration_layer::tnua_conformance_tests!(Avian2dSetup);
@@ -6,19 +6,42 @@
 //! * Optionally: Add [`TnuaAvian3dSensorShape`] to the sensor entities. This means the entity of
 //!   the characters controlled by Tnua, but also other things like the entity generated by
 //!   `TnuaCrouchEnforcer`, that can be affected with a closure.
-use avian3d::{prelude::*, schedule::PhysicsStepSet};
+//! * Optionally: Add [`TnuaAvian3dUseGlobalTransform`] to the character entities, to make Tnua
+//!   read their position and rotation from their `GlobalTransform` instead of from Avian.
+use std::borrow::Cow;
+
+use avian3d::{parry, prelude::*, schedule::PhysicsStepSet};
+use bevy::core::FrameCount;
+use bevy::ecs::query::QueryItem;
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_tnua_physics_integration_layer::math::AdjustPrecision;
 use bevy_tnua_physics_integration_layer::math::AsF32;
 use bevy_tnua_physics_integration_layer::math::Float;
+use bevy_tnua_physics_integration_layer::math::Quaternion;
 use bevy_tnua_physics_integration_layer::math::Vector3;
 
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostPlatform;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostSensor;
+use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGravityField;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaToggle;
 use bevy_tnua_physics_integration_layer::data_for_backends::{
-    TnuaMotor, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker,
+    TnuaBodyTargets, TnuaCharacterColliders, TnuaCharacterCollisionPolicy, TnuaGroundClass,
+    TnuaMotor, TnuaMotorApplication, TnuaMotorLimits, TnuaPaused, TnuaPlaneLock,
+    TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker, TnuaSensorCastCounter,
+    TnuaSensorHitCache, TnuaSensorQueryFilter, TnuaSensorSolidityProbe, TnuaSleepPolicy,
+    TnuaUpdateTier, TNUA_MOTOR_WRITE_EPSILON,
+};
+use bevy_tnua_physics_integration_layer::for_each_sensor;
+use bevy_tnua_physics_integration_layer::obstacle_radar::{
+    TnuaObstacleRadar, TnuaObstacleRadarShape,
+};
+use bevy_tnua_physics_integration_layer::schedules::{TnuaScheduleFilter, TnuaSchedules};
+use bevy_tnua_physics_integration_layer::spatial_ext::{
+    ballistic_arc_segments, collide_and_slide, TnuaCastShape, TnuaPointProjection,
+    TnuaPredictedLanding, TnuaRayCastHit, TnuaRayHit, TnuaShapeCastHit, TnuaSpatialExt,
+    TnuaSweepHit, BALLISTIC_ARC_TIME_STEP,
 };
 use bevy_tnua_physics_integration_layer::subservient_sensors::TnuaSubservientSensor;
 use bevy_tnua_physics_integration_layer::TnuaPipelineStages;
@@ -27,6 +50,10 @@ use bevy_tnua_physics_integration_layer::TnuaSystemSet;
 /// Add this plugin to use avian3d as a physics backend.
 ///
 /// This plugin should be used in addition to `TnuaControllerPlugin`.
+///
+/// It can be added more than once, with different schedules, to drive some characters in one
+/// schedule and some in another - see
+/// [`TnuaSchedule`](bevy_tnua_physics_integration_layer::schedules::TnuaSchedule).
 pub struct TnuaAvian3dPlugin {
     schedule: InternedScheduleLabel,
 }
@@ -47,6 +74,11 @@ impl Default for TnuaAvian3dPlugin {
 
 impl Plugin for TnuaAvian3dPlugin {
     fn build(&self, app: &mut App) {
+        // Avian's collisions are not per schedule, so they only need to be filtered once.
+        if !app.is_plugin_added::<Self>() {
+            app.add_systems(PostProcessCollisions, filter_character_collisions_system);
+        }
+        TnuaSchedules::register(app, self.schedule);
         app.configure_sets(
             self.schedule,
             TnuaSystemSet
@@ -59,61 +91,522 @@ impl Plugin for TnuaAvian3dPlugin {
             (
                 update_rigid_body_trackers_system,
                 update_proximity_sensors_system,
+                update_obstacle_radars_system.after(update_rigid_body_trackers_system),
             )
                 .in_set(TnuaPipelineStages::Sensors),
         );
         app.add_systems(
             self.schedule,
-            apply_motors_system.in_set(TnuaPipelineStages::Motors),
+            (apply_motors_system, apply_ground_reactions_system)
+                .chain()
+                .in_set(TnuaPipelineStages::Motors),
         );
     }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
 }
 
 /// Add this component to make [`TnuaProximitySensor`] cast a shape instead of a ray.
 #[derive(Component)]
 pub struct TnuaAvian3dSensorShape(pub Collider);
 
+/// Add this component to make Tnua read the position and rotation of the entity from its
+/// [`GlobalTransform`] instead of from Avian's [`Position`] and [`Rotation`].
+///
+/// By default, Tnua uses the physics engine's position and rotation for the
+/// [`TnuaRigidBodyTracker`] and for the origin of the [`TnuaProximitySensor`], so that
+/// interpolating or extrapolating the [`Transform`] for rendering does not affect the controller.
+/// Entities without a [`Position`] and a [`Rotation`] always use their [`GlobalTransform`].
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct TnuaAvian3dUseGlobalTransform;
+
+fn tracked_pose(
+    transform: &GlobalTransform,
+    physics_pose: Option<(&Position, &Rotation)>,
+    use_global_transform: bool,
+) -> (Vector3, Quaternion) {
+    match physics_pose {
+        Some((position, rotation)) if !use_global_transform => {
+            (position.0.adjust_precision(), rotation.0.adjust_precision())
+        }
+        _ => {
+            let (_, rotation, translation) = transform.to_scale_rotation_translation();
+            (translation.adjust_precision(), rotation.adjust_precision())
+        }
+    }
+}
+
+/// An implementation of [`TnuaSpatialExt`] for avian3d.
+#[derive(SystemParam)]
+pub struct TnuaSpatialExtAvian3d<'w, 's> {
+    spatial_query_pipeline: Res<'w, SpatialQueryPipeline>,
+    colliders_query: Query<
+        'w,
+        's,
+        (
+            &'static GlobalTransform,
+            &'static Collider,
+            Option<&'static CollisionLayers>,
+        ),
+    >,
+    characters_query: Query<
+        'w,
+        's,
+        (
+            Option<&'static TnuaBodyTargets>,
+            Option<&'static TnuaCharacterColliders>,
+        ),
+    >,
+    collision_layers_query: Query<'w, 's, &'static CollisionLayers>,
+    sensors_query: Query<'w, 's, (), With<Sensor>>,
+    ghosts_query: Query<'w, 's, (), With<TnuaGhostPlatform>>,
+}
+
+impl TnuaSpatialExtAvian3d<'_, '_> {
+    /// A query filter that only passes colliders the character can collide with, and none of
+    /// the character's own colliders.
+    fn character_query_filter(&self, character: Entity) -> Option<SpatialQueryFilter> {
+        let (body_targets, character_colliders) = self.characters_query.get(character).ok()?;
+        let rigid_body = TnuaBodyTargets::resolve(body_targets, character);
+        let collision_layers = self
+            .collision_layers_query
+            .get(rigid_body)
+            .ok()
+            .copied()
+            .unwrap_or_default();
+        Some(
+            SpatialQueryFilter::from_mask(collision_layers.filters).with_excluded_entities(
+                TnuaCharacterColliders::iter_with(character_colliders, rigid_body),
+            ),
+        )
+    }
+}
+
+impl TnuaSpatialExt for TnuaSpatialExtAvian3d<'_, '_> {
+    fn predict_landing(
+        &self,
+        character: Entity,
+        velocity: Vector3,
+        gravity: Vector3,
+        max_time: Float,
+    ) -> Option<TnuaPredictedLanding> {
+        let (transform, collider, collision_layers) = self.colliders_query.get(character).ok()?;
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let query_filter =
+            SpatialQueryFilter::from_mask(collision_layers.copied().unwrap_or_default().filters)
+                .with_excluded_entities([character]);
+        for (start_time, end_time, start, end) in ballistic_arc_segments(
+            translation.adjust_precision(),
+            velocity,
+            gravity,
+            BALLISTIC_ARC_TIME_STEP,
+            max_time,
+        ) {
+            let Ok((direction, length)) = Dir3::new_and_length((end - start).f32()) else {
+                continue;
+            };
+            let length = length.adjust_precision();
+            let mut closest_hit: Option<ShapeHitData> = None;
+            self.spatial_query_pipeline.shape_hits_callback(
+                collider,
+                start,
+                rotation.adjust_precision(),
+                direction,
+                length,
+                true,
+                query_filter.clone(),
+                |shape_hit_data| {
+                    if self.sensors_query.contains(shape_hit_data.entity) {
+                        return true;
+                    }
+                    match &closest_hit {
+                        Some(closest_hit)
+                            if closest_hit.time_of_impact <= shape_hit_data.time_of_impact => {}
+                        _ => {
+                            closest_hit = Some(shape_hit_data);
+                        }
+                    }
+                    true
+                },
+            );
+            if let Some(hit) = closest_hit {
+                return Some(TnuaPredictedLanding {
+                    entity: hit.entity,
+                    position: start + direction.adjust_precision() * hit.time_of_impact,
+                    contact_point: hit.point1,
+                    normal: Dir3::new(hit.normal1.f32()).unwrap_or(-direction),
+                    time: start_time + (end_time - start_time) * hit.time_of_impact / length,
+                });
+            }
+        }
+        None
+    }
+
+    fn sweep_character(
+        &self,
+        character: Entity,
+        origin: Vector3,
+        displacement: Vector3,
+    ) -> Option<TnuaSweepHit> {
+        let (transform, collider, collision_layers) = self.colliders_query.get(character).ok()?;
+        let (_, rotation, _) = transform.to_scale_rotation_translation();
+        let (direction, length) = Dir3::new_and_length(displacement.f32()).ok()?;
+        let query_filter =
+            SpatialQueryFilter::from_mask(collision_layers.copied().unwrap_or_default().filters)
+                .with_excluded_entities([character]);
+        let mut closest_hit: Option<ShapeHitData> = None;
+        self.spatial_query_pipeline.shape_hits_callback(
+            collider,
+            origin,
+            rotation.adjust_precision(),
+            direction,
+            length.adjust_precision(),
+            true,
+            query_filter,
+            |shape_hit_data| {
+                if self.sensors_query.contains(shape_hit_data.entity)
+                    || self.ghosts_query.contains(shape_hit_data.entity)
+                {
+                    return true;
+                }
+                match &closest_hit {
+                    Some(closest_hit)
+                        if closest_hit.time_of_impact <= shape_hit_data.time_of_impact => {}
+                    _ => {
+                        closest_hit = Some(shape_hit_data);
+                    }
+                }
+                true
+            },
+        );
+        let hit = closest_hit?;
+        Some(TnuaSweepHit {
+            entity: hit.entity,
+            distance: hit.time_of_impact,
+            normal: Dir3::new(hit.normal1.f32()).unwrap_or(-direction),
+        })
+    }
+
+    fn cast_shape(
+        &self,
+        character: Entity,
+        shape: &TnuaCastShape,
+        origin: Vector3,
+        direction: Dir3,
+        max_distance: Float,
+    ) -> Option<TnuaShapeCastHit> {
+        let (transform, collider, collision_layers) = self.colliders_query.get(character).ok()?;
+        let (_, rotation, _) = transform.to_scale_rotation_translation();
+        let rotation = rotation.adjust_precision();
+        let origin = transform.transform_point(origin.f32()).adjust_precision();
+        let shape_collider = cast_shape_collider(shape);
+        let query_filter =
+            SpatialQueryFilter::from_mask(collision_layers.copied().unwrap_or_default().filters)
+                .with_excluded_entities([character]);
+        let mut closest_hit: Option<ShapeHitData> = None;
+        self.spatial_query_pipeline.shape_hits_callback(
+            shape_collider.as_ref().unwrap_or(collider),
+            origin,
+            rotation,
+            direction,
+            max_distance,
+            true,
+            query_filter,
+            |shape_hit_data| {
+                if self.sensors_query.contains(shape_hit_data.entity)
+                    || self.ghosts_query.contains(shape_hit_data.entity)
+                {
+                    return true;
+                }
+                match &closest_hit {
+                    Some(closest_hit)
+                        if closest_hit.time_of_impact <= shape_hit_data.time_of_impact => {}
+                    _ => {
+                        closest_hit = Some(shape_hit_data);
+                    }
+                }
+                true
+            },
+        );
+        let hit = closest_hit?;
+        // The hit point and normal of the collider that was hit are in its local space, so use
+        // the ones of the cast shape instead.
+        let position = origin + direction.adjust_precision() * hit.time_of_impact;
+        Some(TnuaShapeCastHit {
+            entity: hit.entity,
+            distance: hit.time_of_impact,
+            position,
+            point: position + rotation * hit.point2,
+            normal: Dir3::new((rotation * -hit.normal2).f32()).unwrap_or(-direction),
+        })
+    }
+
+    fn cast_ray(
+        &self,
+        character: Entity,
+        origin: Vector3,
+        direction: Dir3,
+        max_distance: Float,
+    ) -> Option<TnuaRayCastHit> {
+        let query_filter = self.character_query_filter(character)?;
+        let mut closest_hit: Option<RayHitData> = None;
+        self.spatial_query_pipeline.ray_hits_callback(
+            origin,
+            direction,
+            max_distance,
+            true,
+            query_filter,
+            |ray_hit_data| {
+                if self.sensors_query.contains(ray_hit_data.entity)
+                    || self.ghosts_query.contains(ray_hit_data.entity)
+                {
+                    return true;
+                }
+                match &closest_hit {
+                    Some(closest_hit)
+                        if closest_hit.time_of_impact <= ray_hit_data.time_of_impact => {}
+                    _ => {
+                        closest_hit = Some(ray_hit_data);
+                    }
+                }
+                true
+            },
+        );
+        let hit = closest_hit?;
+        Some(TnuaRayCastHit {
+            entity: hit.entity,
+            distance: hit.time_of_impact,
+            point: origin + direction.adjust_precision() * hit.time_of_impact,
+            normal: Dir3::new(hit.normal.f32()).unwrap_or(-direction),
+        })
+    }
+
+    fn project_point_on(&self, entity: Entity, point: Vector3) -> Option<TnuaPointProjection> {
+        let (transform, collider, _) = self.colliders_query.get(entity).ok()?;
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let (point, is_inside) = collider.project_point(
+            translation.adjust_precision(),
+            rotation.adjust_precision(),
+            point,
+            false,
+        );
+        Some(TnuaPointProjection { point, is_inside })
+    }
+
+    fn cast_ray_on(
+        &self,
+        entity: Entity,
+        origin: Vector3,
+        direction: Dir3,
+        max_distance: Float,
+    ) -> Option<TnuaRayHit> {
+        let (transform, collider, _) = self.colliders_query.get(entity).ok()?;
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let (distance, normal) = collider.cast_ray(
+            translation.adjust_precision(),
+            rotation.adjust_precision(),
+            origin,
+            direction.adjust_precision(),
+            max_distance,
+            true,
+        )?;
+        Some(TnuaRayHit {
+            distance,
+            normal: Dir3::new(normal.f32()).unwrap_or(-direction),
+        })
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn update_rigid_body_trackers_system(
     gravity: Res<Gravity>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    spatial_query_pipeline: Res<SpatialQueryPipeline>,
     mut query: Query<(
+        Entity,
+        &mut TnuaRigidBodyTracker,
+        Option<&TnuaBodyTargets>,
+        Option<&TnuaProximitySensor>,
+        Option<&TnuaToggle>,
+    )>,
+    bodies_query: Query<(
         &GlobalTransform,
+        Option<(&Position, &Rotation)>,
+        Has<TnuaAvian3dUseGlobalTransform>,
         &LinearVelocity,
         &AngularVelocity,
-        &mut TnuaRigidBodyTracker,
-        Option<&TnuaToggle>,
+        &Mass,
+        &Inertia,
     )>,
+    gravity_fields_query: Query<&TnuaGravityField>,
 ) {
-    for (transform, linaer_velocity, angular_velocity, mut tracker, tnua_toggle) in query.iter_mut()
-    {
-        match tnua_toggle.copied().unwrap_or_default() {
+    for (entity, mut tracker, body_targets, sensor, tnua_toggle) in query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
             TnuaToggle::Disabled => continue,
             TnuaToggle::SenseOnly => {}
             TnuaToggle::Enabled => {}
+            TnuaToggle::Frozen => {}
+        }
+        let Ok((
+            transform,
+            physics_pose,
+            use_global_transform,
+            linaer_velocity,
+            angular_velocity,
+            mass,
+            inertia,
+        )) = bodies_query.get(TnuaBodyTargets::resolve(body_targets, entity))
+        else {
+            continue;
+        };
+        let (translation, rotation) = tracked_pose(transform, physics_pose, use_global_transform);
+        let (scale, _, _) = transform.to_scale_rotation_translation();
+        let mut effective_gravity = gravity.0.adjust_precision();
+        if !gravity_fields_query.is_empty() {
+            let probe_point = if let Some(sensor) = sensor {
+                translation + rotation * sensor.cast_origin
+            } else {
+                translation
+            };
+            spatial_query_pipeline.point_intersections_callback(
+                probe_point,
+                SpatialQueryFilter::default(),
+                |entity| {
+                    if let Ok(gravity_field) = gravity_fields_query.get(entity) {
+                        effective_gravity = gravity_field.apply_to(effective_gravity);
+                        false
+                    } else {
+                        true
+                    }
+                },
+            );
         }
-        let (_, rotation, translation) = transform.to_scale_rotation_translation();
         *tracker = TnuaRigidBodyTracker {
-            translation: translation.adjust_precision(),
-            rotation: rotation.adjust_precision(),
+            translation,
+            rotation,
             velocity: linaer_velocity.0.adjust_precision(),
             angvel: angular_velocity.0.adjust_precision(),
-            gravity: gravity.0.adjust_precision(),
+            gravity: effective_gravity,
+            mass: mass.0.adjust_precision(),
+            inertia: Vector3::new(inertia.0.x_axis.x, inertia.0.y_axis.y, inertia.0.z_axis.z)
+                .adjust_precision(),
+            scale: scale.adjust_precision(),
         };
     }
 }
 
-#[allow(clippy::type_complexity)]
+/// The sensor shape, scaled by the scale of the entity it is cast from.
+fn scaled_sensor_shape(shape: &Collider, scale: Vec3) -> Cow<'_, Collider> {
+    if scale == Vec3::ONE {
+        Cow::Borrowed(shape)
+    } else {
+        let mut shape = shape.clone();
+        shape.set_scale(scale.adjust_precision(), 10);
+        Cow::Owned(shape)
+    }
+}
+
+/// Cast a ray against a single collider, for re-testing the entity cached by a
+/// [`TnuaSensorHitCache`] without going over all the colliders in the ray's way.
+fn cast_ray_on_collider(
+    entity: Entity,
+    (collider, position, rotation): (&Collider, &Position, &Rotation),
+    origin: Vector3,
+    direction: Dir3,
+    max_time_of_impact: Float,
+) -> Option<RayHitData> {
+    let (time_of_impact, normal) = collider.cast_ray(
+        *position,
+        *rotation,
+        origin,
+        direction.adjust_precision(),
+        max_time_of_impact,
+        true,
+    )?;
+    Some(RayHitData {
+        entity,
+        time_of_impact,
+        normal,
+    })
+}
+
+/// Like [`cast_ray_on_collider`], but for sensors that cast a shape.
+#[allow(clippy::too_many_arguments)]
+fn cast_shape_on_collider(
+    entity: Entity,
+    (collider, position, rotation): (&Collider, &Position, &Rotation),
+    shape: &Collider,
+    origin: Vector3,
+    shape_rotation: Quaternion,
+    direction: Dir3,
+    max_time_of_impact: Float,
+) -> Option<ShapeHitData> {
+    let collider_isometry =
+        parry::math::Isometry::new(position.0.into(), rotation.0.to_scaled_axis().into());
+    let shape_isometry =
+        parry::math::Isometry::new(origin.into(), shape_rotation.to_scaled_axis().into());
+    let hit = parry::query::cast_shapes(
+        &collider_isometry,
+        &parry::math::Vector::zeros(),
+        collider.shape_scaled().as_ref(),
+        &shape_isometry,
+        &direction.adjust_precision().into(),
+        shape.shape_scaled().as_ref(),
+        parry::query::ShapeCastOptions {
+            max_time_of_impact,
+            stop_at_penetration: false,
+            ..Default::default()
+        },
+    )
+    .ok()??;
+    // Like the hits of the spatial query pipeline, the point and normal on the hit collider are in
+    // world space and the ones on the cast shape are in its local space.
+    Some(ShapeHitData {
+        entity,
+        time_of_impact: hit.time_of_impact,
+        point1: (collider_isometry * hit.witness1).into(),
+        point2: hit.witness2.into(),
+        normal1: (collider_isometry * hit.normal1).into_inner().into(),
+        normal2: hit.normal2.into_inner().into(),
+    })
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn update_proximity_sensors_system(
+    frame_count: Res<FrameCount>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    cast_counter: Option<Res<TnuaSensorCastCounter>>,
     spatial_query_pipeline: Res<SpatialQueryPipeline>,
     collisions: Res<Collisions>,
     mut query: Query<(
         Entity,
-        &GlobalTransform,
         &mut TnuaProximitySensor,
         Option<&TnuaAvian3dSensorShape>,
+        Option<&TnuaSensorQueryFilter>,
+        Option<&TnuaSensorSolidityProbe>,
+        Option<&mut TnuaSensorHitCache>,
         Option<&mut TnuaGhostSensor>,
         Option<&TnuaSubservientSensor>,
         Option<&TnuaToggle>,
     )>,
+    poses_query: Query<(
+        &GlobalTransform,
+        Option<(&Position, &Rotation)>,
+        Has<TnuaAvian3dUseGlobalTransform>,
+    )>,
+    character_bodies_query: Query<(
+        Option<&TnuaBodyTargets>,
+        Option<&TnuaCharacterColliders>,
+        Option<&TnuaUpdateTier>,
+        Option<&TnuaPlaneLock>,
+    )>,
     collision_layers_entity: Query<&CollisionLayers>,
     other_object_query: Query<(
         Option<(&GlobalTransform, &LinearVelocity, &AngularVelocity)>,
@@ -121,28 +614,51 @@ fn update_proximity_sensors_system(
         Has<TnuaGhostPlatform>,
         Has<Sensor>,
     )>,
+    colliders_query: Query<(&Collider, &Position, &Rotation)>,
 ) {
-    query.par_iter_mut().for_each(
+    let record_cast = || {
+        if let Some(cast_counter) = cast_counter.as_deref() {
+            cast_counter.record_cast();
+        }
+    };
+
+    for_each_sensor(
+        &mut query,
         |(
-            owner_entity,
-            transform,
+            sensor_entity,
             mut sensor,
             shape,
+            sensor_query_filter,
+            solidity_probe,
+            mut hit_cache,
             mut ghost_sensor,
             subservient,
             tnua_toggle,
         )| {
-            match tnua_toggle.copied().unwrap_or_default() {
-                TnuaToggle::Disabled => return,
+            match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+                TnuaToggle::Disabled | TnuaToggle::Frozen => return,
                 TnuaToggle::SenseOnly => {}
                 TnuaToggle::Enabled => {}
             }
 
-            // TODO: is there any point in doing these transformations as f64 when that feature
-            // flag is active?
-            let cast_origin = transform
-                .transform_point(sensor.cast_origin.f32())
-                .adjust_precision();
+            // A sensor on the controller entity is cast from the rigid body entity, but a
+            // subservient sensor is cast from its own entity.
+            let pose_entity = TnuaBodyTargets::resolve(
+                character_bodies_query
+                    .get(sensor_entity)
+                    .ok()
+                    .and_then(|(body_targets, _, _, _)| body_targets),
+                sensor_entity,
+            );
+            let Ok((transform, physics_pose, use_global_transform)) = poses_query.get(pose_entity)
+            else {
+                return;
+            };
+            let (sensor_translation, sensor_rotation) =
+                tracked_pose(transform, physics_pose, use_global_transform);
+            let (scale, _, _) = transform.to_scale_rotation_translation();
+            let cast_origin = sensor_translation
+                + sensor_rotation * (scale.adjust_precision() * sensor.cast_origin);
             let cast_direction = sensor.cast_direction;
 
             struct CastResult {
@@ -155,11 +671,36 @@ fn update_proximity_sensors_system(
             let owner_entity = if let Some(subservient) = subservient {
                 subservient.owner_entity
             } else {
-                owner_entity
+                sensor_entity
+            };
+            if !schedule_filter.drives(owner_entity) {
+                return;
+            }
+            let (body_targets, character_colliders, update_tier, plane_lock) =
+                character_bodies_query.get(owner_entity).unwrap_or_default();
+            if !TnuaUpdateTier::should_update_sensors(update_tier, owner_entity, frame_count.0) {
+                return;
+            }
+            let owner_entity = TnuaBodyTargets::resolve(body_targets, owner_entity);
+
+            // A character locked to a plane casts from the plane and along it.
+            let (cast_origin, cast_direction) = match plane_lock {
+                Some(plane_lock) => (
+                    plane_lock.project_point(cast_origin),
+                    plane_lock
+                        .project_direction(cast_direction)
+                        .unwrap_or(cast_direction),
+                ),
+                None => (cast_origin, cast_direction),
             };
 
             let collision_layers = collision_layers_entity.get(owner_entity).ok();
 
+            let entity_to_retest = match (hit_cache.as_mut(), &ghost_sensor) {
+                (Some(hit_cache), None) => hit_cache.entity_to_retest(&sensor),
+                _ => None,
+            };
+
             let mut final_sensor_output = None;
             if let Some(ghost_sensor) = ghost_sensor.as_mut() {
                 ghost_sensor.0.clear();
@@ -173,19 +714,23 @@ fn update_proximity_sensors_system(
                 } = cast_result;
 
                 // This fixes https://github.com/idanarye/bevy-tnua/issues/14
-                if let Some(contacts) = collisions.get(owner_entity, entity) {
-                    let same_order = owner_entity == contacts.entity1;
-                    for manifold in contacts.manifolds.iter() {
-                        if !manifold.contacts.is_empty() {
-                            let manifold_normal = if same_order {
-                                manifold.normal2.adjust_precision()
-                            } else {
-                                manifold.normal1.adjust_precision()
-                            };
-                            if sensor.intersection_match_prevention_cutoff
-                                < manifold_normal.dot(cast_direction.adjust_precision())
-                            {
-                                return true;
+                for collider_entity in
+                    TnuaCharacterColliders::iter_with(character_colliders, owner_entity)
+                {
+                    if let Some(contacts) = collisions.get(collider_entity, entity) {
+                        let same_order = collider_entity == contacts.entity1;
+                        for manifold in contacts.manifolds.iter() {
+                            if !manifold.contacts.is_empty() {
+                                let manifold_normal = if same_order {
+                                    manifold.normal2.adjust_precision()
+                                } else {
+                                    manifold.normal1.adjust_precision()
+                                };
+                                if sensor.intersection_match_prevention_cutoff
+                                    < manifold_normal.dot(cast_direction.adjust_precision())
+                                {
+                                    return true;
+                                }
                             }
                         }
                     }
@@ -204,6 +749,17 @@ fn update_proximity_sensors_system(
                     return false;
                 };
 
+                if let Some(sensor_query_filter) = sensor_query_filter {
+                    let memberships = entity_collision_layers
+                        .copied()
+                        .unwrap_or_default()
+                        .memberships
+                        .0;
+                    if !sensor_query_filter.test(entity, memberships) {
+                        return true;
+                    }
+                }
+
                 let entity_linvel;
                 let entity_angvel;
                 if let Some((entity_transform, entity_linear_velocity, entity_angular_velocity)) =
@@ -231,6 +787,7 @@ fn update_proximity_sensors_system(
                     normal,
                     entity_linvel,
                     entity_angvel,
+                    ground_class: TnuaGroundClass::Walkable,
                 };
 
                 let excluded_by_collision_layers = || {
@@ -253,101 +810,536 @@ fn update_proximity_sensors_system(
                 }
             };
 
-            let query_filter = SpatialQueryFilter::from_excluded_entities([owner_entity]);
-            if let Some(TnuaAvian3dSensorShape(shape)) = shape {
-                let (_, owner_rotation, _) = transform.to_scale_rotation_translation();
-                let owner_rotation = Quat::from_axis_angle(
-                    *cast_direction,
-                    owner_rotation.to_scaled_axis().dot(*cast_direction),
-                );
-                spatial_query_pipeline.shape_hits_callback(
-                    shape,
-                    cast_origin,
-                    owner_rotation.adjust_precision(),
-                    cast_direction,
-                    sensor.cast_range,
-                    true,
-                    query_filter,
-                    |shape_hit_data| {
-                        apply_cast(CastResult {
-                            entity: shape_hit_data.entity,
-                            proximity: shape_hit_data.time_of_impact,
-                            intersection_point: shape_hit_data.point1,
-                            normal: Dir3::new(shape_hit_data.normal1.f32())
-                                .unwrap_or_else(|_| -cast_direction),
-                        })
-                    },
+            let sensor_shape = shape.map(|TnuaAvian3dSensorShape(shape)| {
+                let cast_direction_vector = cast_direction.adjust_precision();
+                (
+                    scaled_sensor_shape(shape, scale),
+                    Quaternion::from_axis_angle(
+                        cast_direction_vector,
+                        sensor_rotation.to_scaled_axis().dot(cast_direction_vector),
+                    ),
+                )
+            });
+            let shape_cast_result = |shape_hit_data: ShapeHitData| CastResult {
+                entity: shape_hit_data.entity,
+                proximity: shape_hit_data.time_of_impact,
+                intersection_point: shape_hit_data.point1,
+                normal: Dir3::new(shape_hit_data.normal1.f32()).unwrap_or_else(|_| -cast_direction),
+            };
+            let ray_cast_result = |ray_hit_data: RayHitData| CastResult {
+                entity: ray_hit_data.entity,
+                proximity: ray_hit_data.time_of_impact,
+                intersection_point: cast_origin
+                    + ray_hit_data.time_of_impact * cast_direction.adjust_precision(),
+                normal: Dir3::new(ray_hit_data.normal.f32()).unwrap_or_else(|_| -cast_direction),
+            };
+
+            let retested = entity_to_retest.is_some_and(|entity| {
+                let Ok(collider) = colliders_query.get(entity) else {
+                    return false;
+                };
+                let cast_result = if let Some((shape, shape_rotation)) = &sensor_shape {
+                    cast_shape_on_collider(
+                        entity,
+                        collider,
+                        shape,
+                        cast_origin,
+                        *shape_rotation,
+                        cast_direction,
+                        sensor.cast_range,
+                    )
+                    .map(shape_cast_result)
+                } else {
+                    cast_ray_on_collider(
+                        entity,
+                        collider,
+                        cast_origin,
+                        cast_direction,
+                        sensor.cast_range,
+                    )
+                    .map(ray_cast_result)
+                };
+                cast_result.is_some_and(|cast_result| !apply_cast(cast_result))
+            });
+
+            if !retested {
+                if let (Some(hit_cache), Some(_)) = (hit_cache.as_mut(), entity_to_retest) {
+                    hit_cache.retest_missed();
+                }
+                // The spatial query does not return the hits in order, so they must be sorted
+                // before deciding which one is the ground and which ones are ghosts in front
+                // of it.
+                let mut cast_results = Vec::new();
+                let query_filter = SpatialQueryFilter::from_excluded_entities(
+                    TnuaCharacterColliders::iter_with(character_colliders, owner_entity),
                 );
-            } else {
-                spatial_query_pipeline.ray_hits_callback(
+                record_cast();
+                if let Some((shape, shape_rotation)) = &sensor_shape {
+                    spatial_query_pipeline.shape_hits_callback(
+                        shape,
+                        cast_origin,
+                        *shape_rotation,
+                        cast_direction,
+                        sensor.cast_range,
+                        true,
+                        query_filter,
+                        |shape_hit_data| {
+                            cast_results.push(shape_cast_result(shape_hit_data));
+                            true
+                        },
+                    );
+                } else {
+                    spatial_query_pipeline.ray_hits_callback(
+                        cast_origin,
+                        cast_direction,
+                        sensor.cast_range,
+                        true,
+                        query_filter,
+                        |ray_hit_data| {
+                            cast_results.push(ray_cast_result(ray_hit_data));
+                            true
+                        },
+                    );
+                }
+                cast_results.sort_by(|a, b| a.proximity.total_cmp(&b.proximity));
+                for cast_result in cast_results {
+                    if !apply_cast(cast_result) {
+                        break;
+                    }
+                }
+            }
+            if let (Some(solidity_probe), Some(_), Some(output)) =
+                (solidity_probe, shape, final_sensor_output.as_mut())
+            {
+                let ground_entity = output.entity;
+                record_cast();
+                let probe_hit = spatial_query_pipeline.cast_ray_predicate(
                     cast_origin,
                     cast_direction,
-                    sensor.cast_range,
+                    output.proximity + solidity_probe.max_extra_distance,
                     true,
-                    query_filter,
-                    |ray_hit_data| {
-                        apply_cast(CastResult {
-                            entity: ray_hit_data.entity,
-                            proximity: ray_hit_data.time_of_impact,
-                            intersection_point: cast_origin
-                                + ray_hit_data.time_of_impact * cast_direction.adjust_precision(),
-                            normal: Dir3::new(ray_hit_data.normal.f32())
-                                .unwrap_or_else(|_| -cast_direction),
-                        })
-                    },
+                    SpatialQueryFilter::default(),
+                    &|other_entity| other_entity == ground_entity,
                 );
+                output.normal = if let Some(hit) = probe_hit {
+                    Dir3::new(hit.normal.f32()).unwrap_or(output.normal)
+                } else {
+                    -cast_direction
+                };
+            }
+            if let (Some(plane_lock), Some(output)) = (plane_lock, final_sensor_output.as_mut()) {
+                output.normal = plane_lock
+                    .project_direction(output.normal)
+                    .unwrap_or(-cast_direction);
             }
             sensor.output = final_sensor_output;
         },
     );
 }
 
-#[allow(clippy::type_complexity)]
-fn apply_motors_system(
+fn cast_shape_collider(shape: &TnuaCastShape) -> Option<Collider> {
+    Some(match shape {
+        TnuaCastShape::CharacterCollider => return None,
+        TnuaCastShape::Sphere { radius } => Collider::sphere(*radius),
+        TnuaCastShape::Box { half_extents } => Collider::cuboid(
+            2.0 * half_extents.x,
+            2.0 * half_extents.y,
+            2.0 * half_extents.z,
+        ),
+        TnuaCastShape::Capsule {
+            radius,
+            half_height,
+        } => Collider::capsule(*radius, 2.0 * half_height),
+    })
+}
+
+fn radar_collider(radar: &TnuaObstacleRadar) -> Collider {
+    match radar.shape {
+        TnuaObstacleRadarShape::Cylinder { radius, height } => Collider::cylinder(radius, height),
+        TnuaObstacleRadarShape::Box { half_extents } => Collider::cuboid(
+            2.0 * half_extents.x,
+            2.0 * half_extents.y,
+            2.0 * half_extents.z,
+        ),
+        TnuaObstacleRadarShape::Sphere { radius } => Collider::sphere(radius),
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn update_obstacle_radars_system(
+    frame_count: Res<FrameCount>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    spatial_query_pipeline: Res<SpatialQueryPipeline>,
+    spatial_ext: TnuaSpatialExtAvian3d,
     mut query: Query<(
-        &TnuaMotor,
-        &mut LinearVelocity,
-        &mut AngularVelocity,
-        &Mass,
-        &Inertia,
-        &mut ExternalForce,
-        &mut ExternalTorque,
+        Entity,
+        &mut TnuaObstacleRadar,
+        &TnuaRigidBodyTracker,
+        Option<&TnuaBodyTargets>,
+        Option<&TnuaCharacterColliders>,
+        Option<&TnuaUpdateTier>,
         Option<&TnuaToggle>,
     )>,
+    collision_layers_query: Query<&CollisionLayers>,
+    mut radar_colliders: Local<Vec<(TnuaObstacleRadarShape, Collider)>>,
+) {
+    // Radars with the same shape (e.g. a crowd of NPCs spawned from the same bundle) share the
+    // collider, which is kept between frames.
+    let previous_radar_colliders = std::mem::take(&mut *radar_colliders);
+    for (_, radar, ..) in query.iter() {
+        if radar_colliders
+            .iter()
+            .all(|(shape, _)| *shape != radar.shape)
+        {
+            let collider = previous_radar_colliders
+                .iter()
+                .find(|(shape, _)| *shape == radar.shape)
+                .map_or_else(|| radar_collider(radar), |(_, collider)| collider.clone());
+            radar_colliders.push((radar.shape, collider));
+        }
+    }
+    let radar_colliders = &*radar_colliders;
+
+    for_each_sensor(
+        &mut query,
+        |(
+            owner_entity,
+            mut radar,
+            tracker,
+            body_targets,
+            character_colliders,
+            update_tier,
+            tnua_toggle,
+        )| {
+            match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+                TnuaToggle::Disabled | TnuaToggle::Frozen => return,
+                TnuaToggle::SenseOnly => {}
+                TnuaToggle::Enabled => {}
+            }
+            if !schedule_filter.drives(owner_entity) {
+                return;
+            }
+            if !TnuaUpdateTier::should_update_sensors(update_tier, owner_entity, frame_count.0) {
+                return;
+            }
+            let owner_entity = TnuaBodyTargets::resolve(body_targets, owner_entity);
+            let up_direction = Dir3::new(-tracker.gravity.f32()).unwrap_or(Dir3::Y);
+            radar.pre_marking_update(tracker.translation, up_direction);
+            let radar = radar.as_mut();
+            let (_, collider) = radar_colliders
+                .iter()
+                .find(|(shape, _)| *shape == radar.shape)
+                .expect("a collider is prepared for the shape of every radar");
+            spatial_query_pipeline.shape_intersections_callback(
+                collider,
+                radar.probe_position(),
+                radar.probe_rotation(),
+                SpatialQueryFilter::from_excluded_entities(TnuaCharacterColliders::iter_with(
+                    character_colliders,
+                    owner_entity,
+                )),
+                |entity| {
+                    let memberships = collision_layers_query
+                        .get(entity)
+                        .copied()
+                        .unwrap_or_default()
+                        .memberships
+                        .0;
+                    if radar.accepts_memberships(memberships) {
+                        radar.mark_seen(entity);
+                    }
+                    true
+                },
+            );
+            if radar.collect_blip_info {
+                radar.update_blip_infos(|radar, blip| {
+                    spatial_ext.obstacle_radar_blip_info(radar, blip)
+                });
+            }
+        },
+    );
+}
+
+type MotorControllerData = (
+    &'static TnuaMotor,
+    Option<&'static mut TnuaMotorLimits>,
+    Option<&'static TnuaPlaneLock>,
+    Option<&'static TnuaMotorApplication>,
+    &'static TnuaRigidBodyTracker,
+    Option<&'static TnuaProximitySensor>,
+    Option<&'static mut TnuaSleepPolicy>,
+    Option<&'static TnuaUpdateTier>,
+    Option<&'static TnuaToggle>,
+);
+
+type MotorBodyData = (
+    &'static mut LinearVelocity,
+    &'static mut AngularVelocity,
+    &'static Mass,
+    &'static Inertia,
+    &'static mut ExternalForce,
+    &'static mut ExternalTorque,
+    &'static mut ExternalImpulse,
+    &'static mut ExternalAngularImpulse,
+);
+
+#[allow(clippy::type_complexity)]
+fn apply_motors_system(
+    time: Res<Time>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    spatial_ext: TnuaSpatialExtAvian3d,
+    mut queries: ParamSet<(
+        Query<(Entity, MotorControllerData, MotorBodyData), Without<TnuaBodyTargets>>,
+        Query<MotorBodyData>,
+    )>,
+    mut redirected_query: Query<(Entity, MotorControllerData, &TnuaBodyTargets)>,
 ) {
-    for (
+    let frame_duration = time.delta_seconds().adjust_precision();
+    // When the motor is on the rigid body entity, every character writes only to its own
+    // components, so they can be processed in parallel.
+    queries
+        .p0()
+        .par_iter_mut()
+        .for_each(|(entity, controller, body)| {
+            if !schedule_filter.drives(entity) {
+                return;
+            }
+            apply_motor(
+                entity,
+                controller,
+                body,
+                &spatial_ext,
+                paused.as_deref(),
+                frame_duration,
+            );
+        });
+    // With `TnuaBodyTargets` several controllers may point at the same rigid body.
+    let mut bodies_query = queries.p1();
+    for (entity, controller, body_targets) in redirected_query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        let Ok(body) = bodies_query.get_mut(body_targets.rigid_body) else {
+            continue;
+        };
+        apply_motor(
+            body_targets.rigid_body,
+            controller,
+            body,
+            &spatial_ext,
+            paused.as_deref(),
+            frame_duration,
+        );
+    }
+}
+
+fn apply_motor(
+    entity: Entity,
+    (
         motor,
+        motor_limits,
+        plane_lock,
+        motor_application,
+        tracker,
+        proximity_sensor,
+        sleep_policy,
+        update_tier,
+        tnua_toggle,
+    ): QueryItem<MotorControllerData>,
+    (
         mut linare_velocity,
         mut angular_velocity,
         mass,
         inertia,
         mut external_force,
         mut external_torque,
-        tnua_toggle,
-    ) in query.iter_mut()
-    {
-        match tnua_toggle.copied().unwrap_or_default() {
-            TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
-                *external_force = Default::default();
-                return;
+        mut external_impulse,
+        mut external_angular_impulse,
+    ): QueryItem<MotorBodyData>,
+    spatial_ext: &TnuaSpatialExtAvian3d,
+    paused: Option<&TnuaPaused>,
+    frame_duration: Float,
+) {
+    match TnuaToggle::resolve(tnua_toggle, paused) {
+        TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
+            *external_force = Default::default();
+            return;
+        }
+        TnuaToggle::Frozen => {
+            if linare_velocity.0 != Vector3::ZERO {
+                linare_velocity.0 = Vector3::ZERO;
             }
-            TnuaToggle::Enabled => {}
+            if angular_velocity.0 != Vector3::ZERO {
+                angular_velocity.0 = Vector3::ZERO;
+            }
+            // Cancel the gravity Avian is going to apply in the next step.
+            let force = -tracker.gravity * mass.0;
+            if TNUA_MOTOR_WRITE_EPSILON < (force - external_force.force()).length() {
+                external_force.set_force(force);
+            }
+            if external_torque.torque() != Vector3::ZERO {
+                external_torque.set_torque(Vector3::ZERO);
+            }
+            return;
+        }
+        TnuaToggle::Enabled => {}
+    }
+    if !TnuaUpdateTier::should_apply_motor(update_tier) {
+        return;
+    }
+    let motor = &match sleep_policy {
+        Some(mut sleep_policy) => {
+            let Some(motor) = sleep_policy.apply(motor, tracker, proximity_sensor, frame_duration)
+            else {
+                return;
+            };
+            motor
+        }
+        None => motor.clone(),
+    };
+    let motor = &match motor_limits {
+        Some(mut motor_limits) => motor_limits.apply(motor),
+        None => motor.clone(),
+    };
+    let motor = &match plane_lock {
+        Some(plane_lock) => plane_lock.apply(motor, tracker, frame_duration),
+        None => motor.clone(),
+    };
+    let motor_application = motor_application.copied().unwrap_or_default();
+    if motor_application == TnuaMotorApplication::Kinematic {
+        if frame_duration <= 0.0 {
+            return;
         }
+        let mut velocity = linare_velocity.0.adjust_precision() + tracker.gravity * frame_duration;
         if motor.lin.boost.is_finite() {
-            linare_velocity.0 += motor.lin.boost;
+            velocity += motor.lin.boost;
         }
         if motor.lin.acceleration.is_finite() {
-            external_force.set_force(motor.lin.acceleration * mass.0);
+            velocity += motor.lin.acceleration * frame_duration;
+        }
+        let displacement = collide_and_slide(velocity * frame_duration, |offset, displacement| {
+            spatial_ext.sweep_character(entity, tracker.translation + offset, displacement)
+        });
+        let velocity = displacement / frame_duration;
+        if TNUA_MOTOR_WRITE_EPSILON < (velocity - linare_velocity.0).length() {
+            linare_velocity.0 = velocity;
         }
+        let mut angvel_change = Vector3::ZERO;
         if motor.ang.boost.is_finite() {
-            angular_velocity.0 += motor.ang.boost;
+            angvel_change += motor.ang.boost;
         }
         if motor.ang.acceleration.is_finite() {
-            external_torque.set_torque(
-                // NOTE: I did not actually verify that this is the correct formula. Nothing uses
-                // angular acceleration yet - only angular impulses.
-                inertia.0 * motor.ang.acceleration,
-            );
+            angvel_change += motor.ang.acceleration * frame_duration;
+        }
+        if TNUA_MOTOR_WRITE_EPSILON < angvel_change.length() {
+            angular_velocity.0 += angvel_change;
+        }
+        return;
+    }
+    if motor.lin.boost.is_finite() && TNUA_MOTOR_WRITE_EPSILON < motor.lin.boost.length() {
+        if motor_application == TnuaMotorApplication::Impulses {
+            external_impulse.apply_impulse(motor.lin.boost * mass.0);
+        } else {
+            linare_velocity.0 += motor.lin.boost;
+        }
+    }
+    if motor.lin.acceleration.is_finite() {
+        let force = motor.lin.acceleration * mass.0;
+        if TNUA_MOTOR_WRITE_EPSILON < (force - external_force.force()).length() {
+            external_force.set_force(force);
+        }
+    }
+    if motor.ang.boost.is_finite() && TNUA_MOTOR_WRITE_EPSILON < motor.ang.boost.length() {
+        if motor_application == TnuaMotorApplication::Impulses {
+            external_angular_impulse.apply_impulse(inertia.0 * motor.ang.boost);
+        } else {
+            angular_velocity.0 += motor.ang.boost;
+        }
+    }
+    if motor.ang.acceleration.is_finite() {
+        // NOTE: I did not actually verify that this is the correct formula. Nothing uses angular
+        // acceleration yet - only angular impulses.
+        let torque = inertia.0 * motor.ang.acceleration;
+        if TNUA_MOTOR_WRITE_EPSILON < (torque - external_torque.torque()).length() {
+            external_torque.set_torque(torque);
         }
     }
 }
+
+fn filter_character_collisions_system(
+    mut collisions: ResMut<Collisions>,
+    policies_query: Query<&TnuaCharacterCollisionPolicy>,
+) {
+    if policies_query.is_empty() {
+        return;
+    }
+    let policy = |collider: Entity, body: Option<Entity>| {
+        policies_query
+            .get(collider)
+            .ok()
+            .or_else(|| policies_query.get(body?).ok())
+    };
+    collisions.retain(|contacts| {
+        TnuaCharacterCollisionPolicy::should_collide(
+            policy(contacts.entity1, contacts.body_entity1),
+            policy(contacts.entity2, contacts.body_entity2),
+        )
+    });
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_ground_reactions_system(
+    time: Res<Time>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    characters_query: Query<(
+        Entity,
+        &TnuaMotor,
+        Option<&TnuaBodyTargets>,
+        Option<&TnuaUpdateTier>,
+        Option<&TnuaToggle>,
+    )>,
+    masses_query: Query<&Mass>,
+    mut grounds_query: Query<(
+        &RigidBody,
+        &Position,
+        &Rotation,
+        &CenterOfMass,
+        &mut ExternalImpulse,
+    )>,
+) {
+    let frame_duration = time.delta_seconds().adjust_precision();
+    for (entity, motor, body_targets, update_tier, tnua_toggle) in characters_query.iter() {
+        let Some(ground_reaction) = motor.ground_reaction.as_ref() else {
+            continue;
+        };
+        if !schedule_filter.drives(entity)
+            || TnuaToggle::resolve(tnua_toggle, paused.as_deref()) != TnuaToggle::Enabled
+            || !TnuaUpdateTier::should_apply_motor(update_tier)
+        {
+            continue;
+        }
+        let Ok(mass) = masses_query.get(TnuaBodyTargets::resolve(body_targets, entity)) else {
+            continue;
+        };
+        let Ok((rigid_body, position, rotation, center_of_mass, mut external_impulse)) =
+            grounds_query.get_mut(ground_reaction.entity)
+        else {
+            continue;
+        };
+        if !rigid_body.is_dynamic() {
+            continue;
+        }
+        let impulse = ground_reaction.acceleration * mass.0 * frame_duration;
+        if !impulse.is_finite() || impulse.length() <= TNUA_MOTOR_WRITE_EPSILON {
+            continue;
+        }
+        external_impulse.apply_impulse_at_point(
+            impulse,
+            ground_reaction.point,
+            position.0 + *rotation * center_of_mass.0,
+        );
+    }
+}
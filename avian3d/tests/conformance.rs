@@ -0,0 +1,65 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_tnua_avian3d::TnuaAvian3dPlugin;
+use bevy_tnua_physics_integration_layer::conformance::TnuaConformanceSetup;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+
+struct Avian3dSetup;
+
+impl TnuaConformanceSetup for Avian3dSetup {
+    fn add_physics(&self, app: &mut App) {
+        app.add_plugins((AssetPlugin::default(), bevy::scene::ScenePlugin));
+        app.init_asset::<Mesh>();
+        app.add_plugins((PhysicsPlugins::default(), TnuaAvian3dPlugin::default()));
+        app.insert_resource(Gravity(Vector3::new(0.0, -9.81, 0.0)));
+    }
+
+    fn spawn_static_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+    ) -> Entity {
+        let size = 2.0 * half_extents;
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Static,
+                Collider::cuboid(size.x, size.y, size.z),
+            ))
+            .id()
+    }
+
+    fn spawn_kinematic_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+        linear_velocity: Vector3,
+        angular_velocity: Vector3,
+    ) -> Entity {
+        let size = 2.0 * half_extents;
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Kinematic,
+                LinearVelocity(linear_velocity),
+                AngularVelocity(angular_velocity),
+                Collider::cuboid(size.x, size.y, size.z),
+            ))
+            .id()
+    }
+
+    fn spawn_dynamic_ball(&self, app: &mut App, transform: Transform, radius: Float) -> Entity {
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Dynamic,
+                Collider::sphere(radius),
+            ))
+            .id()
+    }
+}
+
+bevy_tnua_physics_integration_layer::tnua_conformance_tests!(Avian3dSetup);
+bevy_tnua_physics_integration_layer::tnua_conformance_tests!(Avian3dSetup, check_plane_lock);
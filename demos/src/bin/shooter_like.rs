@@ -433,7 +433,7 @@ fn apply_camera_controls(
 ) {
     let mouse_controls_camera = primary_window_query
         .get_single()
-        .map_or(false, |w| !w.cursor.visible);
+        .is_ok_and(|w| !w.cursor.visible);
     let total_delta = if mouse_controls_camera {
         mouse_motion.read().map(|event| event.delta).sum()
     } else {
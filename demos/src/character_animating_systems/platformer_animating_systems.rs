@@ -64,8 +64,10 @@ pub fn animate_platformer_character(
                         TnuaBuiltinJumpState::SlowDownTooFastSlopeJump { .. } => {
                             AnimationState::Jumping
                         }
-                        TnuaBuiltinJumpState::MaintainingJump => AnimationState::Jumping,
-                        TnuaBuiltinJumpState::StoppedMaintainingJump => AnimationState::Jumping,
+                        TnuaBuiltinJumpState::MaintainingJump { .. } => AnimationState::Jumping,
+                        TnuaBuiltinJumpState::StoppedMaintainingJump { .. } => {
+                            AnimationState::Jumping
+                        }
                         TnuaBuiltinJumpState::FallSection => AnimationState::Falling,
                     }
                 }
@@ -124,10 +126,10 @@ pub fn animate_platformer_character(
                 }
                 // Jumping and dashing can be chained, we want to start a new jump/dash animation
                 // when one jump/dash is chained to another.
-                AnimationState::Jumping | AnimationState::Dashing => {
-                    if controller.action_flow_status().just_starting().is_some() {
-                        player.seek_all_by(0.0);
-                    }
+                AnimationState::Jumping | AnimationState::Dashing
+                    if controller.action_flow_status().just_starting().is_some() =>
+                {
+                    player.seek_all_by(0.0);
                 }
                 // For other animations we don't have anything special to do - so we just let them
                 // continue.
@@ -25,12 +25,12 @@ pub struct LevelSetupHelper2d<'w, 's> {
 }
 
 impl<'w, 's> LevelSetupHelper2d<'w, 's> {
-    pub fn spawn_named(&mut self, name: impl ToString) -> EntityCommands {
+    pub fn spawn_named(&mut self, name: impl ToString) -> EntityCommands<'_> {
         self.commands
             .spawn((LevelObject, Name::new(name.to_string())))
     }
 
-    pub fn spawn_floor(&mut self, color: impl Into<Color>) -> EntityCommands {
+    pub fn spawn_floor(&mut self, color: impl Into<Color>) -> EntityCommands<'_> {
         let mut cmd = self.spawn_named("Floor");
         cmd.insert(SpriteBundle {
             sprite: Sprite {
@@ -58,7 +58,7 @@ impl<'w, 's> LevelSetupHelper2d<'w, 's> {
         color: impl Into<Color>,
         transform: Transform,
         size: Vector2,
-    ) -> EntityCommands {
+    ) -> EntityCommands<'_> {
         let mut cmd = self.spawn_named(name);
 
         cmd.insert(SpriteBundle {
@@ -92,7 +92,7 @@ impl<'w, 's> LevelSetupHelper2d<'w, 's> {
         text_scale: Float,
         transform: Transform,
         #[allow(unused)] radius: Float,
-    ) -> EntityCommands {
+    ) -> EntityCommands<'_> {
         let font = self.asset_server.load("FiraSans-Bold.ttf");
         let child = self
             .spawn((
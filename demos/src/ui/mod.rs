@@ -266,6 +266,7 @@ fn ui_system<C: Component + UiTunable>(
                                     TnuaToggle::Disabled,
                                     TnuaToggle::SenseOnly,
                                     TnuaToggle::Enabled,
+                                    TnuaToggle::Frozen,
                                 ] {
                                     let label = format!("{:?}", option);
                                     ui.selectable_value(tnua_toggle.as_mut(), option, label);
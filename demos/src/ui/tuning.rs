@@ -102,6 +102,10 @@ impl UiTunable for TnuaBuiltinWalk {
     #[cfg(feature = "egui")]
     fn tune(&mut self, ui: &mut egui::Ui) {
         ui.add(egui::Slider::new(&mut self.float_height, 0.0..=10.0).text("Float At"));
+        ui.add(
+            egui::Slider::new(&mut self.float_height_change_duration, 0.0..=2.0)
+                .text("Float Height Change Duration"),
+        );
         ui.add(egui::Slider::new(&mut self.cling_distance, 0.0..=10.0).text("Cling Distance"));
         ui.add(egui::Slider::new(&mut self.spring_strengh, 0.0..=4000.0).text("Spring Strengh"));
         ui.add(egui::Slider::new(&mut self.spring_dampening, 0.0..=1.9).text("Spring Dampening"));
@@ -112,6 +116,11 @@ impl UiTunable for TnuaBuiltinWalk {
             &mut self.air_acceleration,
             0.0..=200.0,
         );
+        slider_or_infinity(ui, "Max Speed", &mut self.max_speed, 0.0..=100.0);
+        slider_or_infinity(ui, "Air Max Speed", &mut self.air_max_speed, 0.0..=100.0);
+        ui.add(egui::Slider::new(&mut self.air_drag, 0.0..=10.0).text("Air Drag"));
+        ui.checkbox(&mut self.conserve_momentum, "Conserve Momentum");
+        ui.add(egui::Slider::new(&mut self.momentum_drag, 0.0..=10.0).text("Momentum Drag"));
 
         ui.add(egui::Slider::new(&mut self.coyote_time, 0.0..=1.0).text("Coyote Time"));
 
@@ -132,6 +141,14 @@ impl UiTunable for TnuaBuiltinWalk {
             &mut self.tilt_offset_angacl,
             0.0..=2000.0,
         );
+        ui.add(egui::Slider::new(&mut self.ground_alignment, 0.0..=1.0).text("Ground Alignment"));
+        ui.add(
+            egui::Slider::new(
+                &mut self.max_ground_alignment_tilt,
+                0.0..=float_consts::FRAC_PI_2,
+            )
+            .text("Max Ground Alignment Tilt (in radians)"),
+        );
 
         slider_or_infinity(
             ui,
@@ -139,11 +156,18 @@ impl UiTunable for TnuaBuiltinWalk {
             &mut self.turning_angvel,
             0.0..=70.0,
         );
+        slider_or_infinity(
+            ui,
+            "Turning Angular Acceleration",
+            &mut self.turning_angacl,
+            0.0..=500.0,
+        );
 
         ui.add(
             egui::Slider::new(&mut self.max_slope, 0.0..=float_consts::FRAC_PI_2)
                 .text("Max Slope (in radians)"),
         );
+        ui.checkbox(&mut self.block_steep_slopes, "Block Steep Slopes");
     }
 }
 
@@ -0,0 +1,993 @@
+//! A test suite that every physics backend should pass.
+//!
+//! The suite checks the backend's side of the contract described in [the crate
+//! documentation](crate) - the sensor outputs, the rigid body trackers, the motors and their
+//! ground reactions,
+//! [`TnuaToggle`] and [`TnuaPaused`], [`TnuaSleepPolicy`], [`TnuaUpdateTier`] and
+//! [`TnuaSensorHitCache`], [`TnuaSensorCastCounter`], the [`TnuaObstacleRadar`], and
+//! [`TnuaSchedule`] - so that all backends
+//! behave the same. 3D backends should also run [`check_plane_lock`].
+//!
+//! To run it, implement [`TnuaConformanceSetup`] in an integration test of the backend crate and
+//! pass it to [`tnua_conformance_tests!`](crate::tnua_conformance_tests):
+//!
+//! ```ignore
+//! use bevy::prelude::*;
+//! use bevy_tnua_physics_integration_layer::conformance::TnuaConformanceSetup;
+//! use bevy_tnua_physics_integration_layer::math::Vector3;
+//!
+//! struct MyBackendSetup;
+//!
+//! impl TnuaConformanceSetup for MyBackendSetup {
+//!     // ...
+//! }
+//!
+//! bevy_tnua_physics_integration_layer::tnua_conformance_tests!(MyBackendSetup);
+//! ```
+//!
+//! All the checks (except for [`check_plane_lock`]) happen in the XY plane, with gravity pointing
+//! down the Y axis, so that they can run in 2D as well.
+//!
+//! Only available with the `conformance` feature.
+use std::time::Duration;
+
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::data_for_backends::{
+    TnuaCharacterCollisionPolicy, TnuaGhostPlatform, TnuaGhostSensor, TnuaGroundReaction,
+    TnuaMotor, TnuaPaused, TnuaPlaneLock, TnuaProximitySensor, TnuaProximitySensorOutput,
+    TnuaRigidBodyTracker, TnuaSensorCastCounter, TnuaSensorHitCache, TnuaSleepPolicy, TnuaToggle,
+    TnuaUpdateTier,
+};
+use crate::math::{AdjustPrecision, AsF32, Float, Quaternion, Vector3};
+use crate::obstacle_radar::{TnuaObstacleRadar, TnuaObstacleRadarShape};
+use crate::schedules::{TnuaSchedule, TnuaSchedules};
+use crate::{TnuaPipelineStages, TnuaSystemSet};
+
+/// The duration of each tick in the apps created by [`create_app`].
+///
+/// The physics engine should be configured to advance by exactly this duration every tick.
+pub const TICK_DURATION: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// What a physics backend provides for running the conformance suite.
+///
+/// Colliders should be spawned in the default collision layers (or groups), and all bodies
+/// should have the physics engine's default damping and friction.
+pub trait TnuaConformanceSetup {
+    /// Add the physics engine and its Tnua backend plugin to the app, with a gravity of `9.81`
+    /// downward along the Y axis.
+    fn add_physics(&self, app: &mut App);
+
+    /// Spawn a static box. In 2D, the Z axis of `half_extents` should be ignored.
+    fn spawn_static_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+    ) -> Entity;
+
+    /// Spawn a kinematic box that moves with the given velocities. In 2D, only the Z axis of
+    /// `angular_velocity` should be used.
+    fn spawn_kinematic_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+        linear_velocity: Vector3,
+        angular_velocity: Vector3,
+    ) -> Entity;
+
+    /// Spawn a dynamic ball with all the components the backend needs for applying a
+    /// [`TnuaMotor`] to it (except for the Tnua components themselves).
+    fn spawn_dynamic_ball(&self, app: &mut App, transform: Transform, radius: Float) -> Entity;
+}
+
+/// Create a headless app with the physics backend, that advances by [`TICK_DURATION`] every
+/// update.
+///
+/// Since the controller is not part of the suite, the app orders the Tnua pipeline stages itself.
+pub fn create_app(setup: &impl TnuaConformanceSetup) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK_DURATION));
+    setup.add_physics(&mut app);
+    app.configure_sets(
+        Update,
+        (
+            TnuaPipelineStages::Sensors,
+            TnuaPipelineStages::SubservientSensors,
+            TnuaPipelineStages::Logic,
+            TnuaPipelineStages::Motors,
+        )
+            .chain()
+            .in_set(TnuaSystemSet),
+    );
+    app
+}
+
+/// Run all the checks of the suite.
+pub fn run_all(setup: &impl TnuaConformanceSetup) {
+    check_sensor_on_flat_ground(setup);
+    check_sensor_on_slope(setup);
+    check_sensor_on_moving_platform(setup);
+    check_sensor_on_rotating_platform(setup);
+    check_ghost_platforms(setup);
+    check_motor_boost(setup);
+    check_motor_acceleration(setup);
+    check_ground_reaction(setup);
+    check_character_collision_policy(setup);
+    check_toggle(setup);
+    check_paused(setup);
+    check_schedules(setup);
+    check_sleep_policy(setup);
+    check_update_tiers(setup);
+    check_sensor_hit_cache(setup);
+    check_sensor_cast_counter(setup);
+    check_obstacle_radar(setup);
+}
+
+fn run_updates(app: &mut App, updates: usize) {
+    for _ in 0..updates {
+        app.update();
+    }
+}
+
+fn spawn_probe(app: &mut App, position: Vector3, bundle: impl Bundle) -> Entity {
+    app.world_mut()
+        .spawn((
+            TransformBundle::from_transform(Transform::from_translation(position.f32())),
+            TnuaProximitySensor {
+                cast_range: 5.0,
+                ..Default::default()
+            },
+            bundle,
+        ))
+        .id()
+}
+
+fn sensor_output(app: &App, probe: Entity) -> Option<&TnuaProximitySensorOutput> {
+    app.world()
+        .get::<TnuaProximitySensor>(probe)
+        .expect("probe must have a TnuaProximitySensor")
+        .output
+        .as_ref()
+}
+
+fn spawn_floor(setup: &impl TnuaConformanceSetup, app: &mut App) -> Entity {
+    setup.spawn_static_box(
+        app,
+        Transform::from_xyz(0.0, -0.5, 0.0),
+        Vector3::new(10.0, 0.5, 10.0),
+    )
+}
+
+#[track_caller]
+fn assert_near(what: &str, actual: Vector3, expected: Vector3, tolerance: Float) {
+    assert!(
+        (actual - expected).abs().max_element() <= tolerance,
+        "{what} is {actual}, expected {expected} (up to {tolerance})",
+    );
+}
+
+/// A ray sensor above flat ground detects the ground, at the right distance and with an upward
+/// normal.
+pub fn check_sensor_on_flat_ground(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let floor = spawn_floor(setup, &mut app);
+    let probe = spawn_probe(&mut app, Vector3::new(0.0, 1.5, 0.0), ());
+    run_updates(&mut app, 3);
+
+    let output = sensor_output(&app, probe).expect("sensor should detect the floor");
+    assert_eq!(output.entity, floor);
+    assert!(
+        (output.proximity - 1.5).abs() <= 0.01,
+        "proximity is {}, expected 1.5",
+        output.proximity
+    );
+    assert_near("normal", output.normal.adjust_precision(), Vector3::Y, 0.01);
+    assert_near("entity_linvel", output.entity_linvel, Vector3::ZERO, 0.01);
+    assert_near("entity_angvel", output.entity_angvel, Vector3::ZERO, 0.01);
+}
+
+/// A ray sensor above a slope detects the slope's surface, with its normal tilted accordingly.
+pub fn check_sensor_on_slope(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let angle: Float = 0.3;
+    let rotation = Quaternion::from_rotation_z(angle);
+    let slope = setup.spawn_static_box(
+        &mut app,
+        Transform::from_rotation(rotation.f32()),
+        Vector3::new(5.0, 0.5, 5.0),
+    );
+    let probe = spawn_probe(&mut app, Vector3::new(0.0, 3.0, 0.0), ());
+    run_updates(&mut app, 3);
+
+    let output = sensor_output(&app, probe).expect("sensor should detect the slope");
+    assert_eq!(output.entity, slope);
+    let expected_proximity = 3.0 - 0.5 / angle.cos();
+    assert!(
+        (output.proximity - expected_proximity).abs() <= 0.01,
+        "proximity is {}, expected {expected_proximity}",
+        output.proximity,
+    );
+    assert_near(
+        "normal",
+        output.normal.adjust_precision(),
+        rotation * Vector3::Y,
+        0.01,
+    );
+}
+
+/// A ray sensor above a moving platform reports the platform's velocity.
+pub fn check_sensor_on_moving_platform(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let platform = setup.spawn_kinematic_box(
+        &mut app,
+        Transform::default(),
+        Vector3::new(5.0, 0.5, 5.0),
+        Vector3::new(2.0, 0.0, 0.0),
+        Vector3::ZERO,
+    );
+    let probe = spawn_probe(&mut app, Vector3::new(0.0, 2.0, 0.0), ());
+    run_updates(&mut app, 3);
+
+    let output = sensor_output(&app, probe).expect("sensor should detect the platform");
+    assert_eq!(output.entity, platform);
+    assert_near(
+        "entity_linvel",
+        output.entity_linvel,
+        Vector3::new(2.0, 0.0, 0.0),
+        0.01,
+    );
+    assert_near("entity_angvel", output.entity_angvel, Vector3::ZERO, 0.01);
+}
+
+/// A ray sensor above a rotating platform reports the velocity of the point it hit, not just the
+/// velocity of the platform's center.
+pub fn check_sensor_on_rotating_platform(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let angular_velocity = Vector3::new(0.0, 0.0, 0.5);
+    let platform = setup.spawn_kinematic_box(
+        &mut app,
+        Transform::default(),
+        Vector3::new(5.0, 0.5, 5.0),
+        Vector3::ZERO,
+        angular_velocity,
+    );
+    let probe = spawn_probe(&mut app, Vector3::new(2.0, 2.0, 0.0), ());
+    run_updates(&mut app, 3);
+
+    let output = sensor_output(&app, probe).expect("sensor should detect the platform");
+    assert_eq!(output.entity, platform);
+    assert_near(
+        "entity_angvel",
+        output.entity_angvel,
+        angular_velocity,
+        0.01,
+    );
+    let hit_point = Vector3::new(2.0, 0.5, 0.0);
+    assert_near(
+        "entity_linvel",
+        output.entity_linvel,
+        angular_velocity.cross(hit_point),
+        0.05,
+    );
+}
+
+/// Ghost platforms are never the sensor output. Sensors with [`TnuaGhostSensor`] report them
+/// there instead.
+pub fn check_ghost_platforms(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let floor = spawn_floor(setup, &mut app);
+    let ghost = setup.spawn_static_box(
+        &mut app,
+        Transform::from_xyz(0.0, 0.75, 0.0),
+        Vector3::new(2.0, 0.25, 2.0),
+    );
+    app.world_mut().entity_mut(ghost).insert(TnuaGhostPlatform);
+    let probe = spawn_probe(
+        &mut app,
+        Vector3::new(0.0, 3.0, 0.0),
+        TnuaGhostSensor::default(),
+    );
+    let probe_without_ghost_sensor = spawn_probe(&mut app, Vector3::new(1.0, 3.0, 0.0), ());
+    run_updates(&mut app, 3);
+
+    let output = sensor_output(&app, probe).expect("sensor should detect the floor");
+    assert_eq!(output.entity, floor);
+    assert!(
+        (output.proximity - 3.0).abs() <= 0.01,
+        "proximity is {}, expected 3.0",
+        output.proximity
+    );
+    let ghost_sensor = app.world().get::<TnuaGhostSensor>(probe).unwrap();
+    assert_eq!(
+        ghost_sensor
+            .iter()
+            .map(|ghost_output| ghost_output.entity)
+            .collect::<Vec<_>>(),
+        [ghost],
+    );
+    let ghost_output = ghost_sensor.iter().next().unwrap();
+    assert!(
+        (ghost_output.proximity - 2.0).abs() <= 0.01,
+        "ghost proximity is {}, expected 2.0",
+        ghost_output.proximity
+    );
+
+    let output =
+        sensor_output(&app, probe_without_ghost_sensor).expect("sensor should detect the floor");
+    assert_eq!(output.entity, floor);
+}
+
+fn spawn_motor_ball(setup: &impl TnuaConformanceSetup, app: &mut App) -> Entity {
+    let ball = setup.spawn_dynamic_ball(app, Transform::from_xyz(0.0, 10.0, 0.0), 0.5);
+    app.world_mut()
+        .entity_mut(ball)
+        .insert((TnuaMotor::default(), TnuaRigidBodyTracker::default()));
+    ball
+}
+
+fn set_motor(app: &mut App, entity: Entity, motor: TnuaMotor) {
+    *app.world_mut().get_mut::<TnuaMotor>(entity).unwrap() = motor;
+}
+
+fn tracker(app: &App, entity: Entity) -> &TnuaRigidBodyTracker {
+    app.world().get::<TnuaRigidBodyTracker>(entity).unwrap()
+}
+
+/// A motor boost changes the velocity immediately, and the rigid body tracker reports it.
+pub fn check_motor_boost(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let ball = spawn_motor_ball(setup, &mut app);
+    run_updates(&mut app, 3);
+    assert_near(
+        "tracked gravity",
+        tracker(&app, ball).gravity,
+        Vector3::new(0.0, -9.81, 0.0),
+        0.01,
+    );
+
+    let mut motor = TnuaMotor::default();
+    motor.lin.boost = Vector3::new(3.0, 0.0, 0.0);
+    motor.ang.boost = Vector3::new(0.0, 0.0, 2.0);
+    set_motor(&mut app, ball, motor);
+    run_updates(&mut app, 1);
+    set_motor(&mut app, ball, TnuaMotor::default());
+    // The tracker is updated at the start of the tick, so it needs another tick to see the boost.
+    run_updates(&mut app, 1);
+
+    let tracker = tracker(&app, ball);
+    assert!(
+        (tracker.velocity.x - 3.0).abs() <= 0.01,
+        "velocity is {}, expected X to be 3.0",
+        tracker.velocity
+    );
+    assert_near("angvel", tracker.angvel, Vector3::new(0.0, 0.0, 2.0), 0.01);
+}
+
+/// A motor acceleration changes the velocity over time, and the rigid body tracker reports it.
+pub fn check_motor_acceleration(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let ball = spawn_motor_ball(setup, &mut app);
+    run_updates(&mut app, 3);
+
+    let mut motor = TnuaMotor::default();
+    motor.lin.acceleration = Vector3::new(6.0, 0.0, 0.0);
+    set_motor(&mut app, ball, motor);
+    run_updates(&mut app, 30);
+    set_motor(&mut app, ball, TnuaMotor::default());
+    run_updates(&mut app, 1);
+
+    let expected = 6.0 * 30.0 * TICK_DURATION.as_secs_f64() as Float;
+    let velocity = tracker(&app, ball).velocity;
+    assert!(
+        (velocity.x - expected).abs() <= 0.1,
+        "velocity is {velocity}, expected X to be {expected}",
+    );
+}
+
+/// The [`TnuaMotor::ground_reaction`] pushes the dynamic rigid body it points at, scaled by the
+/// mass of the character.
+pub fn check_ground_reaction(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let ball = spawn_motor_ball(setup, &mut app);
+    let ground = setup.spawn_dynamic_ball(&mut app, Transform::from_xyz(5.0, 10.0, 0.0), 0.5);
+    app.world_mut()
+        .entity_mut(ground)
+        .insert(TnuaRigidBodyTracker::default());
+    run_updates(&mut app, 3);
+
+    let ground_reaction = TnuaGroundReaction {
+        entity: ground,
+        point: tracker(&app, ground).translation,
+        acceleration: Vector3::new(6.0, 0.0, 0.0),
+    };
+    set_motor(
+        &mut app,
+        ball,
+        TnuaMotor {
+            ground_reaction: Some(ground_reaction),
+            ..Default::default()
+        },
+    );
+    run_updates(&mut app, 30);
+    set_motor(&mut app, ball, TnuaMotor::default());
+    run_updates(&mut app, 2);
+
+    // Both balls have the same mass, so the ground gets the same acceleration.
+    let expected = 6.0 * 30.0 * TICK_DURATION.as_secs_f64() as Float;
+    let velocity = tracker(&app, ground).velocity;
+    assert!(
+        (velocity.x - expected).abs() <= 0.1,
+        "ground velocity is {velocity}, expected X to be {expected}",
+    );
+    let velocity = tracker(&app, ball).velocity;
+    assert!(
+        velocity.x.abs() <= 0.01,
+        "character velocity is {velocity}, expected X to be 0.0",
+    );
+}
+
+/// Characters only collide with each other if both are [`TnuaCharacterCollisionPolicy::Collide`]
+/// (or have no policy at all).
+///
+/// Backends that implement the policy with physics hooks may need to set them up in
+/// [`TnuaConformanceSetup::add_physics`].
+pub fn check_character_collision_policy(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let mut spawn_pair = |x: f32, policies: [Option<TnuaCharacterCollisionPolicy>; 2]| {
+        [(-0.4, policies[0]), (0.4, policies[1])].map(|(offset, policy)| {
+            let ball =
+                setup.spawn_dynamic_ball(&mut app, Transform::from_xyz(x + offset, 10.0, 0.0), 0.5);
+            if let Some(policy) = policy {
+                app.world_mut().entity_mut(ball).insert(policy);
+            }
+            ball
+        })
+    };
+    use TnuaCharacterCollisionPolicy::*;
+    let pass_through = spawn_pair(0.0, [Some(PassThrough), Some(PassThrough)]);
+    let mixed = spawn_pair(5.0, [Some(PassThrough), Some(Collide)]);
+    let colliding = spawn_pair(10.0, [Some(Collide), Some(Collide)]);
+    let without_policy = spawn_pair(15.0, [Some(PassThrough), None]);
+    run_updates(&mut app, 30);
+
+    let distance = |app: &App, [ball1, ball2]: [Entity; 2]| {
+        let translation = |ball| app.world().get::<Transform>(ball).unwrap().translation;
+        translation(ball1).distance(translation(ball2))
+    };
+    for (name, pair, should_collide) in [
+        ("pass-through", pass_through, false),
+        ("mixed", mixed, false),
+        ("colliding", colliding, true),
+        ("without policy", without_policy, true),
+    ] {
+        let distance = distance(&app, pair);
+        if should_collide {
+            assert!(
+                0.85 < distance,
+                "{name} pair should have been pushed apart, but is {distance} apart",
+            );
+        } else {
+            assert!(
+                (distance - 0.8).abs() <= 0.01,
+                "{name} pair should have stayed 0.8 apart, but is {distance} apart",
+            );
+        }
+    }
+}
+
+/// [`TnuaPlaneLock`] pulls the rigid body back to the plane and cancels its off-plane velocity,
+/// and makes the sensor cast from the plane and ignore the slope along the locked axis.
+///
+/// Only 3D backends support [`TnuaPlaneLock`], so this check is not part of [`run_all`] and
+/// needs to be passed explicitly to [`tnua_conformance_tests!`](crate::tnua_conformance_tests).
+pub fn check_plane_lock(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let plane_lock = TnuaPlaneLock::new(Dir3::Z, Vector3::ZERO);
+
+    let ball = setup.spawn_dynamic_ball(&mut app, Transform::from_xyz(0.0, 10.0, 0.5), 0.5);
+    app.world_mut().entity_mut(ball).insert((
+        TnuaMotor::default(),
+        TnuaRigidBodyTracker::default(),
+        plane_lock,
+    ));
+    run_updates(&mut app, 3);
+    let mut motor = TnuaMotor::default();
+    motor.lin.boost = Vector3::new(3.0, 0.0, 2.0);
+    set_motor(&mut app, ball, motor);
+    run_updates(&mut app, 1);
+    set_motor(&mut app, ball, TnuaMotor::default());
+    run_updates(&mut app, 3);
+
+    let tracker = tracker(&app, ball);
+    assert!(
+        tracker.translation.z.abs() <= 0.01,
+        "translation is {}, expected Z to be 0.0",
+        tracker.translation,
+    );
+    assert!(
+        tracker.velocity.z.abs() <= 0.01 && (tracker.velocity.x - 3.0).abs() <= 0.01,
+        "velocity is {}, expected X to be 3.0 and Z to be 0.0",
+        tracker.velocity,
+    );
+
+    let angle: Float = 0.3;
+    let slope = setup.spawn_static_box(
+        &mut app,
+        Transform::from_xyz(20.0, 0.0, 0.0).with_rotation(Quaternion::from_rotation_x(angle).f32()),
+        Vector3::new(5.0, 0.5, 5.0),
+    );
+    let probe = spawn_probe(&mut app, Vector3::new(20.0, 3.0, 1.0), plane_lock);
+    run_updates(&mut app, 3);
+
+    let output = sensor_output(&app, probe).expect("sensor should detect the slope");
+    assert_eq!(output.entity, slope);
+    let expected_proximity = 3.0 - 0.5 / angle.cos();
+    assert!(
+        (output.proximity - expected_proximity).abs() <= 0.01,
+        "proximity is {}, expected {expected_proximity}",
+        output.proximity,
+    );
+    assert_near("normal", output.normal.adjust_precision(), Vector3::Y, 0.01);
+}
+
+/// [`TnuaToggle::Disabled`] stops both the sensor and the motor, [`TnuaToggle::SenseOnly`] only
+/// stops the motor, and [`TnuaToggle::Frozen`] holds the rigid body in place.
+pub fn check_toggle(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    spawn_floor(setup, &mut app);
+    let disabled_probe = spawn_probe(&mut app, Vector3::new(0.0, 1.5, 0.0), TnuaToggle::Disabled);
+    let sense_only_probe =
+        spawn_probe(&mut app, Vector3::new(1.0, 1.5, 0.0), TnuaToggle::SenseOnly);
+    let disabled_ball = spawn_motor_ball(setup, &mut app);
+    let sense_only_ball = spawn_motor_ball(setup, &mut app);
+    let frozen_ball = spawn_motor_ball(setup, &mut app);
+    app.world_mut()
+        .entity_mut(disabled_ball)
+        .insert((TnuaToggle::Disabled, Transform::from_xyz(-5.0, 10.0, 0.0)));
+    app.world_mut()
+        .entity_mut(sense_only_ball)
+        .insert((TnuaToggle::SenseOnly, Transform::from_xyz(5.0, 10.0, 0.0)));
+    app.world_mut()
+        .entity_mut(frozen_ball)
+        .insert((TnuaToggle::Frozen, Transform::from_xyz(10.0, 10.0, 0.0)));
+    run_updates(&mut app, 3);
+
+    assert!(
+        sensor_output(&app, disabled_probe).is_none(),
+        "disabled sensor should not detect anything",
+    );
+    assert!(
+        sensor_output(&app, sense_only_probe).is_some(),
+        "sense-only sensor should detect the floor",
+    );
+
+    let mut motor = TnuaMotor::default();
+    motor.lin.boost = Vector3::new(3.0, 0.0, 0.0);
+    set_motor(&mut app, disabled_ball, motor.clone());
+    set_motor(&mut app, sense_only_ball, motor.clone());
+    set_motor(&mut app, frozen_ball, motor);
+    run_updates(&mut app, 30);
+
+    let sense_only_velocity = tracker(&app, sense_only_ball).velocity;
+    assert!(
+        sense_only_velocity.x.abs() <= 0.01,
+        "sense-only motor should not be applied, but velocity is {sense_only_velocity}",
+    );
+    let disabled_position = app
+        .world()
+        .get::<GlobalTransform>(disabled_ball)
+        .unwrap()
+        .translation();
+    assert!(
+        (disabled_position.x + 5.0).abs() <= 0.01,
+        "disabled motor should not be applied, but position is {disabled_position}",
+    );
+    assert_near(
+        "frozen body position",
+        app.world()
+            .get::<GlobalTransform>(frozen_ball)
+            .unwrap()
+            .translation()
+            .adjust_precision(),
+        Vector3::new(10.0, 10.0, 0.0),
+        0.05,
+    );
+    assert_near(
+        "frozen body velocity",
+        tracker(&app, frozen_ball).velocity,
+        Vector3::ZERO,
+        0.01,
+    );
+}
+
+/// While the [`TnuaPaused`] resource exists, it overrides the [`TnuaToggle`] of all the
+/// characters.
+pub fn check_paused(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    spawn_floor(setup, &mut app);
+    let probe = spawn_probe(&mut app, Vector3::new(0.0, 1.5, 0.0), ());
+    let ball = spawn_motor_ball(setup, &mut app);
+    app.insert_resource(TnuaPaused::default());
+    let mut motor = TnuaMotor::default();
+    motor.lin.boost = Vector3::new(3.0, 0.0, 0.0);
+    set_motor(&mut app, ball, motor.clone());
+    run_updates(&mut app, 30);
+
+    assert!(
+        sensor_output(&app, probe).is_none(),
+        "paused sensor should not detect anything",
+    );
+    assert_near(
+        "paused body position",
+        app.world()
+            .get::<GlobalTransform>(ball)
+            .unwrap()
+            .translation()
+            .adjust_precision(),
+        Vector3::new(0.0, 10.0, 0.0),
+        0.05,
+    );
+
+    app.world_mut().remove_resource::<TnuaPaused>();
+    run_updates(&mut app, 1);
+    set_motor(&mut app, ball, TnuaMotor::default());
+    run_updates(&mut app, 2);
+
+    assert!(
+        sensor_output(&app, probe).is_some(),
+        "sensor should detect the floor after resuming",
+    );
+    let velocity = tracker(&app, ball).velocity;
+    assert!(
+        (velocity.x - 3.0).abs() <= 0.01,
+        "motor should be applied after resuming, but velocity is {velocity}",
+    );
+}
+
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct OtherSchedule;
+
+/// When Tnua is registered in more than one schedule, each schedule only updates the characters
+/// whose [`TnuaSchedule`] points at it.
+pub fn check_schedules(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    TnuaSchedules::register(&mut app, OtherSchedule.intern());
+    spawn_floor(setup, &mut app);
+    let default_probe = spawn_probe(&mut app, Vector3::new(0.0, 1.5, 0.0), ());
+    let own_probe = spawn_probe(
+        &mut app,
+        Vector3::new(1.0, 1.5, 0.0),
+        TnuaSchedule::new(Update),
+    );
+    let other_probe = spawn_probe(
+        &mut app,
+        Vector3::new(2.0, 1.5, 0.0),
+        TnuaSchedule::new(OtherSchedule),
+    );
+    let other_ball = spawn_motor_ball(setup, &mut app);
+    app.world_mut()
+        .entity_mut(other_ball)
+        .insert(TnuaSchedule::new(OtherSchedule));
+    let mut motor = TnuaMotor::default();
+    motor.lin.boost = Vector3::new(3.0, 0.0, 0.0);
+    set_motor(&mut app, other_ball, motor);
+    run_updates(&mut app, 3);
+
+    assert!(
+        sensor_output(&app, default_probe).is_some(),
+        "sensor without TnuaSchedule should be updated by the first schedule",
+    );
+    assert!(
+        sensor_output(&app, own_probe).is_some(),
+        "sensor should be updated by its schedule",
+    );
+    assert!(
+        sensor_output(&app, other_probe).is_none(),
+        "sensor should not be updated by another schedule",
+    );
+    let velocity = tracker(&app, other_ball).velocity;
+    assert!(
+        velocity == Vector3::ZERO,
+        "tracker should not be updated by another schedule, but velocity is {velocity}",
+    );
+}
+
+/// [`TnuaSleepPolicy`] stops applying the motor to an idle body while keeping it in place, and
+/// applies it again once the motor does more than cancel the gravity.
+pub fn check_sleep_policy(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    spawn_floor(setup, &mut app);
+    let ball = spawn_motor_ball(setup, &mut app);
+    app.world_mut().entity_mut(ball).insert((
+        TnuaProximitySensor {
+            cast_range: 20.0,
+            ..Default::default()
+        },
+        TnuaSleepPolicy {
+            time_to_sleep: 0.1,
+            ..Default::default()
+        },
+    ));
+    run_updates(&mut app, 3);
+
+    // Make the body hover, and once its velocity no longer changes - stop it.
+    let mut motor = TnuaMotor::default();
+    motor.lin.acceleration = Vector3::new(0.0, 9.81, 0.0);
+    set_motor(&mut app, ball, motor.clone());
+    run_updates(&mut app, 2);
+    motor.lin.boost = -tracker(&app, ball).velocity;
+    set_motor(&mut app, ball, motor.clone());
+    run_updates(&mut app, 1);
+    motor.lin.boost = Vector3::ZERO;
+    set_motor(&mut app, ball, motor.clone());
+    run_updates(&mut app, 20);
+
+    let sleep_policy = app.world().get::<TnuaSleepPolicy>(ball).unwrap();
+    assert!(
+        sleep_policy.is_asleep(),
+        "hovering body should be asleep, but was only idle for {}",
+        sleep_policy.idle_time,
+    );
+    let position = tracker(&app, ball).translation;
+    run_updates(&mut app, 60);
+    assert_near(
+        "sleeping body position",
+        tracker(&app, ball).translation,
+        position,
+        0.01,
+    );
+
+    motor.lin.boost = Vector3::new(3.0, 0.0, 0.0);
+    set_motor(&mut app, ball, motor);
+    run_updates(&mut app, 1);
+    set_motor(&mut app, ball, TnuaMotor::default());
+    run_updates(&mut app, 1);
+
+    assert!(
+        !app.world()
+            .get::<TnuaSleepPolicy>(ball)
+            .unwrap()
+            .is_asleep(),
+        "body should wake up when the motor moves it",
+    );
+    let velocity = tracker(&app, ball).velocity;
+    assert!(
+        (velocity.x - 3.0).abs() <= 0.01,
+        "velocity is {velocity}, expected X to be 3.0",
+    );
+}
+
+/// [`TnuaUpdateTier::HalfRate`] sensors are updated once every two frames,
+/// [`TnuaUpdateTier::Frozen`] sensors keep their last output, and the motor is not applied to
+/// frozen rigid bodies.
+pub fn check_update_tiers(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    spawn_floor(setup, &mut app);
+    let half_rate_probe = spawn_probe(&mut app, Vector3::new(-1.0, 1.5, 0.0), ());
+    let frozen_probe = spawn_probe(&mut app, Vector3::new(1.0, 1.5, 0.0), ());
+    let frozen_ball = spawn_motor_ball(setup, &mut app);
+    app.world_mut()
+        .entity_mut(frozen_ball)
+        .insert(TnuaUpdateTier::Frozen);
+    run_updates(&mut app, 3);
+
+    for (probe, tier, x) in [
+        (half_rate_probe, TnuaUpdateTier::HalfRate, -1.0),
+        (frozen_probe, TnuaUpdateTier::Frozen, 1.0),
+    ] {
+        app.world_mut()
+            .entity_mut(probe)
+            .insert((tier, Transform::from_xyz(x, 2.5, 0.0)));
+    }
+    let mut motor = TnuaMotor::default();
+    motor.lin.boost = Vector3::new(3.0, 0.0, 0.0);
+    set_motor(&mut app, frozen_ball, motor);
+    // The first update may still see the old `GlobalTransform`, so it takes two more updates to
+    // be sure a half-rate update happened.
+    run_updates(&mut app, 3);
+
+    let half_rate_output =
+        sensor_output(&app, half_rate_probe).expect("half-rate sensor should detect the floor");
+    assert!(
+        (half_rate_output.proximity - 2.5).abs() <= 0.01,
+        "half-rate proximity is {}, expected 2.5",
+        half_rate_output.proximity,
+    );
+    let frozen_output =
+        sensor_output(&app, frozen_probe).expect("frozen sensor should keep its output");
+    assert!(
+        (frozen_output.proximity - 1.5).abs() <= 0.01,
+        "frozen proximity is {}, expected it to stay 1.5",
+        frozen_output.proximity,
+    );
+    let frozen_velocity = tracker(&app, frozen_ball).velocity;
+    assert!(
+        frozen_velocity.x.abs() <= 0.01,
+        "frozen motor should not be applied, but velocity is {frozen_velocity}",
+    );
+}
+
+/// A sensor with [`TnuaSensorHitCache`] keeps detecting the cached entity at the right distance,
+/// detects new colliders once it does a full cast, and falls back to a full cast when the cached
+/// entity is gone.
+pub fn check_sensor_hit_cache(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let floor = spawn_floor(setup, &mut app);
+    let probe = spawn_probe(
+        &mut app,
+        Vector3::new(0.0, 2.5, 0.0),
+        TnuaSensorHitCache::new(2),
+    );
+    run_updates(&mut app, 3);
+    app.world_mut()
+        .entity_mut(probe)
+        .insert(Transform::from_xyz(0.0, 1.5, 0.0));
+    run_updates(&mut app, 3);
+
+    let output = sensor_output(&app, probe).expect("sensor should detect the floor");
+    assert_eq!(output.entity, floor);
+    assert!(
+        (output.proximity - 1.5).abs() <= 0.01,
+        "proximity is {}, expected 1.5",
+        output.proximity
+    );
+
+    let platform = setup.spawn_static_box(
+        &mut app,
+        Transform::from_xyz(0.0, 0.75, 0.0),
+        Vector3::new(2.0, 0.25, 2.0),
+    );
+    run_updates(&mut app, 4);
+
+    let output = sensor_output(&app, probe).expect("sensor should detect the platform");
+    assert_eq!(output.entity, platform);
+    assert!(
+        (output.proximity - 0.5).abs() <= 0.01,
+        "proximity is {}, expected 0.5",
+        output.proximity
+    );
+
+    app.world_mut().despawn(platform);
+    run_updates(&mut app, 2);
+
+    let output = sensor_output(&app, probe).expect("sensor should detect the floor again");
+    assert_eq!(output.entity, floor);
+}
+
+/// Every full cast of a sensor is recorded in the [`TnuaSensorCastCounter`], and re-tests of the
+/// entity of a [`TnuaSensorHitCache`] are not.
+pub fn check_sensor_cast_counter(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    app.init_resource::<TnuaSensorCastCounter>();
+    spawn_floor(setup, &mut app);
+    spawn_probe(&mut app, Vector3::new(-1.0, 1.5, 0.0), ());
+    let cached_probe = spawn_probe(&mut app, Vector3::new(1.0, 1.5, 0.0), ());
+
+    let casts_in_update = |app: &mut App| {
+        app.update();
+        app.world_mut()
+            .resource_mut::<TnuaSensorCastCounter>()
+            .take()
+    };
+    for _ in 0..3 {
+        casts_in_update(&mut app);
+    }
+    assert_eq!(casts_in_update(&mut app), 2);
+
+    app.world_mut()
+        .entity_mut(cached_probe)
+        .insert(TnuaSensorHitCache::new(5));
+    casts_in_update(&mut app);
+    assert_eq!(casts_in_update(&mut app), 1);
+}
+
+fn radar(app: &App, entity: Entity) -> &TnuaObstacleRadar {
+    app.world()
+        .get::<TnuaObstacleRadar>(entity)
+        .expect("entity must have a TnuaObstacleRadar")
+}
+
+/// A [`TnuaObstacleRadar`] detects the colliders inside its volume, and reports them as appeared
+/// and disappeared only on the frames they start and stop being detected.
+pub fn check_obstacle_radar(setup: &impl TnuaConformanceSetup) {
+    let mut app = create_app(setup);
+    let obstacle = setup.spawn_static_box(
+        &mut app,
+        Transform::from_xyz(1.0, 0.0, 0.0),
+        Vector3::new(0.25, 0.25, 0.25),
+    );
+    let radar_entity = app
+        .world_mut()
+        .spawn((
+            TransformBundle::default(),
+            TnuaRigidBodyTracker::default(),
+            TnuaObstacleRadar::new(TnuaObstacleRadarShape::Cylinder {
+                radius: 2.0,
+                height: 2.0,
+            })
+            .with_capacity(4),
+        ))
+        .id();
+
+    for _ in 0..3 {
+        app.update();
+        if radar(&app, radar_entity).has_blip(obstacle) {
+            break;
+        }
+    }
+    let radar_component = radar(&app, radar_entity);
+    assert_eq!(radar_component.iter_blips().collect::<Vec<_>>(), [obstacle]);
+    assert_eq!(
+        radar_component.iter_appeared_blips().collect::<Vec<_>>(),
+        [obstacle],
+    );
+
+    app.update();
+    let radar_component = radar(&app, radar_entity);
+    assert!(radar_component.has_blip(obstacle));
+    assert_eq!(radar_component.iter_appeared_blips().count(), 0);
+    assert_eq!(radar_component.iter_disappeared_blips().count(), 0);
+
+    app.world_mut().despawn(obstacle);
+    for _ in 0..3 {
+        app.update();
+        if !radar(&app, radar_entity).has_blip(obstacle) {
+            break;
+        }
+    }
+    let radar_component = radar(&app, radar_entity);
+    assert_eq!(radar_component.iter_blips().count(), 0);
+    assert_eq!(
+        radar_component.iter_disappeared_blips().collect::<Vec<_>>(),
+        [obstacle],
+    );
+
+    app.update();
+    assert_eq!(
+        radar(&app, radar_entity).iter_disappeared_blips().count(),
+        0
+    );
+}
+
+/// Generate a `#[test]` for each check of the [conformance suite](self).
+///
+/// Must be invoked in an integration test of the backend crate, with an expression that
+/// evaluates to a [`TnuaConformanceSetup`].
+#[macro_export]
+macro_rules! tnua_conformance_tests {
+    ($setup:expr) => {
+        $crate::tnua_conformance_tests!(
+            $setup,
+            check_sensor_on_flat_ground,
+            check_sensor_on_slope,
+            check_sensor_on_moving_platform,
+            check_sensor_on_rotating_platform,
+            check_ghost_platforms,
+            check_motor_boost,
+            check_motor_acceleration,
+            check_ground_reaction,
+            check_character_collision_policy,
+            check_toggle,
+            check_paused,
+            check_schedules,
+            check_sleep_policy,
+            check_update_tiers,
+            check_sensor_hit_cache,
+            check_sensor_cast_counter,
+            check_obstacle_radar,
+        );
+    };
+    ($setup:expr, $($check:ident),* $(,)?) => {
+        $(
+            #[test]
+            fn $check() {
+                $crate::conformance::$check(&$setup);
+            }
+        )*
+    };
+}
@@ -1,6 +1,9 @@
 use std::ops::{Add, AddAssign};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
-use crate::math::{Float, Quaternion, Vector3};
+use crate::math::{float_consts, AdjustPrecision, Float, Quaternion, Vector3};
+use bevy::ecs::entity::EntityHashSet;
 use bevy::prelude::*;
 
 /// Allows disabling Tnua for a specific entity.
@@ -9,7 +12,9 @@ use bevy::prelude::*;
 ///
 /// This component is not mandatory - if omitted, Tnua will just assume it is enabled for that
 /// entity.
-#[derive(Component, Default, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Component, Default, Debug, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TnuaToggle {
     /// Do not update the sensors, and do not apply forces from the motor.
     ///
@@ -26,6 +31,84 @@ pub enum TnuaToggle {
     #[default]
     /// The backend behaves normally - it updates the sensors and applies forces from the motor.
     Enabled,
+    /// Like [`TnuaToggle::Disabled`], but also hold the rigid body in place.
+    ///
+    /// The backend keeps updating the [`TnuaRigidBodyTracker`], and instead of applying the
+    /// motor it zeroes the rigid body's velocities and cancels the gravity (as reported by the
+    /// tracker) every frame.
+    Frozen,
+}
+
+impl TnuaToggle {
+    /// The toggle that is in effect for a character, considering the [`TnuaPaused`] resource.
+    ///
+    /// Backends (and other systems that respect the toggle) should use this instead of reading
+    /// the component directly.
+    pub fn resolve(toggle: Option<&TnuaToggle>, paused: Option<&TnuaPaused>) -> TnuaToggle {
+        if let Some(paused) = paused {
+            return paused.0;
+        }
+        toggle.copied().unwrap_or_default()
+    }
+}
+
+/// Pauses all the characters at once, e.g. during a cutscene.
+///
+/// While this resource exists, every character behaves as if its [`TnuaToggle`] was the one
+/// stored in it (by default [`TnuaToggle::Frozen`]). Unlike pausing the physics time, this does
+/// not affect other rigid bodies. Remove the resource to resume.
+#[derive(Resource, Debug, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Resource)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaPaused(pub TnuaToggle);
+
+impl Default for TnuaPaused {
+    fn default() -> Self {
+        Self(TnuaToggle::Frozen)
+    }
+}
+
+/// Allows updating far away or otherwise unimportant characters less often.
+///
+/// Casting the [`TnuaProximitySensor`] and the obstacle radar is the most expensive part of
+/// updating a character, so with large crowds it may be worthwhile to do it less often for
+/// characters that the player cannot see well.
+///
+/// This component is not mandatory - if omitted, the backend updates the character every frame.
+#[derive(Component, Default, Debug, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaUpdateTier {
+    /// Update the sensors and apply the motor every frame.
+    #[default]
+    Full,
+    /// Update the sensors every other frame. In between, the sensors keep their last output.
+    ///
+    /// The motor is still applied every frame.
+    HalfRate,
+    /// Do not update the sensors and do not apply the motor. The sensors keep their last output,
+    /// and the last force applied to the rigid body remains in effect.
+    Frozen,
+}
+
+impl TnuaUpdateTier {
+    /// Whether the backend should update the sensors of `entity` in frame number `frame` (e.g.
+    /// Bevy's `FrameCount`).
+    ///
+    /// [`HalfRate`](Self::HalfRate) entities are split between the even and the odd frames, so
+    /// that the cost of updating them is spread evenly.
+    pub fn should_update_sensors(tier: Option<&Self>, entity: Entity, frame: u32) -> bool {
+        match tier.copied().unwrap_or_default() {
+            TnuaUpdateTier::Full => true,
+            TnuaUpdateTier::HalfRate => (frame ^ entity.index()) & 1 == 0,
+            TnuaUpdateTier::Frozen => false,
+        }
+    }
+
+    /// Whether the backend should apply the motor.
+    pub fn should_apply_motor(tier: Option<&Self>) -> bool {
+        tier.copied().unwrap_or_default() != TnuaUpdateTier::Frozen
+    }
 }
 
 /// Newtonian state of the rigid body.
@@ -34,7 +117,9 @@ pub enum TnuaToggle {
 /// like velocity are dependent on the physics engine. The physics backend is responsible for
 /// updating this component from the physics engine during
 /// [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors).
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaRigidBodyTracker {
     pub translation: Vector3,
     pub rotation: Quaternion,
@@ -43,6 +128,24 @@ pub struct TnuaRigidBodyTracker {
     /// second. Can be extracted from a quaternion using [`Quaternion::xyz`].
     pub angvel: Vector3,
     pub gravity: Vector3,
+    /// The current mass of the rigid body, or zero if the physics engine did not calculate it
+    /// yet.
+    ///
+    /// The motor works in units that ignore mass, and the backends multiply it by the mass of the
+    /// rigid body when they apply it - so the controller behaves the same when the mass changes at
+    /// runtime (e.g. when the character picks up a heavy object). This field is for code that
+    /// needs the actual forces.
+    pub mass: Float,
+    /// The current principal angular inertia of the rigid body (in local space), or zero if the
+    /// physics engine did not calculate it yet. 2D backends only set the Z component.
+    pub inertia: Vector3,
+    /// The scale of the rigid body, taken from its `GlobalTransform`.
+    ///
+    /// The backends scale the sensor shape by it, and bases like `TnuaBuiltinWalk` scale their
+    /// float height by its component along the local axis of the body that points up (which is
+    /// the Y axis for an upright character), so that the character behaves the same when it grows
+    /// or shrinks.
+    pub scale: Vector3,
 }
 
 impl Default for TnuaRigidBodyTracker {
@@ -53,6 +156,129 @@ impl Default for TnuaRigidBodyTracker {
             velocity: Vector3::ZERO,
             angvel: Vector3::ZERO,
             gravity: Vector3::ZERO,
+            mass: 0.0,
+            inertia: Vector3::ZERO,
+            scale: Vector3::ONE,
+        }
+    }
+}
+
+/// Tells the physics backend that the rigid body of the character is on a different entity.
+///
+/// Add this component to the entity of the `TnuaController` (the one with the
+/// [`TnuaRigidBodyTracker`], the [`TnuaMotor`] and the [`TnuaProximitySensor`]) when the rigid
+/// body and its collider are on another entity - e.g. on a child entity. The backend will then:
+///
+/// * Read the position, the velocity and the mass for the [`TnuaRigidBodyTracker`] from the
+///   rigid body entity.
+/// * Cast the [`TnuaProximitySensor`] from the rigid body entity (with the
+///   [`cast_origin`](TnuaProximitySensor::cast_origin) in its coord system), excluding it and using
+///   its collision groups.
+/// * Apply the [`TnuaMotor`] to the rigid body entity.
+///
+/// The backend-specific components that Tnua requires for applying the motor must be on the rigid
+/// body entity.
+///
+/// This component is not mandatory - if omitted, the backend assumes the rigid body is on the
+/// same entity.
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBodyTargets {
+    /// The entity with the rigid body and the collider of the character.
+    pub rigid_body: Entity,
+}
+
+impl TnuaBodyTargets {
+    /// The entity the backend should use for the rigid body of `entity`, which may have this
+    /// component.
+    pub fn resolve(body_targets: Option<&Self>, entity: Entity) -> Entity {
+        body_targets.map_or(entity, |body_targets| body_targets.rigid_body)
+    }
+}
+
+/// Lists the collider entities of a character that is made of several colliders (e.g. a body
+/// capsule and a head sphere, each on its own child entity of the rigid body).
+///
+/// Add this component to the entity of the `TnuaController`. The [`TnuaProximitySensor`] (and the
+/// obstacle radar) will not detect any of the listed colliders, and the contacts between them and
+/// the ground will be considered when the sensor resolves
+/// [`intersection_match_prevention_cutoff`](TnuaProximitySensor::intersection_match_prevention_cutoff).
+///
+/// The [`TnuaMotor`] is still applied to the single rigid body - the controller entity, or the
+/// one set by [`TnuaBodyTargets`].
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaCharacterColliders {
+    /// The entities with the colliders of the character.
+    pub colliders: Vec<Entity>,
+}
+
+impl TnuaCharacterColliders {
+    pub fn new(colliders: impl IntoIterator<Item = Entity>) -> Self {
+        Self {
+            colliders: colliders.into_iter().collect(),
+        }
+    }
+
+    /// Iterate over `rigid_body` and the colliders listed in `character_colliders`.
+    ///
+    /// Physics backends should use this for excluding the character from its own queries.
+    pub fn iter_with(
+        character_colliders: Option<&Self>,
+        rigid_body: Entity,
+    ) -> impl '_ + Iterator<Item = Entity> {
+        std::iter::once(rigid_body).chain(
+            character_colliders
+                .into_iter()
+                .flat_map(|character_colliders| character_colliders.colliders.iter().copied()),
+        )
+    }
+}
+
+/// How a character interacts physically with other characters.
+///
+/// Add this component to the entity of the `TnuaController`. Two characters only collide with
+/// each other if both are [`Collide`](Self::Collide) - otherwise the physics backend drops the
+/// contacts between them. Characters (and other entities) without this component always collide.
+///
+/// Physics backends should look the policy up on the colliders of a contact and on their rigid
+/// bodies, and use [`should_collide`](Self::should_collide) to decide whether to keep it.
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaCharacterCollisionPolicy {
+    /// Collide with other characters, like with any other rigid body.
+    #[default]
+    Collide,
+    /// Pass through other characters, but get pushed away from them by the motor.
+    ///
+    /// The push is applied by `TnuaCharacterCollisionPlugin` of the main Tnua crate, not by the
+    /// physics backend, and only to characters with a
+    /// [`TnuaObstacleRadar`](crate::obstacle_radar::TnuaObstacleRadar).
+    SoftPush {
+        /// Other characters closer than this distance push this character away.
+        radius: Float,
+        /// The acceleration applied when the other character is right on top of this one. It
+        /// decreases linearly to zero at `radius`.
+        max_acceleration: Float,
+    },
+    /// Pass through other characters.
+    ///
+    /// Characters with an obstacle radar still detect the other characters, and can use it to
+    /// steer around them.
+    PassThrough,
+}
+
+impl TnuaCharacterCollisionPolicy {
+    /// Whether the contacts between two entities with these policies should be kept.
+    pub fn should_collide(policy1: Option<&Self>, policy2: Option<&Self>) -> bool {
+        match (policy1, policy2) {
+            (Some(policy1), Some(policy2)) => {
+                *policy1 == Self::Collide && *policy2 == Self::Collide
+            }
+            _ => true,
         }
     }
 }
@@ -62,7 +288,9 @@ impl Default for TnuaRigidBodyTracker {
 /// The physics backend is responsible for updating this component from the physics engine during
 /// [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors), usually by casting a ray
 /// or a shape in the `cast_direction`.
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaProximitySensor {
     /// The cast origin in the entity's coord system.
     pub cast_origin: Vector3,
@@ -106,7 +334,8 @@ impl Default for TnuaProximitySensor {
 }
 
 /// Information from [`TnuaProximitySensor`] that have detected another collider.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaProximitySensorOutput {
     /// The entity of the collider detected by the ray.
     pub entity: Entity,
@@ -121,10 +350,284 @@ pub struct TnuaProximitySensorOutput {
     /// rotation speed in radians per second. Can be extracted from a quaternion using
     /// [`Quaternion::xyz`].
     pub entity_angvel: Vector3,
+    /// How the detected surface can be stood on.
+    ///
+    /// The physics backend should set it to [`TnuaGroundClass::Walkable`]. Tnua classifies the
+    /// surface after [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors), based on
+    /// the [`TnuaGroundClassifier`] of the sensor entity and the [`TnuaSurfaceMaterial`] of the
+    /// detected entity.
+    pub ground_class: TnuaGroundClass,
+}
+
+/// The classification of a surface detected by a [`TnuaProximitySensor`].
+///
+/// The variants are ordered from the most walkable to the least walkable.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaGroundClass {
+    /// The character can stand on the surface.
+    #[default]
+    Walkable,
+    /// The surface is too steep (or too slippery) to stand on, and the character should slide
+    /// down on it.
+    Slide,
+    /// The surface is not a ground at all - e.g. a wall or an overhang.
+    Blocked,
+}
+
+/// The slope limits used for classifying the surfaces a [`TnuaProximitySensor`] detects.
+///
+/// Add this component to the sensor entity (usually the character entity). Sensors without it
+/// use the default limits, which classify every surface that faces against the cast direction as
+/// [`Walkable`](TnuaGroundClass::Walkable).
+///
+/// The angles are measured between the normal of the surface and the opposite of the sensor's
+/// cast direction (the up direction, for the main sensor).
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaGroundClassifier {
+    /// Surfaces up to this angle (in radians) are [`Walkable`](TnuaGroundClass::Walkable).
+    pub walkable_slope: Float,
+    /// Surfaces steeper than [`walkable_slope`](Self::walkable_slope) and up to this angle (in
+    /// radians) are [`Slide`](TnuaGroundClass::Slide). Steeper surfaces are
+    /// [`Blocked`](TnuaGroundClass::Blocked).
+    pub slide_slope: Float,
+}
+
+impl Default for TnuaGroundClassifier {
+    fn default() -> Self {
+        Self {
+            walkable_slope: float_consts::FRAC_PI_2,
+            slide_slope: float_consts::FRAC_PI_2,
+        }
+    }
+}
+
+impl TnuaGroundClassifier {
+    /// Classify a surface by its normal, and by the material of its entity (if it has one).
+    pub fn classify(
+        &self,
+        normal: Dir3,
+        up: Dir3,
+        material: Option<&TnuaSurfaceMaterial>,
+    ) -> TnuaGroundClass {
+        let angle = normal.angle_between(*up).adjust_precision();
+        let by_slope = if angle <= self.walkable_slope {
+            TnuaGroundClass::Walkable
+        } else if angle <= self.slide_slope {
+            TnuaGroundClass::Slide
+        } else {
+            TnuaGroundClass::Blocked
+        };
+        if let Some(material) = material {
+            by_slope.max(material.min_ground_class)
+        } else {
+            by_slope
+        }
+    }
+}
+
+/// Add this component to a collider entity to change how proximity sensors classify it.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaSurfaceMaterial {
+    /// The surface is never classified as more walkable than this - e.g. set it to
+    /// [`Slide`](TnuaGroundClass::Slide) for ice slides the character can never stand on.
+    pub min_ground_class: TnuaGroundClass,
+}
+
+/// Makes a shape-casting [`TnuaProximitySensor`] verify its hits with a ray.
+///
+/// When a cast shape barely touches the corner of a ledge, the normal of the hit is tilted, and a
+/// character standing on the edge may keep slipping off it and climbing back. With this
+/// component, after the shape cast hits, the backend also casts a ray from the cast origin along
+/// the cast direction, only against the entity the shape hit:
+///
+/// * If the ray hits it, the ray's normal - the normal of the surface right below the cast origin -
+///   replaces the normal of the shape hit.
+/// * If the ray misses, the shape hit was on a corner, and its normal is replaced with the
+///   opposite of the cast direction - treating the ledge as flat ground.
+///
+/// Has no effect on sensors that cast a ray.
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaSensorSolidityProbe {
+    /// How far past the shape hit the ray is cast. Should be a bit more than the extent of the
+    /// cast shape along the cast direction (e.g. the radius, for a ball)
+    pub max_extra_distance: Float,
+}
+
+impl Default for TnuaSensorSolidityProbe {
+    fn default() -> Self {
+        Self {
+            max_extra_distance: 0.5,
+        }
+    }
+}
+
+/// Lets a [`TnuaProximitySensor`] re-test the entity it detected in the previous frame instead of
+/// doing a full cast.
+///
+/// With this component, the backend first casts only against the entity of the previous
+/// [sensor output](TnuaProximitySensor::output). If that cast hits, its result becomes the new
+/// output, and the full cast - which needs to go over all the colliders in the cast's way - is
+/// skipped. If it misses (e.g. the character walked off that entity) the backend does a full cast
+/// as usual.
+///
+/// The downside is that a new collider that appears between the sensor and the cached entity is
+/// not detected while the cached entity is re-tested. To bound this, the cached entity is re-tested
+/// at most [`max_reuse_frames`](Self::max_reuse_frames) frames in a row before a full cast is
+/// forced.
+///
+/// Sensors with a [`TnuaGhostSensor`] always do a full cast, because they need to detect all the
+/// ghost platforms in the cast's way.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaSensorHitCache {
+    /// How many frames in a row the cached entity can be re-tested before a full cast is forced.
+    pub max_reuse_frames: u32,
+    reused_frames: u32,
+}
+
+impl Default for TnuaSensorHitCache {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+impl TnuaSensorHitCache {
+    pub fn new(max_reuse_frames: u32) -> Self {
+        Self {
+            max_reuse_frames,
+            reused_frames: 0,
+        }
+    }
+
+    /// Called by the physics backend before casting the sensor.
+    ///
+    /// Returns the entity the backend should re-test, or `None` if it should do a full cast.
+    pub fn entity_to_retest(&mut self, sensor: &TnuaProximitySensor) -> Option<Entity> {
+        match sensor.output.as_ref() {
+            Some(output) if self.reused_frames < self.max_reuse_frames => {
+                self.reused_frames += 1;
+                Some(output.entity)
+            }
+            _ => {
+                self.reused_frames = 0;
+                None
+            }
+        }
+    }
+
+    /// Called by the physics backend when re-testing the entity from
+    /// [`entity_to_retest`](Self::entity_to_retest) missed, and it did a full cast instead.
+    pub fn retest_missed(&mut self) {
+        self.reused_frames = 0;
+    }
+}
+
+/// Counts the casts the physics backend does for the proximity sensors, for diagnostics.
+///
+/// When this resource exists, the backend should call [`record_cast`](Self::record_cast) for every
+/// cast it does against the physics world for a [`TnuaProximitySensor`] - including the repeated
+/// casts for skipping ghost platforms and the [`TnuaSensorSolidityProbe`] casts. Re-testing the
+/// entity of a [`TnuaSensorHitCache`] only tests a single collider, and is not counted.
+#[derive(Resource, Default, Debug)]
+pub struct TnuaSensorCastCounter(AtomicUsize);
+
+impl TnuaSensorCastCounter {
+    /// Called by the physics backend for every cast. The sensors may be updated in parallel, so
+    /// this does not require mutable access.
+    pub fn record_cast(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The number of casts recorded since the last call, resetting the counter.
+    pub fn take(&mut self) -> usize {
+        std::mem::take(self.0.get_mut())
+    }
+}
+
+/// Filters the colliders a [`TnuaProximitySensor`] can detect.
+///
+/// Add this component to the sensor entity (usually the character entity) to make the sensor
+/// ignore some colliders - e.g. objects the character carries, or projectiles - without changing
+/// the collision layers of these colliders. Ignored colliders are ignored completely - they do
+/// not block the sensor from detecting colliders behind them.
+///
+/// This component is not mandatory - if omitted, the sensor uses the default filtering of the
+/// physics backend.
+#[derive(Component, Clone, Default)]
+pub struct TnuaSensorQueryFilter {
+    /// Colliders of these entities are never detected.
+    pub excluded_entities: EntityHashSet,
+    /// If set, only colliders whose collision layer memberships (or collision group memberships,
+    /// depending on the physics engine) intersect with this mask are detected.
+    pub layer_mask: Option<u32>,
+    /// If set, only colliders of entities for which this predicate returns `true` are detected.
+    pub predicate: Option<Arc<dyn Fn(Entity) -> bool + Send + Sync>>,
+    /// Colliders of these entities are not detected either. Unlike
+    /// [`excluded_entities`](Self::excluded_entities), this set is cleared before the sensors are
+    /// updated and filled again every frame by helpers (like bevy-tnua's `TnuaSensorHitFilter`).
+    pub computed_exclusions: EntityHashSet,
+}
+
+impl TnuaSensorQueryFilter {
+    pub fn with_excluded_entities(mut self, entities: impl IntoIterator<Item = Entity>) -> Self {
+        self.excluded_entities.extend(entities);
+        self
+    }
+
+    pub fn with_layer_mask(mut self, layer_mask: u32) -> Self {
+        self.layer_mask = Some(layer_mask);
+        self
+    }
+
+    pub fn with_predicate(
+        mut self,
+        predicate: impl Fn(Entity) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Check if the sensor may detect a collider of `entity`, whose collision layer (or group)
+    /// memberships are `memberships`.
+    ///
+    /// Physics backends should call this for every collider the sensor hits.
+    pub fn test(&self, entity: Entity, memberships: u32) -> bool {
+        if self.excluded_entities.contains(&entity) || self.computed_exclusions.contains(&entity) {
+            return false;
+        }
+        if let Some(layer_mask) = self.layer_mask {
+            if layer_mask & memberships == 0 {
+                return false;
+            }
+        }
+        if let Some(predicate) = &self.predicate {
+            if !predicate(entity) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl std::fmt::Debug for TnuaSensorQueryFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TnuaSensorQueryFilter")
+            .field("excluded_entities", &self.excluded_entities)
+            .field("layer_mask", &self.layer_mask)
+            .field("predicate", &self.predicate.as_ref().map(|_| ".."))
+            .field("computed_exclusions", &self.computed_exclusions)
+            .finish()
+    }
 }
 
 /// Represents a change to velocity (linear or angular)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaVelChange {
     // The part of the velocity change that gets multiplied by the frame duration.
     //
@@ -199,7 +702,9 @@ impl AddAssign for TnuaVelChange {
 ///
 /// This documentation uses the term "forces", but in fact these numbers ignore mass and are
 /// applied directly to the velocity.
-#[derive(Component, Default, Debug)]
+#[derive(Component, Default, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaMotor {
     /// How much velocity to add to the rigid body in the current frame.
     pub lin: TnuaVelChange,
@@ -208,6 +713,330 @@ pub struct TnuaMotor {
     /// rotation axis multiplied by the rotation speed in radians per second. Can be extracted from
     /// a quaternion using [`Quaternion::xyz`].
     pub ang: TnuaVelChange,
+
+    /// A force the physics backend should apply to the entity the character stands on, in
+    /// reaction to the motor pushing the character away from it.
+    pub ground_reaction: Option<TnuaGroundReaction>,
+}
+
+/// The reaction of the [`TnuaMotor`] on the entity the character stands on - e.g. so that standing
+/// on a boat or a seesaw pushes it down.
+///
+/// The physics backend should apply it only if the entity is a dynamic rigid body.
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaGroundReaction {
+    /// The entity to apply the force to.
+    pub entity: Entity,
+    /// The point, in world coordinates, to apply the force at.
+    pub point: Vector3,
+    /// The force to apply, divided by the mass of the character.
+    ///
+    /// The physics backend should multiply it by the mass of the character's rigid body (not the
+    /// mass of the entity it applies it to).
+    pub acceleration: Vector3,
+}
+
+/// Physics backends should not write [`TnuaMotor`] outputs that change the rigid body's
+/// components by less than this (in the units of these components) - e.g. a zero boost, or the
+/// same force as the one applied in the previous frame.
+///
+/// Writing a component triggers Bevy's change detection even when the value stays the same, and
+/// the physics engine (and other systems) may react to that - e.g. by waking up the rigid body.
+pub const TNUA_MOTOR_WRITE_EPSILON: Float = 1.0e-5;
+
+/// How the physics backend applies the [`TnuaMotor`] to the rigid body.
+///
+/// This component is not mandatory - if omitted, the backend uses
+/// [`TnuaMotorApplication::Direct`].
+#[derive(Component, Default, Debug, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaMotorApplication {
+    /// Add the boosts directly to the velocity of the rigid body, and apply the accelerations as
+    /// forces.
+    #[default]
+    Direct,
+    /// Convert the boosts into impulses (by multiplying them by the mass or the inertia), and
+    /// apply the accelerations as forces. The backend never writes the velocity of the rigid body.
+    ///
+    /// This is slower to respond when the character's body is constrained (e.g. attached to a
+    /// joint), but it lets the physics engine resolve the motor together with the constraints
+    /// instead of having the velocity overwritten behind its back.
+    Impulses,
+    /// For kinematic rigid bodies. The backend integrates the motor and the gravity into the
+    /// velocity itself, moves the character's collider along that velocity with
+    /// [`collide_and_slide`](crate::spatial_ext::collide_and_slide), and sets the velocity of the
+    /// kinematic body to the resulting movement.
+    ///
+    /// The rigid body must be a velocity-based kinematic body (e.g. `RigidBody::Kinematic` in
+    /// Avian, or `RigidBody::KinematicVelocityBased` in Rapier). Backends may offer other ways to
+    /// move the body - e.g. the Rapier backends can pass the movement to Rapier's
+    /// `KinematicCharacterController` instead.
+    Kinematic,
+}
+
+/// Limits on the [`TnuaMotor`] output the physics backend applies to the rigid body.
+///
+/// This component is not mandatory - if omitted, the backend applies the motor output as is.
+/// When present, the backend clamps the length of each part of the motor before applying it, so
+/// that bad inputs or extreme configuration cannot make the physics engine explode.
+///
+/// The limits are in the same units as [`TnuaVelChange`] - they ignore mass.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaMotorLimits {
+    pub max_lin_acceleration: Float,
+    /// The maximum linear velocity change applied as-is in a single frame.
+    pub max_lin_boost: Float,
+    /// The maximum angular acceleration - which determines the maximum torque.
+    pub max_ang_acceleration: Float,
+    /// The maximum angular velocity change applied as-is in a single frame.
+    pub max_ang_boost: Float,
+    /// Which parts of the motor were clamped in the last frame. Set by the physics backend.
+    pub clamped: TnuaMotorClamped,
+}
+
+impl Default for TnuaMotorLimits {
+    fn default() -> Self {
+        Self {
+            max_lin_acceleration: Float::INFINITY,
+            max_lin_boost: Float::INFINITY,
+            max_ang_acceleration: Float::INFINITY,
+            max_ang_boost: Float::INFINITY,
+            clamped: Default::default(),
+        }
+    }
+}
+
+impl TnuaMotorLimits {
+    /// Clamp the motor output, and update [`clamped`](Self::clamped) accordingly.
+    ///
+    /// Physics backends should call this and apply the returned motor instead of the original.
+    pub fn apply(&mut self, motor: &TnuaMotor) -> TnuaMotor {
+        fn clamp(vector: Vector3, max: Float, clamped: &mut bool) -> Vector3 {
+            *clamped = max.powi(2) < vector.length_squared();
+            if *clamped {
+                vector.clamp_length_max(max)
+            } else {
+                vector
+            }
+        }
+        let clamped = &mut self.clamped;
+        TnuaMotor {
+            lin: TnuaVelChange {
+                acceleration: clamp(
+                    motor.lin.acceleration,
+                    self.max_lin_acceleration,
+                    &mut clamped.lin_acceleration,
+                ),
+                boost: clamp(motor.lin.boost, self.max_lin_boost, &mut clamped.lin_boost),
+            },
+            ang: TnuaVelChange {
+                acceleration: clamp(
+                    motor.ang.acceleration,
+                    self.max_ang_acceleration,
+                    &mut clamped.ang_acceleration,
+                ),
+                boost: clamp(motor.ang.boost, self.max_ang_boost, &mut clamped.ang_boost),
+            },
+            ground_reaction: motor.ground_reaction.clone(),
+        }
+    }
+}
+
+/// Reports which parts of the [`TnuaMotor`] were clamped by [`TnuaMotorLimits`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaMotorClamped {
+    pub lin_acceleration: bool,
+    pub lin_boost: bool,
+    pub ang_acceleration: bool,
+    pub ang_boost: bool,
+}
+
+impl TnuaMotorClamped {
+    /// Whether any part of the motor was clamped.
+    pub fn any(&self) -> bool {
+        self.lin_acceleration || self.lin_boost || self.ang_acceleration || self.ang_boost
+    }
+}
+
+/// Locks the movement of the character to a plane, for 2.5D games that use a 3D physics backend
+/// for 3D visuals with 2D gameplay - e.g. side-scrollers.
+///
+/// Add this component to the entity of the `TnuaController`. The backend will then:
+///
+/// * Pass the [`TnuaMotor`] through [`TnuaPlaneLock::apply`], which removes its off-plane part
+///   and makes it cancel the off-plane velocity, the off-plane gravity and any drift away from the
+///   plane.
+/// * Cast the [`TnuaProximitySensor`] from the plane, and flatten the normal it detects into the
+///   plane - so that the geometry sloping along the locked axis does not affect the character.
+///
+/// Only the movement is locked - the character can still turn (e.g. to face left or right).
+///
+/// This component is only supported by the 3D backends. 2D backends are always locked to a plane.
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaPlaneLock {
+    /// The normal of the plane - the axis the character cannot move along.
+    pub normal: Dir3,
+    /// The position of the plane along its normal.
+    pub offset: Float,
+}
+
+impl TnuaPlaneLock {
+    /// Lock the movement to the plane with the given normal that passes through `point`.
+    pub fn new(normal: Dir3, point: Vector3) -> Self {
+        Self {
+            normal,
+            offset: normal.adjust_precision().dot(point),
+        }
+    }
+
+    /// Move a point along the normal until it is on the plane.
+    pub fn project_point(&self, point: Vector3) -> Vector3 {
+        let normal = self.normal.adjust_precision();
+        point - (normal.dot(point) - self.offset) * normal
+    }
+
+    /// Remove the off-plane part of a vector.
+    pub fn project_vector(&self, vector: Vector3) -> Vector3 {
+        vector.reject_from_normalized(self.normal.adjust_precision())
+    }
+
+    /// Remove the off-plane part of a direction, or return `None` if it is perpendicular to the
+    /// plane.
+    pub fn project_direction(&self, direction: Dir3) -> Option<Dir3> {
+        Dir3::new(direction.reject_from_normalized(*self.normal)).ok()
+    }
+
+    /// Make the motor keep the character on the plane.
+    ///
+    /// Physics backends should call this after [`TnuaMotorLimits::apply`], so that the motor
+    /// limits cannot prevent the correction, and apply the returned motor instead of the original.
+    pub fn apply(
+        &self,
+        motor: &TnuaMotor,
+        tracker: &TnuaRigidBodyTracker,
+        frame_duration: Float,
+    ) -> TnuaMotor {
+        let normal = self.normal.adjust_precision();
+        let mut correction = -normal.dot(tracker.velocity);
+        if 0.0 < frame_duration {
+            correction -= (normal.dot(tracker.translation) - self.offset) / frame_duration;
+        }
+        TnuaMotor {
+            lin: TnuaVelChange {
+                acceleration: self.project_vector(motor.lin.acceleration)
+                    - normal.dot(tracker.gravity) * normal,
+                boost: self.project_vector(motor.lin.boost) + correction * normal,
+            },
+            ang: motor.ang.clone(),
+            ground_reaction: motor.ground_reaction.clone(),
+        }
+    }
+}
+
+/// Lets the physics backend stop applying the [`TnuaMotor`] to an idle character, so that the
+/// physics engine can put its rigid body to sleep.
+///
+/// This component is not mandatory - if omitted, the backend writes the motor output to the rigid
+/// body every frame, which keeps it awake even when the character stands still. When present, the
+/// backend stops writing to the rigid body once the character was idle for
+/// [`time_to_sleep`](Self::time_to_sleep) seconds, leaving behind a force that holds the character
+/// against the gravity. Once the character is no longer idle the backend resumes writing to the
+/// rigid body, which wakes it up.
+///
+/// The character is considered idle when it stands on non-moving ground, it does not move itself,
+/// and the motor does nothing but cancel the gravity. This is what happens when a
+/// `TnuaBuiltinWalk` with zero `desired_velocity` floats at its `float_height`.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaSleepPolicy {
+    /// Linear velocities (of the character and of the ground) and linear velocity changes (from
+    /// the motor, in a single frame) shorter than this are considered idle.
+    pub linear_threshold: Float,
+    /// Angular velocities (of the character and of the ground) and angular velocity changes (from
+    /// the motor, in a single frame) smaller than this are considered idle.
+    pub angular_threshold: Float,
+    /// How long, in seconds, the character needs to be idle before the backend stops applying the
+    /// motor.
+    pub time_to_sleep: Float,
+    /// How long, in seconds, the character has been idle. Set by the physics backend.
+    pub idle_time: Float,
+}
+
+impl Default for TnuaSleepPolicy {
+    fn default() -> Self {
+        Self {
+            linear_threshold: 0.05,
+            angular_threshold: 0.05,
+            time_to_sleep: 0.5,
+            idle_time: 0.0,
+        }
+    }
+}
+
+impl TnuaSleepPolicy {
+    /// Update [`idle_time`](Self::idle_time), and decide which motor to apply.
+    ///
+    /// Physics backends should call this every frame before applying the motor, and apply the
+    /// returned motor instead of the original. When it returns `None` the backend should not
+    /// write anything to the rigid body.
+    ///
+    /// On the frame the character falls asleep, the returned motor stops the rigid body and
+    /// leaves behind a force that exactly cancels the gravity, so that the physics engine sees it
+    /// at rest and can put it to sleep.
+    pub fn apply(
+        &mut self,
+        motor: &TnuaMotor,
+        tracker: &TnuaRigidBodyTracker,
+        proximity_sensor: Option<&TnuaProximitySensor>,
+        frame_duration: Float,
+    ) -> Option<TnuaMotor> {
+        let was_asleep = self.is_asleep();
+        let on_still_ground = proximity_sensor
+            .and_then(|proximity_sensor| proximity_sensor.output.as_ref())
+            .is_some_and(|sensor_output| {
+                sensor_output.entity_linvel.length() < self.linear_threshold
+                    && sensor_output.entity_angvel.length() < self.angular_threshold
+            });
+        let idle = on_still_ground
+            && tracker.velocity.length() < self.linear_threshold
+            && tracker.angvel.length() < self.angular_threshold
+            && (motor.lin.boost + (motor.lin.acceleration + tracker.gravity) * frame_duration)
+                .length()
+                < self.linear_threshold
+            && motor.ang.calc_boost(frame_duration).length() < self.angular_threshold;
+        if idle {
+            self.idle_time += frame_duration;
+        } else {
+            self.idle_time = 0.0;
+        }
+        if !self.is_asleep() {
+            Some(motor.clone())
+        } else if was_asleep {
+            None
+        } else {
+            Some(TnuaMotor {
+                lin: TnuaVelChange {
+                    acceleration: -tracker.gravity,
+                    boost: -tracker.velocity,
+                },
+                ang: TnuaVelChange::boost(-tracker.angvel),
+                ground_reaction: motor.ground_reaction.clone(),
+            })
+        }
+    }
+
+    /// Whether the character was idle long enough for the backend to stop applying the motor.
+    pub fn is_asleep(&self) -> bool {
+        self.time_to_sleep <= self.idle_time
+    }
 }
 
 /// An addon for [`TnuaProximitySensor`] that allows it to detect [`TnuaGhostPlatform`] colliders.
@@ -219,7 +1048,9 @@ pub struct TnuaMotor {
 /// See <https://github.com/idanarye/bevy-tnua/wiki/Jump-fall-Through-Platforms>
 ///
 /// See `TnuaSimpleFallThroughPlatformsHelper`.
-#[derive(Component, Default, Debug)]
+#[derive(Component, Default, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaGhostSensor(pub Vec<TnuaProximitySensorOutput>);
 
 impl TnuaGhostSensor {
@@ -237,5 +1068,42 @@ impl TnuaGhostSensor {
 /// See <https://github.com/idanarye/bevy-tnua/wiki/Jump-fall-Through-Platforms>
 ///
 /// See `TnuaSimpleFallThroughPlatformsHelper`.
-#[derive(Component, Default, Debug)]
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaGhostPlatform;
+
+/// A volume that changes the gravity applied to characters inside it.
+///
+/// Place this component on a collider entity (usually a sensor collider) to define a region with
+/// different gravity. During [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors),
+/// the physics backend checks if the [cast origin](TnuaProximitySensor::cast_origin) of the
+/// character's proximity sensor is inside such a region, and if so it writes the gravity of the
+/// region into [`TnuaRigidBodyTracker::gravity`] instead of the physics engine's global gravity.
+///
+/// Note that this only affects Tnua's calculations (including the up direction, which is derived
+/// from the gravity). Making the physics engine apply the same gravity to the rigid body (e.g. by
+/// using a gravity scale of zero and applying the gravity as a force) is the responsibility of
+/// the game code.
+///
+/// If the character is inside multiple overlapping gravity fields, which one gets used is
+/// unspecified.
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaGravityField {
+    /// Use this gravity vector instead of the global gravity.
+    Absolute(Vector3),
+    /// Multiply the global gravity by this factor.
+    Scaled(Float),
+}
+
+impl TnuaGravityField {
+    /// Calculate the gravity inside the field, given the global gravity.
+    pub fn apply_to(&self, global_gravity: Vector3) -> Vector3 {
+        match self {
+            TnuaGravityField::Absolute(gravity) => *gravity,
+            TnuaGravityField::Scaled(scale) => *scale * global_gravity,
+        }
+    }
+}
@@ -12,7 +12,10 @@
 //! * Add systems, to the [`TnuaPipelineStages::Sensors`] stage, that update:
 //!   * [`TnuaRigidBodyTracker`](data_for_backends::TnuaRigidBodyTracker) with the objects current
 //!     kinematic status (position, rotation, velocity, angular velocity) as well as the gravity
-//!     currently applied to it.
+//!     currently applied to it. If the cast origin of the entity's
+//!     [`TnuaProximitySensor`](data_for_backends::TnuaProximitySensor) is inside a collider with
+//!     a [`TnuaGravityField`](data_for_backends::TnuaGravityField), the gravity should be taken
+//!     from that field instead.
 //!   * [`TnuaProximitySensor`](data_for_backends::TnuaProximitySensor) with the _first_ tangible
 //!     collider within range, and [`TnuaGhostSensor`](data_for_backends::TnuaGhostSensor) with
 //!     _all_ the ghost colliders found before that tangible collider.
@@ -23,6 +26,23 @@
 //!       physically interact with the character's collider - as long as it has the component it is
 //!       considered a ghost collider.
 //!     * The sensor should ignore the owner entity's collider.
+//!     * The [`ground_class`](data_for_backends::TnuaProximitySensorOutput::ground_class) of the
+//!       output should be set to `Walkable`. Tnua classifies the surface after the sensors stage.
+//!     * If the sensor has the
+//!       [`TnuaSensorQueryFilter`](data_for_backends::TnuaSensorQueryFilter) component, the sensor
+//!       should ignore the colliders that do not pass
+//!       [`TnuaSensorQueryFilter::test`](data_for_backends::TnuaSensorQueryFilter::test).
+//!     * If the sensor casts a shape and has the
+//!       [`TnuaSensorSolidityProbe`](data_for_backends::TnuaSensorSolidityProbe) component, the
+//!       normal of the hit should be verified with a ray as described in the component's
+//!       documentation.
+//!     * If the sensor has the [`TnuaSensorHitCache`](data_for_backends::TnuaSensorHitCache)
+//!       component and no `TnuaGhostSensor`, the sensor should first cast only against the entity
+//!       returned by
+//!       [`entity_to_retest`](data_for_backends::TnuaSensorHitCache::entity_to_retest), and only
+//!       do a full cast if that misses.
+//!     * If the [`TnuaSensorCastCounter`](data_for_backends::TnuaSensorCastCounter) resource
+//!       exists, every cast should be recorded in it.
 //!     * If the sensor has the
 //!       [`TnuaSubservientSensor`](subservient_sensors::TnuaSubservientSensor) component, the
 //!       "owner entity" is defined as the `owner_entity` field from that component and not the
@@ -33,17 +53,42 @@
 //!       physics engine. The name of that component should be
 //!       `Tnua<physics-engine-name>SensorShape`.
 //!
+//!   * [`TnuaObstacleRadar`](obstacle_radar::TnuaObstacleRadar): call
+//!     [`pre_marking_update`](obstacle_radar::TnuaObstacleRadar::pre_marking_update) with the
+//!     position of the entity and its up direction (the opposite of the gravity in its
+//!     [`TnuaRigidBodyTracker`](data_for_backends::TnuaRigidBodyTracker)), and then
+//!     [`mark_seen`](obstacle_radar::TnuaObstacleRadar::mark_seen) for every collider - other than
+//!     the entity's own - that intersects with the radar's shape at
+//!     [`probe_position`](obstacle_radar::TnuaObstacleRadar::probe_position) and
+//!     [`probe_rotation`](obstacle_radar::TnuaObstacleRadar::probe_rotation) and passes
+//!     [`accepts_memberships`](obstacle_radar::TnuaObstacleRadar::accepts_memberships).
+//!
 //!   The integration crate may update all these components in one system or multiple systems as it
-//!   sees fit.
+//!   sees fit. It should iterate over the sensors with [`for_each_sensor`], so that the
+//!   `deterministic` feature can make the iteration order stable.
 //!
 //! * Add a system, to the [`TnuaPipelineStages::Motors`] stage, that applies all the impulses and
-//!   accelerations from [`TnuaMotor`](data_for_backends::TnuaMotor) components.
+//!   accelerations from [`TnuaMotor`](data_for_backends::TnuaMotor) components. If the entity
+//!   has a [`TnuaMotorLimits`](data_for_backends::TnuaMotorLimits) component, the motor should be
+//!   passed through [`TnuaMotorLimits::apply`](data_for_backends::TnuaMotorLimits::apply) first.
+//!   3D backends should then pass it through
+//!   [`TnuaPlaneLock::apply`](data_for_backends::TnuaPlaneLock::apply) if the entity has a
+//!   [`TnuaPlaneLock`](data_for_backends::TnuaPlaneLock) component.
+//!   If the entity has a [`TnuaMotorApplication`](data_for_backends::TnuaMotorApplication)
+//!   component, the backend should apply the motor the way it specifies.
 //!
 //!   Here, too, if it makes sense to split this work into multiple systems the integration crate
 //!   may do so at its own discretion.
 //!
+//! * In all these systems, respect the [`TnuaToggle`](data_for_backends::TnuaToggle) of the
+//!   entity as resolved by [`TnuaToggle::resolve`](data_for_backends::TnuaToggle::resolve), which
+//!   takes the [`TnuaPaused`](data_for_backends::TnuaPaused) resource into account.
+//!
 //! * Ensure that [`TnuaSystemSet`] runs before the integration backend's systems.
 //!
+//! * Define a `SystemParam` named `TnuaSpatialExt<physics-engine-name>` that implements
+//!   [`TnuaSpatialExt`](spatial_ext::TnuaSpatialExt) using the physics engine's spatial queries.
+//!
 //! The integration backend's systems must run with the same timing as the physics backend. If the
 //! physics backend supports running in a different schedule, the integration plugin should also
 //! support it by adding a `::new()` method that accepts a schedule and registers all the systems
@@ -60,10 +105,16 @@
 //! body and a collider, so they should not go in that bundle, but if the crate needs things users
 //! rarely think about - for example, bevy_rapier's `ReadMassProperties` - then these components
 //! should go in that bundle.
+use bevy::ecs::query::{QueryData, QueryFilter, QueryItem};
 use bevy::prelude::*;
 
+#[cfg(feature = "conformance")]
+pub mod conformance;
 pub mod data_for_backends;
 pub mod math;
+pub mod obstacle_radar;
+pub mod schedules;
+pub mod spatial_ext;
 pub mod subservient_sensors;
 
 /// Umbrella system set for [`TnuaPipelineStages`].
@@ -85,3 +136,18 @@ pub enum TnuaPipelineStages {
     /// Forces are applied in the physics backend.
     Motors,
 }
+
+/// Run `func` on every item of a query of sensors, the way backends should update them.
+///
+/// Normally the items are processed in parallel. With the `deterministic` feature they are
+/// processed sequentially, in the stable order of the query. This is meant for lockstep networking
+/// (e.g. GGRS), where every peer must run the simulation exactly the same way.
+pub fn for_each_sensor<'a, D: QueryData, F: QueryFilter>(
+    query: &'a mut Query<'_, '_, D, F>,
+    func: impl Fn(QueryItem<'a, D>) + Send + Sync + Clone,
+) {
+    #[cfg(feature = "deterministic")]
+    query.iter_mut().for_each(func);
+    #[cfg(not(feature = "deterministic"))]
+    query.par_iter_mut().for_each(func);
+}
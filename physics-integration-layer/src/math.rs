@@ -8,6 +8,47 @@ pub use std::f32::consts as float_consts;
 #[cfg(feature = "f64")]
 pub use std::f64::consts as float_consts;
 
+/// Transcendental functions of [`Float`].
+///
+/// The standard library may implement these with the platform's math library, whose results can
+/// differ in the last bits between platforms. With the `deterministic` feature they are
+/// implemented with [`libm`](https://docs.rs/libm) instead, so that they give the same results
+/// everywhere. The feature also makes glam - and therefore Bevy's vector and rotation math - use
+/// `libm`.
+pub mod ops {
+    use super::Float;
+
+    macro_rules! float_ops {
+        ($($(#[$attr:meta])* fn $name:ident($($arg:ident),*) => $f32_fn:ident, $f64_fn:ident;)*) => {
+            $(
+                $(#[$attr])*
+                #[inline]
+                pub fn $name($($arg: Float),*) -> Float {
+                    #[cfg(all(feature = "deterministic", not(feature = "f64")))]
+                    return libm::$f32_fn($($arg),*);
+                    #[cfg(all(feature = "deterministic", feature = "f64"))]
+                    return libm::$f64_fn($($arg),*);
+                    #[cfg(not(feature = "deterministic"))]
+                    return Float::$name($($arg),*);
+                }
+            )*
+        };
+    }
+
+    float_ops! {
+        /// `e^x`.
+        fn exp(x) => expf, exp;
+        /// The natural logarithm of `x`.
+        fn ln(x) => logf, log;
+        /// `x^y`.
+        fn powf(x, y) => powf, pow;
+        /// The cosine of `x` (in radians).
+        fn cos(x) => cosf, cos;
+        /// The four quadrant arctangent of `y` and `x`, in radians.
+        fn atan2(y, x) => atan2f, atan2;
+    }
+}
+
 use bevy::math::{DQuat, DVec2, DVec3};
 use bevy::math::{Quat, Vec2, Vec3};
 
@@ -175,3 +216,10 @@ impl AsF32 for DQuat {
         self.as_quat()
     }
 }
+
+impl AsF32 for Quat {
+    type F32 = Self;
+    fn f32(&self) -> Self::F32 {
+        *self
+    }
+}
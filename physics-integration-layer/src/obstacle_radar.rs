@@ -0,0 +1,268 @@
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+
+use crate::math::{AdjustPrecision, Float, Quaternion, Vector3};
+
+/// The volume a [`TnuaObstacleRadar`] probes for obstacles.
+///
+/// The volume is aligned to the up direction of the character (the opposite of its gravity), so
+/// the Y axis of the volume is the up direction.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaObstacleRadarShape {
+    /// A cylinder around the up direction. In 2D this is a rectangle.
+    Cylinder { radius: Float, height: Float },
+    /// A box with the given half extents. In 2D the Z extent is ignored.
+    Box { half_extents: Vector3 },
+    /// A sphere. In 2D this is a circle.
+    Sphere { radius: Float },
+}
+
+impl TnuaObstacleRadarShape {
+    /// Half the extent of the volume along the up direction.
+    pub fn half_height(&self) -> Float {
+        match self {
+            Self::Cylinder { height, .. } => 0.5 * height,
+            Self::Box { half_extents } => half_extents.y,
+            Self::Sphere { radius } => *radius,
+        }
+    }
+}
+
+/// Geometry of a blip of a [`TnuaObstacleRadar`], collected when
+/// [`collect_blip_info`](TnuaObstacleRadar::collect_blip_info) is set.
+#[derive(Debug, Clone)]
+pub struct TnuaObstacleRadarBlipInfo {
+    /// The point on the blip's collider that is closest to the center of the probed volume.
+    pub closest_point: Vector3,
+
+    /// The normal of the blip's surface at the closest point, pointing toward the center of the
+    /// probed volume.
+    pub normal: Dir3,
+
+    /// The height of the blip's top edge above the character's position, along the up direction.
+    ///
+    /// `None` if the top edge is not inside the probed volume.
+    pub top_edge_height: Option<Float>,
+
+    /// Whether the blip continues above the top of the probed volume - e.g. a wall that is too
+    /// high to climb over.
+    pub extends_above: bool,
+}
+
+/// The bookkeeping of a single entity tracked by a [`TnuaObstacleRadar`].
+#[derive(Debug, Clone)]
+struct TnuaObstacleRadarBlip {
+    seen: bool,
+    previously_seen: bool,
+    info: Option<TnuaObstacleRadarBlipInfo>,
+}
+
+/// Detects the colliders around the character.
+///
+/// Add this component to the character entity. Every frame, during
+/// [`TnuaPipelineStages::Sensors`](crate::TnuaPipelineStages::Sensors), the physics backend
+/// finds all the colliders that intersect with the [`shape`](Self::shape) placed at the
+/// character's position (raised by the [`vertical_offset`](Self::vertical_offset)) and marks
+/// them as "blips" of the radar. Actions and gameplay code can then iterate over the blips
+/// without running their own spatial queries.
+///
+/// The character's own collider is never a blip.
+///
+/// Set [`collect_blip_info`](Self::collect_blip_info) to also have the backend calculate a
+/// [`TnuaObstacleRadarBlipInfo`] for every blip.
+///
+/// The radar keeps its blips in storage that is reused between frames, so once it grows to the
+/// number of colliders the radar usually detects, updating it does not allocate. Use
+/// [`with_capacity`](Self::with_capacity) to start it at that size.
+#[derive(Component, Debug, Clone)]
+pub struct TnuaObstacleRadar {
+    /// The volume to probe.
+    pub shape: TnuaObstacleRadarShape,
+    /// The distance, along the up direction, from the character's position to the center of the
+    /// probed volume. Use it to make a radar that only sees obstacles at torso or head height.
+    pub vertical_offset: Float,
+    /// If set, only colliders whose collision layer memberships (or collision group memberships,
+    /// depending on the physics engine) intersect with this mask are detected.
+    pub layer_mask: Option<u32>,
+    /// Whether the physics backend should calculate a [`TnuaObstacleRadarBlipInfo`] for every
+    /// blip. This costs a few more spatial queries per blip.
+    pub collect_blip_info: bool,
+    tracked_position: Vector3,
+    probe_position: Vector3,
+    up_direction: Dir3,
+    blips: EntityHashMap<TnuaObstacleRadarBlip>,
+    blips_to_update: Vec<Entity>,
+}
+
+impl TnuaObstacleRadar {
+    pub fn new(shape: TnuaObstacleRadarShape) -> Self {
+        Self {
+            shape,
+            vertical_offset: 0.0,
+            layer_mask: None,
+            collect_blip_info: false,
+            tracked_position: Vector3::ZERO,
+            probe_position: Vector3::ZERO,
+            up_direction: Dir3::Y,
+            blips: Default::default(),
+            blips_to_update: Default::default(),
+        }
+    }
+
+    /// Reserve room for tracking this many colliders, so that the radar does not need to grow its
+    /// storage while detecting them.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.blips.reserve(capacity);
+        self.blips_to_update.reserve(capacity);
+        self
+    }
+
+    pub fn with_vertical_offset(mut self, vertical_offset: Float) -> Self {
+        self.vertical_offset = vertical_offset;
+        self
+    }
+
+    pub fn with_layer_mask(mut self, layer_mask: u32) -> Self {
+        self.layer_mask = Some(layer_mask);
+        self
+    }
+
+    pub fn with_blip_info(mut self) -> Self {
+        self.collect_blip_info = true;
+        self
+    }
+
+    /// Called by the physics backend before it marks the blips of the current frame.
+    pub fn pre_marking_update(&mut self, tracked_position: Vector3, up_direction: Dir3) {
+        self.tracked_position = tracked_position;
+        self.probe_position =
+            tracked_position + self.vertical_offset * up_direction.adjust_precision();
+        self.up_direction = up_direction;
+        // Blips that were not seen in the last frame already had their chance to be reported as
+        // disappeared.
+        self.blips.retain(|_, blip| blip.seen);
+        for blip in self.blips.values_mut() {
+            blip.previously_seen = true;
+            blip.seen = false;
+            blip.info = None;
+        }
+    }
+
+    /// Called by the physics backend for every collider that intersects with the probed volume.
+    pub fn mark_seen(&mut self, entity: Entity) {
+        self.blips
+            .entry(entity)
+            .or_insert(TnuaObstacleRadarBlip {
+                seen: false,
+                previously_seen: false,
+                info: None,
+            })
+            .seen = true;
+    }
+
+    /// Called by the physics backend, after marking the blips, when
+    /// [`collect_blip_info`](Self::collect_blip_info) is set.
+    pub fn set_blip_info(&mut self, entity: Entity, info: TnuaObstacleRadarBlipInfo) {
+        if let Some(blip) = self.blips.get_mut(&entity) {
+            if blip.seen {
+                blip.info = Some(info);
+            }
+        }
+    }
+
+    /// Called by the physics backend, after marking the blips, when
+    /// [`collect_blip_info`](Self::collect_blip_info) is set.
+    ///
+    /// Calls `calc_info` for every blip, and sets the info it returns with
+    /// [`set_blip_info`](Self::set_blip_info). Unlike collecting the blips and then setting their
+    /// info, this does not allocate.
+    pub fn update_blip_infos(
+        &mut self,
+        mut calc_info: impl FnMut(&Self, Entity) -> Option<TnuaObstacleRadarBlipInfo>,
+    ) {
+        let mut blips_to_update = std::mem::take(&mut self.blips_to_update);
+        blips_to_update.clear();
+        blips_to_update.extend(self.iter_blips());
+        for &entity in blips_to_update.iter() {
+            if let Some(info) = calc_info(self, entity) {
+                self.set_blip_info(entity, info);
+            }
+        }
+        self.blips_to_update = blips_to_update;
+    }
+
+    /// Whether a collider with these collision layer (or collision group) memberships should be
+    /// detected, according to the [`layer_mask`](Self::layer_mask).
+    pub fn accepts_memberships(&self, memberships: u32) -> bool {
+        match self.layer_mask {
+            Some(layer_mask) => layer_mask & memberships != 0,
+            None => true,
+        }
+    }
+
+    /// The position of the character in the last frame.
+    pub fn tracked_position(&self) -> Vector3 {
+        self.tracked_position
+    }
+
+    /// The center of the probed volume in the last frame.
+    pub fn probe_position(&self) -> Vector3 {
+        self.probe_position
+    }
+
+    /// The rotation that aligns the Y axis of the probed volume with the up direction.
+    pub fn probe_rotation(&self) -> Quaternion {
+        Quaternion::from_rotation_arc(Vector3::Y, self.up_direction.adjust_precision())
+    }
+
+    /// The up direction of the character in the last frame.
+    pub fn up_direction(&self) -> Dir3 {
+        self.up_direction
+    }
+
+    /// The entities of all the colliders the radar detected in the last frame.
+    pub fn iter_blips(&self) -> impl '_ + Iterator<Item = Entity> {
+        self.blips
+            .iter()
+            .filter(|(_, blip)| blip.seen)
+            .map(|(entity, _)| *entity)
+    }
+
+    /// Like [`iter_blips`](Self::iter_blips), but with the geometry of each blip (if it was
+    /// collected).
+    pub fn iter_blips_with_info(
+        &self,
+    ) -> impl '_ + Iterator<Item = (Entity, Option<&TnuaObstacleRadarBlipInfo>)> {
+        self.blips
+            .iter()
+            .filter(|(_, blip)| blip.seen)
+            .map(|(entity, blip)| (*entity, blip.info.as_ref()))
+    }
+
+    /// The geometry of a blip the radar detected in the last frame, if it was collected.
+    pub fn blip_info(&self, entity: Entity) -> Option<&TnuaObstacleRadarBlipInfo> {
+        self.blips.get(&entity)?.info.as_ref()
+    }
+
+    /// The blips the radar detected in the last frame but not in the frame before it.
+    pub fn iter_appeared_blips(&self) -> impl '_ + Iterator<Item = Entity> {
+        self.blips
+            .iter()
+            .filter(|(_, blip)| blip.seen && !blip.previously_seen)
+            .map(|(entity, _)| *entity)
+    }
+
+    /// The blips the radar detected in the frame before the last one, but no longer detects.
+    pub fn iter_disappeared_blips(&self) -> impl '_ + Iterator<Item = Entity> {
+        self.blips
+            .iter()
+            .filter(|(_, blip)| !blip.seen && blip.previously_seen)
+            .map(|(entity, _)| *entity)
+    }
+
+    /// Whether the radar detected the entity in the last frame.
+    pub fn has_blip(&self, entity: Entity) -> bool {
+        self.blips.get(&entity).is_some_and(|blip| blip.seen)
+    }
+}
@@ -0,0 +1,113 @@
+//! Support for running Tnua in more than one schedule at once.
+//!
+//! The Tnua plugins (the controller plugin and the physics backend plugins) can be added more than
+//! once, each time with a different schedule - e.g. once with `FixedUpdate` for the player (for
+//! determinism) and once with `Update` for cosmetic NPCs. Each character is then driven by only
+//! one of these schedules, chosen with the [`TnuaSchedule`] component.
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::{TnuaPipelineStages, TnuaSystemSet};
+
+/// Chooses the schedule that drives a character, when the Tnua plugins are added in more than one
+/// schedule.
+///
+/// Characters without this component are driven by the schedule of the first Tnua plugin that was
+/// added to the app. A character whose schedule has no Tnua plugins will not be updated at all.
+///
+/// Put this component on the entity that has the `TnuaController` - subservient sensors and
+/// obstacle radars follow the schedule of their owner entity.
+#[derive(Component, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TnuaSchedule(pub InternedScheduleLabel);
+
+impl TnuaSchedule {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self(schedule.intern())
+    }
+}
+
+/// The schedules the Tnua plugins were added to, and the one that is currently running.
+///
+/// Plugins should call [`TnuaSchedules::register`] for the schedule they add their systems to,
+/// and systems should use [`TnuaScheduleFilter`] to skip the characters that are driven by
+/// another schedule.
+#[derive(Resource, Debug)]
+pub struct TnuaSchedules {
+    schedules: Vec<InternedScheduleLabel>,
+    current: Option<InternedScheduleLabel>,
+}
+
+impl TnuaSchedules {
+    /// Register a schedule that Tnua systems run in.
+    ///
+    /// This can be called for the same schedule multiple times (e.g. by both the controller
+    /// plugin and the physics backend plugin) - only the first call for each schedule has an
+    /// effect.
+    pub fn register(app: &mut App, schedule: InternedScheduleLabel) {
+        let mut schedules = app
+            .world_mut()
+            .get_resource_or_insert_with(|| TnuaSchedules {
+                schedules: Vec::new(),
+                current: None,
+            });
+        if schedules.schedules.contains(&schedule) {
+            return;
+        }
+        schedules.schedules.push(schedule);
+        app.add_systems(
+            schedule,
+            (move |mut schedules: ResMut<TnuaSchedules>| {
+                schedules.current = Some(schedule);
+            })
+            .in_set(TnuaSystemSet)
+            .before(TnuaPipelineStages::Sensors),
+        );
+    }
+
+    /// The schedules Tnua runs in. The first one drives the characters without a
+    /// [`TnuaSchedule`].
+    pub fn schedules(&self) -> &[InternedScheduleLabel] {
+        &self.schedules
+    }
+
+    /// The schedule Tnua is currently running in, or the last one it ran in.
+    pub fn current(&self) -> Option<InternedScheduleLabel> {
+        self.current
+    }
+
+    /// Check if a character with the given [`TnuaSchedule`] should be updated by the schedule
+    /// that is currently running.
+    pub fn drives(&self, schedule: Option<&TnuaSchedule>) -> bool {
+        if self.schedules.len() <= 1 {
+            return true;
+        }
+        let schedule = schedule
+            .map(|schedule| schedule.0)
+            .unwrap_or(self.schedules[0]);
+        self.current == Some(schedule)
+    }
+}
+
+/// A system param for skipping the characters that are driven by another schedule.
+#[derive(SystemParam)]
+pub struct TnuaScheduleFilter<'w, 's> {
+    schedules: Res<'w, TnuaSchedules>,
+    schedules_query: Query<'w, 's, &'static TnuaSchedule>,
+}
+
+impl TnuaScheduleFilter<'_, '_> {
+    /// Check if the character with the `TnuaController` on `entity` should be updated by the
+    /// schedule that is currently running.
+    pub fn drives(&self, entity: Entity) -> bool {
+        if self.schedules.schedules.len() <= 1 {
+            return true;
+        }
+        self.schedules.drives(self.schedules_query.get(entity).ok())
+    }
+
+    /// The schedule that is currently running. See [`TnuaSchedules::current`].
+    pub fn current_schedule(&self) -> Option<InternedScheduleLabel> {
+        self.schedules.current()
+    }
+}
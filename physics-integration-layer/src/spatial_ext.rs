@@ -0,0 +1,379 @@
+use bevy::prelude::*;
+
+use crate::math::{AdjustPrecision, AsF32, Float, Vector3};
+use crate::obstacle_radar::{TnuaObstacleRadar, TnuaObstacleRadarBlipInfo};
+
+/// Spatial queries that physics backends provide for Tnua and for the game code.
+///
+/// Physics backends implement this on a `SystemParam` named
+/// `TnuaSpatialExt<physics-engine-name>`, so that systems can take it as a parameter.
+pub trait TnuaSpatialExt {
+    /// Predict where a character will land if it moves along a ballistic arc.
+    ///
+    /// The character's collider is swept along the arc that starts at its current position with
+    /// the given `velocity` and `gravity`, for up to `max_time` seconds. Colliders that the
+    /// character cannot collide with (sensors, or colliders excluded by the collision layers or
+    /// groups) are ignored.
+    ///
+    /// Returns `None` if the character (or its collider) could not be found, or if it does not
+    /// hit anything within `max_time`.
+    fn predict_landing(
+        &self,
+        character: Entity,
+        velocity: Vector3,
+        gravity: Vector3,
+        max_time: Float,
+    ) -> Option<TnuaPredictedLanding>;
+
+    /// Sweep the character's collider from `origin` along `displacement`, and find the first
+    /// collider it hits.
+    ///
+    /// The collider keeps the rotation the character currently has. Colliders that the character
+    /// cannot collide with (sensors, ghost platforms, or colliders excluded by the collision
+    /// layers or groups) are ignored, and so are colliders the character's collider already
+    /// penetrates at `origin`.
+    ///
+    /// Returns `None` if the character (or its collider) could not be found, or if it does not
+    /// hit anything.
+    fn sweep_character(
+        &self,
+        character: Entity,
+        origin: Vector3,
+        displacement: Vector3,
+    ) -> Option<TnuaSweepHit>;
+
+    /// Cast a shape from a point relative to the character, and find the first collider it hits.
+    ///
+    /// `origin` is in the character's local space (like
+    /// [`TnuaProximitySensor::cast_origin`](crate::data_for_backends::TnuaProximitySensor::cast_origin)),
+    /// and the shape is rotated with the character. `direction` is in world space. Colliders the
+    /// character cannot collide with (sensors, ghost platforms, or colliders excluded by the
+    /// collision layers or groups) are ignored.
+    ///
+    /// Use this for casts that custom actions need, so that they do not have to depend on a
+    /// specific physics backend.
+    ///
+    /// Returns `None` if the character (or its collider) could not be found, or if the shape does
+    /// not hit anything within `max_distance`.
+    fn cast_shape(
+        &self,
+        character: Entity,
+        shape: &TnuaCastShape,
+        origin: Vector3,
+        direction: Dir3,
+        max_distance: Float,
+    ) -> Option<TnuaShapeCastHit>;
+
+    /// Cast a ray, in world space, with the collision filter of the character, and find the first
+    /// collider it hits.
+    ///
+    /// The character's own colliders (the rigid body set by
+    /// [`TnuaBodyTargets`](crate::data_for_backends::TnuaBodyTargets) and the colliders listed in
+    /// [`TnuaCharacterColliders`](crate::data_for_backends::TnuaCharacterColliders)) are ignored,
+    /// and so are colliders the character cannot collide with (sensors, ghost platforms, or
+    /// colliders excluded by the collision layers or groups). The character itself does not need
+    /// a collider. In 2D, the Z axis is ignored.
+    ///
+    /// Returns `None` if the character could not be found, or if the ray does not hit anything
+    /// within `max_distance`.
+    fn cast_ray(
+        &self,
+        character: Entity,
+        origin: Vector3,
+        direction: Dir3,
+        max_distance: Float,
+    ) -> Option<TnuaRayCastHit>;
+
+    /// Check if nothing blocks the line between `origin` and `target_point`, from the point of
+    /// view of the character.
+    ///
+    /// The line is checked with [`cast_ray`](Self::cast_ray), so it is only blocked by colliders
+    /// the character can collide with. Hitting a collider of the `target` entity itself does not
+    /// block the line, so `target_point` can be the center of the target (and `origin` can be
+    /// the character's eyes - e.g. the position of bevy-tnua's `TnuaEyeAnchor`).
+    fn has_line_of_sight(
+        &self,
+        character: Entity,
+        origin: Vector3,
+        target: Entity,
+        target_point: Vector3,
+    ) -> bool {
+        let Ok((direction, distance)) = Dir3::new_and_length((target_point - origin).f32()) else {
+            return true;
+        };
+        self.cast_ray(character, origin, direction, distance.adjust_precision())
+            .is_none_or(|hit| hit.entity == target)
+    }
+
+    /// Check if the character could stand at `point`.
+    ///
+    /// A ray is cast down along `up`, from `probe_distance` above `point` to `probe_distance`
+    /// below it, and the ground it hits is standable if the angle between its normal and `up` is
+    /// no more than `max_slope` (usually the `max_slope` of `TnuaBuiltinWalk`). This does not check
+    /// that the character's collider fits there - use
+    /// [`sweep_character`](Self::sweep_character) or [`cast_shape`](Self::cast_shape) for that.
+    ///
+    /// Returns the ground hit if the point is standable, and `None` if it is not (or if the
+    /// character could not be found).
+    fn standable_ground_at(
+        &self,
+        character: Entity,
+        point: Vector3,
+        up: Dir3,
+        max_slope: Float,
+        probe_distance: Float,
+    ) -> Option<TnuaRayCastHit> {
+        let up_vector = up.adjust_precision();
+        let hit = self.cast_ray(
+            character,
+            point + probe_distance * up_vector,
+            -up,
+            2.0 * probe_distance,
+        )?;
+        (hit.normal.adjust_precision().angle_between(up_vector) <= max_slope).then_some(hit)
+    }
+
+    /// Find the point on the surface of the collider of `entity` that is closest to `point`.
+    ///
+    /// Returns `None` if the entity has no collider.
+    fn project_point_on(&self, entity: Entity, point: Vector3) -> Option<TnuaPointProjection>;
+
+    /// Cast a ray against the collider of `entity`, ignoring all other colliders.
+    ///
+    /// A ray that starts inside the collider hits it at distance zero.
+    ///
+    /// Returns `None` if the entity has no collider, or if the ray does not hit it within
+    /// `max_distance`.
+    fn cast_ray_on(
+        &self,
+        entity: Entity,
+        origin: Vector3,
+        direction: Dir3,
+        max_distance: Float,
+    ) -> Option<TnuaRayHit>;
+
+    /// Calculate the geometry of a blip the [`TnuaObstacleRadar`] detected.
+    ///
+    /// Physics backends call this for every blip when
+    /// [`collect_blip_info`](TnuaObstacleRadar::collect_blip_info) is set, so usually there is
+    /// no need to call it directly.
+    fn obstacle_radar_blip_info(
+        &self,
+        radar: &TnuaObstacleRadar,
+        entity: Entity,
+    ) -> Option<TnuaObstacleRadarBlipInfo> {
+        let up = radar.up_direction().adjust_precision();
+        let probe_position = radar.probe_position();
+        let projection = self.project_point_on(entity, probe_position)?;
+        let toward_probe = if projection.is_inside {
+            projection.point - probe_position
+        } else {
+            probe_position - projection.point
+        };
+        let normal = Dir3::new(toward_probe.f32()).unwrap_or(radar.up_direction());
+
+        // Look for the top edge by casting down, from the top of the probed volume, slightly
+        // inside the blip's surface.
+        let horizontal_normal = normal
+            .adjust_precision()
+            .reject_from_normalized(up)
+            .normalize_or_zero();
+        let top = probe_position + up * radar.shape.half_height();
+        let ray_origin = projection.point + up * up.dot(top - projection.point)
+            - horizontal_normal * OBSTACLE_RADAR_EDGE_PROBE_INSET;
+        let edge_hit = self.cast_ray_on(
+            entity,
+            ray_origin,
+            -radar.up_direction(),
+            2.0 * radar.shape.half_height(),
+        );
+        let extends_above = edge_hit
+            .as_ref()
+            .is_some_and(|hit| hit.distance <= Float::EPSILON);
+        let top_edge_height = edge_hit.filter(|_| !extends_above).map(|hit| {
+            let edge_point = ray_origin - up * hit.distance;
+            up.dot(edge_point - radar.tracked_position())
+        });
+
+        Some(TnuaObstacleRadarBlipInfo {
+            closest_point: projection.point,
+            normal,
+            top_edge_height,
+            extends_above,
+        })
+    }
+}
+
+/// A shape for [`TnuaSpatialExt::cast_shape`].
+///
+/// In 2D, the Z axis is ignored - spheres are circles and boxes are rectangles.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TnuaCastShape {
+    /// The character's own collider.
+    CharacterCollider,
+    /// A sphere.
+    Sphere { radius: Float },
+    /// A box with the given half extents.
+    Box { half_extents: Vector3 },
+    /// A capsule along the Y axis. `half_height` does not include the caps.
+    Capsule { radius: Float, half_height: Float },
+}
+
+/// The result of [`TnuaSpatialExt::cast_shape`].
+#[derive(Debug, Clone)]
+pub struct TnuaShapeCastHit {
+    /// The entity of the collider that was hit.
+    pub entity: Entity,
+
+    /// How far the shape moved along the direction before it hit.
+    pub distance: Float,
+
+    /// The position of the center of the shape when it hit, in world space.
+    pub position: Vector3,
+
+    /// The point where the shape touched the collider it hit, in world space.
+    pub point: Vector3,
+
+    /// The normal of the surface that was hit, pointing away from it.
+    pub normal: Dir3,
+}
+
+/// The result of [`TnuaSpatialExt::cast_ray`].
+#[derive(Debug, Clone)]
+pub struct TnuaRayCastHit {
+    /// The entity of the collider that was hit.
+    pub entity: Entity,
+
+    /// The distance from the origin of the ray to the hit point.
+    pub distance: Float,
+
+    /// The point where the ray hit the collider, in world space.
+    pub point: Vector3,
+
+    /// The normal of the surface that was hit, pointing away from it.
+    pub normal: Dir3,
+}
+
+/// How deep inside a blip's surface [`TnuaSpatialExt::obstacle_radar_blip_info`] looks for its
+/// top edge.
+pub const OBSTACLE_RADAR_EDGE_PROBE_INSET: Float = 0.01;
+
+/// The result of [`TnuaSpatialExt::project_point_on`].
+#[derive(Debug, Clone)]
+pub struct TnuaPointProjection {
+    /// The point on the surface of the collider.
+    pub point: Vector3,
+
+    /// Whether the projected point was inside the collider.
+    pub is_inside: bool,
+}
+
+/// The result of [`TnuaSpatialExt::cast_ray_on`].
+#[derive(Debug, Clone)]
+pub struct TnuaRayHit {
+    /// The distance from the origin of the ray to the hit point.
+    pub distance: Float,
+
+    /// The normal of the surface that was hit, pointing away from it.
+    pub normal: Dir3,
+}
+
+/// The result of [`TnuaSpatialExt::predict_landing`].
+#[derive(Debug, Clone)]
+pub struct TnuaPredictedLanding {
+    /// The entity of the collider the character will land on.
+    pub entity: Entity,
+
+    /// The position of the character (its center, not the contact point) when it lands.
+    pub position: Vector3,
+
+    /// The point where the character's collider will touch the collider it lands on.
+    pub contact_point: Vector3,
+
+    /// The normal of the surface the character will land on.
+    pub normal: Dir3,
+
+    /// The time, in seconds, until the character lands.
+    pub time: Float,
+}
+
+/// The time step, in seconds, that backends should use when sweeping a shape along a ballistic
+/// arc.
+pub const BALLISTIC_ARC_TIME_STEP: Float = 1.0 / 30.0;
+
+/// Split a ballistic arc into straight segments for sweeping shapes along it.
+///
+/// Yields `(start_time, end_time, start_position, end_position)` for each segment, until
+/// `max_time` is reached.
+pub fn ballistic_arc_segments(
+    origin: Vector3,
+    velocity: Vector3,
+    gravity: Vector3,
+    time_step: Float,
+    max_time: Float,
+) -> impl Iterator<Item = (Float, Float, Vector3, Vector3)> {
+    let position_at = move |time: Float| origin + velocity * time + 0.5 * gravity * time * time;
+    let time_step = time_step.max(Float::EPSILON);
+    let mut time: Float = 0.0;
+    std::iter::from_fn(move || {
+        if max_time <= time {
+            return None;
+        }
+        let next_time = (time + time_step).min(max_time);
+        let segment = (time, next_time, position_at(time), position_at(next_time));
+        time = next_time;
+        Some(segment)
+    })
+}
+
+/// The result of [`TnuaSpatialExt::sweep_character`].
+#[derive(Debug, Clone)]
+pub struct TnuaSweepHit {
+    /// The entity of the collider that was hit.
+    pub entity: Entity,
+
+    /// How far the character's collider can move along the displacement before it hits.
+    pub distance: Float,
+
+    /// The normal of the surface that was hit, pointing away from it.
+    pub normal: Dir3,
+}
+
+/// The distance [`collide_and_slide`] keeps between the character's collider and the colliders it
+/// slides against.
+pub const COLLIDE_AND_SLIDE_SKIN_WIDTH: Float = 0.01;
+
+/// The maximum number of surfaces [`collide_and_slide`] slides against in a single call.
+pub const COLLIDE_AND_SLIDE_MAX_ITERATIONS: usize = 4;
+
+/// Calculate how far a character can move along `displacement`, sliding along the surfaces it
+/// hits instead of stopping at them.
+///
+/// `sweep` is called with an offset from the character's current position and the displacement
+/// to sweep from there, and should usually delegate to [`TnuaSpatialExt::sweep_character`].
+///
+/// Returns the displacement the character can actually move.
+pub fn collide_and_slide(
+    displacement: Vector3,
+    mut sweep: impl FnMut(Vector3, Vector3) -> Option<TnuaSweepHit>,
+) -> Vector3 {
+    let mut moved = Vector3::ZERO;
+    let mut remaining = displacement;
+    for _ in 0..COLLIDE_AND_SLIDE_MAX_ITERATIONS {
+        let length = remaining.length();
+        if length <= Float::EPSILON {
+            break;
+        }
+        let direction = remaining / length;
+        let Some(hit) = sweep(moved, remaining + direction * COLLIDE_AND_SLIDE_SKIN_WIDTH) else {
+            moved += remaining;
+            break;
+        };
+        let travel = (hit.distance - COLLIDE_AND_SLIDE_SKIN_WIDTH).clamp(0.0, length);
+        moved += direction * travel;
+        let leftover = remaining - direction * travel;
+        let normal = hit.normal.adjust_precision();
+        remaining = leftover - normal * leftover.dot(normal).min(0.0);
+    }
+    moved
+}
@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 
-#[derive(Component)]
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaSubservientSensor {
     pub owner_entity: Entity,
 }
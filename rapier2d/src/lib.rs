@@ -7,7 +7,22 @@
 //! * Optionally: Add [`TnuaRapier2dSensorShape`] to the sensor entities. This means the entity of
 //!   the characters controlled by Tnua, but also other things like the entity generated by
 //!   `TnuaCrouchEnforcer`, that can be affected with a closure.
+//! * Optionally: Add [`TnuaRapier2dSensorCollisionGroups`] to the sensor entities, to make them
+//!   filter the colliders they detect with different collision groups than the character's.
+//! * Optionally: Use [`TnuaRapier2dCollisionHooks`] as the physics hooks of Rapier's plugin
+//!   (`RapierPhysicsPlugin::<TnuaRapier2dCollisionHooks>::default()`) to support
+//!   `TnuaCharacterCollisionPolicy`.
+//! * Optionally: Add Rapier's `KinematicCharacterController` to characters that use
+//!   `TnuaMotorApplication::Kinematic`, to move them with Rapier's collide-and-slide (including
+//!   its autostep and snap-to-ground) instead of Tnua's. The character must have a
+//!   `RigidBody::KinematicPositionBased` body, and the controller's `translation` is overwritten
+//!   with the movement of the motor every frame.
+use std::borrow::Cow;
+
+use bevy::core::FrameCount;
+use bevy::ecs::query::QueryItem;
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::utils::HashSet;
 use bevy_rapier2d::prelude::*;
@@ -16,9 +31,26 @@ use bevy_rapier2d::rapier::prelude::InteractionGroups;
 
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostPlatform;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGhostSensor;
+use bevy_tnua_physics_integration_layer::data_for_backends::TnuaGravityField;
+use bevy_tnua_physics_integration_layer::data_for_backends::TnuaPaused;
 use bevy_tnua_physics_integration_layer::data_for_backends::TnuaToggle;
+use bevy_tnua_physics_integration_layer::data_for_backends::TNUA_MOTOR_WRITE_EPSILON;
 use bevy_tnua_physics_integration_layer::data_for_backends::{
-    TnuaMotor, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker,
+    TnuaBodyTargets, TnuaCharacterColliders, TnuaCharacterCollisionPolicy, TnuaGroundClass,
+    TnuaMotor, TnuaMotorApplication, TnuaMotorLimits, TnuaProximitySensor,
+    TnuaProximitySensorOutput, TnuaRigidBodyTracker, TnuaSensorCastCounter, TnuaSensorHitCache,
+    TnuaSensorQueryFilter, TnuaSensorSolidityProbe, TnuaSleepPolicy, TnuaUpdateTier,
+};
+use bevy_tnua_physics_integration_layer::for_each_sensor;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+use bevy_tnua_physics_integration_layer::obstacle_radar::{
+    TnuaObstacleRadar, TnuaObstacleRadarShape,
+};
+use bevy_tnua_physics_integration_layer::schedules::{TnuaScheduleFilter, TnuaSchedules};
+use bevy_tnua_physics_integration_layer::spatial_ext::{
+    ballistic_arc_segments, collide_and_slide, TnuaCastShape, TnuaPointProjection,
+    TnuaPredictedLanding, TnuaRayCastHit, TnuaRayHit, TnuaShapeCastHit, TnuaSpatialExt,
+    TnuaSweepHit, BALLISTIC_ARC_TIME_STEP,
 };
 use bevy_tnua_physics_integration_layer::subservient_sensors::TnuaSubservientSensor;
 use bevy_tnua_physics_integration_layer::TnuaPipelineStages;
@@ -27,6 +59,10 @@ use bevy_tnua_physics_integration_layer::TnuaSystemSet;
 /// Add this plugin to use bevy_rapier2d as a physics backend.
 ///
 /// This plugin should be used in addition to `TnuaControllerPlugin`.
+///
+/// It can be added more than once, with different schedules, to drive some characters in one
+/// schedule and some in another - see
+/// [`TnuaSchedule`](bevy_tnua_physics_integration_layer::schedules::TnuaSchedule).
 pub struct TnuaRapier2dPlugin {
     schedule: InternedScheduleLabel,
 }
@@ -47,6 +83,7 @@ impl Default for TnuaRapier2dPlugin {
 
 impl Plugin for TnuaRapier2dPlugin {
     fn build(&self, app: &mut App) {
+        TnuaSchedules::register(app, self.schedule);
         app.configure_sets(
             self.schedule,
             TnuaSystemSet.before(PhysicsSet::SyncBackend).run_if(
@@ -57,15 +94,23 @@ impl Plugin for TnuaRapier2dPlugin {
             self.schedule,
             (
                 update_rigid_body_trackers_system,
+                activate_character_collision_hooks_system,
                 update_proximity_sensors_system,
+                update_obstacle_radars_system.after(update_rigid_body_trackers_system),
             )
                 .in_set(TnuaPipelineStages::Sensors),
         );
         app.add_systems(
             self.schedule,
-            apply_motors_system.in_set(TnuaPipelineStages::Motors),
+            (apply_motors_system, apply_ground_reactions_system)
+                .chain()
+                .in_set(TnuaPipelineStages::Motors),
         );
     }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
 }
 
 /// `bevy_rapier2d`-specific components required for Tnua to work.
@@ -73,6 +118,7 @@ impl Plugin for TnuaRapier2dPlugin {
 pub struct TnuaRapier2dIOBundle {
     pub velocity: Velocity,
     pub external_force: ExternalForce,
+    pub external_impulse: ExternalImpulse,
     pub read_mass_properties: ReadMassProperties,
 }
 
@@ -80,28 +126,362 @@ pub struct TnuaRapier2dIOBundle {
 #[derive(Component)]
 pub struct TnuaRapier2dSensorShape(pub Collider);
 
+/// Add this component to make [`TnuaProximitySensor`] use these collision groups instead of the
+/// ones of the character's collider.
+///
+/// Colliders that do not interact with these groups will not be detected by the sensor.
+#[derive(Component)]
+pub struct TnuaRapier2dSensorCollisionGroups(pub CollisionGroups);
+
+/// Physics hooks that drop the contacts between characters according to their
+/// [`TnuaCharacterCollisionPolicy`].
+///
+/// Pass them to Rapier's plugin with `RapierPhysicsPlugin::<TnuaRapier2dCollisionHooks>`. The
+/// backend activates the hooks on the colliders of characters with a
+/// [`TnuaCharacterCollisionPolicy`].
+///
+/// Note that the hooks replace Rapier's default filtering of contact pairs - which only computes
+/// contacts that involve a dynamic rigid body - so they re-implement it for the pairs they keep.
+#[derive(SystemParam)]
+pub struct TnuaRapier2dCollisionHooks<'w, 's> {
+    policies_query: Query<'w, 's, &'static TnuaCharacterCollisionPolicy>,
+}
+
+impl TnuaRapier2dCollisionHooks<'_, '_> {
+    fn policy(
+        &self,
+        collider: Entity,
+        rigid_body: Option<Entity>,
+    ) -> Option<&TnuaCharacterCollisionPolicy> {
+        self.policies_query
+            .get(collider)
+            .ok()
+            .or_else(|| self.policies_query.get(rigid_body?).ok())
+    }
+}
+
+impl BevyPhysicsHooks for TnuaRapier2dCollisionHooks<'_, '_> {
+    fn filter_contact_pair(&self, context: PairFilterContextView) -> Option<SolverFlags> {
+        if !TnuaCharacterCollisionPolicy::should_collide(
+            self.policy(context.collider1(), context.rigid_body1()),
+            self.policy(context.collider2(), context.rigid_body2()),
+        ) {
+            return None;
+        }
+        let is_dynamic = |handle: Option<rapier::prelude::RigidBodyHandle>| {
+            handle.is_some_and(|handle| context.raw.bodies[handle].is_dynamic())
+        };
+        if is_dynamic(context.raw.rigid_body1) || is_dynamic(context.raw.rigid_body2) {
+            Some(SolverFlags::COMPUTE_IMPULSES)
+        } else {
+            None
+        }
+    }
+}
+
+/// An implementation of [`TnuaSpatialExt`] for bevy_rapier2d.
+#[derive(SystemParam)]
+pub struct TnuaSpatialExtRapier2d<'w, 's> {
+    rapier_context: Res<'w, RapierContext>,
+    colliders_query: Query<'w, 's, (&'static GlobalTransform, &'static Collider)>,
+    characters_query: Query<
+        'w,
+        's,
+        (
+            Option<&'static TnuaBodyTargets>,
+            Option<&'static TnuaCharacterColliders>,
+        ),
+    >,
+    collision_groups_query: Query<'w, 's, &'static CollisionGroups>,
+    ghosts_query: Query<'w, 's, (), With<TnuaGhostPlatform>>,
+}
+
+impl TnuaSpatialExtRapier2d<'_, '_> {
+    fn apply_collision_groups(&self, character: Entity, query_filter: &mut QueryFilter) {
+        if let Some(character_collider) = get_collider(&self.rapier_context, character) {
+            let collision_groups = character_collider.collision_groups();
+            query_filter.groups = Some(CollisionGroups {
+                memberships: Group::from_bits_truncate(collision_groups.memberships.bits()),
+                filters: Group::from_bits_truncate(collision_groups.filter.bits()),
+            });
+        }
+    }
+}
+
+impl TnuaSpatialExt for TnuaSpatialExtRapier2d<'_, '_> {
+    fn predict_landing(
+        &self,
+        character: Entity,
+        velocity: Vector3,
+        gravity: Vector3,
+        max_time: Float,
+    ) -> Option<TnuaPredictedLanding> {
+        let (transform, collider) = self.colliders_query.get(character).ok()?;
+        let mut query_filter = QueryFilter::new()
+            .exclude_rigid_body(character)
+            .exclude_sensors();
+        self.apply_collision_groups(character, &mut query_filter);
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let rotation = rotation.to_scaled_axis().z;
+        for (start_time, end_time, start, end) in ballistic_arc_segments(
+            translation.truncate().extend(0.0),
+            velocity,
+            gravity,
+            BALLISTIC_ARC_TIME_STEP,
+            max_time,
+        ) {
+            let Some((entity, hit)) = self.rapier_context.cast_shape(
+                start.truncate(),
+                rotation,
+                (end - start).truncate(),
+                collider,
+                ShapeCastOptions {
+                    max_time_of_impact: 1.0,
+                    target_distance: 0.0,
+                    stop_at_penetration: false,
+                    compute_impact_geometry_on_penetration: false,
+                },
+                query_filter,
+            ) else {
+                continue;
+            };
+            let Some(details) = hit.details else {
+                continue;
+            };
+            return Some(TnuaPredictedLanding {
+                entity,
+                position: start.lerp(end, hit.time_of_impact),
+                contact_point: details.witness1.extend(0.0),
+                normal: Dir3::new(details.normal1.extend(0.0))
+                    .unwrap_or_else(|_| Dir3::new(start - end).unwrap_or(Dir3::Y)),
+                time: start_time + (end_time - start_time) * hit.time_of_impact,
+            });
+        }
+        None
+    }
+
+    fn sweep_character(
+        &self,
+        character: Entity,
+        origin: Vector3,
+        displacement: Vector3,
+    ) -> Option<TnuaSweepHit> {
+        let (transform, collider) = self.colliders_query.get(character).ok()?;
+        let is_not_ghost = |entity: Entity| !self.ghosts_query.contains(entity);
+        let mut query_filter = QueryFilter::new()
+            .exclude_rigid_body(character)
+            .exclude_sensors()
+            .predicate(&is_not_ghost);
+        self.apply_collision_groups(character, &mut query_filter);
+        let (_, rotation, _) = transform.to_scale_rotation_translation();
+        let (entity, hit) = self.rapier_context.cast_shape(
+            origin.truncate(),
+            rotation.to_scaled_axis().z,
+            displacement.truncate(),
+            collider,
+            ShapeCastOptions {
+                max_time_of_impact: 1.0,
+                target_distance: 0.0,
+                stop_at_penetration: false,
+                compute_impact_geometry_on_penetration: false,
+            },
+            query_filter,
+        )?;
+        let details = hit.details?;
+        Some(TnuaSweepHit {
+            entity,
+            distance: hit.time_of_impact * displacement.truncate().length(),
+            normal: Dir3::new(details.normal1.extend(0.0))
+                .unwrap_or_else(|_| Dir3::new(-displacement).unwrap_or(Dir3::Y)),
+        })
+    }
+
+    fn cast_shape(
+        &self,
+        character: Entity,
+        shape: &TnuaCastShape,
+        origin: Vector3,
+        direction: Dir3,
+        max_distance: Float,
+    ) -> Option<TnuaShapeCastHit> {
+        let (transform, collider) = self.colliders_query.get(character).ok()?;
+        let is_not_ghost = |entity: Entity| !self.ghosts_query.contains(entity);
+        let mut query_filter = QueryFilter::new()
+            .exclude_rigid_body(character)
+            .exclude_sensors()
+            .predicate(&is_not_ghost);
+        self.apply_collision_groups(character, &mut query_filter);
+        let (_, rotation, _) = transform.to_scale_rotation_translation();
+        let origin = transform.transform_point(origin);
+        let shape_collider = cast_shape_collider(shape);
+        let (entity, hit) = self.rapier_context.cast_shape(
+            origin.truncate(),
+            rotation.to_scaled_axis().z,
+            direction.truncate(),
+            shape_collider.as_ref().unwrap_or(collider),
+            ShapeCastOptions {
+                max_time_of_impact: max_distance,
+                target_distance: 0.0,
+                stop_at_penetration: false,
+                compute_impact_geometry_on_penetration: false,
+            },
+            query_filter,
+        )?;
+        let details = hit.details?;
+        // The hit point and normal of the collider that was hit are in its local space, so use
+        // the ones of the cast shape instead.
+        let position = origin + *direction * hit.time_of_impact;
+        Some(TnuaShapeCastHit {
+            entity,
+            distance: hit.time_of_impact,
+            position,
+            point: position + rotation * details.witness2.extend(0.0),
+            normal: Dir3::new(rotation * -details.normal2.extend(0.0)).unwrap_or(-direction),
+        })
+    }
+
+    fn cast_ray(
+        &self,
+        character: Entity,
+        origin: Vector3,
+        direction: Dir3,
+        max_distance: Float,
+    ) -> Option<TnuaRayCastHit> {
+        let (body_targets, character_colliders) = self.characters_query.get(character).ok()?;
+        let rigid_body = TnuaBodyTargets::resolve(body_targets, character);
+        // The character's colliders may be on child entities of the rigid body, so exclude them
+        // explicitly instead of only excluding the rigid body.
+        let is_detectable = |entity: Entity| {
+            !self.ghosts_query.contains(entity)
+                && !TnuaCharacterColliders::iter_with(character_colliders, rigid_body)
+                    .any(|character_entity| character_entity == entity)
+        };
+        let mut query_filter = QueryFilter::new()
+            .exclude_rigid_body(rigid_body)
+            .exclude_sensors()
+            .predicate(&is_detectable);
+        // The collider may not be synced to Rapier yet - but it may still have collision groups.
+        query_filter.groups = self.collision_groups_query.get(rigid_body).ok().copied();
+        self.apply_collision_groups(rigid_body, &mut query_filter);
+        let direction_2d = Dir2::new(direction.truncate()).ok()?;
+        let (entity, intersection) = self.rapier_context.cast_ray_and_get_normal(
+            origin.truncate(),
+            *direction_2d,
+            max_distance,
+            true,
+            query_filter,
+        )?;
+        Some(TnuaRayCastHit {
+            entity,
+            distance: intersection.time_of_impact,
+            point: intersection.point.extend(0.0),
+            normal: Dir3::new(intersection.normal.extend(0.0)).unwrap_or(-direction),
+        })
+    }
+
+    fn project_point_on(&self, entity: Entity, point: Vector3) -> Option<TnuaPointProjection> {
+        let (transform, collider) = self.colliders_query.get(entity).ok()?;
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let projection = collider.project_point(
+            translation.truncate(),
+            rotation.to_scaled_axis().z,
+            point.truncate(),
+            false,
+        );
+        Some(TnuaPointProjection {
+            point: projection.point.extend(0.0),
+            is_inside: projection.is_inside,
+        })
+    }
+
+    fn cast_ray_on(
+        &self,
+        entity: Entity,
+        origin: Vector3,
+        direction: Dir3,
+        max_distance: Float,
+    ) -> Option<TnuaRayHit> {
+        let (transform, collider) = self.colliders_query.get(entity).ok()?;
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let intersection = collider.cast_ray_and_get_normal(
+            translation.truncate(),
+            rotation.to_scaled_axis().z,
+            origin.truncate(),
+            direction.truncate(),
+            max_distance,
+            true,
+        )?;
+        Some(TnuaRayHit {
+            distance: intersection.time_of_impact,
+            normal: Dir3::new(intersection.normal.extend(0.0)).unwrap_or(-direction),
+        })
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn update_rigid_body_trackers_system(
     rapier_config: Res<RapierConfiguration>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    rapier_context: Res<RapierContext>,
     mut query: Query<(
-        &GlobalTransform,
-        &Velocity,
+        Entity,
         &mut TnuaRigidBodyTracker,
+        Option<&TnuaBodyTargets>,
+        Option<&TnuaProximitySensor>,
         Option<&TnuaToggle>,
     )>,
+    bodies_query: Query<(&GlobalTransform, &Velocity, Option<&ReadMassProperties>)>,
+    gravity_fields_query: Query<&TnuaGravityField>,
 ) {
-    for (transform, velocity, mut tracker, tnua_toggle) in query.iter_mut() {
-        match tnua_toggle.copied().unwrap_or_default() {
+    for (entity, mut tracker, body_targets, sensor, tnua_toggle) in query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
             TnuaToggle::Disabled => continue,
             TnuaToggle::SenseOnly => {}
             TnuaToggle::Enabled => {}
+            TnuaToggle::Frozen => {}
         }
-        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let Ok((transform, velocity, mass_properties)) =
+            bodies_query.get(TnuaBodyTargets::resolve(body_targets, entity))
+        else {
+            continue;
+        };
+        let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+        let mut effective_gravity = rapier_config.gravity.extend(0.0);
+        if !gravity_fields_query.is_empty() {
+            let probe_point = if let Some(sensor) = sensor {
+                transform.transform_point(sensor.cast_origin)
+            } else {
+                translation
+            };
+            rapier_context.intersections_with_point(
+                probe_point.truncate(),
+                QueryFilter::default(),
+                |entity| {
+                    if let Ok(gravity_field) = gravity_fields_query.get(entity) {
+                        effective_gravity = gravity_field.apply_to(effective_gravity);
+                        false
+                    } else {
+                        true
+                    }
+                },
+            );
+        }
+        let mass_properties = mass_properties
+            .map(|mass_properties| *mass_properties.get())
+            .unwrap_or_default();
         *tracker = TnuaRigidBodyTracker {
             translation,
             rotation,
             velocity: velocity.linvel.extend(0.0),
             angvel: Vec3::new(0.0, 0.0, velocity.angvel),
-            gravity: rapier_config.gravity.extend(0.0),
+            gravity: effective_gravity,
+            mass: mass_properties.mass,
+            inertia: Vector3::new(0.0, 0.0, mass_properties.principal_inertia),
+            scale,
         };
     }
 }
@@ -116,36 +496,124 @@ fn get_collider(
 }
 
 #[allow(clippy::type_complexity)]
+fn activate_character_collision_hooks_system(
+    mut commands: Commands,
+    query: Query<(Entity, Option<&TnuaCharacterColliders>), Added<TnuaCharacterCollisionPolicy>>,
+    active_hooks_query: Query<&ActiveHooks>,
+) {
+    for (entity, character_colliders) in query.iter() {
+        for collider in TnuaCharacterColliders::iter_with(character_colliders, entity) {
+            let active_hooks = active_hooks_query
+                .get(collider)
+                .copied()
+                .unwrap_or_else(|_| ActiveHooks::empty());
+            if !active_hooks.contains(ActiveHooks::FILTER_CONTACT_PAIRS) {
+                commands
+                    .entity(collider)
+                    .insert(active_hooks | ActiveHooks::FILTER_CONTACT_PAIRS);
+            }
+        }
+    }
+}
+
+/// The point a rigid body rotates around (its center of mass) in world space, falling back to its
+/// translation if it cannot be found in Rapier.
+fn rotation_center(
+    rapier_context: &RapierContext,
+    entity: Entity,
+    transform: &GlobalTransform,
+) -> Vec2 {
+    rapier_context
+        .entity2body()
+        .get(&entity)
+        .and_then(|handle| rapier_context.bodies.get(*handle))
+        .map(|body| {
+            let center_of_mass = body.center_of_mass();
+            Vec2::new(center_of_mass.x, center_of_mass.y)
+        })
+        .unwrap_or_else(|| transform.translation().truncate())
+}
+
+/// The sensor shape, scaled by the scale of the entity it is cast from.
+fn scaled_sensor_shape(shape: &Collider, scale: Vec3) -> Cow<'_, Collider> {
+    if scale == Vec3::ONE {
+        Cow::Borrowed(shape)
+    } else {
+        let mut shape = shape.clone();
+        shape.set_scale(scale.truncate(), 10);
+        Cow::Owned(shape)
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn update_proximity_sensors_system(
+    frame_count: Res<FrameCount>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    cast_counter: Option<Res<TnuaSensorCastCounter>>,
     rapier_context: Res<RapierContext>,
     mut query: Query<(
         Entity,
-        &GlobalTransform,
         &mut TnuaProximitySensor,
         Option<&TnuaRapier2dSensorShape>,
+        Option<&TnuaRapier2dSensorCollisionGroups>,
+        Option<&TnuaSensorQueryFilter>,
+        Option<&TnuaSensorSolidityProbe>,
+        Option<&mut TnuaSensorHitCache>,
         Option<&mut TnuaGhostSensor>,
         Option<&TnuaSubservientSensor>,
         Option<&TnuaToggle>,
     )>,
+    transforms_query: Query<&GlobalTransform>,
+    character_bodies_query: Query<(
+        Option<&TnuaBodyTargets>,
+        Option<&TnuaCharacterColliders>,
+        Option<&TnuaUpdateTier>,
+    )>,
     ghost_platforms_query: Query<(), With<TnuaGhostPlatform>>,
+    collision_groups_query: Query<&CollisionGroups>,
     other_object_query_query: Query<(&GlobalTransform, &Velocity)>,
 ) {
-    query.par_iter_mut().for_each(
+    let record_cast = || {
+        if let Some(cast_counter) = cast_counter.as_deref() {
+            cast_counter.record_cast();
+        }
+    };
+
+    for_each_sensor(
+        &mut query,
         |(
-            owner_entity,
-            transform,
+            sensor_entity,
             mut sensor,
             shape,
+            collision_groups_override,
+            sensor_query_filter,
+            solidity_probe,
+            mut hit_cache,
             mut ghost_sensor,
             subservient,
             tnua_toggle,
         )| {
-            match tnua_toggle.copied().unwrap_or_default() {
-                TnuaToggle::Disabled => return,
+            match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+                TnuaToggle::Disabled | TnuaToggle::Frozen => return,
                 TnuaToggle::SenseOnly => {}
                 TnuaToggle::Enabled => {}
             }
+            // A sensor on the controller entity is cast from the rigid body entity, but a
+            // subservient sensor is cast from its own entity.
+            let Ok(transform) = transforms_query.get(TnuaBodyTargets::resolve(
+                character_bodies_query
+                    .get(sensor_entity)
+                    .ok()
+                    .and_then(|(body_targets, _, _)| body_targets),
+                sensor_entity,
+            )) else {
+                return;
+            };
             let cast_origin = transform.transform_point(sensor.cast_origin);
+            let (scale, _, _) = transform.to_scale_rotation_translation();
+            let shape =
+                shape.map(|TnuaRapier2dSensorShape(shape)| scaled_sensor_shape(shape, scale));
             let cast_direction = sensor.cast_direction;
 
             struct CastResult {
@@ -160,8 +628,17 @@ fn update_proximity_sensors_system(
             let owner_entity = if let Some(subservient) = subservient {
                 subservient.owner_entity
             } else {
-                owner_entity
+                sensor_entity
             };
+            if !schedule_filter.drives(owner_entity) {
+                return;
+            }
+            let (body_targets, character_colliders, update_tier) =
+                character_bodies_query.get(owner_entity).unwrap_or_default();
+            if !TnuaUpdateTier::should_update_sensors(update_tier, owner_entity, frame_count.0) {
+                return;
+            }
+            let owner_entity = TnuaBodyTargets::resolve(body_targets, owner_entity);
 
             let mut query_filter = QueryFilter::new().exclude_rigid_body(owner_entity);
             let owner_solver_groups: InteractionGroups;
@@ -174,35 +651,61 @@ fn update_proximity_sensors_system(
                 });
                 owner_solver_groups = owner_collider.solver_groups();
             } else {
+                // The collider was not synced to Rapier yet - but it may still have collision
+                // groups.
+                query_filter.groups = collision_groups_query.get(owner_entity).ok().copied();
                 owner_solver_groups = InteractionGroups::all();
             }
+            if let Some(TnuaRapier2dSensorCollisionGroups(collision_groups)) =
+                collision_groups_override
+            {
+                query_filter.groups = Some(*collision_groups);
+            }
 
             let mut already_visited_ghost_entities = HashSet::<Entity>::default();
 
             let has_ghost_sensor = ghost_sensor.is_some();
 
-            let do_cast = |cast_range_skip: f32,
-                           already_visited_ghost_entities: &HashSet<Entity>|
-             -> Option<CastResult> {
-                let predicate = |other_entity: Entity| {
-                    if let Some(other_collider) = get_collider(&rapier_context, other_entity) {
-                        if !other_collider.solver_groups().test(owner_solver_groups) {
-                            if has_ghost_sensor && ghost_platforms_query.contains(other_entity) {
-                                if already_visited_ghost_entities.contains(&other_entity) {
-                                    return false;
-                                }
-                            } else {
-                                return false;
-                            }
-                        }
-                        if other_collider.is_sensor() {
+            let entity_to_retest = match (hit_cache.as_mut(), has_ghost_sensor) {
+                (Some(hit_cache), false) => hit_cache.entity_to_retest(&sensor),
+                _ => None,
+            };
+
+            // Whether the sensor can detect the entity, regardless of the ghost platforms it
+            // already went through.
+            let is_detectable = |other_entity: Entity| {
+                if character_colliders.is_some_and(|character_colliders| {
+                    character_colliders.colliders.contains(&other_entity)
+                }) {
+                    return false;
+                }
+                if let Some(other_collider) = get_collider(&rapier_context, other_entity) {
+                    if let Some(sensor_query_filter) = sensor_query_filter {
+                        let memberships = other_collider.collision_groups().memberships.bits();
+                        if !sensor_query_filter.test(other_entity, memberships) {
                             return false;
                         }
                     }
+                    let detectable_ghost =
+                        has_ghost_sensor && ghost_platforms_query.contains(other_entity);
+                    if !detectable_ghost
+                        && !other_collider.solver_groups().test(owner_solver_groups)
+                    {
+                        return false;
+                    }
+                    if other_collider.is_sensor() {
+                        return false;
+                    }
+                }
 
-                    // This fixes https://github.com/idanarye/bevy-tnua/issues/14
-                    if let Some(contact) = rapier_context.contact_pair(owner_entity, other_entity) {
-                        let same_order = owner_entity == contact.collider1();
+                // This fixes https://github.com/idanarye/bevy-tnua/issues/14
+                for collider_entity in
+                    TnuaCharacterColliders::iter_with(character_colliders, owner_entity)
+                {
+                    if let Some(contact) =
+                        rapier_context.contact_pair(collider_entity, other_entity)
+                    {
+                        let same_order = collider_entity == contact.collider1();
                         for manifold in contact.manifolds() {
                             if 0 < manifold.num_points() {
                                 let manifold_normal = if same_order {
@@ -218,12 +721,22 @@ fn update_proximity_sensors_system(
                             }
                         }
                     }
-                    true
+                }
+                true
+            };
+
+            let do_cast = |cast_range_skip: f32,
+                           already_visited_ghost_entities: &HashSet<Entity>|
+             -> Option<CastResult> {
+                record_cast();
+                let predicate = |other_entity: Entity| {
+                    !already_visited_ghost_entities.contains(&other_entity)
+                        && is_detectable(other_entity)
                 };
                 let query_filter = query_filter.predicate(&predicate);
                 let cast_origin = cast_origin + cast_range_skip * *cast_direction;
                 let cast_range = sensor.cast_range - cast_range_skip;
-                if let Some(TnuaRapier2dSensorShape(shape)) = shape {
+                if let Some(shape) = &shape {
                     rapier_context
                         .cast_shape(
                             cast_origin.truncate(),
@@ -267,6 +780,66 @@ fn update_proximity_sensors_system(
                 }
             };
 
+            // Cast only against the cached entity, without going over all the colliders in the
+            // cast's way.
+            let retest = |entity: Entity| -> Option<CastResult> {
+                if ghost_platforms_query.contains(entity) || !is_detectable(entity) {
+                    return None;
+                }
+                let collider = get_collider(&rapier_context, entity)?;
+                if let Some(shape) = &shape {
+                    let shape_isometry =
+                        rapier::math::Isometry::new(cast_origin.truncate().into(), 0.0);
+                    let hit = rapier::parry::query::cast_shapes(
+                        collider.position(),
+                        &rapier::math::Vector::zeros(),
+                        collider.shape(),
+                        &shape_isometry,
+                        &cast_direction.truncate().into(),
+                        shape.raw.as_ref(),
+                        ShapeCastOptions {
+                            max_time_of_impact: sensor.cast_range,
+                            target_distance: 0.0,
+                            stop_at_penetration: false,
+                            compute_impact_geometry_on_penetration: false,
+                        },
+                    )
+                    .ok()??;
+                    let normal: Vec2 = (collider.position() * hit.normal1).into_inner().into();
+                    Some(CastResult {
+                        entity,
+                        proximity: hit.time_of_impact,
+                        intersection_point: (collider.position() * hit.witness1).into(),
+                        normal: Dir3::new(normal.extend(0.0)).unwrap_or_else(|_| -cast_direction),
+                    })
+                } else {
+                    let hit = collider.shape().cast_ray_and_get_normal(
+                        collider.position(),
+                        &rapier::parry::query::Ray::new(
+                            cast_origin.truncate().into(),
+                            cast_direction.truncate().into(),
+                        ),
+                        sensor.cast_range,
+                        false,
+                    )?;
+                    let normal: Vec2 = hit.normal.into();
+                    Some(CastResult {
+                        entity,
+                        proximity: hit.time_of_impact,
+                        intersection_point: cast_origin.truncate()
+                            + hit.time_of_impact * cast_direction.truncate(),
+                        normal: Dir3::new(normal.extend(0.0)).unwrap_or_else(|_| -cast_direction),
+                    })
+                }
+            };
+
+            let mut retest_result = entity_to_retest.and_then(retest);
+            if let (Some(hit_cache), Some(_), None) =
+                (hit_cache.as_mut(), entity_to_retest, &retest_result)
+            {
+                hit_cache.retest_missed();
+            }
+
             let mut cast_range_skip = 0.0;
             if let Some(ghost_sensor) = ghost_sensor.as_mut() {
                 ghost_sensor.0.clear();
@@ -277,7 +850,9 @@ fn update_proximity_sensors_system(
                     proximity,
                     intersection_point,
                     normal,
-                }) = do_cast(cast_range_skip, &already_visited_ghost_entities)
+                }) = retest_result
+                    .take()
+                    .or_else(|| do_cast(cast_range_skip, &already_visited_ghost_entities))
                 {
                     let entity_linvel;
                     let entity_angvel;
@@ -287,8 +862,8 @@ fn update_proximity_sensors_system(
                         entity_angvel = Vec3::new(0.0, 0.0, entity_velocity.angvel);
                         entity_linvel = entity_velocity.linvel.extend(0.0)
                             + if 0.0 < entity_velocity.angvel.abs() {
-                                let relative_point =
-                                    intersection_point - entity_transform.translation().truncate();
+                                let relative_point = intersection_point
+                                    - rotation_center(&rapier_context, entity, entity_transform);
                                 // NOTE: no need to project relative_point on the rotation plane, it will not
                                 // affect the cross product.
                                 entity_angvel.cross(relative_point.extend(0.0))
@@ -305,6 +880,7 @@ fn update_proximity_sensors_system(
                         normal,
                         entity_linvel,
                         entity_angvel,
+                        ground_class: TnuaGroundClass::Walkable,
                     };
                     if ghost_platforms_query.contains(entity) {
                         cast_range_skip = proximity;
@@ -319,40 +895,437 @@ fn update_proximity_sensors_system(
                     break 'sensor_output None;
                 }
             };
+            if let (Some(solidity_probe), Some(_), Some(output)) =
+                (solidity_probe, shape, sensor.output.as_mut())
+            {
+                let ground_entity = output.entity;
+                record_cast();
+                let probe_hit = rapier_context.cast_ray_and_get_normal(
+                    cast_origin.truncate(),
+                    cast_direction.truncate(),
+                    output.proximity + solidity_probe.max_extra_distance,
+                    false,
+                    QueryFilter::new().predicate(&|other_entity| other_entity == ground_entity),
+                );
+                output.normal = if let Some((_, hit)) = probe_hit {
+                    Dir3::new(hit.normal.extend(0.0)).unwrap_or(output.normal)
+                } else {
+                    -cast_direction
+                };
+            }
         },
     );
 }
 
-fn apply_motors_system(
+fn cast_shape_collider(shape: &TnuaCastShape) -> Option<Collider> {
+    Some(match shape {
+        TnuaCastShape::CharacterCollider => return None,
+        TnuaCastShape::Sphere { radius } => Collider::ball(*radius),
+        TnuaCastShape::Box { half_extents } => Collider::cuboid(half_extents.x, half_extents.y),
+        TnuaCastShape::Capsule {
+            radius,
+            half_height,
+        } => Collider::capsule_y(*half_height, *radius),
+    })
+}
+
+fn radar_collider(radar: &TnuaObstacleRadar) -> Collider {
+    match radar.shape {
+        TnuaObstacleRadarShape::Cylinder { radius, height } => {
+            Collider::cuboid(radius, 0.5 * height)
+        }
+        TnuaObstacleRadarShape::Box { half_extents } => {
+            Collider::cuboid(half_extents.x, half_extents.y)
+        }
+        TnuaObstacleRadarShape::Sphere { radius } => Collider::ball(radius),
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn update_obstacle_radars_system(
+    frame_count: Res<FrameCount>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    rapier_context: Res<RapierContext>,
+    spatial_ext: TnuaSpatialExtRapier2d,
     mut query: Query<(
-        &TnuaMotor,
-        &mut Velocity,
-        &ReadMassProperties,
-        &mut ExternalForce,
+        Entity,
+        &mut TnuaObstacleRadar,
+        &TnuaRigidBodyTracker,
+        Option<&TnuaBodyTargets>,
+        Option<&TnuaCharacterColliders>,
+        Option<&TnuaUpdateTier>,
         Option<&TnuaToggle>,
     )>,
+    mut radar_colliders: Local<Vec<(TnuaObstacleRadarShape, Collider)>>,
 ) {
-    for (motor, mut velocity, mass_properties, mut external_force, tnua_toggle) in query.iter_mut()
-    {
-        match tnua_toggle.copied().unwrap_or_default() {
-            TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
-                *external_force = Default::default();
+    // Radars with the same shape (e.g. a crowd of NPCs spawned from the same bundle) share the
+    // collider, which is kept between frames.
+    let previous_radar_colliders = std::mem::take(&mut *radar_colliders);
+    for (_, radar, ..) in query.iter() {
+        if radar_colliders
+            .iter()
+            .all(|(shape, _)| *shape != radar.shape)
+        {
+            let collider = previous_radar_colliders
+                .iter()
+                .find(|(shape, _)| *shape == radar.shape)
+                .map_or_else(|| radar_collider(radar), |(_, collider)| collider.clone());
+            radar_colliders.push((radar.shape, collider));
+        }
+    }
+    let radar_colliders = &*radar_colliders;
+
+    for_each_sensor(
+        &mut query,
+        |(
+            owner_entity,
+            mut radar,
+            tracker,
+            body_targets,
+            character_colliders,
+            update_tier,
+            tnua_toggle,
+        )| {
+            match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+                TnuaToggle::Disabled | TnuaToggle::Frozen => return,
+                TnuaToggle::SenseOnly => {}
+                TnuaToggle::Enabled => {}
+            }
+            if !schedule_filter.drives(owner_entity) {
                 return;
             }
-            TnuaToggle::Enabled => {}
+            if !TnuaUpdateTier::should_update_sensors(update_tier, owner_entity, frame_count.0) {
+                return;
+            }
+            let owner_entity = TnuaBodyTargets::resolve(body_targets, owner_entity);
+            let up_direction = Dir3::new(-tracker.gravity).unwrap_or(Dir3::Y);
+            radar.pre_marking_update(tracker.translation, up_direction);
+            let radar = radar.as_mut();
+            let (_, collider) = radar_colliders
+                .iter()
+                .find(|(shape, _)| *shape == radar.shape)
+                .expect("a collider is prepared for the shape of every radar");
+            rapier_context.intersections_with_shape(
+                radar.probe_position().truncate(),
+                radar.probe_rotation().to_scaled_axis().z,
+                collider,
+                QueryFilter::new().exclude_rigid_body(owner_entity),
+                |entity| {
+                    if character_colliders.is_some_and(|character_colliders| {
+                        character_colliders.colliders.contains(&entity)
+                    }) {
+                        return true;
+                    }
+                    let memberships = get_collider(&rapier_context, entity)
+                        .map_or(u32::MAX, |collider| {
+                            collider.collision_groups().memberships.bits()
+                        });
+                    if radar.accepts_memberships(memberships) {
+                        radar.mark_seen(entity);
+                    }
+                    true
+                },
+            );
+            if radar.collect_blip_info {
+                radar.update_blip_infos(|radar, blip| {
+                    spatial_ext.obstacle_radar_blip_info(radar, blip)
+                });
+            }
+        },
+    );
+}
+
+type MotorControllerData = (
+    &'static TnuaMotor,
+    Option<&'static mut TnuaMotorLimits>,
+    Option<&'static TnuaMotorApplication>,
+    &'static TnuaRigidBodyTracker,
+    Option<&'static TnuaProximitySensor>,
+    Option<&'static mut TnuaSleepPolicy>,
+    Option<&'static TnuaUpdateTier>,
+    Option<&'static TnuaToggle>,
+);
+
+type MotorBodyData = (
+    &'static mut Velocity,
+    &'static ReadMassProperties,
+    &'static mut ExternalForce,
+    Option<&'static mut ExternalImpulse>,
+    Option<&'static mut KinematicCharacterController>,
+    &'static mut Transform,
+);
+
+#[allow(clippy::type_complexity)]
+fn apply_motors_system(
+    time: Res<Time>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    spatial_ext: TnuaSpatialExtRapier2d,
+    mut queries: ParamSet<(
+        Query<(Entity, MotorControllerData, MotorBodyData), Without<TnuaBodyTargets>>,
+        Query<MotorBodyData>,
+    )>,
+    mut redirected_query: Query<(Entity, MotorControllerData, &TnuaBodyTargets)>,
+) {
+    let frame_duration = time.delta_seconds();
+    // When the motor is on the rigid body entity, every character writes only to its own
+    // components, so they can be processed in parallel.
+    queries
+        .p0()
+        .par_iter_mut()
+        .for_each(|(entity, controller, body)| {
+            if !schedule_filter.drives(entity) {
+                return;
+            }
+            apply_motor(
+                entity,
+                controller,
+                body,
+                &spatial_ext,
+                paused.as_deref(),
+                frame_duration,
+            );
+        });
+    // With `TnuaBodyTargets` several controllers may point at the same rigid body.
+    let mut bodies_query = queries.p1();
+    for (entity, controller, body_targets) in redirected_query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
         }
+        let Ok(body) = bodies_query.get_mut(body_targets.rigid_body) else {
+            continue;
+        };
+        apply_motor(
+            body_targets.rigid_body,
+            controller,
+            body,
+            &spatial_ext,
+            paused.as_deref(),
+            frame_duration,
+        );
+    }
+}
+
+fn apply_motor(
+    entity: Entity,
+    (
+        motor,
+        motor_limits,
+        motor_application,
+        tracker,
+        proximity_sensor,
+        sleep_policy,
+        update_tier,
+        tnua_toggle,
+    ): QueryItem<MotorControllerData>,
+    (
+        mut velocity,
+        mass_properties,
+        mut external_force,
+        external_impulse,
+        character_controller,
+        mut transform,
+    ): QueryItem<MotorBodyData>,
+    spatial_ext: &TnuaSpatialExtRapier2d,
+    paused: Option<&TnuaPaused>,
+    frame_duration: Float,
+) {
+    match TnuaToggle::resolve(tnua_toggle, paused) {
+        TnuaToggle::Disabled | TnuaToggle::SenseOnly => {
+            *external_force = Default::default();
+            return;
+        }
+        TnuaToggle::Frozen => {
+            if *velocity != Velocity::zero() {
+                *velocity = Velocity::zero();
+            }
+            // Cancel the gravity Rapier is going to apply in the next step.
+            let force = -tracker.gravity.truncate() * mass_properties.get().mass;
+            if TNUA_MOTOR_WRITE_EPSILON < (force - external_force.force).length() {
+                external_force.force = force;
+            }
+            if external_force.torque != 0.0 {
+                external_force.torque = 0.0;
+            }
+            return;
+        }
+        TnuaToggle::Enabled => {}
+    }
+    if !TnuaUpdateTier::should_apply_motor(update_tier) {
+        return;
+    }
+    let motor = &match sleep_policy {
+        Some(mut sleep_policy) => {
+            let Some(motor) = sleep_policy.apply(motor, tracker, proximity_sensor, frame_duration)
+            else {
+                return;
+            };
+            motor
+        }
+        None => motor.clone(),
+    };
+    let motor = &match motor_limits {
+        Some(mut motor_limits) => motor_limits.apply(motor),
+        None => motor.clone(),
+    };
+    let motor_application = motor_application.copied().unwrap_or_default();
+    if motor_application == TnuaMotorApplication::Kinematic {
+        if frame_duration <= 0.0 {
+            return;
+        }
+        let mut linvel = velocity.linvel.extend(0.0) + tracker.gravity * frame_duration;
         if motor.lin.boost.is_finite() {
-            velocity.linvel += motor.lin.boost.truncate();
+            linvel += motor.lin.boost;
         }
         if motor.lin.acceleration.is_finite() {
-            external_force.force = motor.lin.acceleration.truncate() * mass_properties.get().mass;
+            linvel += motor.lin.acceleration * frame_duration;
+        }
+        if let Some(mut character_controller) = character_controller {
+            // Rapier's controller moves the transform of a position-based body, and Rapier
+            // calculates the velocity (which the tracker reads) from that movement.
+            character_controller.translation = Some(linvel.truncate() * frame_duration);
+            let mut angvel = velocity.angvel;
+            if motor.ang.boost.is_finite() {
+                angvel += motor.ang.boost.z;
+            }
+            if motor.ang.acceleration.is_finite() {
+                angvel += motor.ang.acceleration.z * frame_duration;
+            }
+            if TNUA_MOTOR_WRITE_EPSILON < angvel.abs() {
+                transform.rotate_z(angvel * frame_duration);
+            }
+            return;
         }
+        let displacement = collide_and_slide(linvel * frame_duration, |offset, displacement| {
+            spatial_ext.sweep_character(entity, tracker.translation + offset, displacement)
+        });
+        let linvel = displacement.truncate() / frame_duration;
+        if TNUA_MOTOR_WRITE_EPSILON < (linvel - velocity.linvel).length() {
+            velocity.linvel = linvel;
+        }
+        let mut angvel_change = 0.0;
         if motor.ang.boost.is_finite() {
-            velocity.angvel += motor.ang.boost.z;
+            angvel_change += motor.ang.boost.z;
         }
         if motor.ang.acceleration.is_finite() {
-            external_force.torque =
-                motor.ang.acceleration.z * mass_properties.get().principal_inertia;
+            angvel_change += motor.ang.acceleration.z * frame_duration;
+        }
+        if TNUA_MOTOR_WRITE_EPSILON < angvel_change.abs() {
+            velocity.angvel += angvel_change;
+        }
+        return;
+    }
+    let mass_properties = *mass_properties.get();
+    // `ReadMassProperties` is only updated after a physics step, so it is still zero in the
+    // first frame after the rigid body is created. Until then, apply the motor directly to the
+    // velocity - otherwise the character would drop for a frame.
+    let mass_known = 0.0 < mass_properties.mass;
+    let mut external_impulse = if motor_application == TnuaMotorApplication::Impulses {
+        if external_impulse.is_none() {
+            warn_once!(
+                "TnuaMotorApplication::Impulses requires an ExternalImpulse component \
+                (included in TnuaRapier2dIOBundle) - falling back to \
+                TnuaMotorApplication::Direct"
+            );
+        }
+        external_impulse.filter(|_| mass_known)
+    } else {
+        None
+    };
+    if motor.lin.boost.is_finite() && TNUA_MOTOR_WRITE_EPSILON < motor.lin.boost.length() {
+        if let Some(external_impulse) = external_impulse.as_mut() {
+            external_impulse.impulse += motor.lin.boost.truncate() * mass_properties.mass;
+        } else {
+            velocity.linvel += motor.lin.boost.truncate();
+        }
+    }
+    if motor.lin.acceleration.is_finite() {
+        if mass_known {
+            let force = motor.lin.acceleration.truncate() * mass_properties.mass;
+            if TNUA_MOTOR_WRITE_EPSILON < (force - external_force.force).length() {
+                external_force.force = force;
+            }
+        } else {
+            external_force.force = Default::default();
+            velocity.linvel += motor.lin.acceleration.truncate() * frame_duration;
+        }
+    }
+    if motor.ang.boost.is_finite() && TNUA_MOTOR_WRITE_EPSILON < motor.ang.boost.z.abs() {
+        if let Some(external_impulse) = external_impulse.as_mut() {
+            external_impulse.torque_impulse +=
+                motor.ang.boost.z * mass_properties.principal_inertia;
+        } else {
+            velocity.angvel += motor.ang.boost.z;
+        }
+    }
+    if motor.ang.acceleration.is_finite() {
+        if mass_known {
+            let torque = motor.ang.acceleration.z * mass_properties.principal_inertia;
+            if TNUA_MOTOR_WRITE_EPSILON < (torque - external_force.torque).abs() {
+                external_force.torque = torque;
+            }
+        } else {
+            external_force.torque = Default::default();
+            velocity.angvel += motor.ang.acceleration.z * frame_duration;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn apply_ground_reactions_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    rapier_context: Res<RapierContext>,
+    characters_query: Query<(
+        Entity,
+        &TnuaMotor,
+        Option<&TnuaBodyTargets>,
+        Option<&TnuaUpdateTier>,
+        Option<&TnuaToggle>,
+    )>,
+    masses_query: Query<&ReadMassProperties>,
+    mut grounds_query: Query<(&RigidBody, &GlobalTransform, Option<&mut ExternalImpulse>)>,
+) {
+    let frame_duration = time.delta_seconds();
+    for (entity, motor, body_targets, update_tier, tnua_toggle) in characters_query.iter() {
+        let Some(ground_reaction) = motor.ground_reaction.as_ref() else {
+            continue;
+        };
+        if !schedule_filter.drives(entity)
+            || TnuaToggle::resolve(tnua_toggle, paused.as_deref()) != TnuaToggle::Enabled
+            || !TnuaUpdateTier::should_apply_motor(update_tier)
+        {
+            continue;
+        }
+        let Ok(mass_properties) = masses_query.get(TnuaBodyTargets::resolve(body_targets, entity))
+        else {
+            continue;
+        };
+        let Ok((rigid_body, transform, external_impulse)) =
+            grounds_query.get_mut(ground_reaction.entity)
+        else {
+            continue;
+        };
+        if *rigid_body != RigidBody::Dynamic {
+            continue;
+        }
+        let impulse =
+            ground_reaction.acceleration.truncate() * mass_properties.get().mass * frame_duration;
+        if !impulse.is_finite() || impulse.length() <= TNUA_MOTOR_WRITE_EPSILON {
+            continue;
+        }
+        let impulse = ExternalImpulse::at_point(
+            impulse,
+            ground_reaction.point.truncate(),
+            rotation_center(&rapier_context, ground_reaction.entity, transform),
+        );
+        if let Some(mut external_impulse) = external_impulse {
+            *external_impulse += impulse;
+        } else {
+            commands.entity(ground_reaction.entity).insert(impulse);
         }
     }
 }
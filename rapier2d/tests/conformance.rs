@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use bevy_tnua_physics_integration_layer::conformance::TnuaConformanceSetup;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector2, Vector3};
+use bevy_tnua_rapier2d::{TnuaRapier2dCollisionHooks, TnuaRapier2dIOBundle, TnuaRapier2dPlugin};
+
+struct Rapier2dSetup;
+
+impl TnuaConformanceSetup for Rapier2dSetup {
+    fn add_physics(&self, app: &mut App) {
+        app.add_plugins((AssetPlugin::default(), bevy::scene::ScenePlugin));
+        app.init_asset::<Mesh>();
+        app.add_plugins((
+            RapierPhysicsPlugin::<TnuaRapier2dCollisionHooks>::default(),
+            TnuaRapier2dPlugin::default(),
+        ));
+        app.add_systems(Startup, |mut cfg: ResMut<RapierConfiguration>| {
+            cfg.gravity = Vector2::new(0.0, -9.81);
+        });
+    }
+
+    fn spawn_static_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+    ) -> Entity {
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Fixed,
+                Collider::cuboid(half_extents.x, half_extents.y),
+            ))
+            .id()
+    }
+
+    fn spawn_kinematic_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+        linear_velocity: Vector3,
+        angular_velocity: Vector3,
+    ) -> Entity {
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::KinematicVelocityBased,
+                Velocity {
+                    linvel: linear_velocity.truncate(),
+                    angvel: angular_velocity.z,
+                },
+                Collider::cuboid(half_extents.x, half_extents.y),
+            ))
+            .id()
+    }
+
+    fn spawn_dynamic_ball(&self, app: &mut App, transform: Transform, radius: Float) -> Entity {
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Dynamic,
+                Collider::ball(radius),
+                TnuaRapier2dIOBundle::default(),
+            ))
+            .id()
+    }
+}
+
+bevy_tnua_physics_integration_layer::tnua_conformance_tests!(Rapier2dSetup);
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_tnua_physics_integration_layer::conformance::TnuaConformanceSetup;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+use bevy_tnua_rapier3d::{TnuaRapier3dCollisionHooks, TnuaRapier3dIOBundle, TnuaRapier3dPlugin};
+
+struct Rapier3dSetup;
+
+impl TnuaConformanceSetup for Rapier3dSetup {
+    fn add_physics(&self, app: &mut App) {
+        app.add_plugins((AssetPlugin::default(), bevy::scene::ScenePlugin));
+        app.init_asset::<Mesh>();
+        app.add_plugins((
+            RapierPhysicsPlugin::<TnuaRapier3dCollisionHooks>::default(),
+            TnuaRapier3dPlugin::default(),
+        ));
+        app.add_systems(Startup, |mut cfg: ResMut<RapierConfiguration>| {
+            cfg.gravity = Vector3::new(0.0, -9.81, 0.0);
+        });
+    }
+
+    fn spawn_static_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+    ) -> Entity {
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Fixed,
+                Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            ))
+            .id()
+    }
+
+    fn spawn_kinematic_box(
+        &self,
+        app: &mut App,
+        transform: Transform,
+        half_extents: Vector3,
+        linear_velocity: Vector3,
+        angular_velocity: Vector3,
+    ) -> Entity {
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::KinematicVelocityBased,
+                Velocity {
+                    linvel: linear_velocity,
+                    angvel: angular_velocity,
+                },
+                Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            ))
+            .id()
+    }
+
+    fn spawn_dynamic_ball(&self, app: &mut App, transform: Transform, radius: Float) -> Entity {
+        app.world_mut()
+            .spawn((
+                TransformBundle::from_transform(transform),
+                RigidBody::Dynamic,
+                Collider::ball(radius),
+                TnuaRapier3dIOBundle::default(),
+            ))
+            .id()
+    }
+}
+
+bevy_tnua_physics_integration_layer::tnua_conformance_tests!(Rapier3dSetup);
+bevy_tnua_physics_integration_layer::tnua_conformance_tests!(Rapier3dSetup, check_plane_lock);
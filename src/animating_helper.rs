@@ -113,11 +113,11 @@ impl<State> TnuaAnimatingState<State> {
         &mut self,
         new_state: State,
         comparison: impl FnOnce(&State, &State) -> bool,
-    ) -> TnuaAnimatingStateDirective<State> {
+    ) -> TnuaAnimatingStateDirective<'_, State> {
         let is_same = self
             .state
             .as_ref()
-            .map_or(false, |old_state| comparison(old_state, &new_state));
+            .is_some_and(|old_state| comparison(old_state, &new_state));
         let old_state = self.state.replace(new_state);
         if is_same {
             TnuaAnimatingStateDirective::Maintain {
@@ -134,7 +134,7 @@ impl<State> TnuaAnimatingState<State> {
     /// Consider a new animation to play.
     ///
     /// The new animation is considered the same if and only if it is equal to the old animation.
-    pub fn update_by_value(&mut self, new_state: State) -> TnuaAnimatingStateDirective<State>
+    pub fn update_by_value(&mut self, new_state: State) -> TnuaAnimatingStateDirective<'_, State>
     where
         State: PartialEq,
     {
@@ -151,7 +151,7 @@ impl<State> TnuaAnimatingState<State> {
     pub fn update_by_discriminant(
         &mut self,
         new_state: State,
-    ) -> TnuaAnimatingStateDirective<State> {
+    ) -> TnuaAnimatingStateDirective<'_, State> {
         self.update_by(new_state, |a, b| discriminant(a) == discriminant(b))
     }
 
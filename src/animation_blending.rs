@@ -0,0 +1,312 @@
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{Float, Quaternion, Vector2, Vector3};
+
+/// The animations to blend on a given frame, together with their parameters.
+///
+/// This is both the input and the output of [`TnuaAnimationBlender`] - the input is the target
+/// blend for the current frame, and the output is that target after crossfading from the blends
+/// of the previous frames.
+#[derive(Debug, Clone)]
+pub struct TnuaAnimationBlend<K> {
+    /// The animations to play and their weights.
+    ///
+    /// In the output of [`TnuaAnimationBlender`] the weights are normalized so that they sum to
+    /// 1, and animations with zero weight are not included.
+    pub weights: Vec<(K, Float)>,
+
+    /// A speed multiplier for playing the animations.
+    pub speed: Float,
+
+    /// The direction of the movement in the character's space, for directional animations (e.g.
+    /// strafing). `X` is to the character's right and `Y` is to its front. Either normalized or
+    /// zero.
+    pub direction: Vector2,
+}
+
+impl<K> Default for TnuaAnimationBlend<K> {
+    fn default() -> Self {
+        Self {
+            weights: Vec::new(),
+            speed: 1.0,
+            direction: Vector2::ZERO,
+        }
+    }
+}
+
+impl<K> TnuaAnimationBlend<K> {
+    /// A blend that plays only a single animation.
+    pub fn single(key: K) -> Self {
+        Self {
+            weights: vec![(key, 1.0)],
+            ..Default::default()
+        }
+    }
+
+    /// Set the speed multiplier.
+    pub fn with_speed(mut self, speed: Float) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Set the [`direction`](Self::direction) from a world-space velocity and the rotation of the
+    /// character.
+    ///
+    /// The character's front is its negative `Z` axis (which is Bevy's convention), and the
+    /// vertical part of the velocity is ignored.
+    pub fn with_direction_from_velocity(
+        mut self,
+        velocity: Vector3,
+        character_rotation: Quaternion,
+    ) -> Self {
+        let local = character_rotation.inverse() * velocity;
+        self.direction = Vector2::new(local.x, -local.z).normalize_or_zero();
+        self
+    }
+}
+
+/// Linear blending between animations based on a single parameter - e.g. blending idle, walk and
+/// run animations by the speed of the character.
+#[derive(Debug, Clone)]
+pub struct TnuaBlendSpace1d<K> {
+    points: Vec<(Float, K)>,
+}
+
+impl<K: Clone> TnuaBlendSpace1d<K> {
+    /// Create a blend space from the values of the parameter at which each animation is played
+    /// exclusively.
+    pub fn new(points: impl IntoIterator<Item = (Float, K)>) -> Self {
+        let mut points: Vec<(Float, K)> = points.into_iter().collect();
+        points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self { points }
+    }
+
+    /// The weights of the animations for the given value of the parameter.
+    ///
+    /// Values outside the range of the blend space are clamped to it.
+    pub fn weights(&self, value: Float) -> Vec<(K, Float)> {
+        let Some(index) = self.points.iter().position(|(point, _)| value < *point) else {
+            return self
+                .points
+                .last()
+                .map(|(_, key)| vec![(key.clone(), 1.0)])
+                .unwrap_or_default();
+        };
+        if index == 0 {
+            return vec![(self.points[0].1.clone(), 1.0)];
+        }
+        let (from_value, from_key) = &self.points[index - 1];
+        let (to_value, to_key) = &self.points[index];
+        let t = (value - from_value) / (to_value - from_value);
+        vec![(from_key.clone(), 1.0 - t), (to_key.clone(), t)]
+    }
+
+    /// A blend with the weights for the given value of the parameter.
+    pub fn blend(&self, value: Float) -> TnuaAnimationBlend<K> {
+        TnuaAnimationBlend {
+            weights: self.weights(value),
+            ..Default::default()
+        }
+    }
+}
+
+/// Utility for crossfading between blends of animations.
+///
+/// This is an alternative to [`TnuaAnimatingState`](crate::TnuaAnimatingState) for when the
+/// animations should be blended rather than switched. Add `TnuaAnimationBlender<K>` as a
+/// component, where `K` identifies an animation (e.g. an `AnimationNodeIndex`, or an `enum`).
+/// Each frame, decide (with the help of [`TnuaController`](crate::prelude::TnuaController) and
+/// possibly [`TnuaBlendSpace1d`]) on a target [`TnuaAnimationBlend`] and feed it to
+/// [`update`](Self::update). The weights of the animations move toward the target over the
+/// crossfade duration, and the result can be mapped to the animation player:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::{TnuaAnimationBlend, TnuaAnimationBlender, TnuaBlendSpace1d};
+/// # use bevy_tnua::math::AdjustPrecision;
+/// # #[derive(Resource)]
+/// # struct AnimationNodes {
+/// #     idle: AnimationNodeIndex,
+/// #     walk: AnimationNodeIndex,
+/// #     run: AnimationNodeIndex,
+/// #     fall: AnimationNodeIndex,
+/// # }
+/// fn animating_system(
+///     mut query: Query<(
+///         &mut TnuaAnimationBlender<AnimationNodeIndex>,
+///         &TnuaController,
+///         &mut AnimationPlayer,
+///     )>,
+///     animation_nodes: Res<AnimationNodes>,
+///     time: Res<Time>,
+/// ) {
+///     let locomotion = TnuaBlendSpace1d::new([
+///         (0.0, animation_nodes.idle),
+///         (2.0, animation_nodes.walk),
+///         (6.0, animation_nodes.run),
+///     ]);
+///     for (mut blender, controller, mut animation_player) in query.iter_mut() {
+///         let Some((_, basis_state)) = controller.concrete_basis::<TnuaBuiltinWalk>() else {
+///             continue;
+///         };
+///         let target = if basis_state.standing_on_entity().is_some() {
+///             locomotion.blend(basis_state.running_velocity.length())
+///         } else {
+///             TnuaAnimationBlend::single(animation_nodes.fall)
+///         };
+///         let blend = blender.update(target, time.delta_seconds().adjust_precision());
+///         for (node, weight) in blend.weights.iter() {
+///             let active = animation_player.play(*node).repeat();
+///             active.set_weight(*weight as f32);
+///             active.set_speed(blend.speed as f32);
+///         }
+///         let faded_out: Vec<AnimationNodeIndex> = animation_player
+///             .playing_animations()
+///             .map(|(node, _)| *node)
+///             .filter(|node| !blend.weights.iter().any(|(n, _)| n == node))
+///             .collect();
+///         for node in faded_out {
+///             animation_player.stop(node);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct TnuaAnimationBlender<K> {
+    /// The duration, in seconds, of a crossfade between two animations that does not have a
+    /// specific duration set by [`with_crossfade`](Self::with_crossfade).
+    pub default_crossfade: Float,
+    crossfades: Vec<(K, K, Float)>,
+    primary: Option<K>,
+    fade_rate: Float,
+    output: TnuaAnimationBlend<K>,
+}
+
+impl<K> Default for TnuaAnimationBlender<K> {
+    fn default() -> Self {
+        Self {
+            default_crossfade: 0.2,
+            crossfades: Vec::new(),
+            primary: None,
+            fade_rate: Float::INFINITY,
+            output: Default::default(),
+        }
+    }
+}
+
+impl<K: Clone + PartialEq> TnuaAnimationBlender<K> {
+    /// Set the duration, in seconds, of the crossfade when the most weighted animation changes
+    /// from `from` to `to`.
+    pub fn with_crossfade(mut self, from: K, to: K, duration: Float) -> Self {
+        self.crossfades
+            .retain(|(f, t, _)| !(*f == from && *t == to));
+        self.crossfades.push((from, to, duration));
+        self
+    }
+
+    /// The duration, in seconds, of the crossfade when the most weighted animation changes from
+    /// `from` to `to`.
+    pub fn crossfade_duration(&self, from: &K, to: &K) -> Float {
+        self.crossfades
+            .iter()
+            .find_map(|(f, t, duration)| (f == from && t == to).then_some(*duration))
+            .unwrap_or(self.default_crossfade)
+    }
+
+    /// Move the blend toward the target blend.
+    ///
+    /// When the most weighted animation of the target changes, the crossfade duration for the
+    /// change is used for fading the weights of all the animations toward the target. Changes
+    /// in the weights of a blend space are also smoothed by that duration, so they can lag behind
+    /// the target by up to that duration.
+    ///
+    /// The first blend is applied immediately, without crossfading.
+    pub fn update(
+        &mut self,
+        target: TnuaAnimationBlend<K>,
+        frame_duration: Float,
+    ) -> &TnuaAnimationBlend<K> {
+        let total: Float = target
+            .weights
+            .iter()
+            .map(|(_, weight)| weight.max(0.0))
+            .sum();
+        let target_weight = |key: &K| -> Float {
+            if total <= 0.0 {
+                return 0.0;
+            }
+            target
+                .weights
+                .iter()
+                .filter(|(k, _)| k == key)
+                .map(|(_, weight)| weight.max(0.0) / total)
+                .sum()
+        };
+
+        let new_primary = target
+            .weights
+            .iter()
+            .filter(|(_, weight)| 0.0 < *weight)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(key, _)| key.clone());
+        if new_primary != self.primary {
+            self.fade_rate = match (&self.primary, &new_primary) {
+                (Some(from), Some(to)) => {
+                    let duration = self.crossfade_duration(from, to);
+                    if 0.0 < duration {
+                        1.0 / duration
+                    } else {
+                        Float::INFINITY
+                    }
+                }
+                _ => Float::INFINITY,
+            };
+            self.primary = new_primary;
+        }
+
+        let max_change = if self.fade_rate.is_finite() {
+            self.fade_rate * frame_duration
+        } else {
+            Float::INFINITY
+        };
+        let mut weights = std::mem::take(&mut self.output.weights);
+        for (key, _) in target.weights.iter() {
+            if !weights.iter().any(|(k, _)| k == key) {
+                weights.push((key.clone(), 0.0));
+            }
+        }
+        for (key, weight) in weights.iter_mut() {
+            let target = target_weight(key);
+            *weight += (target - *weight).clamp(-max_change, max_change);
+        }
+        weights.retain(|(_, weight)| 0.0 < *weight);
+        let sum: Float = weights.iter().map(|(_, weight)| *weight).sum();
+        if 0.0 < sum {
+            for (_, weight) in weights.iter_mut() {
+                *weight /= sum;
+            }
+        }
+
+        self.output = TnuaAnimationBlend {
+            weights,
+            speed: target.speed,
+            direction: target.direction,
+        };
+        &self.output
+    }
+
+    /// The last blend returned by [`update`](Self::update).
+    pub fn blend(&self) -> &TnuaAnimationBlend<K> {
+        &self.output
+    }
+
+    /// The weight, in the last blend, of the given animation.
+    pub fn weight_of(&self, key: &K) -> Float {
+        self.output
+            .weights
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, weight)| *weight)
+            .sum()
+    }
+}
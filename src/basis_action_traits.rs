@@ -4,7 +4,9 @@ use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
 
 use std::{any::Any, time::Duration};
 
-use crate::{TnuaMotor, TnuaProximitySensor, TnuaRigidBodyTracker};
+use crate::control_helpers::TnuaAuxiliarySensors;
+use crate::controller::{TnuaExternalFlow, TnuaGroundHistory};
+use crate::{TnuaMotor, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaRigidBodyTracker};
 
 /// Various data passed to [`TnuaBasis::apply`].
 pub struct TnuaBasisContext<'a> {
@@ -19,6 +21,16 @@ pub struct TnuaBasisContext<'a> {
 
     /// The direction considered as "up".
     pub up_direction: Dir3,
+
+    /// The moving medium (wind, water current) the character is in.
+    ///
+    /// Its drag is added to the motor by the controller after the basis and the action are
+    /// applied. Bases that want to resist it need to offset it themselves.
+    pub external_flow: TnuaExternalFlow,
+
+    /// The velocity calculated from [`TnuaRootMotion`](crate::controller::TnuaRootMotion), if the
+    /// character has one and it was fed.
+    pub root_motion: Option<Vector3>,
 }
 
 /// The main movement command of a character.
@@ -32,7 +44,10 @@ pub struct TnuaBasisContext<'a> {
 /// overwritten each frame by the controller system of the game code. Configuration is considered
 /// as part of the input. If the basis needs to persist data between frames it must keep it in its
 /// [state](Self::State).
-pub trait TnuaBasis: 'static + Send + Sync {
+///
+/// Both the input and the state must be [`Clone`], so that the controller can be cloned (e.g. for
+/// snapshotting it in rollback networking).
+pub trait TnuaBasis: 'static + Send + Sync + Clone {
     /// The default name of the basis.
     ///
     /// [Once `type_name` becomes `const`](https://github.com/rust-lang/rust/issues/63084), this
@@ -50,7 +65,7 @@ pub trait TnuaBasis: 'static + Send + Sync {
     ///
     /// 3. Inspect the basis from game code systems, like an animation controlling system that
     ///    needs to know which animation to play based on the basis' current state.
-    type State: Default + Send + Sync;
+    type State: Default + Send + Sync + Clone;
 
     /// This is where the basis affects the character's motion.
     ///
@@ -132,6 +147,9 @@ pub trait DynamicBasis: Send + Sync + Any + 'static {
 
     #[doc(hidden)]
     fn violate_coyote_time(&mut self);
+
+    #[doc(hidden)]
+    fn clone_boxed(&self) -> Box<dyn DynamicBasis>;
 }
 
 pub(crate) struct BoxableBasis<B: TnuaBasis> {
@@ -188,6 +206,13 @@ impl<B: TnuaBasis> DynamicBasis for BoxableBasis<B> {
     fn violate_coyote_time(&mut self) {
         self.input.violate_coyote_time(&mut self.state)
     }
+
+    fn clone_boxed(&self) -> Box<dyn DynamicBasis> {
+        Box::new(Self {
+            input: self.input.clone(),
+            state: self.state.clone(),
+        })
+    }
 }
 
 /// Various data passed to [`TnuaAction::apply`].
@@ -204,11 +229,31 @@ pub struct TnuaActionContext<'a> {
     /// The direction considered as "up".
     pub up_direction: Dir3,
 
+    /// The moving medium (wind, water current) the character is in.
+    pub external_flow: TnuaExternalFlow,
+
+    /// The velocity calculated from [`TnuaRootMotion`](crate::controller::TnuaRootMotion), if the
+    /// character has one and it was fed.
+    pub root_motion: Option<Vector3>,
+
     /// An accessor to the currently active basis.
     pub basis: &'a dyn DynamicBasis,
+
+    /// The extra sensors of the character, if it has a
+    /// [`TnuaAuxiliarySensors`](crate::control_helpers::TnuaAuxiliarySensors) component.
+    pub auxiliary_sensors: Option<&'a TnuaAuxiliarySensors>,
+
+    /// What the proximity sensor detected in the last few ticks.
+    pub ground_history: &'a TnuaGroundHistory,
 }
 
 impl<'a> TnuaActionContext<'a> {
+    /// The output of an [auxiliary sensor](crate::control_helpers::TnuaAuxiliarySensors) of the
+    /// character, if it exists and detected something.
+    pub fn auxiliary_sensor(&self, name: &str) -> Option<&'a TnuaProximitySensorOutput> {
+        self.auxiliary_sensors?.output(name)
+    }
+
     /// Can be used to get the concrete basis.
     ///
     /// Use with care - actions that use it will only be usable with one basis.
@@ -227,11 +272,14 @@ impl<'a> TnuaActionContext<'a> {
             tracker: self.tracker,
             proximity_sensor: self.proximity_sensor,
             up_direction: self.up_direction,
+            external_flow: self.external_flow,
+            root_motion: self.root_motion,
         }
     }
 
     pub fn frame_duration_as_duration(&self) -> Duration {
-        Duration::from_secs_f64(self.frame_duration.into())
+        #[allow(clippy::unnecessary_cast)]
+        Duration::from_secs_f64(self.frame_duration as f64)
     }
 }
 
@@ -365,7 +413,10 @@ pub enum TnuaActionInitiationDirective {
 /// overwritten each frame by the controller system of the game code - although unlike basis the
 /// input will probably be the exact same. Configuration is considered as part of the input. If the
 /// action needs to persist data between frames it must keep it in its [state](Self::State).
-pub trait TnuaAction: 'static + Send + Sync {
+///
+/// Both the input and the state must be [`Clone`], so that the controller can be cloned (e.g. for
+/// snapshotting it in rollback networking).
+pub trait TnuaAction: 'static + Send + Sync + Clone {
     /// The default name of the action.
     ///
     /// [Once `type_name` becomes `const`](https://github.com/rust-lang/rust/issues/63084), this
@@ -383,7 +434,7 @@ pub trait TnuaAction: 'static + Send + Sync {
     ///
     /// 3. Inspect the action from game code systems, like an animation controlling system that
     ///    needs to know which animation to play based on the action's current state.
-    type State: Default + Send + Sync;
+    type State: Default + Send + Sync + Clone;
 
     /// Set this to true for actions that may launch the character into the air.
     const VIOLATES_COYOTE_TIME: bool;
@@ -428,6 +479,80 @@ pub trait TnuaAction: 'static + Send + Sync {
         ctx: TnuaActionContext,
         being_fed_for: &Stopwatch,
     ) -> TnuaActionInitiationDirective;
+
+    /// The time, in seconds, after the action ends during which the controller will refuse to
+    /// start it again.
+    ///
+    /// The controller tracks the cooldowns by the action's name. They can be queried with
+    /// [`TnuaController::cooldown_remaining`](crate::prelude::TnuaController::cooldown_remaining).
+    fn cooldown(&self) -> Float {
+        0.0
+    }
+
+    /// Describe the stage the action is at, for animation and debugging.
+    ///
+    /// This is what [`TnuaController::snapshot`](crate::prelude::TnuaController::snapshot) uses
+    /// to describe the action without having to downcast it.
+    fn stage(&self, _state: &Self::State) -> TnuaActionStage {
+        TnuaActionStage::new("Active")
+    }
+
+    /// The maximum speed, along the down direction, at which the character can fall while the
+    /// action is active (e.g. for a glide action).
+    ///
+    /// The cap is enforced even if the character has no
+    /// [`TnuaTerminalVelocity`](crate::controller::TnuaTerminalVelocity) component. If it does
+    /// have one, a cap it sets for the action by name takes precedence over this.
+    fn terminal_velocity(&self, _state: &Self::State) -> Option<Float> {
+        None
+    }
+
+    /// Scale the action according to an analog input (e.g. trigger pressure or stick
+    /// deflection).
+    ///
+    /// `strength` is between 0.0 and 1.0. This is called by
+    /// [`TnuaController::action_with_strength`](crate::prelude::TnuaController::action_with_strength)
+    /// before the action is fed. The default implementation ignores it.
+    fn apply_strength(&mut self, _strength: Float) {}
+}
+
+/// The stage an action is at. See [`TnuaAction::stage`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TnuaActionStage {
+    /// The name of the stage (e.g. `"Sinking"` or `"Maintaining"` for crouching).
+    pub name: &'static str,
+
+    /// How far the action is through the stage, from 0.0 to 1.0, if the action can tell.
+    pub progress: Option<Float>,
+
+    /// The expected time, in seconds, until the stage ends, if the action can tell.
+    ///
+    /// This is a prediction, based on the current motion of the character - it can change from
+    /// frame to frame (e.g. when the player releases the jump button).
+    pub remaining: Option<Float>,
+}
+
+impl TnuaActionStage {
+    /// A stage without progress information.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            progress: None,
+            remaining: None,
+        }
+    }
+
+    /// Set the progress through the stage (clamped to between 0.0 and 1.0).
+    pub fn with_progress(mut self, progress: Float) -> Self {
+        self.progress = Some(progress.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Set the expected time, in seconds, until the stage ends.
+    pub fn with_remaining(mut self, remaining: Float) -> Self {
+        self.remaining = Some(remaining.max(0.0));
+        self
+    }
 }
 
 pub trait DynamicAction: Send + Sync + Any + 'static {
@@ -446,6 +571,10 @@ pub trait DynamicAction: Send + Sync + Any + 'static {
         being_fed_for: &Stopwatch,
     ) -> TnuaActionInitiationDirective;
     fn violates_coyote_time(&self) -> bool;
+    fn cooldown(&self) -> Float;
+    fn stage(&self) -> TnuaActionStage;
+    fn terminal_velocity(&self) -> Option<Float>;
+    fn clone_boxed(&self) -> Box<dyn DynamicAction>;
 }
 
 pub(crate) struct BoxableAction<A: TnuaAction> {
@@ -496,4 +625,23 @@ impl<A: TnuaAction> DynamicAction for BoxableAction<A> {
     fn violates_coyote_time(&self) -> bool {
         A::VIOLATES_COYOTE_TIME
     }
+
+    fn cooldown(&self) -> Float {
+        self.input.cooldown()
+    }
+
+    fn stage(&self) -> TnuaActionStage {
+        self.input.stage(&self.state)
+    }
+
+    fn terminal_velocity(&self) -> Option<Float> {
+        self.input.terminal_velocity(&self.state)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn DynamicAction> {
+        Box::new(Self {
+            input: self.input.clone(),
+            state: self.state.clone(),
+        })
+    }
 }
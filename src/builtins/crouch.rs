@@ -5,7 +5,7 @@ use crate::control_helpers::TnuaCrouchEnforcedAction;
 use crate::{TnuaAction, TnuaMotor, TnuaVelChange};
 use crate::{
     TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
-    TnuaActionLifecycleStatus,
+    TnuaActionLifecycleStatus, TnuaActionStage,
 };
 
 use super::TnuaBuiltinWalk;
@@ -22,7 +22,12 @@ use super::TnuaBuiltinWalk;
 /// upward toward the obstacle - which will bring about undesired physics behavior (especially if
 /// the player tries to move). To prevent that, use this action together with
 /// [`TnuaCrouchEnforcer`](crate::control_helpers::TnuaCrouchEnforcer).
-#[derive(Clone)]
+#[derive(Clone, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct TnuaBuiltinCrouch {
     /// Controls how low the character will crouch, compared to its regular float offset while
     /// standing.
@@ -69,6 +74,18 @@ impl TnuaAction for TnuaBuiltinCrouch {
     type State = TnuaBuiltinCrouchState;
     const VIOLATES_COYOTE_TIME: bool = false;
 
+    fn apply_strength(&mut self, strength: Float) {
+        self.float_offset *= strength;
+    }
+
+    fn stage(&self, state: &Self::State) -> TnuaActionStage {
+        TnuaActionStage::new(match state {
+            TnuaBuiltinCrouchState::Sinking => "Sinking",
+            TnuaBuiltinCrouchState::Maintaining => "Maintaining",
+            TnuaBuiltinCrouchState::Rising => "Rising",
+        })
+    }
+
     fn initiation_decision(
         &self,
         ctx: TnuaActionContext,
@@ -95,7 +112,8 @@ impl TnuaAction for TnuaBuiltinCrouch {
         let Some(sensor_output) = &ctx.proximity_sensor.output else {
             return TnuaActionLifecycleDirective::Reschedule { after_seconds: 0.0 };
         };
-        let spring_offset_up = walk_basis.float_height - sensor_output.proximity.adjust_precision();
+        let spring_offset_up =
+            walk_state.effective_float_height() - sensor_output.proximity.adjust_precision();
         let spring_offset_down =
             spring_offset_up.adjust_precision() + self.float_offset.adjust_precision();
 
@@ -180,7 +198,8 @@ impl TnuaBuiltinCrouch {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TnuaBuiltinCrouchState {
     /// The character is transitioning from standing to crouching.
     #[default]
@@ -4,11 +4,16 @@ use bevy::prelude::*;
 use crate::util::rotation_arc_around_axis;
 use crate::{
     prelude::*, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
-    TnuaActionLifecycleStatus, TnuaMotor,
+    TnuaActionLifecycleStatus, TnuaActionStage, TnuaMotor,
 };
 
 /// The basic dash [action](TnuaAction).
-#[derive(Clone)]
+#[derive(Clone, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct TnuaBuiltinDash {
     /// The direction and distance of the dash.
     ///
@@ -45,6 +50,9 @@ pub struct TnuaBuiltinDash {
     /// possible (typically when a character is still in the air and about the land) and the dash
     /// action would still get registered and be executed once the dash is possible.
     pub input_buffer_time: Float,
+
+    /// The time, in seconds, after the dash ends during which another dash cannot start.
+    pub cooldown: Float,
 }
 
 impl Default for TnuaBuiltinDash {
@@ -58,6 +66,7 @@ impl Default for TnuaBuiltinDash {
             acceleration: 400.0,
             brake_acceleration: 200.0,
             input_buffer_time: 0.2,
+            cooldown: 0.0,
         }
     }
 }
@@ -67,6 +76,30 @@ impl TnuaAction for TnuaBuiltinDash {
     type State = TnuaBuiltinDashState;
     const VIOLATES_COYOTE_TIME: bool = true;
 
+    fn apply_strength(&mut self, strength: Float) {
+        self.displacement *= strength;
+    }
+
+    fn stage(&self, state: &Self::State) -> TnuaActionStage {
+        match state {
+            TnuaBuiltinDashState::PreDash => TnuaActionStage::new("PreDash"),
+            TnuaBuiltinDashState::During {
+                remaining_distance, ..
+            } => {
+                let mut stage = TnuaActionStage::new("During");
+                let total_distance = self.displacement.length();
+                if 0.0 < total_distance {
+                    stage = stage.with_progress(1.0 - remaining_distance / total_distance);
+                }
+                if 0.0 < self.speed {
+                    stage = stage.with_remaining(remaining_distance / self.speed);
+                }
+                stage
+            }
+            TnuaBuiltinDashState::Braking { .. } => TnuaActionStage::new("Braking"),
+        }
+    }
+
     fn initiation_decision(
         &self,
         ctx: crate::TnuaActionContext,
@@ -84,6 +117,10 @@ impl TnuaAction for TnuaBuiltinDash {
         }
     }
 
+    fn cooldown(&self) -> Float {
+        self.cooldown
+    }
+
     fn apply(
         &self,
         state: &mut Self::State,
@@ -104,6 +141,7 @@ impl TnuaAction for TnuaBuiltinDash {
                         destination: ctx.tracker.translation + self.displacement,
                         desired_forward: self.desired_forward,
                         consider_blocked_if_speed_is_less_than: Float::NEG_INFINITY,
+                        remaining_distance: self.displacement.length(),
                     };
                     continue;
                 }
@@ -112,10 +150,12 @@ impl TnuaAction for TnuaBuiltinDash {
                     destination,
                     desired_forward,
                     consider_blocked_if_speed_is_less_than,
+                    remaining_distance,
                 } => {
                     let distance_to_destination = direction
                         .adjust_precision()
                         .dot(*destination - ctx.tracker.translation);
+                    *remaining_distance = distance_to_destination.max(0.0);
                     if distance_to_destination < 0.0 {
                         *state = TnuaBuiltinDashState::Braking {
                             direction: *direction,
@@ -147,7 +187,7 @@ impl TnuaAction for TnuaBuiltinDash {
                         let up = up.adjust_precision();
                         let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
                         let rotation_along_up_axis = rotation_arc_around_axis(
-                            Dir3::Y,
+                            ctx.up_direction,
                             current_forward,
                             desired_forward.adjust_precision(),
                         )
@@ -178,7 +218,8 @@ impl TnuaAction for TnuaBuiltinDash {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TnuaBuiltinDashState {
     #[default]
     PreDash,
@@ -187,6 +228,8 @@ pub enum TnuaBuiltinDashState {
         destination: Vector3,
         desired_forward: Option<Dir3>,
         consider_blocked_if_speed_is_less_than: Float,
+        /// The distance, along the dash direction, left until the destination.
+        remaining_distance: Float,
     },
     Braking {
         direction: Dir3,
@@ -1,10 +1,10 @@
 use crate::math::{AdjustPrecision, Float, Vector3};
 use bevy::prelude::*;
 
-use crate::util::SegmentedJumpInitialVelocityCalculator;
+use crate::util::{SegmentedJumpInitialVelocityCalculator, TnuaCurve};
 use crate::{
     TnuaAction, TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
-    TnuaActionLifecycleStatus,
+    TnuaActionLifecycleStatus, TnuaActionStage,
 };
 
 /// The basic jump [action](TnuaAction).
@@ -16,8 +16,14 @@ use crate::{
 /// The action must be fed for as long as the player holds the jump button. Once the action stops
 /// being fed, it'll apply extra gravity to shorten the jump. If the game desires fixed height
 /// jumps instead (where the player cannot make lower jumps by tapping the jump button)
-/// [`shorten_extra_gravity`](Self::shorten_extra_gravity) should be set to `0.0`.
-#[derive(Clone)]
+/// [`shorten_extra_gravity`](Self::shorten_extra_gravity) should be set to `0.0`. For finer
+/// control over how high early-released jumps go, use [`release_curve`](Self::release_curve).
+#[derive(Clone, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct TnuaBuiltinJump {
     /// The height the character will jump to.
     ///
@@ -66,6 +72,28 @@ pub struct TnuaBuiltinJump {
     /// **NOTE**: This force will be added to the normal gravity.
     pub shorten_extra_gravity: Float,
 
+    /// Shapes the height of jumps that were released early.
+    ///
+    /// Maps how long the jump was held - as a fraction of the time it takes the full jump to
+    /// reach its peak - to the fraction of [`height`](Self::height) the character will reach. For
+    /// example, a curve through `(0.0, 0.3)` and `(1.0, 1.0)` makes a tap jump to 30% of the full
+    /// height, and releasing halfway through the rise jump to 65% of it.
+    ///
+    /// When set, releasing the jump applies exactly the extra gravity needed for peaking at that
+    /// height instead of [`shorten_extra_gravity`](Self::shorten_extra_gravity). Releasing after
+    /// the character already passed that height stops its ascent immediately.
+    pub release_curve: Option<TnuaCurve>,
+
+    /// How much of the velocity of the ground, perpendicular to the up direction, the character
+    /// keeps when it jumps.
+    pub inherit_horizontal_platform_velocity: TnuaBuiltinJumpVelocityInheritance,
+
+    /// How much of the velocity of the ground, along the up direction, the character keeps when
+    /// it jumps.
+    ///
+    /// The jump's own upward velocity is always added on top of the inherited velocity.
+    pub inherit_vertical_platform_velocity: TnuaBuiltinJumpVelocityInheritance,
+
     /// Used to decrease the time the character spends "floating" at the peak of the jump.
     ///
     /// When the character's upward velocity is above this value,
@@ -111,6 +139,9 @@ impl Default for TnuaBuiltinJump {
             takeoff_above_velocity: 2.0,
             fall_extra_gravity: 20.0,
             shorten_extra_gravity: 60.0,
+            release_curve: None,
+            inherit_horizontal_platform_velocity: TnuaBuiltinJumpVelocityInheritance::Full,
+            inherit_vertical_platform_velocity: TnuaBuiltinJumpVelocityInheritance::Full,
             peak_prevention_at_upward_velocity: 1.0,
             peak_prevention_extra_gravity: 20.0,
             reschedule_cooldown: None,
@@ -124,6 +155,26 @@ impl TnuaAction for TnuaBuiltinJump {
     type State = TnuaBuiltinJumpState;
     const VIOLATES_COYOTE_TIME: bool = true;
 
+    fn apply_strength(&mut self, strength: Float) {
+        self.height *= strength;
+    }
+
+    fn stage(&self, state: &Self::State) -> TnuaActionStage {
+        let stage = TnuaActionStage::new(match state {
+            TnuaBuiltinJumpState::NoJump => "NoJump",
+            TnuaBuiltinJumpState::StartingJump { .. } => "StartingJump",
+            TnuaBuiltinJumpState::SlowDownTooFastSlopeJump { .. } => "SlowDownTooFastSlopeJump",
+            TnuaBuiltinJumpState::MaintainingJump { .. } => "MaintainingJump",
+            TnuaBuiltinJumpState::StoppedMaintainingJump { .. } => "StoppedMaintainingJump",
+            TnuaBuiltinJumpState::FallSection => "FallSection",
+        });
+        if let Some(time_to_apex) = state.predicted_time_to_apex() {
+            stage.with_remaining(time_to_apex)
+        } else {
+            stage
+        }
+    }
+
     fn initiation_decision(
         &self,
         ctx: TnuaActionContext,
@@ -149,19 +200,8 @@ impl TnuaAction for TnuaBuiltinJump {
         let up = ctx.up_direction.adjust_precision();
 
         if lifecycle_status.just_started() {
-            let mut calculator = SegmentedJumpInitialVelocityCalculator::new(self.height);
-            let gravity = ctx.tracker.gravity.dot(-up);
-            let kinetic_energy = calculator
-                .add_segment(
-                    gravity + self.peak_prevention_extra_gravity,
-                    self.peak_prevention_at_upward_velocity,
-                )
-                .add_segment(gravity, self.takeoff_above_velocity)
-                .add_final_segment(gravity + self.takeoff_extra_gravity)
-                .kinetic_energy()
-                .expect("`add_final_segment` should have covered remaining height");
             *state = TnuaBuiltinJumpState::StartingJump {
-                desired_energy: kinetic_energy,
+                desired_energy: self.desired_energy(ctx.tracker.gravity.dot(-up)),
             };
         }
 
@@ -192,12 +232,37 @@ impl TnuaAction for TnuaBuiltinJump {
                     let relative_velocity =
                         effective_velocity.dot(up) - ctx.basis.vertical_velocity().max(0.0);
 
+                    // The velocity the character has because of the ground it stands on.
+                    let platform_velocity = ctx.tracker.velocity - effective_velocity;
+                    let platform_upward_velocity = platform_velocity.dot(up);
+                    let inherited_upward_velocity = self
+                        .inherit_vertical_platform_velocity
+                        .apply(platform_upward_velocity * up)
+                        .dot(up);
+
                     motor.lin.cancel_on_axis(up);
-                    motor.lin.boost += (desired_upward_velocity - relative_velocity) * up;
+                    motor.lin.boost += (desired_upward_velocity - relative_velocity
+                        + inherited_upward_velocity
+                        - platform_upward_velocity)
+                        * up;
                     if 0.0 <= extra_height {
+                        // Unlike the upward boost, which is recalculated every frame, this changes
+                        // the velocity the basis works against - so it must only be applied once.
+                        let platform_horizontal_velocity = platform_velocity.reject_from(up);
+                        motor.lin.boost += self
+                            .inherit_horizontal_platform_velocity
+                            .apply(platform_horizontal_velocity)
+                            - platform_horizontal_velocity;
+                        let zero_potential_energy_at = ctx.tracker.translation - extra_height * up;
                         *state = TnuaBuiltinJumpState::SlowDownTooFastSlopeJump {
                             desired_energy: *desired_energy,
-                            zero_potential_energy_at: ctx.tracker.translation - extra_height * up,
+                            zero_potential_energy_at,
+                            held_for: 0.0,
+                            apex: zero_potential_energy_at + self.height * up,
+                            time_to_apex: rise_time(
+                                desired_upward_velocity,
+                                &self.maintained_rise_segments(gravity),
+                            ),
                         };
                     }
                     self.directive_simple_or_reschedule(lifecycle_status)
@@ -205,13 +270,16 @@ impl TnuaAction for TnuaBuiltinJump {
                 TnuaBuiltinJumpState::SlowDownTooFastSlopeJump {
                     desired_energy,
                     zero_potential_energy_at,
+                    held_for,
+                    apex: _,
+                    time_to_apex,
                 } => {
                     let upward_velocity = up.dot(effective_velocity);
                     if upward_velocity <= ctx.basis.vertical_velocity() {
                         *state = TnuaBuiltinJumpState::FallSection;
                         continue;
                     } else if !lifecycle_status.is_active() {
-                        *state = TnuaBuiltinJumpState::StoppedMaintainingJump;
+                        *state = self.released_state(&ctx, *zero_potential_energy_at, *held_for);
                         continue;
                     }
                     let relative_velocity = effective_velocity.dot(up);
@@ -225,9 +293,21 @@ impl TnuaAction for TnuaBuiltinJump {
                             desired_kinetic_energy,
                         );
                     if relative_velocity <= desired_upward_velocity {
-                        *state = TnuaBuiltinJumpState::MaintainingJump;
+                        let rise_segments = self.maintained_rise_segments(gravity);
+                        *state = TnuaBuiltinJumpState::MaintainingJump {
+                            zero_potential_energy_at: *zero_potential_energy_at,
+                            held_for: *held_for,
+                            apex: ctx.tracker.translation
+                                + rise_height(relative_velocity, &rise_segments) * up,
+                            time_to_apex: rise_time(relative_velocity, &rise_segments),
+                        };
                         continue;
                     } else {
+                        *held_for += ctx.frame_duration;
+                        *time_to_apex = rise_time(
+                            desired_upward_velocity,
+                            &self.maintained_rise_segments(gravity),
+                        );
                         let mut extra_gravity = self.upslope_extra_gravity;
                         if self.takeoff_above_velocity <= relative_velocity {
                             extra_gravity += self.takeoff_extra_gravity;
@@ -237,8 +317,22 @@ impl TnuaAction for TnuaBuiltinJump {
                         self.directive_simple_or_reschedule(lifecycle_status)
                     }
                 }
-                TnuaBuiltinJumpState::MaintainingJump => {
+                TnuaBuiltinJumpState::MaintainingJump {
+                    zero_potential_energy_at,
+                    held_for,
+                    apex,
+                    time_to_apex,
+                } => {
+                    let zero_potential_energy_at = *zero_potential_energy_at;
+                    if lifecycle_status.is_active() {
+                        *held_for += ctx.frame_duration;
+                    }
+                    let held_for = *held_for;
                     let relevant_upward_velocity = effective_velocity.dot(up);
+                    let rise_segments = self.maintained_rise_segments(ctx.tracker.gravity.dot(-up));
+                    *apex = ctx.tracker.translation
+                        + rise_height(relevant_upward_velocity, &rise_segments) * up;
+                    *time_to_apex = rise_time(relevant_upward_velocity, &rise_segments);
                     if relevant_upward_velocity <= 0.0 {
                         *state = TnuaBuiltinJumpState::FallSection;
                         motor.lin.cancel_on_axis(up);
@@ -258,19 +352,23 @@ impl TnuaAction for TnuaBuiltinJump {
                         }
                         TnuaActionLifecycleStatus::CancelledInto => self.finish_or_reschedule(),
                         TnuaActionLifecycleStatus::NoLongerFed => {
-                            *state = TnuaBuiltinJumpState::StoppedMaintainingJump;
+                            *state = self.released_state(&ctx, zero_potential_energy_at, held_for);
                             TnuaActionLifecycleDirective::StillActive
                         }
                     }
                 }
-                TnuaBuiltinJumpState::StoppedMaintainingJump => {
+                TnuaBuiltinJumpState::StoppedMaintainingJump {
+                    apex,
+                    from_release_curve,
+                    time_to_apex,
+                } => {
                     if matches!(lifecycle_status, TnuaActionLifecycleStatus::CancelledInto) {
                         self.finish_or_reschedule()
                     } else {
                         let landed = ctx
                             .basis
                             .displacement()
-                            .map_or(false, |displacement| displacement.dot(up) <= 0.0);
+                            .is_some_and(|displacement| displacement.dot(up) <= 0.0);
                         if landed {
                             self.finish_or_reschedule()
                         } else {
@@ -280,10 +378,31 @@ impl TnuaAction for TnuaBuiltinJump {
                                 continue;
                             }
 
-                            let extra_gravity = if self.takeoff_above_velocity <= upward_velocity {
-                                self.shorten_extra_gravity + self.takeoff_extra_gravity
+                            let gravity = ctx.tracker.gravity.dot(-up);
+                            let extra_gravity = if *from_release_curve {
+                                let height_to_apex = (*apex - ctx.tracker.translation).dot(up);
+                                if height_to_apex <= 0.0 {
+                                    motor.lin.cancel_on_axis(up);
+                                    motor.lin.boost -= upward_velocity * up;
+                                    *state = TnuaBuiltinJumpState::FallSection;
+                                    return TnuaActionLifecycleDirective::StillActive;
+                                }
+                                // With a constant deceleration, the average speed until the apex
+                                // is half the current speed.
+                                *time_to_apex = 2.0 * height_to_apex / upward_velocity;
+                                // The deceleration that stops the character exactly at the apex.
+                                (upward_velocity.powi(2) / (2.0 * height_to_apex) - gravity)
+                                    .max(0.0)
                             } else {
-                                self.shorten_extra_gravity
+                                let rise_segments = self.shortened_rise_segments(gravity);
+                                *apex = ctx.tracker.translation
+                                    + rise_height(upward_velocity, &rise_segments) * up;
+                                *time_to_apex = rise_time(upward_velocity, &rise_segments);
+                                if self.takeoff_above_velocity <= upward_velocity {
+                                    self.shorten_extra_gravity + self.takeoff_extra_gravity
+                                } else {
+                                    self.shorten_extra_gravity
+                                }
                             };
 
                             motor.lin.cancel_on_axis(up);
@@ -296,7 +415,7 @@ impl TnuaAction for TnuaBuiltinJump {
                     let landed = ctx
                         .basis
                         .displacement()
-                        .map_or(false, |displacement| displacement.dot(up) <= 0.0);
+                        .is_some_and(|displacement| displacement.dot(up) <= 0.0);
                     if landed
                         || matches!(lifecycle_status, TnuaActionLifecycleStatus::CancelledInto)
                     {
@@ -315,6 +434,148 @@ impl TnuaAction for TnuaBuiltinJump {
 }
 
 impl TnuaBuiltinJump {
+    fn desired_energy(&self, gravity: Float) -> Float {
+        SegmentedJumpInitialVelocityCalculator::new(self.height)
+            .add_segment(
+                gravity + self.peak_prevention_extra_gravity,
+                self.peak_prevention_at_upward_velocity,
+            )
+            .add_segment(gravity, self.takeoff_above_velocity)
+            .add_final_segment(gravity + self.takeoff_extra_gravity)
+            .kinetic_energy()
+            .expect("`add_final_segment` should have covered remaining height")
+    }
+
+    /// The upper bounds of the upward velocity ranges, and the deceleration in each of them, while
+    /// the jump is maintained.
+    fn maintained_rise_segments(&self, gravity: Float) -> [(Float, Float); 3] {
+        [
+            (
+                self.peak_prevention_at_upward_velocity,
+                gravity + self.peak_prevention_extra_gravity,
+            ),
+            (
+                self.takeoff_above_velocity
+                    .max(self.peak_prevention_at_upward_velocity),
+                gravity,
+            ),
+            (Float::INFINITY, gravity + self.takeoff_extra_gravity),
+        ]
+    }
+
+    /// Like [`maintained_rise_segments`](Self::maintained_rise_segments), but for a jump that was
+    /// released without a [`release_curve`](Self::release_curve).
+    fn shortened_rise_segments(&self, gravity: Float) -> [(Float, Float); 2] {
+        [
+            (
+                self.takeoff_above_velocity,
+                gravity + self.shorten_extra_gravity,
+            ),
+            (
+                Float::INFINITY,
+                gravity + self.shorten_extra_gravity + self.takeoff_extra_gravity,
+            ),
+        ]
+    }
+
+    fn released_state(
+        &self,
+        ctx: &TnuaActionContext,
+        zero_potential_energy_at: Vector3,
+        held_for: Float,
+    ) -> TnuaBuiltinJumpState {
+        let up = ctx.up_direction.adjust_precision();
+        let gravity = ctx.tracker.gravity.dot(-up);
+        if let Some(release_curve) = &self.release_curve {
+            let full_rise_time = rise_time(
+                SegmentedJumpInitialVelocityCalculator::kinetic_energy_to_velocity(
+                    self.desired_energy(gravity),
+                ),
+                &self.maintained_rise_segments(gravity),
+            );
+            let hold_fraction = if 0.0 < full_rise_time {
+                held_for / full_rise_time
+            } else {
+                1.0
+            };
+            let height_fraction = release_curve.sample(hold_fraction).clamp(0.0, 1.0);
+            let apex = zero_potential_energy_at + height_fraction * self.height * up;
+            let upward_velocity = ctx.basis.effective_velocity().dot(up);
+            let height_to_apex = (apex - ctx.tracker.translation).dot(up);
+            TnuaBuiltinJumpState::StoppedMaintainingJump {
+                apex,
+                from_release_curve: true,
+                time_to_apex: if 0.0 < upward_velocity && 0.0 < height_to_apex {
+                    2.0 * height_to_apex / upward_velocity
+                } else {
+                    0.0
+                },
+            }
+        } else {
+            let upward_velocity = ctx.basis.effective_velocity().dot(up);
+            let rise_segments = self.shortened_rise_segments(gravity);
+            TnuaBuiltinJumpState::StoppedMaintainingJump {
+                apex: ctx.tracker.translation + rise_height(upward_velocity, &rise_segments) * up,
+                from_release_curve: false,
+                time_to_apex: rise_time(upward_velocity, &rise_segments),
+            }
+        }
+    }
+
+    /// Simulate the trajectory of the jump, e.g. for drawing a jump arc preview.
+    ///
+    /// The simulation applies the same extra gravities the jump applies, assuming that:
+    ///
+    /// * The jump starts from the float height, with no vertical velocity.
+    /// * The jump action is fed for the entire jump (so it reaches its full
+    ///   [`height`](Self::height)).
+    /// * The horizontal velocity does not change during the jump.
+    ///
+    /// Yields `(time, position)` pairs, starting at `start` with time 0 and advancing by
+    /// `time_step` seconds. The iterator is endless - the caller should stop it when the
+    /// trajectory reaches the ground (e.g. by comparing it to the result of
+    /// [`TnuaSpatialExt::predict_landing`](crate::spatial_ext::TnuaSpatialExt::predict_landing))
+    /// or after enough time has passed.
+    pub fn trajectory(
+        &self,
+        start: Vector3,
+        horizontal_velocity: Vector3,
+        gravity: Vector3,
+        time_step: Float,
+    ) -> impl Iterator<Item = (Float, Vector3)> {
+        let gravity_strength = gravity.length();
+        let up = if 0.0 < gravity_strength {
+            -gravity / gravity_strength
+        } else {
+            Vector3::Y
+        };
+        let horizontal_velocity = horizontal_velocity.reject_from(up);
+        let mut upward_velocity =
+            SegmentedJumpInitialVelocityCalculator::kinetic_energy_to_velocity(
+                self.desired_energy(gravity_strength),
+            );
+        let time_step = time_step.max(Float::EPSILON);
+        let mut time: Float = 0.0;
+        let mut position = start;
+        let jump = self.clone();
+        std::iter::from_fn(move || {
+            let result = (time, position);
+            let extra_gravity = if upward_velocity <= 0.0 {
+                jump.fall_extra_gravity
+            } else if upward_velocity < jump.peak_prevention_at_upward_velocity {
+                jump.peak_prevention_extra_gravity
+            } else if jump.takeoff_above_velocity <= upward_velocity {
+                jump.takeoff_extra_gravity
+            } else {
+                0.0
+            };
+            upward_velocity -= (gravity_strength + extra_gravity) * time_step;
+            position += (horizontal_velocity + upward_velocity * up) * time_step;
+            time += time_step;
+            Some(result)
+        })
+    }
+
     fn finish_or_reschedule(&self) -> TnuaActionLifecycleDirective {
         if let Some(cooldown) = self.reschedule_cooldown {
             TnuaActionLifecycleDirective::Reschedule {
@@ -337,7 +598,74 @@ impl TnuaBuiltinJump {
     }
 }
 
-#[derive(Default, Debug)]
+/// How much of the velocity of the ground a [`TnuaBuiltinJump`] keeps at takeoff.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaBuiltinJumpVelocityInheritance {
+    /// Lose the velocity of the ground - the jump starts as if the ground was not moving.
+    None,
+    /// Keep the entire velocity of the ground.
+    #[default]
+    Full,
+    /// Keep the velocity of the ground, but not more than this speed.
+    Clamped(Float),
+    /// Keep this fraction of the velocity of the ground.
+    Scaled(Float),
+}
+
+impl TnuaBuiltinJumpVelocityInheritance {
+    /// Calculate the part of the ground's velocity that the character keeps.
+    pub fn apply(&self, platform_velocity: Vector3) -> Vector3 {
+        match self {
+            TnuaBuiltinJumpVelocityInheritance::None => Vector3::ZERO,
+            TnuaBuiltinJumpVelocityInheritance::Full => platform_velocity,
+            TnuaBuiltinJumpVelocityInheritance::Clamped(max_speed) => {
+                platform_velocity.clamp_length_max(max_speed.max(0.0))
+            }
+            TnuaBuiltinJumpVelocityInheritance::Scaled(factor) => *factor * platform_velocity,
+        }
+    }
+}
+
+/// The height a character moving upward with `upward_velocity` will rise until it stops, when the
+/// deceleration is given by `segments` - a list of the upper bounds of upward velocity ranges and
+/// the deceleration in each of them, sorted from the bottom.
+fn rise_height(upward_velocity: Float, segments: &[(Float, Float)]) -> Float {
+    let mut height = 0.0;
+    let mut lower_bound: Float = 0.0;
+    for &(upper_bound, deceleration) in segments {
+        if upward_velocity <= lower_bound {
+            break;
+        }
+        if deceleration <= 0.0 {
+            return Float::INFINITY;
+        }
+        let velocity = upward_velocity.min(upper_bound);
+        height += (velocity.powi(2) - lower_bound.powi(2)) / (2.0 * deceleration);
+        lower_bound = upper_bound;
+    }
+    height
+}
+
+/// Like [`rise_height`], but calculates the time it'll take the character to stop.
+fn rise_time(upward_velocity: Float, segments: &[(Float, Float)]) -> Float {
+    let mut time = 0.0;
+    let mut lower_bound: Float = 0.0;
+    for &(upper_bound, deceleration) in segments {
+        if upward_velocity <= lower_bound {
+            break;
+        }
+        if deceleration <= 0.0 {
+            return Float::INFINITY;
+        }
+        time += (upward_velocity.min(upper_bound) - lower_bound) / deceleration;
+        lower_bound = upper_bound;
+    }
+    time
+}
+
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TnuaBuiltinJumpState {
     #[default]
     NoJump,
@@ -354,8 +682,63 @@ pub enum TnuaBuiltinJumpState {
     SlowDownTooFastSlopeJump {
         desired_energy: Float,
         zero_potential_energy_at: Vector3,
+        /// How long, in seconds, the jump was held since the takeoff.
+        held_for: Float,
+        apex: Vector3,
+        /// The predicted time, in seconds, until the character reaches the apex.
+        time_to_apex: Float,
+    },
+    MaintainingJump {
+        zero_potential_energy_at: Vector3,
+        /// How long, in seconds, the jump was held since the takeoff.
+        held_for: Float,
+        apex: Vector3,
+        /// The predicted time, in seconds, until the character reaches the apex.
+        time_to_apex: Float,
+    },
+    StoppedMaintainingJump {
+        apex: Vector3,
+        /// Whether the apex was decided by the jump's
+        /// [`release_curve`](TnuaBuiltinJump::release_curve), and the character is braking to stop
+        /// exactly at it.
+        from_release_curve: bool,
+        /// The predicted time, in seconds, until the character reaches the apex.
+        time_to_apex: Float,
     },
-    MaintainingJump,
-    StoppedMaintainingJump,
     FallSection,
 }
+
+impl TnuaBuiltinJumpState {
+    /// The position the character's center is expected to reach at the top of the jump.
+    ///
+    /// Only available while the character is going up - not before the takeoff and not after the
+    /// peak. The prediction is updated every frame, so it changes when the player releases the
+    /// jump button.
+    pub fn predicted_apex(&self) -> Option<Vector3> {
+        match self {
+            TnuaBuiltinJumpState::NoJump
+            | TnuaBuiltinJumpState::StartingJump { .. }
+            | TnuaBuiltinJumpState::FallSection => None,
+            TnuaBuiltinJumpState::SlowDownTooFastSlopeJump { apex, .. }
+            | TnuaBuiltinJumpState::MaintainingJump { apex, .. }
+            | TnuaBuiltinJumpState::StoppedMaintainingJump { apex, .. } => Some(*apex),
+        }
+    }
+
+    /// The predicted time, in seconds, until the character reaches the top of the jump.
+    ///
+    /// Like [`predicted_apex`](Self::predicted_apex), this is only available while the character
+    /// is going up, and is updated every frame.
+    pub fn predicted_time_to_apex(&self) -> Option<Float> {
+        match self {
+            TnuaBuiltinJumpState::NoJump
+            | TnuaBuiltinJumpState::StartingJump { .. }
+            | TnuaBuiltinJumpState::FallSection => None,
+            TnuaBuiltinJumpState::SlowDownTooFastSlopeJump { time_to_apex, .. }
+            | TnuaBuiltinJumpState::MaintainingJump { time_to_apex, .. }
+            | TnuaBuiltinJumpState::StoppedMaintainingJump { time_to_apex, .. } => {
+                Some(*time_to_apex)
+            }
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use std::time::Duration;
 
 use crate::{
-    math::{AdjustPrecision, AsF32, Float, Vector3},
+    math::{ops, AdjustPrecision, AsF32, Float, Vector3},
     prelude::*,
     util::rotation_arc_around_axis,
     TnuaActionContext, TnuaActionInitiationDirective, TnuaActionLifecycleDirective,
-    TnuaActionLifecycleStatus, TnuaMotor, TnuaVelChange,
+    TnuaActionLifecycleStatus, TnuaActionStage, TnuaMotor, TnuaVelChange,
 };
 use bevy::prelude::*;
 
@@ -24,7 +24,12 @@ use bevy::prelude::*;
 /// * [`barrier_strength_diminishing`](Self::barrier_strength_diminishing). Setting it too low
 ///   makes it very hard for the character to push through the boundary. It starts getting slightly
 ///   weird below 1.0, and really weird below 0.5. Better keep it at above - 1.0 levels.
-#[derive(Clone)]
+#[derive(Clone, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct TnuaBuiltinKnockback {
     /// Initial impulse to apply to the character before the Pushover stage starts.
     ///
@@ -84,6 +89,17 @@ impl TnuaAction for TnuaBuiltinKnockback {
     type State = TnuaBuiltinKnockbackState;
     const VIOLATES_COYOTE_TIME: bool = true;
 
+    fn apply_strength(&mut self, strength: Float) {
+        self.shove *= strength;
+    }
+
+    fn stage(&self, state: &Self::State) -> TnuaActionStage {
+        TnuaActionStage::new(match state {
+            TnuaBuiltinKnockbackState::Shove => "Shove",
+            TnuaBuiltinKnockbackState::Pushback { .. } => "Pushback",
+        })
+    }
+
     fn apply(
         &self,
         state: &mut Self::State,
@@ -178,7 +194,8 @@ impl TnuaAction for TnuaBuiltinKnockback {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TnuaBuiltinKnockbackState {
     /// Applying the [`shove`](TnuaBuiltinKnockback::shove) impulse to the character.
     #[default]
@@ -191,6 +208,8 @@ pub enum TnuaBuiltinKnockbackState {
 
 /// An indication that a character was knocked back and "struggles" to get back to its original
 /// velocity.
+#[derive(Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct VelocityBoundary {
     base: Float,
     original_frontier: Float,
@@ -303,7 +322,7 @@ impl VelocityBoundary {
 
         let total_boost = boost_outside_barrier + boost_inside_barrier;
 
-        let barrier_strength = self.percentage_left().powf(barrier_strength_diminishing);
+        let barrier_strength = ops::powf(self.percentage_left(), barrier_strength_diminishing);
         let total_boost = (1.0 - barrier_strength) * boost + barrier_strength * total_boost;
 
         Some((-self.direction, -total_boost))
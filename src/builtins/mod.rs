@@ -3,9 +3,15 @@ mod dash;
 mod jump;
 mod knockback;
 mod walk;
+mod walk_animation_params;
 
 pub use crouch::{TnuaBuiltinCrouch, TnuaBuiltinCrouchState};
 pub use dash::{TnuaBuiltinDash, TnuaBuiltinDashState};
-pub use jump::{TnuaBuiltinJump, TnuaBuiltinJumpState};
+pub use jump::{TnuaBuiltinJump, TnuaBuiltinJumpState, TnuaBuiltinJumpVelocityInheritance};
 pub use knockback::{TnuaBuiltinKnockback, TnuaBuiltinKnockbackState};
-pub use walk::{TnuaBuiltinWalk, TnuaBuiltinWalkState};
+pub use walk::{
+    TnuaBuiltinWalk, TnuaBuiltinWalkFacing, TnuaBuiltinWalkInputConditioning,
+    TnuaBuiltinWalkSpringTuning, TnuaBuiltinWalkState,
+};
+pub(crate) use walk_animation_params::update_walk_animation_params;
+pub use walk_animation_params::TnuaAnimationParams;
@@ -1,11 +1,14 @@
 use std::time::Duration;
 
-use crate::math::{float_consts, AdjustPrecision, AsF32, Float, Quaternion, Vector3};
+use crate::math::{float_consts, ops, AdjustPrecision, AsF32, Float, Quaternion, Vector3};
 use bevy::prelude::*;
 
-use crate::util::rotation_arc_around_axis;
+use crate::util::{rotation_arc_around_axis, signed_angle_around_axis, TnuaCurve};
 use crate::TnuaBasisContext;
-use crate::{TnuaBasis, TnuaVelChange};
+use crate::{
+    TnuaBasis, TnuaGroundClass, TnuaGroundReaction, TnuaProximitySensorOutput,
+    TnuaRigidBodyTracker, TnuaVelChange,
+};
 
 /// The most common [basis](TnuaBasis) - walk around as a floating capsule.
 ///
@@ -32,19 +35,33 @@ use crate::{TnuaBasis, TnuaVelChange};
 ///   leaving `desired_forward` as the default `Vector3::ZERO` may cause the character to spin
 ///   uncontrollably when it contacts other colliders. Unless, of course, some other mechanism
 ///   prevents that.
-#[derive(Clone)]
+#[derive(Clone, Reflect)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct TnuaBuiltinWalk {
     /// The direction (in the world space) and speed to accelerate to.
     ///
     /// Tnua assumes that this vector is orthogonal to the up dierction.
+    ///
+    /// Ignored when the character is driven by
+    /// [`TnuaRootMotion`](crate::controller::TnuaRootMotion).
     pub desired_velocity: Vector3,
 
     /// If non-zero, Tnua will rotate the character so that its negative Z will face in that
     /// direction.
     ///
     /// Tnua assumes that this vector is orthogonal to the up direction.
+    ///
+    /// With [`TnuaBuiltinWalkFacing::Movement`], this is only used while the character is not
+    /// moving.
     pub desired_forward: Option<Dir3>,
 
+    /// How the character chooses the direction to face.
+    pub facing: TnuaBuiltinWalkFacing,
+
     /// The height at which the character will float above ground at rest.
     ///
     /// Note that this is the height of the character's center of mass - not the distance from its
@@ -52,8 +69,22 @@ pub struct TnuaBuiltinWalk {
     ///
     /// To make a character crouch, instead of altering this field, prefer to use the
     /// [`TnuaBuiltinCrouch`](crate::builtins::TnuaBuiltinCrouch) action.
+    ///
+    /// This height is multiplied by the character's scale along the axis of its body that points
+    /// up (see [`TnuaBuiltinWalkState::effective_float_height`]), so it does not need to be
+    /// adjusted when the character grows or shrinks.
     pub float_height: Float,
 
+    /// The time, in seconds, it takes the character to move to a new
+    /// [`float_height`](Self::float_height) when it changes at runtime.
+    ///
+    /// When this is zero, changes to the float height take effect immediately - which can make
+    /// the character pop up or drop down. Otherwise the target height is eased toward the new
+    /// value over this duration. While rising, the eased height does not get too far ahead of the
+    /// character's actual height, so if something above blocks it the character will not be
+    /// pushed into the obstruction and will resume rising once it is cleared.
+    pub float_height_change_duration: Float,
+
     /// Extra distance above the `float_height` where the spring is still in effect.
     ///
     /// When the character is at at most this distance above the
@@ -75,21 +106,85 @@ pub struct TnuaBuiltinWalk {
     ///
     /// Note that as this approaches 2.0, the character starts to shake violently and eventually
     /// get launched upward at great speed.
+    ///
+    /// Instead of tuning this and [`spring_strengh`](Self::spring_strengh) by hand, consider
+    /// calculating both with [`TnuaBuiltinWalkSpringTuning`].
     pub spring_dampening: Float,
 
     /// The acceleration for horizontal movement.
     ///
     /// Note that this is the acceleration for starting the horizontal motion and for reaching the
     /// top speed. When braking or changing direction the acceleration is greater, up to 2 times
-    /// `acceleration` when doing a 180 turn.
+    /// `acceleration` when doing a 180 turn. Use
+    /// [`acceleration_curve`](Self::acceleration_curve) to shape it differently.
     pub acceleration: Float,
 
+    /// Shapes the horizontal acceleration by how fast the character already moves toward
+    /// [`desired_velocity`](Self::desired_velocity).
+    ///
+    /// The curve maps the speed ratio - the character's velocity in the direction of
+    /// `desired_velocity` divided by its length - to a factor of [`acceleration`](Self::acceleration)
+    /// (or of [`air_acceleration`](Self::air_acceleration) while in the air). A ratio of `0.0`
+    /// means starting from standstill, `1.0` means moving at the desired speed, negative ratios
+    /// mean turning around and ratios above `1.0` mean slowing down to the desired speed. For
+    /// example, a curve through `(-1.0, 3.0)`, `(0.0, 2.0)` and `(1.0, 0.5)` gives a snappy
+    /// start, a soft approach to the desired speed and a strong turn-around.
+    ///
+    /// When `None` (the default), or when the character is stopping, the acceleration grows from
+    /// 1 to 2 times its value as the direction changes - see [`acceleration`](Self::acceleration).
+    pub acceleration_curve: Option<TnuaCurve>,
+
     /// The acceleration for horizontal movement while in the air.
     ///
     /// Set to 0.0 to completely disable air movement.
     pub air_acceleration: Float,
 
+    /// The maximum horizontal speed the character will walk at while on the ground.
+    ///
+    /// [`desired_velocity`](Self::desired_velocity) is clamped to this speed. Infinite by default,
+    /// which means the speed is fully determined by `desired_velocity`.
+    pub max_speed: Float,
+
+    /// The maximum horizontal speed the character will steer toward while in the air.
+    ///
+    /// Like [`max_speed`](Self::max_speed), but for airborne characters. This only limits the
+    /// steering - a character that jumped while running faster than that (or was launched by
+    /// something) keeps its speed, and only loses it to the [`air_drag`](Self::air_drag).
+    pub air_max_speed: Float,
+
+    /// Slows down the horizontal movement of the character while in the air, as a fraction of
+    /// the horizontal velocity lost per second.
+    ///
+    /// Unlike the [`air_acceleration`](Self::air_acceleration), the drag also works when the
+    /// player does not steer, and is not limited by it. Set to 0.0 (the default) to disable.
+    pub air_drag: Float,
+
+    /// Prevent the walk from slowing the character down when it moves faster than
+    /// [`desired_velocity`](Self::desired_velocity) in the same direction.
+    ///
+    /// This allows movement styles where the character retains momentum - e.g. bunny hopping, or
+    /// speed gained from slopes, launch pads and dashes. The part of the velocity that is not in
+    /// the direction of `desired_velocity` is still corrected as usual, and when the player stops
+    /// steering the character stops as usual. The extra speed only decays by the
+    /// [`momentum_drag`](Self::momentum_drag) (and the [`air_drag`](Self::air_drag) while in the
+    /// air).
+    pub conserve_momentum: bool,
+
+    /// How fast the speed beyond [`desired_velocity`](Self::desired_velocity) decays when
+    /// [`conserve_momentum`](Self::conserve_momentum) is set, as a fraction of that extra speed
+    /// lost per second.
+    pub momentum_drag: Float,
+
     /// The time, in seconds, the character can still jump after losing their footing.
+    ///
+    /// During this time the character is not considered airborne, so actions that require the
+    /// character to be grounded (like a [`TnuaBuiltinJump`](crate::builtins::TnuaBuiltinJump)
+    /// without [`allow_in_air`](crate::builtins::TnuaBuiltinJump::allow_in_air)) can still start.
+    /// Starting an action that [violates coyote time](crate::TnuaAction::VIOLATES_COYOTE_TIME)
+    /// ends it immediately.
+    ///
+    /// Use [`TnuaBuiltinWalkState::coyote_time_remaining`] to inspect it, and
+    /// [`TnuaBuiltinWalkState::used_coyote_time`] to check if an action was granted thanks to it.
     pub coyote_time: Float,
 
     /// Extra gravity for free fall (fall that's not initiated by a jump or some other action that
@@ -114,11 +209,85 @@ pub struct TnuaBuiltinWalk {
     /// case this paramter is redundant and can be set to 0.0.
     pub tilt_offset_angacl: Float,
 
+    /// How much the character should lean with the ground it stands on, instead of standing
+    /// upright.
+    ///
+    /// 0.0 (the default) keeps the character's up axis aligned to the up direction, and 1.0
+    /// aligns it fully with the ground normal - which is useful for hover vehicles and animal
+    /// characters. Values in between blend the two. Airborne characters always straighten up.
+    ///
+    /// NOTE: This has no effect if the character's rotation is locked.
+    pub ground_alignment: Float,
+
+    /// The maximum angle, in radians, between the up direction and the character's up axis that
+    /// [`ground_alignment`](Self::ground_alignment) can tilt the character.
+    pub max_ground_alignment_tilt: Float,
+
     /// The maximum angular velocity used for turning the character when the direction changes.
     pub turning_angvel: Float,
 
+    /// The maximum angular acceleration used for reaching `turning_angvel`, and for slowing down
+    /// the turn when the character approaches the direction it should face.
+    ///
+    /// Set to infinity (the default) to make the character start and stop turning immediately,
+    /// with the same turning response as versions that did not have this field.
+    pub turning_angacl: Float,
+
     /// The maximum slope, in radians, that the character can stand on without slipping.
+    ///
+    /// Regardless of this value, the character also slips on surfaces that the proximity sensor
+    /// did not classify as [`Walkable`](TnuaGroundClass::Walkable).
     pub max_slope: Float,
+
+    /// Changes the walking speed by the angle of the slope the character walks on.
+    ///
+    /// The curve maps the angle, in radians, of the slope along the direction of
+    /// [`desired_velocity`](Self::desired_velocity) - positive when walking uphill and negative
+    /// when walking downhill - to a factor of the speed. For example, a curve through
+    /// `(-0.5, 1.2)`, `(0.0, 1.0)` and `(0.5, 0.6)` makes the character slower uphill and faster
+    /// downhill. Walking across a slope does not change the speed.
+    ///
+    /// When `None` (the default) the speed is the same regardless of the slope.
+    pub slope_speed_curve: Option<TnuaCurve>,
+
+    /// Prevent the character from walking up slopes steeper than [`max_slope`](Self::max_slope).
+    ///
+    /// The character can still walk across such slopes and down them - only the uphill part of
+    /// [`desired_velocity`](Self::desired_velocity) is removed.
+    pub block_steep_slopes: bool,
+
+    /// Extra distance, beyond the [`cling_distance`](Self::cling_distance), where a character that
+    /// was standing on the ground in the previous frame is pulled back to it instead of becoming
+    /// airborne.
+    ///
+    /// This keeps the character on the ground when cresting a slope or walking down steps. Set
+    /// to 0.0 (the default) to disable snapping.
+    ///
+    /// Snapping never prevents jumps, because the jump actions make the character airborne.
+    pub ground_snap_distance: Float,
+
+    /// The maximum speed, along the up direction and relative to the ground, at which the
+    /// character can be snapped to the ground.
+    ///
+    /// Characters moving faster than that (e.g. launched by an explosion or falling off a
+    /// conveyor) are allowed to become airborne.
+    pub ground_snap_max_speed: Float,
+
+    /// The fraction of the float spring's force that pushes back on the entity the character
+    /// stands on.
+    ///
+    /// When this is positive and the character stands on a dynamic rigid body (e.g. a boat, a
+    /// seesaw or a crate), the physics backend applies the opposite of the spring's force -
+    /// multiplied by this factor - to that body, so that the character's weight pushes it down.
+    /// Set to 1.0 for a realistic reaction, or to 0.0 (the default) to disable it.
+    pub ground_downforce: Float,
+
+    /// Shaping and smoothing of [`desired_velocity`](Self::desired_velocity) and
+    /// [`desired_forward`](Self::desired_forward) before they are used, for when they come
+    /// straight from a gamepad stick.
+    ///
+    /// `None` (the default) uses them as they are.
+    pub input_conditioning: Option<TnuaBuiltinWalkInputConditioning>,
 }
 
 impl Default for TnuaBuiltinWalk {
@@ -126,18 +295,35 @@ impl Default for TnuaBuiltinWalk {
         Self {
             desired_velocity: Vector3::ZERO,
             desired_forward: None,
+            facing: TnuaBuiltinWalkFacing::Strafe,
             float_height: 0.0,
+            float_height_change_duration: 0.0,
             cling_distance: 1.0,
             spring_strengh: 400.0,
             spring_dampening: 1.2,
             acceleration: 60.0,
+            acceleration_curve: None,
             air_acceleration: 20.0,
+            max_speed: Float::INFINITY,
+            air_max_speed: Float::INFINITY,
+            air_drag: 0.0,
+            conserve_momentum: false,
+            momentum_drag: 0.0,
             coyote_time: 0.15,
             free_fall_extra_gravity: 60.0,
             tilt_offset_angvel: 5.0,
             tilt_offset_angacl: 500.0,
+            ground_alignment: 0.0,
+            max_ground_alignment_tilt: float_consts::FRAC_PI_4,
             turning_angvel: 10.0,
+            turning_angacl: Float::INFINITY,
             max_slope: float_consts::FRAC_PI_2,
+            slope_speed_curve: None,
+            block_steep_slopes: false,
+            ground_snap_distance: 0.0,
+            ground_snap_max_speed: 2.0,
+            ground_downforce: 0.0,
+            input_conditioning: None,
         }
     }
 }
@@ -151,13 +337,30 @@ impl TnuaBasis for TnuaBuiltinWalk {
             #[allow(clippy::unnecessary_cast)]
             stopwatch.tick(Duration::from_secs_f64(ctx.frame_duration as f64));
         }
+        self.ease_float_height(state, &ctx);
+        let (conditioned_velocity, conditioned_forward) =
+            self.condition_input(state, ctx.frame_duration);
+        let float_height = state.effective_float_height;
+
+        // Detections beyond the cling distance are only within range for ground snapping.
+        let sensor_output = ctx
+            .proximity_sensor
+            .output
+            .as_ref()
+            .filter(|sensor_output| {
+                sensor_output.proximity <= float_height + self.cling_distance
+                    || self.should_snap_to_ground(state, &ctx, sensor_output)
+            });
+        state.snapping_to_ground = sensor_output.is_some_and(|sensor_output| {
+            float_height + self.cling_distance < sensor_output.proximity
+        });
 
         let climb_vectors: Option<ClimbVectors>;
         let considered_in_air: bool;
         let impulse_to_offset: Vector3;
         let slipping_vector: Option<Vector3>;
 
-        if let Some(sensor_output) = &ctx.proximity_sensor.output {
+        if let Some(sensor_output) = sensor_output {
             state.effective_velocity = ctx.tracker.velocity - sensor_output.entity_linvel;
             let sideways_unnormalized = sensor_output
                 .normal
@@ -180,7 +383,9 @@ impl TnuaBasis for TnuaBuiltinWalk {
                     .normal
                     .angle_between(*ctx.up_direction)
                     .adjust_precision();
-                if angle_with_floor <= self.max_slope {
+                if angle_with_floor <= self.max_slope
+                    && sensor_output.ground_class == TnuaGroundClass::Walkable
+                {
                     None
                 } else {
                     Some(
@@ -233,10 +438,48 @@ impl TnuaBasis for TnuaBuiltinWalk {
             .effective_velocity
             .reject_from(ctx.up_direction.adjust_precision());
 
-        let desired_boost = self.desired_velocity - velocity_on_plane;
+        // Root motion, when available, replaces the user-specified velocity.
+        let desired_velocity = if let Some(root_motion) = ctx.root_motion {
+            root_motion.reject_from(ctx.up_direction.adjust_precision())
+        } else {
+            conditioned_velocity
+        }
+        .clamp_length_max(if considered_in_air {
+            self.air_max_speed
+        } else {
+            self.max_speed
+        });
+
+        let desired_velocity = if let Some(sensor_output) = sensor_output {
+            self.slope_adjusted_velocity(
+                &ctx,
+                desired_velocity,
+                sensor_output.normal.adjust_precision(),
+            )
+        } else {
+            desired_velocity
+        };
+
+        let desired_velocity = if self.conserve_momentum {
+            self.momentum_conserving_velocity(
+                desired_velocity,
+                velocity_on_plane,
+                ctx.frame_duration,
+            )
+        } else {
+            desired_velocity
+        };
+
+        let desired_boost = desired_velocity - velocity_on_plane;
 
-        let safe_direction_coefficient = self
-            .desired_velocity
+        // The controller adds the flow's drag on top of the motor, so the walk needs to offset it
+        // as part of its own (limited) acceleration.
+        let flow_acceleration_on_plane = ctx
+            .external_flow
+            .acceleration_on(ctx.tracker.velocity, ctx.frame_duration)
+            .reject_from(ctx.up_direction.adjust_precision());
+
+        let safe_direction_coefficient = desired_velocity
             .normalize_or_zero()
             .dot(velocity_on_plane.normalize_or_zero());
         let direction_change_factor = 1.5 - 0.5 * safe_direction_coefficient;
@@ -246,7 +489,14 @@ impl TnuaBasis for TnuaBuiltinWalk {
         } else {
             self.acceleration
         };
-        let max_acceleration = direction_change_factor * relevant_acceleration_limit;
+        let acceleration_factor = match (&self.acceleration_curve, desired_velocity.try_normalize())
+        {
+            (Some(acceleration_curve), Some(desired_direction)) => acceleration_curve
+                .sample(velocity_on_plane.dot(desired_direction) / desired_velocity.length())
+                .max(0.0),
+            _ => direction_change_factor,
+        };
+        let max_acceleration = acceleration_factor * relevant_acceleration_limit;
 
         state.vertical_velocity = if let Some(climb_vectors) = &climb_vectors {
             state.effective_velocity.dot(climb_vectors.direction)
@@ -257,10 +507,10 @@ impl TnuaBasis for TnuaBuiltinWalk {
             0.0
         };
 
-        let walk_vel_change = if self.desired_velocity == Vector3::ZERO && slipping_vector.is_none()
-        {
+        let walk_vel_change = if desired_velocity == Vector3::ZERO && slipping_vector.is_none() {
             // When stopping, prefer a boost to be able to reach a precise stop (see issue #39)
-            let walk_boost = desired_boost.clamp_length_max(ctx.frame_duration * max_acceleration);
+            let walk_boost = (desired_boost - flow_acceleration_on_plane * ctx.frame_duration)
+                .clamp_length_max(ctx.frame_duration * max_acceleration);
             let walk_boost = if let Some(climb_vectors) = &climb_vectors {
                 climb_vectors.project(walk_boost)
             } else {
@@ -270,8 +520,9 @@ impl TnuaBasis for TnuaBuiltinWalk {
         } else {
             // When accelerating, prefer an acceleration because the physics backends treat it
             // better (see issue #34)
-            let walk_acceleration =
-                (desired_boost / ctx.frame_duration).clamp_length_max(max_acceleration);
+            let walk_acceleration = (desired_boost / ctx.frame_duration
+                - flow_acceleration_on_plane)
+                .clamp_length_max(max_acceleration);
             let walk_acceleration =
                 if let (Some(climb_vectors), None) = (&climb_vectors, slipping_vector) {
                     climb_vectors.project(walk_acceleration)
@@ -317,6 +568,14 @@ impl TnuaBasis for TnuaBuiltinWalk {
             }
         };
 
+        let walk_vel_change = if considered_in_air && 0.0 < self.air_drag {
+            // Never remove more than the entire velocity in a single frame.
+            let drag_fraction = (self.air_drag * ctx.frame_duration).min(1.0);
+            walk_vel_change + TnuaVelChange::boost(-drag_fraction * velocity_on_plane)
+        } else {
+            walk_vel_change
+        };
+
         let upward_impulse: TnuaVelChange = 'upward_impulse: {
             let should_disable_due_to_slipping =
                 slipping_vector.is_some() && state.vertical_velocity <= 0.0;
@@ -325,11 +584,11 @@ impl TnuaBasis for TnuaBuiltinWalk {
                 match &mut state.airborne_timer {
                     None => {
                         if let (false, Some(sensor_output)) =
-                            (should_disable_due_to_slipping, &ctx.proximity_sensor.output)
+                            (should_disable_due_to_slipping, sensor_output)
                         {
                             // not doing the jump calculation here
                             let spring_offset =
-                                self.float_height - sensor_output.proximity.adjust_precision();
+                                float_height - sensor_output.proximity.adjust_precision();
                             state.standing_offset =
                                 -spring_offset * ctx.up_direction.adjust_precision();
                             break 'upward_impulse self.spring_force(state, &ctx, spring_offset);
@@ -343,10 +602,11 @@ impl TnuaBasis for TnuaBuiltinWalk {
                     }
                     Some(_) => {
                         if let (false, Some(sensor_output)) =
-                            (should_disable_due_to_slipping, &ctx.proximity_sensor.output)
+                            (should_disable_due_to_slipping, sensor_output)
                         {
-                            if sensor_output.proximity.adjust_precision() <= self.float_height {
+                            if sensor_output.proximity.adjust_precision() <= float_height {
                                 state.airborne_timer = None;
+                                state.used_coyote_time = false;
                                 continue;
                             }
                         }
@@ -364,6 +624,25 @@ impl TnuaBasis for TnuaBuiltinWalk {
             TnuaVelChange::ZERO
         };
 
+        motor.ground_reaction = match sensor_output {
+            Some(sensor_output)
+                if 0.0 < self.ground_downforce && state.airborne_timer.is_none() =>
+            {
+                let mut support = upward_impulse.acceleration;
+                if 0.0 < ctx.frame_duration {
+                    support += upward_impulse.boost / ctx.frame_duration;
+                }
+                Some(TnuaGroundReaction {
+                    entity: sensor_output.entity,
+                    point: ctx.tracker.translation
+                        + ctx.tracker.rotation * ctx.proximity_sensor.cast_origin
+                        + sensor_output.proximity
+                            * ctx.proximity_sensor.cast_direction.adjust_precision(),
+                    acceleration: -self.ground_downforce * support,
+                })
+            }
+            _ => None,
+        };
         motor.lin = walk_vel_change + TnuaVelChange::boost(impulse_to_offset) + upward_impulse;
         let new_velocity = state.effective_velocity
             + motor.lin.boost
@@ -376,8 +655,14 @@ impl TnuaBasis for TnuaBuiltinWalk {
         let torque_to_fix_tilt = {
             let tilted_up = ctx.tracker.rotation.mul_vec3(Vector3::Y);
 
-            let rotation_required_to_fix_tilt =
-                Quaternion::from_rotation_arc(tilted_up, ctx.up_direction.adjust_precision());
+            let target_up = match sensor_output {
+                Some(sensor_output) if state.airborne_timer.is_none() => {
+                    self.ground_aligned_up(&ctx, sensor_output.normal.adjust_precision())
+                }
+                _ => ctx.up_direction.adjust_precision(),
+            };
+
+            let rotation_required_to_fix_tilt = Quaternion::from_rotation_arc(tilted_up, target_up);
 
             let desired_angvel = (rotation_required_to_fix_tilt.xyz() / ctx.frame_duration)
                 .clamp_length_max(self.tilt_offset_angvel);
@@ -387,26 +672,47 @@ impl TnuaBasis for TnuaBuiltinWalk {
 
         // Turning
 
-        let desired_angvel = if let Some(desired_forward) = self.desired_forward {
+        // NOTE: This is the regular axis system so we used the configured up.
+        let existing_angvel = ctx.tracker.angvel.dot(ctx.up_direction.adjust_precision());
+
+        let desired_angvel = if let Some(desired_forward) =
+            self.facing_direction(&ctx, conditioned_velocity, conditioned_forward)
+        {
             let current_forward = ctx.tracker.rotation.mul_vec3(Vector3::NEG_Z);
-            let rotation_along_up_axis = rotation_arc_around_axis(
-                ctx.up_direction,
-                current_forward,
-                desired_forward.adjust_precision(),
-            )
-            .unwrap_or(0.0);
-            (rotation_along_up_axis / ctx.frame_duration)
-                .clamp(-self.turning_angvel, self.turning_angvel)
+            if !self.turning_angacl.is_finite() {
+                // Without a turn acceleration, keep the original response - which aims for the
+                // sine of half the remaining angle rather than for the angle itself.
+                let rotation_along_up_axis =
+                    rotation_arc_around_axis(ctx.up_direction, current_forward, desired_forward)
+                        .unwrap_or(0.0);
+                (rotation_along_up_axis / ctx.frame_duration)
+                    .clamp(-self.turning_angvel, self.turning_angvel)
+            } else if let Some(mut turn_angle) =
+                signed_angle_around_axis(ctx.up_direction, current_forward, desired_forward)
+            {
+                // Near a half turn both ways are almost equally short, so keep turning the way the
+                // character is already turning instead of jittering between them.
+                if float_consts::PI - HALF_TURN_HYSTERESIS < turn_angle.abs()
+                    && existing_angvel * turn_angle < 0.0
+                {
+                    turn_angle -= turn_angle.signum() * 2.0 * float_consts::PI;
+                }
+                // The fastest the character can turn while still being able to stop in time.
+                let max_angvel = self
+                    .turning_angvel
+                    .min((2.0 * self.turning_angacl * turn_angle.abs()).sqrt());
+                (turn_angle / ctx.frame_duration).clamp(-max_angvel, max_angvel)
+            } else {
+                0.0
+            }
         } else {
             0.0
         };
 
-        // NOTE: This is the regular axis system so we used the configured up.
-        let existing_angvel = ctx.tracker.angvel.dot(ctx.up_direction.adjust_precision());
-
-        // This is the torque. Should it be clamped by an acceleration? From experimenting with
-        // this I think it's meaningless and only causes bugs.
-        let torque_to_turn = desired_angvel - existing_angvel;
+        // This is the torque. With an infinite `turning_angacl` it is not clamped at all.
+        let max_angvel_change = ctx.frame_duration * self.turning_angacl;
+        let torque_to_turn =
+            (desired_angvel - existing_angvel).clamp(-max_angvel_change, max_angvel_change);
 
         let existing_turn_torque = torque_to_fix_tilt.dot(ctx.up_direction.adjust_precision());
         let torque_to_turn = torque_to_turn - existing_turn_torque;
@@ -416,8 +722,12 @@ impl TnuaBasis for TnuaBuiltinWalk {
         );
     }
 
-    fn proximity_sensor_cast_range(&self, _state: &Self::State) -> Float {
-        self.float_height + self.cling_distance
+    fn proximity_sensor_cast_range(&self, state: &Self::State) -> Float {
+        if state.airborne_timer.is_none() {
+            state.effective_float_height + self.cling_distance + self.ground_snap_distance
+        } else {
+            state.effective_float_height + self.cling_distance
+        }
     }
 
     fn displacement(&self, state: &Self::State) -> Option<Vector3> {
@@ -449,12 +759,225 @@ impl TnuaBasis for TnuaBuiltinWalk {
 
     fn violate_coyote_time(&self, state: &mut Self::State) {
         if let Some(timer) = &mut state.airborne_timer {
+            // A timer that did not tick yet was only created this frame, after the action has
+            // already started.
+            if !timer.finished() && 0.0 < timer.elapsed_secs() {
+                state.used_coyote_time = true;
+            }
             timer.set_duration(Duration::ZERO);
         }
     }
 }
 
 impl TnuaBuiltinWalk {
+    /// Set [`spring_strengh`](Self::spring_strengh) and
+    /// [`spring_dampening`](Self::spring_dampening) using a [`TnuaBuiltinWalkSpringTuning`].
+    pub fn with_spring_tuning(
+        self,
+        tuning: TnuaBuiltinWalkSpringTuning,
+        frame_duration: Float,
+    ) -> Self {
+        let (spring_strengh, spring_dampening) =
+            tuning.spring_strength_and_dampening(frame_duration);
+        Self {
+            spring_strengh,
+            spring_dampening,
+            ..self
+        }
+    }
+
+    fn facing_direction(
+        &self,
+        ctx: &TnuaBasisContext,
+        desired_velocity: Vector3,
+        desired_forward: Option<Vector3>,
+    ) -> Option<Vector3> {
+        match self.facing {
+            TnuaBuiltinWalkFacing::Strafe => {}
+            TnuaBuiltinWalkFacing::Movement => {
+                if let Ok(direction) = Dir3::new(
+                    desired_velocity
+                        .reject_from(ctx.up_direction.adjust_precision())
+                        .f32(),
+                ) {
+                    return Some(direction.adjust_precision());
+                }
+            }
+        }
+        desired_forward
+    }
+
+    /// The desired velocity and forward direction after applying the
+    /// [`input_conditioning`](Self::input_conditioning).
+    fn condition_input(
+        &self,
+        state: &mut TnuaBuiltinWalkState,
+        frame_duration: Float,
+    ) -> (Vector3, Option<Vector3>) {
+        let desired_forward = self
+            .desired_forward
+            .map(|desired_forward| desired_forward.adjust_precision());
+        let Some(conditioning) = self.input_conditioning.as_ref() else {
+            state.conditioned_velocity = self.desired_velocity;
+            state.conditioned_forward = desired_forward;
+            return (self.desired_velocity, desired_forward);
+        };
+
+        let target_velocity = conditioning.shape(self.desired_velocity);
+        let blend = if 0.0 < conditioning.smoothing_time {
+            1.0 - ops::exp(-frame_duration / conditioning.smoothing_time)
+        } else {
+            1.0
+        };
+
+        let velocity = state
+            .conditioned_velocity
+            .lerp(target_velocity, blend.min(1.0));
+        // The smoothed velocity only approaches zero, so snap it once it is inside the deadzone to
+        // let the character actually stop.
+        state.conditioned_velocity = if target_velocity == Vector3::ZERO
+            && velocity.length() <= conditioning.radial_deadzone * conditioning.full_speed
+        {
+            Vector3::ZERO
+        } else {
+            velocity
+        };
+
+        state.conditioned_forward = match (state.conditioned_forward, desired_forward) {
+            (Some(current), Some(target)) => Some(
+                current
+                    .lerp(target, blend.min(1.0))
+                    .try_normalize()
+                    .unwrap_or(target),
+            ),
+            (_, target) => target,
+        };
+
+        (state.conditioned_velocity, state.conditioned_forward)
+    }
+
+    fn ground_aligned_up(&self, ctx: &TnuaBasisContext, ground_normal: Vector3) -> Vector3 {
+        let up = ctx.up_direction.adjust_precision();
+        if self.ground_alignment <= 0.0 {
+            return up;
+        }
+        let axis = up.cross(ground_normal).normalize_or_zero();
+        if axis == Vector3::ZERO {
+            return up;
+        }
+        let tilt = (self.ground_alignment.min(1.0) * up.angle_between(ground_normal))
+            .min(self.max_ground_alignment_tilt);
+        Quaternion::from_axis_angle(axis, tilt).mul_vec3(up)
+    }
+
+    fn slope_adjusted_velocity(
+        &self,
+        ctx: &TnuaBasisContext,
+        desired_velocity: Vector3,
+        ground_normal: Vector3,
+    ) -> Vector3 {
+        let up = ctx.up_direction.adjust_precision();
+        let ground_normal_on_plane = ground_normal.reject_from(up);
+        let Some(uphill) = (-ground_normal_on_plane).try_normalize() else {
+            // Flat ground
+            return desired_velocity;
+        };
+        let uphill_speed = desired_velocity.dot(uphill);
+        if self.block_steep_slopes
+            && 0.0 < uphill_speed
+            && self.max_slope < ground_normal.angle_between(up)
+        {
+            return desired_velocity - uphill_speed * uphill;
+        }
+        let Some(slope_speed_curve) = &self.slope_speed_curve else {
+            return desired_velocity;
+        };
+        let Some(direction) = desired_velocity.try_normalize() else {
+            return desired_velocity;
+        };
+        let slope_angle_along_direction = ops::atan2(
+            ground_normal_on_plane.length() * direction.dot(uphill),
+            ground_normal.dot(up),
+        );
+        slope_speed_curve
+            .sample(slope_angle_along_direction)
+            .max(0.0)
+            * desired_velocity
+    }
+
+    /// Raise the speed of `desired_velocity` to the speed the character already moves at in its
+    /// direction, minus the [`momentum_drag`](Self::momentum_drag).
+    fn momentum_conserving_velocity(
+        &self,
+        desired_velocity: Vector3,
+        velocity_on_plane: Vector3,
+        frame_duration: Float,
+    ) -> Vector3 {
+        let Some(direction) = desired_velocity.try_normalize() else {
+            return desired_velocity;
+        };
+        let desired_speed = desired_velocity.length();
+        let current_speed = velocity_on_plane.dot(direction);
+        if current_speed <= desired_speed {
+            return desired_velocity;
+        }
+        let extra_speed = (current_speed - desired_speed)
+            * (1.0 - (self.momentum_drag * frame_duration).min(1.0));
+        (desired_speed + extra_speed) * direction
+    }
+
+    fn ease_float_height(&self, state: &mut TnuaBuiltinWalkState, ctx: &TnuaBasisContext) {
+        let target = self.float_height * up_scale(ctx.tracker, ctx.up_direction);
+        let Some(previous_target) = state
+            .float_height_target
+            .filter(|_| 0.0 < self.float_height_change_duration)
+        else {
+            state.effective_float_height = target;
+            state.float_height_target = Some(target);
+            return;
+        };
+        if previous_target != target {
+            state.float_height_change_rate =
+                (target - state.effective_float_height).abs() / self.float_height_change_duration;
+            state.float_height_target = Some(target);
+        }
+        let current = state.effective_float_height;
+        let step = state.float_height_change_rate * ctx.frame_duration;
+        state.effective_float_height = if current < target {
+            let mut new_height = (current + step).min(target);
+            if let Some(sensor_output) = ctx.proximity_sensor.output.as_ref() {
+                // If the character does not keep up (e.g. because of a low ceiling), wait for it
+                // instead of compressing the spring more and more.
+                let max_lead = state.float_height_change_rate * FLOAT_HEIGHT_CHANGE_MAX_LEAD_TIME;
+                new_height = new_height.min((sensor_output.proximity + max_lead).max(current));
+            }
+            new_height
+        } else {
+            (current - step).max(target)
+        };
+    }
+
+    fn should_snap_to_ground(
+        &self,
+        state: &TnuaBuiltinWalkState,
+        ctx: &TnuaBasisContext,
+        sensor_output: &TnuaProximitySensorOutput,
+    ) -> bool {
+        if state.airborne_timer.is_some()
+            || state.effective_float_height + self.cling_distance + self.ground_snap_distance
+                < sensor_output.proximity
+        {
+            return false;
+        }
+        if state.snapping_to_ground {
+            // The snapping itself accelerates the character toward the ground.
+            return true;
+        }
+        let relative_vertical_speed = (ctx.tracker.velocity - sensor_output.entity_linvel)
+            .dot(ctx.up_direction.adjust_precision());
+        relative_vertical_speed.abs() <= self.ground_snap_max_speed
+    }
+
     /// Calculate the vertical spring force that this basis would need to apply assuming its
     /// vertical distance from the vertical distance it needs to be at equals the `spring_offset`
     /// argument.
@@ -485,15 +1008,183 @@ impl TnuaBuiltinWalk {
     }
 }
 
-#[derive(Debug)]
+/// Calculates the [`spring_strengh`](TnuaBuiltinWalk::spring_strengh) and
+/// [`spring_dampening`](TnuaBuiltinWalk::spring_dampening) of [`TnuaBuiltinWalk`] from how the
+/// float spring should behave.
+///
+/// The spring works on accelerations rather than forces, so the mass of the character does not
+/// affect its behavior and does not need to be taken into account.
+///
+/// ```
+/// # use bevy_tnua::builtins::{TnuaBuiltinWalk, TnuaBuiltinWalkSpringTuning};
+/// let walk = TnuaBuiltinWalk {
+///     float_height: 1.5,
+///     ..Default::default()
+/// }
+/// .with_spring_tuning(TnuaBuiltinWalkSpringTuning::critically_damped(0.2), 1.0 / 60.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TnuaBuiltinWalkSpringTuning {
+    /// The time, in seconds, it takes the character to (almost) settle at the float height after
+    /// being displaced from it - e.g. after landing.
+    pub settle_time: Float,
+    /// How far the character goes past the float height before settling, as a fraction of the
+    /// initial displacement - e.g. `0.1` for an overshoot of 10%. Zero means no overshoot at all.
+    pub overshoot: Float,
+}
+
+impl TnuaBuiltinWalkSpringTuning {
+    /// The fastest settling spring that does not overshoot.
+    pub fn critically_damped(settle_time: Float) -> Self {
+        Self {
+            settle_time,
+            overshoot: 0.0,
+        }
+    }
+
+    /// Calculate the [`spring_strengh`](TnuaBuiltinWalk::spring_strengh) and the
+    /// [`spring_dampening`](TnuaBuiltinWalk::spring_dampening).
+    ///
+    /// The dampening is applied once per frame, so it depends on `frame_duration` - the duration
+    /// of the frames in the schedule Tnua runs in. Very short settle times at low frame rates
+    /// result in a dampening near 2.0, which makes the spring unstable.
+    pub fn spring_strength_and_dampening(&self, frame_duration: Float) -> (Float, Float) {
+        // Damping ratio from the overshoot of a second order system.
+        let damping_ratio = if 0.0 < self.overshoot {
+            let log_overshoot = ops::ln(self.overshoot.min(0.99));
+            -log_overshoot / (float_consts::PI.powi(2) + log_overshoot.powi(2)).sqrt()
+        } else {
+            1.0
+        };
+        // Natural frequency from the common estimate of the settle time (within 2%).
+        let natural_frequency = 4.0 / (damping_ratio * self.settle_time.max(Float::EPSILON));
+        (
+            natural_frequency.powi(2),
+            2.0 * damping_ratio * natural_frequency * frame_duration,
+        )
+    }
+}
+
+/// Conditioning of the raw input fed to [`TnuaBuiltinWalk`] - see
+/// [`input_conditioning`](TnuaBuiltinWalk::input_conditioning).
+///
+/// The shaping works on the speed of [`desired_velocity`](TnuaBuiltinWalk::desired_velocity)
+/// relative to [`full_speed`](Self::full_speed) - which should be the speed a fully tilted stick
+/// is mapped to - so it does not matter how the stick's input was scaled. The order is:
+///
+/// 1. Speeds inside the [`radial_deadzone`](Self::radial_deadzone) become zero, and the speeds
+///    above it are rescaled so that the speed ratio still goes from 0 to 1.
+/// 2. The [`response_curve`](Self::response_curve) is applied to the speed ratio.
+/// 3. The resulting velocity, and [`desired_forward`](TnuaBuiltinWalk::desired_forward), are
+///    smoothed over the [`smoothing_time`](Self::smoothing_time).
+///
+/// ```
+/// # use bevy_tnua::builtins::{TnuaBuiltinWalk, TnuaBuiltinWalkInputConditioning};
+/// # use bevy_tnua::util::TnuaCurve;
+/// let walk = TnuaBuiltinWalk {
+///     float_height: 1.5,
+///     input_conditioning: Some(TnuaBuiltinWalkInputConditioning {
+///         // Finer control at low tilts.
+///         response_curve: Some(TnuaCurve::new([(0.0, 0.0), (0.5, 0.25), (1.0, 1.0)])),
+///         ..TnuaBuiltinWalkInputConditioning::new(10.0)
+///     }),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaBuiltinWalkInputConditioning {
+    /// The speed of [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) that corresponds to a
+    /// fully tilted stick.
+    pub full_speed: Float,
+
+    /// The fraction of [`full_speed`](Self::full_speed) under which the desired velocity is
+    /// ignored - both for moving and for facing the movement direction.
+    pub radial_deadzone: Float,
+
+    /// Maps the speed ratio (after the deadzone) to the speed ratio that is used. `None` means a
+    /// linear response.
+    pub response_curve: Option<TnuaCurve>,
+
+    /// The time constant, in seconds, of the smoothing applied to the desired velocity and the
+    /// desired forward direction - roughly the window of input they are averaged over. Zero
+    /// disables the smoothing.
+    pub smoothing_time: Float,
+}
+
+impl TnuaBuiltinWalkInputConditioning {
+    /// Conditioning with the default deadzone and smoothing for a stick mapped to `full_speed`.
+    pub fn new(full_speed: Float) -> Self {
+        Self {
+            full_speed,
+            radial_deadzone: 0.15,
+            response_curve: None,
+            smoothing_time: 0.05,
+        }
+    }
+
+    /// Apply the deadzone and the response curve (but not the smoothing) to a velocity.
+    pub fn shape(&self, velocity: Vector3) -> Vector3 {
+        if self.full_speed <= 0.0 {
+            return velocity;
+        }
+        let Some(direction) = velocity.try_normalize() else {
+            return Vector3::ZERO;
+        };
+        let ratio = velocity.length() / self.full_speed;
+        if ratio <= self.radial_deadzone {
+            return Vector3::ZERO;
+        }
+        let ratio = if self.radial_deadzone < 1.0 {
+            (ratio - self.radial_deadzone) / (1.0 - self.radial_deadzone)
+        } else {
+            ratio
+        };
+        let ratio = if let Some(response_curve) = self.response_curve.as_ref() {
+            response_curve.sample(ratio)
+        } else {
+            ratio
+        };
+        ratio.max(0.0) * self.full_speed * direction
+    }
+}
+
+/// How [`TnuaBuiltinWalk`] chooses the direction the character faces.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaBuiltinWalkFacing {
+    /// Face [`desired_forward`](TnuaBuiltinWalk::desired_forward), regardless of the direction
+    /// the character moves in.
+    ///
+    /// Use this for strafing - e.g. when the facing follows an aim direction driven by the camera
+    /// or by a lock-on target.
+    #[default]
+    Strafe,
+    /// Face the direction of [`desired_velocity`](TnuaBuiltinWalk::desired_velocity). When it is
+    /// zero, face [`desired_forward`](TnuaBuiltinWalk::desired_forward) instead (if it is set).
+    Movement,
+}
+
+/// How close (in radians) the turn must be to a half turn for the character to keep turning in
+/// the direction it already turns.
+const HALF_TURN_HYSTERESIS: Float = 0.1;
+
+/// How far ahead (in seconds of easing) the eased float height may get of the character's actual
+/// height while rising.
+const FLOAT_HEIGHT_CHANGE_MAX_LEAD_TIME: Float = 0.25;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 struct StandingOnState {
     entity: Entity,
     entity_linvel: Vector3,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TnuaBuiltinWalkState {
     airborne_timer: Option<Timer>,
+    used_coyote_time: bool,
     /// The current distance of the character from the distance its supposed to float at.
     pub standing_offset: Vector3,
     standing_on: Option<StandingOnState>,
@@ -505,13 +1196,68 @@ pub struct TnuaBuiltinWalkState {
     /// ([`standing_on_entity`](Self::standing_on_entity) returns `Some`) then the
     /// `running_velocity` will be relative to the velocity of that entity.
     pub running_velocity: Vector3,
+    snapping_to_ground: bool,
+    effective_float_height: Float,
+    float_height_target: Option<Float>,
+    float_height_change_rate: Float,
+    conditioned_velocity: Vector3,
+    conditioned_forward: Option<Vector3>,
 }
 
 impl TnuaBuiltinWalkState {
+    /// Whether the character is beyond its [cling distance](TnuaBuiltinWalk::cling_distance) and
+    /// is being pulled back to the ground by [ground
+    /// snapping](TnuaBuiltinWalk::ground_snap_distance).
+    pub fn snapping_to_ground(&self) -> bool {
+        self.snapping_to_ground
+    }
+
     /// Returns the entity that the character currently stands on.
     pub fn standing_on_entity(&self) -> Option<Entity> {
         Some(self.standing_on.as_ref()?.entity)
     }
+
+    /// The time, in seconds, left for the character's [coyote
+    /// time](TnuaBuiltinWalk::coyote_time).
+    ///
+    /// Returns `None` if the character is not in coyote time - either because it is standing on
+    /// the ground or because it is fully airborne.
+    pub fn coyote_time_remaining(&self) -> Option<Float> {
+        let timer = self.airborne_timer.as_ref()?;
+        if timer.finished() {
+            None
+        } else {
+            Some(timer.remaining_secs().adjust_precision())
+        }
+    }
+
+    /// Whether an action that [violates coyote time](crate::TnuaAction::VIOLATES_COYOTE_TIME) was
+    /// started during coyote time since the character last left the ground.
+    ///
+    /// This is typically a jump that was only possible thanks to the coyote time, and can be used
+    /// for analytics or for picking a different animation.
+    pub fn used_coyote_time(&self) -> bool {
+        self.used_coyote_time
+    }
+
+    /// The [`float_height`](TnuaBuiltinWalk::float_height), multiplied by the character's scale
+    /// along the local axis of its body that is the closest to the up direction - so a rotated
+    /// character, or one walking on a wall with a dynamic up direction, is scaled by the right
+    /// axis.
+    ///
+    /// While the float height is being eased (see
+    /// [`float_height_change_duration`](TnuaBuiltinWalk::float_height_change_duration)) this is
+    /// the current eased value rather than the final one.
+    pub fn effective_float_height(&self) -> Float {
+        self.effective_float_height
+    }
+
+    /// The desired velocity after the [input
+    /// conditioning](TnuaBuiltinWalk::input_conditioning) - the velocity the character actually
+    /// tries to reach (before slope and momentum adjustments).
+    pub fn conditioned_velocity(&self) -> Vector3 {
+        self.conditioned_velocity
+    }
 }
 
 struct ClimbVectors {
@@ -526,3 +1272,10 @@ impl ClimbVectors {
         axis_direction + axis_sideways
     }
 }
+
+/// The scale of the character's body along the direction that is currently up.
+fn up_scale(tracker: &TnuaRigidBodyTracker, up_direction: Dir3) -> Float {
+    (tracker.rotation.inverse() * up_direction.adjust_precision())
+        .abs()
+        .dot(tracker.scale)
+}
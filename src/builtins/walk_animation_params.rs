@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+
+use crate::controller::TnuaController;
+use crate::math::{AdjustPrecision, Float, Vector2};
+use crate::schedules::TnuaScheduleFilter;
+use crate::{TnuaProximitySensor, TnuaRigidBodyTracker};
+
+use super::TnuaBuiltinWalk;
+
+/// Normalized locomotion parameters of a character that uses [`TnuaBuiltinWalk`], for driving
+/// animation graphs.
+///
+/// Add this component to the character entity, and [`TnuaControllerPlugin`] will update it every
+/// frame after applying the basis. The parameters are not updated while the basis is not a
+/// [`TnuaBuiltinWalk`].
+///
+/// [`TnuaControllerPlugin`]: crate::prelude::TnuaControllerPlugin
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaAnimationParams {
+    /// The speed that [`planar_speed_ratio`](Self::planar_speed_ratio) is relative to. Usually the
+    /// top speed the character is walked at.
+    pub reference_speed: Float,
+
+    /// The falling speed that [`fall_speed_ratio`](Self::fall_speed_ratio) is relative to.
+    pub reference_fall_speed: Float,
+
+    /// The speed of the character along the ground (relative to the entity it stands on),
+    /// divided by [`reference_speed`](Self::reference_speed).
+    ///
+    /// This is not clamped, and can exceed 1 if the character is pushed faster than that.
+    pub planar_speed_ratio: Float,
+
+    /// The direction of the character's movement along the ground, in the character's space. `X`
+    /// is to the character's right and `Y` is to its front (its negative `Z` axis, which is Bevy's
+    /// convention). Either normalized or zero.
+    pub strafe_direction: Vector2,
+
+    /// The angular velocity, in radians per second, of the character around the up direction.
+    /// Positive when turning left (counterclockwise when viewed from above).
+    pub turn_rate: Float,
+
+    /// Whether the character is airborne (including during coyote time).
+    pub airborne: bool,
+
+    /// While airborne, the downward speed of the character divided by
+    /// [`reference_fall_speed`](Self::reference_fall_speed), clamped to the `0..=1` range. Zero
+    /// while standing.
+    pub fall_speed_ratio: Float,
+}
+
+impl Default for TnuaAnimationParams {
+    fn default() -> Self {
+        Self {
+            reference_speed: 10.0,
+            reference_fall_speed: 20.0,
+            planar_speed_ratio: 0.0,
+            strafe_direction: Vector2::ZERO,
+            turn_rate: 0.0,
+            airborne: false,
+            fall_speed_ratio: 0.0,
+        }
+    }
+}
+
+pub(crate) fn update_walk_animation_params(
+    mut query: Query<(
+        Entity,
+        &TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+        &mut TnuaAnimationParams,
+    )>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    for (entity, controller, tracker, sensor, mut params) in query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        let Some((_, walk_state)) = controller.concrete_basis::<TnuaBuiltinWalk>() else {
+            continue;
+        };
+        let up = -sensor.cast_direction.adjust_precision();
+        let params = params.as_mut();
+
+        let planar_velocity = walk_state.running_velocity.reject_from(up);
+        params.planar_speed_ratio = if 0.0 < params.reference_speed {
+            planar_velocity.length() / params.reference_speed
+        } else {
+            0.0
+        };
+
+        let local_velocity = tracker.rotation.inverse() * planar_velocity;
+        params.strafe_direction =
+            Vector2::new(local_velocity.x, -local_velocity.z).normalize_or_zero();
+
+        params.turn_rate = tracker.angvel.dot(up);
+
+        params.airborne = walk_state.standing_on_entity().is_none();
+        params.fall_speed_ratio = if params.airborne && 0.0 < params.reference_fall_speed {
+            (-tracker.velocity.dot(up) / params.reference_fall_speed).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+    }
+}
@@ -0,0 +1,248 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Vector3;
+use serde::{Deserialize, Serialize};
+
+use crate::builtins::{TnuaBuiltinCrouch, TnuaBuiltinDash, TnuaBuiltinJump, TnuaBuiltinWalk};
+use crate::TnuaUserControlsSystemSet;
+
+/// A plugin required for loading [`TnuaControllerConfig`] assets and applying them to
+/// [`TnuaConfigured`] entities.
+pub struct TnuaControllerConfigPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaControllerConfigPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaControllerConfigPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl Plugin for TnuaControllerConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TnuaControllerConfig>();
+        app.register_asset_loader(TnuaControllerConfigLoader);
+        app.register_asset_loader(TnuaControllerConfigTomlLoader);
+        app.add_systems(
+            self.schedule,
+            update_configured_controllers.before(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+/// The parameters of the builtin basis and actions, loaded from a RON or a TOML file.
+///
+/// Files with the `.tnua.ron` or the `.tnua.toml` extension are loaded as this asset. All the
+/// fields are optional, and so are the fields of the basis and actions inside them - missing
+/// fields get their default values:
+///
+/// ```ron
+/// (
+///     walk: Some((
+///         float_height: 1.5,
+///         acceleration: 60.0,
+///     )),
+///     jump: Some((
+///         height: 4.0,
+///     )),
+/// )
+/// ```
+///
+/// The same config in TOML:
+///
+/// ```toml
+/// [walk]
+/// float_height = 1.5
+/// acceleration = 60.0
+///
+/// [jump]
+/// height = 4.0
+/// ```
+///
+/// Add [`TnuaConfigured`] to the character entity to have the loaded config (and any reloads of
+/// it) available to the system that controls the character.
+#[derive(Asset, TypePath, Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct TnuaControllerConfig {
+    pub walk: Option<TnuaBuiltinWalk>,
+    pub jump: Option<TnuaBuiltinJump>,
+    pub crouch: Option<TnuaBuiltinCrouch>,
+    pub dash: Option<TnuaBuiltinDash>,
+}
+
+impl TnuaControllerConfig {
+    /// The walk basis from the config (or the default one), with the user input set.
+    pub fn walk(
+        &self,
+        desired_velocity: Vector3,
+        desired_forward: Option<Dir3>,
+    ) -> TnuaBuiltinWalk {
+        TnuaBuiltinWalk {
+            desired_velocity,
+            desired_forward,
+            ..self.walk.clone().unwrap_or_default()
+        }
+    }
+
+    /// The jump action from the config (or the default one).
+    pub fn jump(&self) -> TnuaBuiltinJump {
+        self.jump.clone().unwrap_or_default()
+    }
+
+    /// The crouch action from the config (or the default one).
+    pub fn crouch(&self) -> TnuaBuiltinCrouch {
+        self.crouch.clone().unwrap_or_default()
+    }
+
+    /// The dash action from the config (or the default one), with the user input set.
+    pub fn dash(&self, displacement: Vector3, desired_forward: Option<Dir3>) -> TnuaBuiltinDash {
+        TnuaBuiltinDash {
+            displacement,
+            desired_forward,
+            ..self.dash.clone().unwrap_or_default()
+        }
+    }
+}
+
+/// The error of loading a [`TnuaControllerConfig`].
+#[derive(thiserror::Error, Debug)]
+pub enum TnuaControllerConfigLoaderError {
+    #[error("Could not read the config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse the config file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("The config file is not valid UTF-8: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error("Could not parse the config file: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+#[derive(Default)]
+pub struct TnuaControllerConfigLoader;
+
+impl AssetLoader for TnuaControllerConfigLoader {
+    type Asset = TnuaControllerConfig;
+    type Settings = ();
+    type Error = TnuaControllerConfigLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<TnuaControllerConfig, TnuaControllerConfigLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tnua.ron"]
+    }
+}
+
+#[derive(Default)]
+pub struct TnuaControllerConfigTomlLoader;
+
+impl AssetLoader for TnuaControllerConfigTomlLoader {
+    type Asset = TnuaControllerConfig;
+    type Settings = ();
+    type Error = TnuaControllerConfigLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<TnuaControllerConfig, TnuaControllerConfigLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(toml::from_str(std::str::from_utf8(&bytes)?)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tnua.toml"]
+    }
+}
+
+/// Keeps a character entity updated with a [`TnuaControllerConfig`] asset.
+///
+/// Whenever the asset is loaded or reloaded (e.g. when editing the file with Bevy's
+/// `file_watcher` feature enabled), [`config`](Self::config) is updated. The system that controls
+/// the character should feed the basis and actions based on it:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::config_asset::TnuaConfigured;
+/// # use bevy_tnua::math::Vector3;
+/// fn apply_controls(mut query: Query<(&mut TnuaController, &TnuaConfigured)>) {
+///     for (mut controller, configured) in query.iter_mut() {
+///         let Some(config) = configured.config() else {
+///             continue;
+///         };
+///         controller.basis(config.walk(Vector3::ZERO, None));
+///     }
+/// }
+/// ```
+///
+/// Without [`TnuaControllerConfigPlugin`] this component will not do anything.
+#[derive(Component)]
+pub struct TnuaConfigured {
+    pub handle: Handle<TnuaControllerConfig>,
+    config: Option<TnuaControllerConfig>,
+}
+
+impl TnuaConfigured {
+    pub fn new(handle: Handle<TnuaControllerConfig>) -> Self {
+        Self {
+            handle,
+            config: None,
+        }
+    }
+
+    /// The config, or `None` if the asset was not loaded yet.
+    pub fn config(&self) -> Option<&TnuaControllerConfig> {
+        self.config.as_ref()
+    }
+
+    /// Mutable access to the config, for tweaking it at runtime.
+    ///
+    /// The changes are not written back to the asset, and are lost when it gets reloaded.
+    pub fn config_mut(&mut self) -> Option<&mut TnuaControllerConfig> {
+        self.config.as_mut()
+    }
+}
+
+fn update_configured_controllers(
+    mut asset_events: EventReader<AssetEvent<TnuaControllerConfig>>,
+    assets: Res<Assets<TnuaControllerConfig>>,
+    mut query: Query<&mut TnuaConfigured>,
+) {
+    let modified_ids: Vec<AssetId<TnuaControllerConfig>> = asset_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    for mut configured in query.iter_mut() {
+        let id = configured.handle.id();
+        if configured.config.is_some() && !modified_ids.contains(&id) {
+            continue;
+        }
+        if let Some(config) = assets.get(id) {
+            configured.config = Some(config.clone());
+        }
+    }
+}
@@ -8,7 +8,7 @@ use crate::prelude::*;
 /// It's [`update`](Self::update) must be called every frame - even when the result is not used.
 ///
 /// For simpler usage, see [`TnuaSimpleAirActionsCounter`].
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct TnuaAirActionsTracker {
     considered_in_air: bool,
 }
@@ -21,7 +21,7 @@ impl TnuaAirActionsTracker {
             TnuaActionFlowStatus::ActionOngoing(action_name) => {
                 if controller
                     .dynamic_action()
-                    .map_or(false, |action| action.violates_coyote_time())
+                    .is_some_and(|action| action.violates_coyote_time())
                 {
                     if self.considered_in_air {
                         TnuaAirActionsUpdate::NoChange
@@ -40,7 +40,7 @@ impl TnuaAirActionsTracker {
             } => {
                 if controller
                     .dynamic_action()
-                    .map_or(false, |action| action.violates_coyote_time())
+                    .is_some_and(|action| action.violates_coyote_time())
                 {
                     self.considered_in_air = true;
                     TnuaAirActionsUpdate::AirActionStarted(action_name)
@@ -102,7 +102,7 @@ pub enum TnuaAirActionsUpdate {
 /// A simple counter that counts together all the air actions a character is able to perform.
 ///
 /// It's [`update`](Self::update) must be called every frame.
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone)]
 pub struct TnuaSimpleAirActionsCounter {
     tracker: TnuaAirActionsTracker,
     current_action: Option<(&'static str, usize)>,
@@ -111,7 +111,10 @@ pub struct TnuaSimpleAirActionsCounter {
 
 impl TnuaSimpleAirActionsCounter {
     /// Call this every frame to track the air actions.
-    pub fn update(&mut self, controller: &TnuaController) {
+    ///
+    /// Returns the update from the underlying [`TnuaAirActionsTracker`], for users that need to
+    /// react to it.
+    pub fn update(&mut self, controller: &TnuaController) -> TnuaAirActionsUpdate {
         let update = self.tracker.update(controller);
         match update {
             TnuaAirActionsUpdate::NoChange => {}
@@ -132,6 +135,7 @@ impl TnuaSimpleAirActionsCounter {
                 self.air_actions_count = 0;
             }
         }
+        update
     }
 
     /// Resets the air actions counter to a specific count, excluding the current action.
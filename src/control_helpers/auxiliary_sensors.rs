@@ -0,0 +1,276 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaProximitySensorOutput, TnuaSystemSet};
+
+/// A plugin required for making [`TnuaAuxiliarySensors`] work.
+///
+/// Must be registered in the same schedule as the rest of the Tnua plugins.
+pub struct TnuaAuxiliarySensorsPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaAuxiliarySensorsPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaAuxiliarySensorsPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl Plugin for TnuaAuxiliarySensorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            (
+                sync_auxiliary_sensors
+                    .in_set(TnuaSystemSet)
+                    .before(TnuaPipelineStages::Sensors),
+                collect_auxiliary_sensor_outputs.in_set(TnuaPipelineStages::SubservientSensors),
+            ),
+        );
+    }
+}
+
+/// The placement of a sensor in [`TnuaAuxiliarySensors`].
+#[derive(Debug, Clone)]
+pub struct TnuaAuxiliarySensorConfig {
+    /// The origin of the cast, relative to the character entity.
+    pub cast_origin: Vector3,
+
+    /// The direction of the cast.
+    pub cast_direction: Dir3,
+
+    /// The maximum distance from the origin in which the sensor detects colliders.
+    pub cast_range: Float,
+
+    /// Whether [`cast_direction`](Self::cast_direction) is in the character's local space (and
+    /// therefore turns with the character, e.g. for a front wall sensor) or in world space.
+    pub rotate_with_character: bool,
+}
+
+impl Default for TnuaAuxiliarySensorConfig {
+    fn default() -> Self {
+        Self {
+            cast_origin: Vector3::ZERO,
+            cast_direction: Dir3::NEG_Y,
+            cast_range: 0.0,
+            rotate_with_character: false,
+        }
+    }
+}
+
+struct AuxiliarySensor {
+    config: TnuaAuxiliarySensorConfig,
+    #[allow(clippy::type_complexity)]
+    modify_sensor: Option<Box<dyn Send + Sync + Fn(&mut EntityCommands)>>,
+    entity: Option<Entity>,
+    output: Option<TnuaProximitySensorOutput>,
+}
+
+/// Named proximity sensors, in addition to the main one, that are cast from the character.
+///
+/// Each sensor is a child entity with a [`TnuaSubservientSensor`], so the physics backend updates
+/// it together with the main sensor. After the sensors are updated, their outputs are copied into
+/// this component, where gameplay code can read them by name - and so can actions, via
+/// [`TnuaActionContext::auxiliary_sensor`](crate::TnuaActionContext::auxiliary_sensor).
+///
+/// Requires the plugin [`TnuaAuxiliarySensorsPlugin`].
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::control_helpers::{TnuaAuxiliarySensors, TnuaAuxiliarySensorConfig};
+/// # use bevy_tnua::math::Vector3;
+/// # let mut commands: Commands = panic!();
+/// # let player_entity: Entity = panic!();
+/// commands.entity(player_entity).insert(
+///     TnuaAuxiliarySensors::default()
+///         .with(
+///             "front-wall",
+///             TnuaAuxiliarySensorConfig {
+///                 cast_direction: Dir3::NEG_Z,
+///                 cast_range: 0.8,
+///                 rotate_with_character: true,
+///                 ..Default::default()
+///             },
+///         )
+///         .with(
+///             "head",
+///             TnuaAuxiliarySensorConfig {
+///                 cast_origin: Vector3::new(0.0, 0.9, 0.0),
+///                 cast_direction: Dir3::Y,
+///                 cast_range: 0.5,
+///                 ..Default::default()
+///             },
+///         ),
+/// );
+///
+/// # let auxiliary_sensors: TnuaAuxiliarySensors = panic!();
+/// // Later:
+/// if auxiliary_sensors.output("front-wall").is_some() {
+///     // Start climbing
+/// }
+/// ```
+///
+/// Note that the outputs are only available from the frame after the sensor was added, because
+/// the sensor entity is spawned with [`Commands`].
+#[derive(Component, Default)]
+pub struct TnuaAuxiliarySensors {
+    sensors: HashMap<&'static str, AuxiliarySensor>,
+    removed_entities: Vec<Entity>,
+}
+
+impl TnuaAuxiliarySensors {
+    /// Add a sensor (or replace the configuration of an existing one).
+    pub fn with(mut self, name: &'static str, config: TnuaAuxiliarySensorConfig) -> Self {
+        self.set(name, config);
+        self
+    }
+
+    /// Add a sensor, with a function that is called with the command that creates the sensor
+    /// entity. This function has the opportunity to add things to the sensor entity - mostly
+    /// cast-shape components.
+    ///
+    /// If a sensor with that name already exists, it is recreated.
+    pub fn with_modified(
+        mut self,
+        name: &'static str,
+        config: TnuaAuxiliarySensorConfig,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        self.remove(name);
+        self.sensors.insert(
+            name,
+            AuxiliarySensor {
+                config,
+                modify_sensor: Some(Box::new(modify_sensor)),
+                entity: None,
+                output: None,
+            },
+        );
+        self
+    }
+
+    /// Add a sensor, or replace the configuration of an existing one (keeping its entity).
+    pub fn set(&mut self, name: &'static str, config: TnuaAuxiliarySensorConfig) {
+        if let Some(sensor) = self.sensors.get_mut(name) {
+            sensor.config = config;
+        } else {
+            self.sensors.insert(
+                name,
+                AuxiliarySensor {
+                    config,
+                    modify_sensor: None,
+                    entity: None,
+                    output: None,
+                },
+            );
+        }
+    }
+
+    /// Remove a sensor. Its entity will be despawned.
+    pub fn remove(&mut self, name: &str) {
+        if let Some(sensor) = self.sensors.remove(name) {
+            self.removed_entities.extend(sensor.entity);
+        }
+    }
+
+    /// The configuration of a sensor, for changing it in place.
+    pub fn config_mut(&mut self, name: &str) -> Option<&mut TnuaAuxiliarySensorConfig> {
+        Some(&mut self.sensors.get_mut(name)?.config)
+    }
+
+    pub fn config(&self, name: &str) -> Option<&TnuaAuxiliarySensorConfig> {
+        Some(&self.sensors.get(name)?.config)
+    }
+
+    /// What the sensor detected in the last frame, or `None` if it detected nothing (or if there
+    /// is no sensor with that name)
+    pub fn output(&self, name: &str) -> Option<&TnuaProximitySensorOutput> {
+        self.sensors.get(name)?.output.as_ref()
+    }
+
+    /// The child entity of the sensor, once it is spawned.
+    pub fn sensor_entity(&self, name: &str) -> Option<Entity> {
+        self.sensors.get(name)?.entity
+    }
+
+    /// The names of all the sensors.
+    pub fn names(&self) -> impl '_ + Iterator<Item = &'static str> {
+        self.sensors.keys().copied()
+    }
+}
+
+fn sync_auxiliary_sensors(
+    mut query: Query<(Entity, &GlobalTransform, &mut TnuaAuxiliarySensors)>,
+    mut sensors_query: Query<&mut TnuaProximitySensor, With<TnuaSubservientSensor>>,
+    mut commands: Commands,
+) {
+    for (owner_entity, transform, mut auxiliary_sensors) in query.iter_mut() {
+        let auxiliary_sensors = auxiliary_sensors.as_mut();
+        for entity in auxiliary_sensors.removed_entities.drain(..) {
+            if let Some(entity_commands) = commands.get_entity(entity) {
+                entity_commands.despawn_recursive();
+            }
+        }
+        let (_, rotation, _) = transform.to_scale_rotation_translation();
+        for sensor in auxiliary_sensors.sensors.values_mut() {
+            let config = &sensor.config;
+            let cast_direction = if config.rotate_with_character {
+                rotation * config.cast_direction
+            } else {
+                config.cast_direction
+            };
+            if let Some(mut proximity_sensor) = sensor
+                .entity
+                .and_then(|entity| sensors_query.get_mut(entity).ok())
+            {
+                proximity_sensor.cast_origin = config.cast_origin;
+                proximity_sensor.cast_direction = cast_direction;
+                proximity_sensor.cast_range = config.cast_range;
+            } else if sensor.entity.is_none() {
+                let mut cmd = commands.spawn((
+                    TransformBundle {
+                        ..Default::default()
+                    },
+                    TnuaSubservientSensor { owner_entity },
+                    TnuaProximitySensor {
+                        cast_origin: config.cast_origin,
+                        cast_direction,
+                        cast_range: config.cast_range,
+                        ..Default::default()
+                    },
+                ));
+                cmd.set_parent(owner_entity);
+                if let Some(modify_sensor) = sensor.modify_sensor.as_ref() {
+                    modify_sensor(&mut cmd);
+                }
+                sensor.entity = Some(cmd.id());
+            }
+        }
+    }
+}
+
+fn collect_auxiliary_sensor_outputs(
+    mut query: Query<&mut TnuaAuxiliarySensors>,
+    sensors_query: Query<&TnuaProximitySensor, With<TnuaSubservientSensor>>,
+) {
+    for mut auxiliary_sensors in query.iter_mut() {
+        for sensor in auxiliary_sensors.sensors.values_mut() {
+            sensor.output = sensor
+                .entity
+                .and_then(|entity| sensors_query.get(entity).ok())
+                .and_then(|proximity_sensor| proximity_sensor.output.clone());
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Float;
+
+use crate::control_helpers::crowd_separation::{apply_crowd_separation, TnuaSeparationSource};
+use crate::controller::apply_controller_system;
+use crate::{TnuaCharacterCollisionPolicy, TnuaPipelineStages};
+
+pub struct TnuaCharacterCollisionPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaCharacterCollisionPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaCharacterCollisionPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaCharacterCollisionPolicy::SoftPush`] push the characters.
+///
+/// The push works like [`TnuaCrowdSeparation`](crate::control_helpers::TnuaCrowdSeparation) - the
+/// character is pushed away from the characters with a [`TnuaCharacterCollisionPolicy`] that its
+/// [`TnuaObstacleRadar`](crate::obstacle_radar::TnuaObstacleRadar) detects - so a character with
+/// `SoftPush` must also have a radar.
+///
+/// The other parts of [`TnuaCharacterCollisionPolicy`] - dropping the contacts between the
+/// characters - are implemented by the physics backend.
+impl Plugin for TnuaCharacterCollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            apply_crowd_separation::<TnuaCharacterCollisionPolicy>
+                .in_set(TnuaPipelineStages::Logic)
+                .after(apply_controller_system),
+        );
+    }
+}
+
+impl TnuaSeparationSource for TnuaCharacterCollisionPolicy {
+    fn separation_params(&self) -> Option<(Float, Float)> {
+        match *self {
+            Self::SoftPush {
+                radius,
+                max_acceleration,
+            } => Some((radius, max_acceleration)),
+            Self::Collide | Self::PassThrough => None,
+        }
+    }
+}
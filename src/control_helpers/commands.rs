@@ -0,0 +1,338 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use crate::builtins::{TnuaBuiltinJump, TnuaBuiltinWalk};
+use crate::control_helpers::{
+    TnuaJumpLink, TnuaJumpLinkExecutor, TnuaJumpLinkStatus, TnuaSteering,
+};
+use crate::controller::TnuaController;
+use crate::schedules::TnuaScheduleFilter;
+use crate::{TnuaRigidBodyTracker, TnuaUserControlsSystemSet};
+
+pub struct TnuaCommandsPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaCommandsPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaCommandsPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaCommands`] work.
+impl Plugin for TnuaCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            execute_commands.in_set(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+/// A movement command for [`TnuaCommands`].
+#[derive(Debug, Clone)]
+pub enum TnuaCommand {
+    /// Stand still.
+    Idle,
+
+    /// Walk to a point, slowing down near it.
+    MoveTo {
+        /// The point to walk to. Only its offset on the plane perpendicular to the up direction
+        /// matters.
+        target: Vector3,
+        /// The speed to walk at.
+        speed: Float,
+    },
+
+    /// Jump from where the character currently is to a landing point (see [`TnuaJumpLink`]).
+    JumpOverGap {
+        /// Where the character should land (the position of the character's center).
+        landing: Vector3,
+        /// The height of the top of the jump, above the character's position when the command
+        /// starts.
+        apex_height: Float,
+    },
+}
+
+/// Where the character of [`TnuaCommands`] should look.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TnuaFacingCommand {
+    /// Face the direction the character moves in (and keep the last direction while standing).
+    Movement,
+    /// Face a fixed direction.
+    Direction(Dir3),
+    /// Face a point.
+    Point(Vector3),
+    /// Face another entity (e.g. a target the character is chasing or a player it talks to).
+    Entity(Entity),
+}
+
+/// The status of the last [`TnuaCommand`] given to [`TnuaCommands`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaCommandStatus {
+    /// The command is [`TnuaCommand::Idle`], and was not issued as the result of another command.
+    Idle,
+    /// The command is being executed.
+    InProgress,
+    /// The command finished successfully. The character is now idle.
+    Succeeded,
+    /// The command could not be finished - e.g. a jump that did not take off or that landed
+    /// somewhere else. The character is now idle.
+    Failed,
+}
+
+/// High-level commands for a character, for AI and behavior trees.
+///
+/// Instead of feeding the controller every frame, the decision-making code writes a
+/// [`TnuaCommand`] (and optionally a [`TnuaFacingCommand`]) and checks the
+/// [`status`](Self::status) to decide what to do next. [`TnuaCommandsPlugin`] translates the
+/// commands into the walk basis and the jump action every frame:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::{TnuaCommandStatus, TnuaCommands, TnuaFacingCommand};
+/// # use bevy_tnua::math::Vector3;
+/// # #[derive(Component)]
+/// # struct Guard {
+/// #     post: Vector3,
+/// #     intruder: Option<Entity>,
+/// # }
+/// fn guard_behavior(mut query: Query<(&Guard, &mut TnuaCommands)>) {
+///     for (guard, mut commands) in query.iter_mut() {
+///         if let Some(intruder) = guard.intruder {
+///             commands.face(TnuaFacingCommand::Entity(intruder));
+///         } else if commands.status() != TnuaCommandStatus::InProgress {
+///             commands.move_to(guard.post, 4.0);
+///             commands.face(TnuaFacingCommand::Movement);
+///         }
+///     }
+/// }
+/// ```
+///
+/// While a command is executed, nothing else should feed the basis or the jump action.
+///
+/// Requires the plugin [`TnuaCommandsPlugin`].
+#[derive(Component, Clone)]
+pub struct TnuaCommands {
+    /// The template for the walk basis. Its `desired_velocity` and `desired_forward` are
+    /// overwritten.
+    pub walk: TnuaBuiltinWalk,
+
+    /// The template for the jump action of [`TnuaCommand::JumpOverGap`]. Its height is
+    /// overwritten.
+    pub jump: TnuaBuiltinJump,
+
+    /// The horizontal distance from the target of [`TnuaCommand::MoveTo`] at which it is
+    /// considered reached.
+    pub arrival_distance: Float,
+
+    /// The distance from the target of [`TnuaCommand::MoveTo`] at which the character starts
+    /// slowing down.
+    pub slowdown_distance: Float,
+
+    command: TnuaCommand,
+    facing: TnuaFacingCommand,
+    status: TnuaCommandStatus,
+    jump_link_executor: TnuaJumpLinkExecutor,
+    jump_started: bool,
+    last_movement_direction: Option<Dir3>,
+}
+
+impl TnuaCommands {
+    /// Create an idle command interface that feeds the given templates.
+    pub fn new(walk: TnuaBuiltinWalk, jump: TnuaBuiltinJump) -> Self {
+        Self {
+            walk,
+            jump,
+            arrival_distance: 0.5,
+            slowdown_distance: 2.0,
+            command: TnuaCommand::Idle,
+            facing: TnuaFacingCommand::Movement,
+            status: TnuaCommandStatus::Idle,
+            jump_link_executor: Default::default(),
+            jump_started: false,
+            last_movement_direction: None,
+        }
+    }
+
+    /// Replace the current command.
+    pub fn command(&mut self, command: TnuaCommand) {
+        self.status = match command {
+            TnuaCommand::Idle => TnuaCommandStatus::Idle,
+            _ => TnuaCommandStatus::InProgress,
+        };
+        self.command = command;
+        self.jump_link_executor.cancel();
+        self.jump_started = false;
+    }
+
+    /// Walk to `target` at `speed`.
+    pub fn move_to(&mut self, target: Vector3, speed: Float) {
+        self.command(TnuaCommand::MoveTo { target, speed });
+    }
+
+    /// Jump from the current position to `landing`, reaching `apex_height` above the current
+    /// position.
+    ///
+    /// The character should already be at the edge of the gap, and (unless its
+    /// [`air_acceleration`](TnuaBuiltinWalk::air_acceleration) is high) already moving toward
+    /// the landing point.
+    pub fn jump_over_gap(&mut self, landing: Vector3, apex_height: Float) {
+        self.command(TnuaCommand::JumpOverGap {
+            landing,
+            apex_height,
+        });
+    }
+
+    /// Stop moving.
+    pub fn stop(&mut self) {
+        self.command(TnuaCommand::Idle);
+    }
+
+    /// Set where the character should look. This does not affect the current command or its
+    /// status.
+    pub fn face(&mut self, facing: TnuaFacingCommand) {
+        self.facing = facing;
+    }
+
+    /// The current command.
+    pub fn current_command(&self) -> &TnuaCommand {
+        &self.command
+    }
+
+    /// The current facing command.
+    pub fn current_facing(&self) -> TnuaFacingCommand {
+        self.facing
+    }
+
+    /// The status of the last command.
+    pub fn status(&self) -> TnuaCommandStatus {
+        self.status
+    }
+
+    fn finish(&mut self, status: TnuaCommandStatus) {
+        self.command = TnuaCommand::Idle;
+        self.status = status;
+        self.jump_started = false;
+    }
+}
+
+fn execute_commands(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaCommands,
+        &mut TnuaController,
+        &TnuaRigidBodyTracker,
+    )>,
+    targets_query: Query<&GlobalTransform>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    let frame_duration = time.delta_seconds().adjust_precision();
+    for (entity, mut commands, mut controller, tracker) in query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        let commands = commands.as_mut();
+        let position = tracker.translation;
+        let up = Dir3::new(-tracker.gravity.f32()).unwrap_or(Dir3::Y);
+
+        let facing_target = match commands.facing {
+            TnuaFacingCommand::Movement => None,
+            TnuaFacingCommand::Direction(direction) => Some(direction.adjust_precision()),
+            TnuaFacingCommand::Point(point) => Some(point - position),
+            TnuaFacingCommand::Entity(target) => targets_query
+                .get(target)
+                .ok()
+                .map(|transform| transform.translation().adjust_precision() - position),
+        };
+        let mut walk = commands.walk.clone();
+        if let Some(facing_target) = facing_target {
+            walk.desired_forward =
+                Dir3::new(facing_target.reject_from(up.adjust_precision()).f32()).ok();
+        }
+
+        let velocity = match commands.command.clone() {
+            TnuaCommand::Idle => Vector3::ZERO,
+            TnuaCommand::MoveTo { target, speed } => {
+                let offset = (target - position).reject_from(up.adjust_precision());
+                if offset.length() <= commands.arrival_distance {
+                    commands.finish(TnuaCommandStatus::Succeeded);
+                    Vector3::ZERO
+                } else {
+                    TnuaSteering {
+                        max_speed: speed,
+                        slowdown_distance: commands.slowdown_distance,
+                        ..TnuaSteering::new(up)
+                    }
+                    .arrive(position, target)
+                }
+            }
+            TnuaCommand::JumpOverGap {
+                landing,
+                apex_height,
+            } => {
+                if !commands.jump_started {
+                    let link = TnuaJumpLink {
+                        start: position,
+                        end: landing,
+                        apex_height,
+                    };
+                    if commands
+                        .jump_link_executor
+                        .traverse(link, tracker.gravity, commands.jump.clone())
+                        .is_err()
+                    {
+                        commands.finish(TnuaCommandStatus::Failed);
+                    } else {
+                        commands.jump_started = true;
+                    }
+                }
+                if commands.jump_started {
+                    commands.jump_link_executor.landing_tolerance = commands.arrival_distance;
+                    match commands.jump_link_executor.update(
+                        controller.as_mut(),
+                        position,
+                        frame_duration,
+                        walk.clone(),
+                    ) {
+                        // The executor already fed the controller.
+                        TnuaJumpLinkStatus::InProgress => continue,
+                        TnuaJumpLinkStatus::Succeeded => {
+                            commands.finish(TnuaCommandStatus::Succeeded)
+                        }
+                        TnuaJumpLinkStatus::Idle
+                        | TnuaJumpLinkStatus::LandedElsewhere
+                        | TnuaJumpLinkStatus::TimedOut => {
+                            commands.finish(TnuaCommandStatus::Failed)
+                        }
+                    }
+                }
+                Vector3::ZERO
+            }
+        };
+
+        if let Ok(direction) = Dir3::new(velocity.f32()) {
+            commands.last_movement_direction = Some(direction);
+        }
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: velocity,
+            desired_forward: if commands.facing == TnuaFacingCommand::Movement {
+                commands.last_movement_direction.or(walk.desired_forward)
+            } else {
+                walk.desired_forward
+            },
+            ..walk
+        });
+    }
+}
@@ -0,0 +1,188 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::{StaticSystemParam, SystemParam};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float, Vector3};
+
+use crate::controller::{apply_controller_system, apply_terminal_velocity_system, TnuaController};
+use crate::schedules::TnuaScheduleFilter;
+use crate::spatial_ext::TnuaSpatialExt;
+use crate::{
+    TnuaMotor, TnuaPaused, TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker,
+    TnuaToggle, TnuaVelChange,
+};
+
+/// A plugin required for making [`TnuaCornerNudge`] work.
+///
+/// The type parameter is the [`TnuaSpatialExt`] implementation of the physics backend, e.g.
+/// `TnuaCornerNudgePlugin::<TnuaSpatialExtAvian3d>::default()`.
+pub struct TnuaCornerNudgePlugin<S> {
+    schedule: InternedScheduleLabel,
+    _phantom: PhantomData<fn() -> S>,
+}
+
+impl<S> TnuaCornerNudgePlugin<S> {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S> Default for TnuaCornerNudgePlugin<S> {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl<S: SystemParam + 'static> Plugin for TnuaCornerNudgePlugin<S>
+where
+    for<'w, 's> S::Item<'w, 's>: TnuaSpatialExt,
+{
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaCornerNudge>();
+        app.add_systems(
+            self.schedule,
+            apply_corner_nudge::<S>
+                .in_set(TnuaPipelineStages::Logic)
+                .after(apply_controller_system)
+                .before(apply_terminal_velocity_system),
+        );
+    }
+}
+
+/// Nudges a rising character sideways when it is about to hit the lip of a platform by a small
+/// margin, so that the jump does not get cut short.
+///
+/// While the character is airborne and moving up, its collider is swept ahead along the up
+/// direction. If it is about to hit something, the collider is swept again from positions moved
+/// sideways (away from the hit) by up to [`max_nudge`](Self::max_nudge), and if one of them is
+/// clear the character is moved there in a single frame.
+///
+/// The sideways direction is taken from the normal of the hit, so this works best with rounded
+/// colliders (capsules and balls) - a flat collider hitting a flat ceiling is never nudged.
+///
+/// Requires the plugin [`TnuaCornerNudgePlugin`].
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaCornerNudge {
+    /// The maximum distance the character can be nudged sideways.
+    pub max_nudge: Float,
+
+    /// How far ahead, in seconds of the current upward speed, to look for lips to avoid.
+    pub lookahead_time: Float,
+
+    /// How many nudge distances (evenly spread up to [`max_nudge`](Self::max_nudge)) to try. The
+    /// smallest one that clears the lip is used.
+    pub attempts: u32,
+
+    velocity_to_cancel: Vector3,
+}
+
+impl Default for TnuaCornerNudge {
+    fn default() -> Self {
+        Self {
+            max_nudge: 0.3,
+            lookahead_time: 0.1,
+            attempts: 4,
+            velocity_to_cancel: Vector3::ZERO,
+        }
+    }
+}
+
+impl TnuaCornerNudge {
+    fn find_nudge(
+        &self,
+        spatial_ext: &impl TnuaSpatialExt,
+        character: Entity,
+        origin: Vector3,
+        up: Vector3,
+        rise: Vector3,
+    ) -> Option<Vector3> {
+        let hit = spatial_ext.sweep_character(character, origin, rise)?;
+        let direction = hit
+            .normal
+            .adjust_precision()
+            .reject_from(up)
+            .try_normalize()?;
+        (1..=self.attempts).find_map(|attempt| {
+            let nudge = direction * self.max_nudge * attempt as Float / self.attempts as Float;
+            if spatial_ext
+                .sweep_character(character, origin, nudge)
+                .is_some()
+            {
+                return None;
+            }
+            if spatial_ext
+                .sweep_character(character, origin + nudge, rise)
+                .is_some()
+            {
+                return None;
+            }
+            Some(nudge)
+        })
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_corner_nudge<S: SystemParam + 'static>(
+    time: Res<Time>,
+    spatial_ext: StaticSystemParam<S>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaCornerNudge,
+        &TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+        &mut TnuaMotor,
+        Option<&TnuaToggle>,
+    )>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+) where
+    for<'w, 's> S::Item<'w, 's>: TnuaSpatialExt,
+{
+    let frame_duration = time.delta_seconds().adjust_precision();
+    if frame_duration <= 0.0 {
+        return;
+    }
+    for (entity, mut corner_nudge, controller, tracker, sensor, mut motor, tnua_toggle) in
+        query.iter_mut()
+    {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly | TnuaToggle::Frozen => continue,
+            TnuaToggle::Enabled => {}
+        }
+
+        // The nudge is applied as a velocity for a single frame, so it needs to be taken back.
+        if corner_nudge.velocity_to_cancel != Vector3::ZERO {
+            motor.lin += TnuaVelChange::boost(-corner_nudge.velocity_to_cancel);
+            corner_nudge.velocity_to_cancel = Vector3::ZERO;
+            continue;
+        }
+
+        if controller.is_airborne().ok() != Some(true) {
+            continue;
+        }
+        let up = -sensor.cast_direction.adjust_precision();
+        let upward_speed = tracker.velocity.dot(up);
+        if upward_speed <= 0.0 {
+            continue;
+        }
+        let rise = up * upward_speed * corner_nudge.lookahead_time;
+        let Some(nudge) =
+            corner_nudge.find_nudge(&*spatial_ext, entity, tracker.translation, up, rise)
+        else {
+            continue;
+        };
+        let velocity = nudge / frame_duration;
+        motor.lin += TnuaVelChange::boost(velocity);
+        corner_nudge.velocity_to_cancel = velocity;
+    }
+}
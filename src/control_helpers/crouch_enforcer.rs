@@ -61,6 +61,13 @@ impl Plugin for TnuaCrouchEnforcerPlugin {
 ///         ..Default::default()
 ///     }));
 ///     ```
+///
+/// The crouch enforcer can also swap the character's collider for a smaller one while crouching,
+/// so that the character can fit into crawl spaces - see
+/// [`with_collider_swap`](TnuaCrouchEnforcer::with_collider_swap).
+///
+/// To check if there is room to stand up without using the crouch enforcer (or from a different
+/// action), use [`TnuaHeadroomQuery`](crate::control_helpers::TnuaHeadroomQuery).
 #[derive(Component)]
 pub struct TnuaCrouchEnforcer {
     sensor_entity: Option<Entity>,
@@ -68,6 +75,13 @@ pub struct TnuaCrouchEnforcer {
     modify_sensor: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
     enforced_action: Option<(Box<dyn DynamicCrouchEnforcedAction>, bool)>,
     currently_enforcing: bool,
+    collider_swap: Option<ColliderSwap>,
+}
+
+struct ColliderSwap {
+    crouched: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
+    standing: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
+    is_crouched: bool,
 }
 
 impl TnuaCrouchEnforcer {
@@ -92,9 +106,58 @@ impl TnuaCrouchEnforcer {
             modify_sensor: Box::new(modify_sensor),
             enforced_action: None,
             currently_enforcing: false,
+            collider_swap: None,
         }
     }
 
+    /// Swap the collider of the character while it crouches.
+    ///
+    /// `crouched` is called with the command of the character entity when the crouch action starts,
+    /// and `standing` is called when the action ends and there is room to stand up (as detected by
+    /// the crouch enforcer's sensor). Both functions should insert the physics backend's collider
+    /// component (or modify it some other way, e.g. by scaling it):
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_tnua::control_helpers::TnuaCrouchEnforcer;
+    /// # #[derive(Component)]
+    /// # struct Collider;
+    /// # impl Collider {
+    /// #     fn capsule(_: f32, _: f32) -> Self { Self }
+    /// # }
+    /// TnuaCrouchEnforcer::new(0.5 * Vec3::Y, |_| {}).with_collider_swap(
+    ///     |cmd| {
+    ///         cmd.insert(Collider::capsule(0.5, 0.0));
+    ///     },
+    ///     |cmd| {
+    ///         cmd.insert(Collider::capsule(0.5, 1.0));
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// Note that the sensor's `offset` (as set by [`TnuaCrouchEnforcer::new`]) is not affected by
+    /// this, and should still be placed at the top of the standing collider.
+    pub fn with_collider_swap(
+        mut self,
+        crouched: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+        standing: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        self.collider_swap = Some(ColliderSwap {
+            crouched: Box::new(crouched),
+            standing: Box::new(standing),
+            is_crouched: false,
+        });
+        self
+    }
+
+    /// Whether the collider was swapped for the crouching one by
+    /// [`with_collider_swap`](Self::with_collider_swap).
+    pub fn is_collider_crouched(&self) -> bool {
+        self.collider_swap
+            .as_ref()
+            .is_some_and(|collider_swap| collider_swap.is_crouched)
+    }
+
     pub fn enforcing<A: TnuaCrouchEnforcedAction>(&mut self, mut crouch_action: A) -> A {
         if let Some((enforced_action, fed_this_frame)) = self.enforced_action.as_mut() {
             if enforced_action.overwrite(&crouch_action).is_ok() {
@@ -117,7 +180,7 @@ impl TnuaCrouchEnforcer {
 }
 
 /// An action that can be enforced by [`TnuaCrouchEnforcer`].
-pub trait TnuaCrouchEnforcedAction: TnuaAction + Clone {
+pub trait TnuaCrouchEnforcedAction: TnuaAction {
     /// The range, from the sensor's offset (as set by [`TnuaCrouchEnforcer::new`]), to check for a
     /// ceiling. If the sensor finds anything within that range - the crouch will be enforced.
     fn range_to_cast_up(&self, state: &Self::State) -> Float;
@@ -170,6 +233,7 @@ fn update_crouch_enforcer(
             cast_range: Float,
         }
         let set_sensor: Option<SetSensor>;
+        let crouch_action_active: bool;
         if let Some((enforced_action, fed_this_frame)) = crouch_enforcer.enforced_action.as_mut() {
             if *fed_this_frame {
                 set_sensor = enforced_action
@@ -181,13 +245,16 @@ fn update_crouch_enforcer(
                             cast_range,
                         })
                     });
+                crouch_action_active = set_sensor.is_some();
                 *fed_this_frame = false;
             } else {
                 set_sensor = None;
+                crouch_action_active = false;
                 crouch_enforcer.enforced_action = None;
             }
         } else {
             set_sensor = None;
+            crouch_action_active = false;
         }
 
         if let Some(SetSensor {
@@ -245,5 +312,18 @@ fn update_crouch_enforcer(
         } else {
             crouch_enforcer.currently_enforcing = false;
         }
+
+        let should_crouch = crouch_action_active || crouch_enforcer.currently_enforcing;
+        if let Some(collider_swap) = crouch_enforcer.collider_swap.as_mut() {
+            if collider_swap.is_crouched != should_crouch {
+                let mut cmd = commands.entity(owner_entity);
+                if should_crouch {
+                    (collider_swap.crouched)(&mut cmd);
+                } else {
+                    (collider_swap.standing)(&mut cmd);
+                }
+                collider_swap.is_crouched = should_crouch;
+            }
+        }
     }
 }
@@ -0,0 +1,131 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float};
+
+use crate::control_helpers::TnuaSteering;
+use crate::controller::{apply_controller_system, apply_terminal_velocity_system};
+use crate::obstacle_radar::TnuaObstacleRadar;
+use crate::schedules::TnuaScheduleFilter;
+use crate::{TnuaMotor, TnuaPaused, TnuaPipelineStages, TnuaProximitySensor, TnuaToggle};
+
+pub struct TnuaCrowdSeparationPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaCrowdSeparationPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaCrowdSeparationPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaCrowdSeparation`] work.
+impl Plugin for TnuaCrowdSeparationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaCrowdSeparation>();
+        app.add_systems(
+            self.schedule,
+            apply_crowd_separation::<TnuaCrowdSeparation>
+                .in_set(TnuaPipelineStages::Logic)
+                .after(apply_controller_system)
+                .before(apply_terminal_velocity_system),
+        );
+    }
+}
+
+/// Softly pushes nearby characters away from each other.
+///
+/// Add this component to characters that should not stack on or jitter against each other (e.g.
+/// groups of NPCs). Each character with this component is pushed away from the other characters
+/// with this component that its [`TnuaObstacleRadar`] detects closer than
+/// [`radius`](Self::radius), by adding an acceleration to its [`TnuaMotor`] after the basis and
+/// the action have set it.
+///
+/// Requires the plugin [`TnuaCrowdSeparationPlugin`], and a [`TnuaObstacleRadar`] on the
+/// character entity that is at least as wide as the [`radius`](Self::radius).
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaCrowdSeparation {
+    /// Other characters closer than this distance will push this character away.
+    pub radius: Float,
+
+    /// The acceleration applied when the other character is right on top of this one. It
+    /// decreases linearly to zero at [`radius`](Self::radius).
+    pub max_acceleration: Float,
+}
+
+impl Default for TnuaCrowdSeparation {
+    fn default() -> Self {
+        Self {
+            radius: 1.5,
+            max_acceleration: 20.0,
+        }
+    }
+}
+
+/// A component that makes its character get pushed away from nearby characters with the same
+/// component.
+pub(crate) trait TnuaSeparationSource: Component {
+    /// The radius and the maximum acceleration of the push, or `None` if this character should
+    /// not be pushed.
+    fn separation_params(&self) -> Option<(Float, Float)>;
+}
+
+impl TnuaSeparationSource for TnuaCrowdSeparation {
+    fn separation_params(&self) -> Option<(Float, Float)> {
+        Some((self.radius, self.max_acceleration))
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_crowd_separation<C: TnuaSeparationSource>(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &C,
+        &TnuaObstacleRadar,
+        &TnuaProximitySensor,
+        &mut TnuaMotor,
+        Option<&TnuaToggle>,
+    )>,
+    positions_query: Query<&GlobalTransform, With<C>>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    for (entity, transform, source, radar, sensor, mut motor, tnua_toggle) in query.iter_mut() {
+        let Some((radius, max_acceleration)) = source.separation_params() else {
+            continue;
+        };
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly | TnuaToggle::Frozen => continue,
+            TnuaToggle::Enabled => {}
+        }
+        let steering = TnuaSteering {
+            max_speed: max_acceleration,
+            separation_distance: radius,
+            ..TnuaSteering::new(-sensor.cast_direction)
+        };
+        motor.lin.acceleration += steering.separation_from_radar(
+            transform.translation().adjust_precision(),
+            radar,
+            |blip| {
+                if blip == entity {
+                    return None;
+                }
+                let other_transform = positions_query.get(blip).ok()?;
+                Some(other_transform.translation().adjust_precision())
+            },
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{
+    ops, AdjustPrecision, AsF32, Float, Quaternion, Vector3,
+};
+
+use crate::controller::{apply_controller_system, TnuaController};
+use crate::schedules::TnuaScheduleFilter;
+use crate::{TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker};
+
+pub struct TnuaFirstPersonPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaFirstPersonPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaFirstPersonPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaEyeAnchor`] work.
+impl Plugin for TnuaFirstPersonPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaEyeAnchor>();
+        app.add_systems(
+            self.schedule,
+            update_eye_anchor
+                .in_set(TnuaPipelineStages::Logic)
+                .after(apply_controller_system),
+        );
+    }
+}
+
+/// A smoothed position for attaching a first-person camera to a character.
+///
+/// A floating character constantly oscillates a little around its float height, and rises and
+/// sinks in small jerks when it climbs stairs or crouches. This is not noticeable from a
+/// third-person view, but a first-person camera attached to the character directly makes all of it
+/// visible. This component tracks the character's height above the ground with a low-pass filter,
+/// and offsets the eyes to cancel the part of the character's vertical movement that the filter
+/// removed.
+///
+/// While the character is airborne the eyes follow the character directly (the correction from
+/// the last time it stood on the ground fades away over [`smoothing_time`](Self::smoothing_time)).
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaEyeAnchor;
+/// # #[derive(Component)]
+/// # struct FirstPersonCamera {
+/// #     character: Entity,
+/// #     pitch: f32,
+/// # }
+/// fn place_camera(
+///     characters_query: Query<&TnuaEyeAnchor>,
+///     mut cameras_query: Query<(&FirstPersonCamera, &mut Transform)>,
+/// ) {
+///     for (camera, mut transform) in cameras_query.iter_mut() {
+///         if let Ok(eye_anchor) = characters_query.get(camera.character) {
+///             let pitch = Quat::from_rotation_x(camera.pitch);
+///             *transform = eye_anchor.transform() * Transform::from_rotation(pitch);
+///         }
+///     }
+/// }
+/// ```
+///
+/// Requires the plugin [`TnuaFirstPersonPlugin`].
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaEyeAnchor {
+    /// The height of the eyes above the character's center.
+    pub eye_height: Float,
+
+    /// The time constant, in seconds, of the filter applied to the height of the character above
+    /// the ground. Higher values make the camera smoother, but also make it lag more behind the
+    /// character when it goes up or down stairs and slopes.
+    pub smoothing_time: Float,
+
+    smoothed_proximity: Option<Float>,
+    correction: Float,
+    position: Vector3,
+    rotation: Quaternion,
+    velocity: Vector3,
+}
+
+impl Default for TnuaEyeAnchor {
+    fn default() -> Self {
+        Self::new(0.6)
+    }
+}
+
+impl TnuaEyeAnchor {
+    /// Create an eye anchor with the given [`eye_height`](Self::eye_height).
+    pub fn new(eye_height: Float) -> Self {
+        Self {
+            eye_height,
+            smoothing_time: 0.1,
+            smoothed_proximity: None,
+            correction: 0.0,
+            position: Vector3::ZERO,
+            rotation: Quaternion::IDENTITY,
+            velocity: Vector3::ZERO,
+        }
+    }
+
+    /// The smoothed position of the character's eyes - the recommended attachment point for a
+    /// first-person camera.
+    pub fn position(&self) -> Vector3 {
+        self.position
+    }
+
+    /// The velocity of the [`position`](Self::position). Unlike the velocity of the character,
+    /// this does not include the oscillations of the float spring, so it is safe to use for
+    /// head-bob and similar camera effects.
+    pub fn velocity(&self) -> Vector3 {
+        self.velocity
+    }
+
+    /// The distance the eyes are moved along the up direction from where they would have been
+    /// without the smoothing.
+    pub fn correction(&self) -> Float {
+        self.correction
+    }
+
+    /// A transform with the [`position`](Self::position) of the eyes and the rotation of the
+    /// character.
+    pub fn transform(&self) -> Transform {
+        Transform {
+            translation: self.position.f32(),
+            rotation: self.rotation.f32(),
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+fn update_eye_anchor(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+        &mut TnuaEyeAnchor,
+    )>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    let frame_duration = time.delta_seconds().adjust_precision();
+    if frame_duration <= 0.0 {
+        return;
+    }
+    for (entity, controller, tracker, sensor, mut eye_anchor) in query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        let eye_anchor = eye_anchor.as_mut();
+        let up = -sensor.cast_direction.adjust_precision();
+        let blend = if 0.0 < eye_anchor.smoothing_time {
+            1.0 - ops::exp(-frame_duration / eye_anchor.smoothing_time)
+        } else {
+            1.0
+        };
+
+        let previous_correction = eye_anchor.correction;
+        match (controller.is_airborne().ok(), sensor.output.as_ref()) {
+            (Some(false), Some(sensor_output)) => {
+                let proximity = sensor_output.proximity;
+                // Continue from where the eyes were, so that landing does not make them jump.
+                let smoothed = eye_anchor
+                    .smoothed_proximity
+                    .unwrap_or(proximity + eye_anchor.correction);
+                let smoothed = smoothed + (proximity - smoothed) * blend;
+                eye_anchor.smoothed_proximity = Some(smoothed);
+                eye_anchor.correction = smoothed - proximity;
+            }
+            _ => {
+                eye_anchor.smoothed_proximity = None;
+                eye_anchor.correction *= 1.0 - blend;
+            }
+        }
+
+        eye_anchor.position =
+            tracker.translation + (eye_anchor.eye_height + eye_anchor.correction) * up;
+        eye_anchor.rotation = tracker.rotation;
+        eye_anchor.velocity =
+            tracker.velocity + (eye_anchor.correction - previous_correction) / frame_duration * up;
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float};
+
+use crate::controller::{apply_controller_system, TnuaController};
+use crate::schedules::TnuaScheduleFilter;
+use crate::{
+    TnuaGroundClass, TnuaPaused, TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker,
+    TnuaSurfaceMaterial, TnuaToggle,
+};
+
+pub struct TnuaFootstepsPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaFootstepsPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaFootstepsPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaFootsteps`] work.
+impl Plugin for TnuaFootstepsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaFootsteps>();
+        app.add_event::<TnuaFootstepEvent>();
+        app.add_systems(
+            self.schedule,
+            send_footstep_events
+                .in_set(TnuaPipelineStages::Logic)
+                .after(apply_controller_system),
+        );
+    }
+}
+
+/// Add this component to a character to make [`TnuaFootstepsPlugin`] send a
+/// [`TnuaFootstepEvent`] every [`stride`](Self::stride) units of distance it travels on the
+/// ground.
+///
+/// The distance is measured along the ground (perpendicular to the up direction) and relative to
+/// the entity the character stands on, so riding a moving platform does not make footsteps. It is
+/// not reset while the character is airborne, so a character that keeps running after landing
+/// continues the rhythm of its steps.
+///
+/// Requires the plugin [`TnuaFootstepsPlugin`].
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaFootsteps {
+    /// The distance the character needs to travel on the ground between two footsteps.
+    pub stride: Float,
+
+    distance_since_last_step: Float,
+    steps_taken: u32,
+}
+
+impl Default for TnuaFootsteps {
+    fn default() -> Self {
+        Self::new(0.8)
+    }
+}
+
+impl TnuaFootsteps {
+    /// Create a footsteps tracker with the given [`stride`](Self::stride).
+    pub fn new(stride: Float) -> Self {
+        Self {
+            stride,
+            distance_since_last_step: 0.0,
+            steps_taken: 0,
+        }
+    }
+}
+
+/// An event sent by [`TnuaFootstepsPlugin`] when a character with a [`TnuaFootsteps`] component
+/// takes a step.
+///
+/// Use it for footstep sounds and footprints, instead of tracking the ground contact and the speed
+/// of the character in the audio code.
+#[derive(Event, Debug, Clone)]
+pub struct TnuaFootstepEvent {
+    /// The character entity that took the step.
+    pub entity: Entity,
+
+    /// The entity the character stepped on.
+    pub ground_entity: Entity,
+
+    /// How the proximity sensor classified the ground the character stepped on.
+    pub ground_class: TnuaGroundClass,
+
+    /// The [`TnuaSurfaceMaterial`] of the ground entity, if it has one.
+    pub surface_material: Option<TnuaSurfaceMaterial>,
+
+    /// The speed, along the ground, of the character when it took the step.
+    pub speed: Float,
+
+    /// The number of steps the character took before this one. Can be used to alternate between
+    /// the left and the right foot.
+    pub step_index: u32,
+}
+
+#[allow(clippy::type_complexity)]
+fn send_footstep_events(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+        &mut TnuaFootsteps,
+        Option<&TnuaToggle>,
+    )>,
+    material_query: Query<&TnuaSurfaceMaterial>,
+    paused: Option<Res<TnuaPaused>>,
+    mut writer: EventWriter<TnuaFootstepEvent>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    let frame_duration = time.delta_seconds().adjust_precision();
+    for (entity, controller, tracker, sensor, mut footsteps, tnua_toggle) in query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly | TnuaToggle::Frozen => continue,
+            TnuaToggle::Enabled => {}
+        }
+        if controller.is_airborne().ok() != Some(false) {
+            continue;
+        }
+        let Some(sensor_output) = sensor.output.as_ref() else {
+            continue;
+        };
+        let up = -sensor.cast_direction.adjust_precision();
+        let speed = (tracker.velocity - sensor_output.entity_linvel)
+            .reject_from(up)
+            .length();
+        if speed == 0.0 || footsteps.stride <= 0.0 {
+            continue;
+        }
+        let footsteps = footsteps.as_mut();
+        footsteps.distance_since_last_step += speed * frame_duration;
+        while footsteps.stride <= footsteps.distance_since_last_step {
+            footsteps.distance_since_last_step -= footsteps.stride;
+            writer.send(TnuaFootstepEvent {
+                entity,
+                ground_entity: sensor_output.entity,
+                ground_class: sensor_output.ground_class,
+                surface_material: material_query.get(sensor_output.entity).ok().copied(),
+                speed,
+                step_index: footsteps.steps_taken,
+            });
+            footsteps.steps_taken = footsteps.steps_taken.wrapping_add(1);
+        }
+    }
+}
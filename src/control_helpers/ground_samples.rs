@@ -0,0 +1,189 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use crate::subservient_sensors::TnuaSubservientSensor;
+use crate::{TnuaPipelineStages, TnuaProximitySensor};
+
+pub struct TnuaGroundSamplesPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaGroundSamplesPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaGroundSamplesPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaGroundSamples`] work.
+impl Plugin for TnuaGroundSamplesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_ground_samples.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Samples the ground at several points around the character, e.g. for placing feet with IK.
+///
+/// This will create a child entity with a proximity sensor for each sample offset. The sensors are
+/// cast in the same direction as the character's main sensor, and their results are published in
+/// [`samples`](Self::samples) so that foot-IK solvers can place the feet on stairs and slopes.
+///
+/// Using it requires adding the plugin [`TnuaGroundSamplesPlugin`] and adding
+/// [`TnuaGroundSamples`] as a component to the character entity:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaGroundSamples;
+/// # use bevy_tnua::math::Vector3;
+/// # fn setup(mut commands: Commands, character_entity: Entity) {
+/// commands.entity(character_entity).insert(TnuaGroundSamples::new(
+///     [Vector3::new(-0.2, 0.0, 0.0), Vector3::new(0.2, 0.0, 0.0)],
+///     2.0,
+///     |_| {},
+/// ));
+/// # }
+/// ```
+#[derive(Component)]
+pub struct TnuaGroundSamples {
+    offsets: Vec<Vector3>,
+    cast_range: Float,
+    modify_sensor: Box<dyn Send + Sync + Fn(&mut EntityCommands)>,
+    sensor_entities: Vec<Entity>,
+    samples: Vec<Option<TnuaGroundSample>>,
+}
+
+/// A single ground sample collected by [`TnuaGroundSamples`].
+#[derive(Debug, Clone)]
+pub struct TnuaGroundSample {
+    /// The entity of the collider that was hit.
+    pub entity: Entity,
+
+    /// The point, in world space, where the cast hit the ground.
+    pub position: Vector3,
+
+    /// The normal of the ground at the hit point.
+    pub normal: Dir3,
+}
+
+impl TnuaGroundSamples {
+    /// Create a new ground sampler, to be added as a component to the character entity.
+    ///
+    /// # Arguments:
+    ///
+    /// * `offsets` - the origins of the sample casts, relative to the character entity. Typically
+    ///   one for each foot.
+    /// * `cast_range` - how far to cast each sample.
+    /// * `modify_sensor` - a function called with the command that creates each of the sensors.
+    ///   This function has the opportunity to add things to the sensor entity - mostly cast-shape
+    ///   components.
+    pub fn new(
+        offsets: impl IntoIterator<Item = Vector3>,
+        cast_range: Float,
+        modify_sensor: impl 'static + Send + Sync + Fn(&mut EntityCommands),
+    ) -> Self {
+        let offsets: Vec<Vector3> = offsets.into_iter().collect();
+        Self {
+            samples: vec![None; offsets.len()],
+            offsets,
+            cast_range,
+            modify_sensor: Box::new(modify_sensor),
+            sensor_entities: Vec::new(),
+        }
+    }
+
+    /// The samples from the last time the sensors ran, in the same order as the offsets.
+    ///
+    /// A sample is `None` if its cast did not hit anything within range.
+    pub fn samples(&self) -> &[Option<TnuaGroundSample>] {
+        &self.samples
+    }
+
+    /// The sample of the offset at the given index.
+    pub fn sample(&self, index: usize) -> Option<&TnuaGroundSample> {
+        self.samples.get(index)?.as_ref()
+    }
+}
+
+fn update_ground_samples(
+    mut query: Query<(Entity, &mut TnuaGroundSamples)>,
+    mut sensors_query: Query<(
+        &mut TnuaProximitySensor,
+        Option<&GlobalTransform>,
+        Has<TnuaSubservientSensor>,
+    )>,
+    mut commands: Commands,
+) {
+    for (owner_entity, mut ground_samples) in query.iter_mut() {
+        let ground_samples = ground_samples.as_mut();
+        let Ok((main_sensor, _, _)) = sensors_query.get(owner_entity) else {
+            continue;
+        };
+        let cast_direction = main_sensor.cast_direction;
+
+        if ground_samples.sensor_entities.len() != ground_samples.offsets.len() {
+            for sensor_entity in ground_samples.sensor_entities.drain(..) {
+                commands.entity(sensor_entity).despawn_recursive();
+            }
+            for offset in ground_samples.offsets.iter() {
+                let mut cmd = commands.spawn((
+                    TransformBundle {
+                        ..Default::default()
+                    },
+                    TnuaSubservientSensor { owner_entity },
+                    TnuaProximitySensor {
+                        cast_origin: *offset,
+                        cast_direction,
+                        cast_range: ground_samples.cast_range,
+                        ..Default::default()
+                    },
+                ));
+                cmd.set_parent(owner_entity);
+                (ground_samples.modify_sensor)(&mut cmd);
+                ground_samples.sensor_entities.push(cmd.id());
+            }
+            ground_samples.samples = vec![None; ground_samples.offsets.len()];
+            continue;
+        }
+
+        for ((sensor_entity, offset), sample) in ground_samples
+            .sensor_entities
+            .iter()
+            .zip(ground_samples.offsets.iter())
+            .zip(ground_samples.samples.iter_mut())
+        {
+            let Ok((mut sensor, transform, true)) = sensors_query.get_mut(*sensor_entity) else {
+                *sample = None;
+                continue;
+            };
+            *sample = sensor.output.as_ref().map(|output| {
+                let origin = if let Some(transform) = transform {
+                    transform
+                        .transform_point(sensor.cast_origin.f32())
+                        .adjust_precision()
+                } else {
+                    sensor.cast_origin
+                };
+                TnuaGroundSample {
+                    entity: output.entity,
+                    position: origin + sensor.cast_direction.adjust_precision() * output.proximity,
+                    normal: output.normal,
+                }
+            });
+            sensor.cast_origin = *offset;
+            sensor.cast_direction = cast_direction;
+            sensor.cast_range = ground_samples.cast_range;
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::ecs::system::{StaticSystemParam, SystemParam};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Float};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::spatial_ext::TnuaSpatialExt;
+use crate::{TnuaProximitySensor, TnuaRigidBodyTracker};
+
+/// A system param for checking if a character has room above it - e.g. to stand up from a crouch,
+/// to rise from prone to crouch, or to land a vault under a low ceiling.
+///
+/// This sweeps the character's own collider upward, so unlike [`TnuaCrouchEnforcer`] it does not
+/// need a dedicated sensor and can be used at any time, by gameplay code or by systems that decide
+/// which actions to feed.
+///
+/// The type parameter is the [`TnuaSpatialExt`] implementation of the physics backend:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy::ecs::system::SystemParam;
+/// # use bevy_tnua::control_helpers::TnuaHeadroomQuery;
+/// # use bevy_tnua::spatial_ext::TnuaSpatialExt;
+/// # #[derive(Component)]
+/// # struct Player;
+/// // `S` would usually be a concrete type, like `TnuaSpatialExtAvian3d`.
+/// fn show_stand_up_prompt<S: SystemParam + 'static>(
+///     headroom: TnuaHeadroomQuery<S>,
+///     query: Query<Entity, With<Player>>,
+/// ) where
+///     for<'w, 's> S::Item<'w, 's>: TnuaSpatialExt,
+/// {
+///     for player in query.iter() {
+///         if headroom.has_room_to_stand(player) == Some(true) {
+///             // show the prompt
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`TnuaCrouchEnforcer`]: crate::control_helpers::TnuaCrouchEnforcer
+#[derive(SystemParam)]
+pub struct TnuaHeadroomQuery<'w, 's, S: SystemParam + 'static> {
+    spatial_ext: StaticSystemParam<'w, 's, S>,
+    characters_query: Query<
+        'w,
+        's,
+        (
+            &'static TnuaRigidBodyTracker,
+            &'static TnuaProximitySensor,
+            Option<&'static TnuaController>,
+        ),
+    >,
+}
+
+impl<S: SystemParam + 'static> TnuaHeadroomQuery<'_, '_, S>
+where
+    for<'w, 's> S::Item<'w, 's>: TnuaSpatialExt,
+{
+    /// How far, up to `max_distance`, the character can rise before its collider hits something.
+    ///
+    /// The up direction is the opposite of the cast direction of the character's
+    /// [`TnuaProximitySensor`].
+    ///
+    /// Returns `None` if the character could not be found.
+    pub fn headroom(&self, character: Entity, max_distance: Float) -> Option<Float> {
+        let (tracker, sensor, _) = self.characters_query.get(character).ok()?;
+        let up = -sensor.cast_direction.adjust_precision();
+        Some(
+            match self.spatial_ext.sweep_character(
+                character,
+                tracker.translation,
+                up * max_distance,
+            ) {
+                Some(hit) => hit.distance.min(max_distance),
+                None => max_distance,
+            },
+        )
+    }
+
+    /// Whether the character can rise by `height` without its collider hitting anything.
+    ///
+    /// Returns `None` if the character could not be found.
+    pub fn has_room(&self, character: Entity, height: Float) -> Option<bool> {
+        Some(height <= self.headroom(character, height)?)
+    }
+
+    /// Whether a character that floats lower than its
+    /// [`float_height`](TnuaBuiltinWalk::float_height) (e.g. because it is crouching) can rise
+    /// back to it.
+    ///
+    /// Returns `None` if the character could not be found, if its basis is not
+    /// [`TnuaBuiltinWalk`], or if it is not standing on anything.
+    pub fn has_room_to_stand(&self, character: Entity) -> Option<bool> {
+        let (_, sensor, controller) = self.characters_query.get(character).ok()?;
+        let (_, walk_state) = controller?.concrete_basis::<TnuaBuiltinWalk>()?;
+        let sensor_output = sensor.output.as_ref()?;
+        let height = walk_state.effective_float_height() - sensor_output.proximity;
+        if height <= 0.0 {
+            return Some(true);
+        }
+        self.has_room(character, height)
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AsF32, Float, Vector3};
+
+use crate::builtins::{TnuaBuiltinJump, TnuaBuiltinWalk};
+use crate::controller::TnuaController;
+
+/// A jump from one point to another - e.g. an off-mesh connection of a navigation mesh.
+#[derive(Debug, Clone)]
+pub struct TnuaJumpLink {
+    /// Where the character jumps from (the position of the character's center).
+    pub start: Vector3,
+
+    /// Where the character should land (the position of the character's center).
+    pub end: Vector3,
+
+    /// The height of the top of the jump, above [`start`](Self::start).
+    ///
+    /// Must be higher than the end of the link.
+    pub apex_height: Float,
+}
+
+/// The reason a [`TnuaJumpLink`] cannot be planned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaJumpLinkError {
+    /// There is no gravity, so the jump has no arc.
+    NoGravity,
+    /// The [`apex_height`](TnuaJumpLink::apex_height) is not above the end of the link.
+    ApexTooLow,
+}
+
+/// The parameters calculated for traversing a [`TnuaJumpLink`].
+#[derive(Clone)]
+pub struct TnuaJumpLinkPlan {
+    /// The jump action to feed.
+    ///
+    /// The extra gravities that are not taken into account by the plan are disabled in it.
+    pub jump: TnuaBuiltinJump,
+
+    /// The horizontal velocity the character needs to keep during the jump.
+    pub horizontal_velocity: Vector3,
+
+    /// The expected time, in seconds, from takeoff to landing.
+    pub flight_time: Float,
+}
+
+impl TnuaJumpLink {
+    /// Calculate the jump parameters needed for traversing the link.
+    ///
+    /// The `template` is used for all the jump settings that are not calculated. Its
+    /// [`fall_extra_gravity`](TnuaBuiltinJump::fall_extra_gravity) is taken into account, but the
+    /// other extra gravities that shape the ascent are disabled to keep the arc predictable.
+    pub fn plan(
+        &self,
+        gravity: Vector3,
+        template: TnuaBuiltinJump,
+    ) -> Result<TnuaJumpLinkPlan, TnuaJumpLinkError> {
+        let gravity_strength = gravity.length();
+        if gravity_strength <= 0.0 {
+            return Err(TnuaJumpLinkError::NoGravity);
+        }
+        let up = -gravity / gravity_strength;
+        let offset = self.end - self.start;
+        let height_difference = offset.dot(up);
+        if self.apex_height <= 0.0 || self.apex_height <= height_difference {
+            return Err(TnuaJumpLinkError::ApexTooLow);
+        }
+
+        let fall_gravity = gravity_strength + template.fall_extra_gravity.max(0.0);
+        let ascent_time = (2.0 * self.apex_height / gravity_strength).sqrt();
+        let descent_time = (2.0 * (self.apex_height - height_difference) / fall_gravity).sqrt();
+        let flight_time = ascent_time + descent_time;
+
+        Ok(TnuaJumpLinkPlan {
+            jump: TnuaBuiltinJump {
+                height: self.apex_height,
+                allow_in_air: false,
+                upslope_extra_gravity: 0.0,
+                takeoff_extra_gravity: 0.0,
+                peak_prevention_extra_gravity: 0.0,
+                ..template
+            },
+            horizontal_velocity: offset.reject_from(up) / flight_time,
+            flight_time,
+        })
+    }
+}
+
+/// The status of a [`TnuaJumpLinkExecutor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaJumpLinkStatus {
+    /// No jump link is being traversed.
+    Idle,
+    /// The jump link is being traversed.
+    InProgress,
+    /// The character has landed at the end of the jump link.
+    Succeeded,
+    /// The character has landed too far from the end of the jump link.
+    LandedElsewhere,
+    /// The character did not land in time (or did not take off at all).
+    TimedOut,
+}
+
+/// Helper for making a character traverse [`TnuaJumpLink`]s.
+///
+/// Place this component on the character entity. When the character is at the start of the jump
+/// link, call [`traverse`](Self::traverse). Then call [`update`](Self::update) every frame instead
+/// of feeding the walk basis and the jump action directly, until it stops returning
+/// [`TnuaJumpLinkStatus::InProgress`].
+///
+/// Note that the character is expected to already move at the
+/// [`horizontal_velocity`](TnuaJumpLinkPlan::horizontal_velocity) when it takes off (or to have a
+/// high enough [`air_acceleration`](TnuaBuiltinWalk::air_acceleration) to reach it quickly) -
+/// otherwise it may fall short of the end of the link.
+#[derive(Component, Clone)]
+pub struct TnuaJumpLinkExecutor {
+    current: Option<TnuaJumpLinkTraversal>,
+
+    /// How far from the end of the link the character may land and still have the traversal
+    /// considered successful.
+    pub landing_tolerance: Float,
+}
+
+#[derive(Clone)]
+struct TnuaJumpLinkTraversal {
+    link: TnuaJumpLink,
+    plan: TnuaJumpLinkPlan,
+    elapsed: Float,
+    took_off: bool,
+}
+
+impl Default for TnuaJumpLinkExecutor {
+    fn default() -> Self {
+        Self {
+            current: None,
+            landing_tolerance: 0.5,
+        }
+    }
+}
+
+impl TnuaJumpLinkExecutor {
+    /// Start traversing a jump link.
+    ///
+    /// `gravity` is the gravity affecting the character - usually the
+    /// [`gravity`](crate::TnuaRigidBodyTracker::gravity) of its rigid body tracker.
+    pub fn traverse(
+        &mut self,
+        link: TnuaJumpLink,
+        gravity: Vector3,
+        template: TnuaBuiltinJump,
+    ) -> Result<&TnuaJumpLinkPlan, TnuaJumpLinkError> {
+        let plan = link.plan(gravity, template)?;
+        let traversal = self.current.insert(TnuaJumpLinkTraversal {
+            link,
+            plan,
+            elapsed: 0.0,
+            took_off: false,
+        });
+        Ok(&traversal.plan)
+    }
+
+    /// Stop traversing the jump link.
+    pub fn cancel(&mut self) {
+        self.current = None;
+    }
+
+    /// The plan of the jump link currently being traversed.
+    pub fn current_plan(&self) -> Option<&TnuaJumpLinkPlan> {
+        Some(&self.current.as_ref()?.plan)
+    }
+
+    /// Call this every frame to execute the jump link.
+    ///
+    /// While the traversal is in progress, this feeds the walk basis (based on `walk`, with the
+    /// velocity and direction overwritten) and the planned jump action to the controller. Once
+    /// the traversal is finished, nothing is fed and the game code should resume feeding the
+    /// controller by itself.
+    pub fn update(
+        &mut self,
+        controller: &mut TnuaController,
+        position: Vector3,
+        frame_duration: Float,
+        walk: TnuaBuiltinWalk,
+    ) -> TnuaJumpLinkStatus {
+        let Some(traversal) = self.current.as_mut() else {
+            return TnuaJumpLinkStatus::Idle;
+        };
+        traversal.elapsed += frame_duration;
+
+        let airborne = controller
+            .dynamic_basis()
+            .is_some_and(|basis| basis.is_airborne());
+        if airborne {
+            traversal.took_off = true;
+        } else if traversal.took_off {
+            let status = if position.distance(traversal.link.end) <= self.landing_tolerance {
+                TnuaJumpLinkStatus::Succeeded
+            } else {
+                TnuaJumpLinkStatus::LandedElsewhere
+            };
+            self.current = None;
+            return status;
+        }
+
+        // Allow some leeway for takeoff and for floating back to the ground after landing.
+        if 2.0 * traversal.plan.flight_time + 0.5 < traversal.elapsed {
+            self.current = None;
+            return TnuaJumpLinkStatus::TimedOut;
+        }
+
+        let horizontal_velocity = traversal.plan.horizontal_velocity;
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: horizontal_velocity,
+            desired_forward: Dir3::new(horizontal_velocity.f32())
+                .ok()
+                .or(walk.desired_forward),
+            ..walk
+        });
+        controller.action(traversal.plan.jump.clone());
+        TnuaJumpLinkStatus::InProgress
+    }
+}
@@ -0,0 +1,215 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::ecs::system::{StaticSystemParam, SystemParam};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::{apply_controller_system, TnuaController};
+use crate::schedules::TnuaScheduleFilter;
+use crate::spatial_ext::TnuaSpatialExt;
+use crate::{
+    TnuaPaused, TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaToggle,
+};
+
+/// A plugin required for making [`TnuaLedgeSafety`] work.
+///
+/// The type parameter is the [`TnuaSpatialExt`] implementation of the physics backend, e.g.
+/// `TnuaLedgeSafetyPlugin::<TnuaSpatialExtAvian3d>::default()`.
+pub struct TnuaLedgeSafetyPlugin<S> {
+    schedule: InternedScheduleLabel,
+    _phantom: PhantomData<fn() -> S>,
+}
+
+impl<S> TnuaLedgeSafetyPlugin<S> {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S> Default for TnuaLedgeSafetyPlugin<S> {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl<S: SystemParam + 'static> Plugin for TnuaLedgeSafetyPlugin<S>
+where
+    for<'w, 's> S::Item<'w, 's>: TnuaSpatialExt,
+{
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaLedgeSafety>();
+        app.add_event::<TnuaLedgeEvent>();
+        app.add_systems(
+            self.schedule,
+            apply_ledge_safety::<S>
+                .in_set(TnuaPipelineStages::Logic)
+                .before(apply_controller_system),
+        );
+    }
+}
+
+/// What [`TnuaLedgeSafety`] does when the character walks toward a ledge.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaLedgeResponse {
+    /// Cancel the [`desired_velocity`](TnuaBuiltinWalk::desired_velocity), so that the character
+    /// stops before the ledge. A [`TnuaLedgeEvent`] is still sent.
+    #[default]
+    Stop,
+    /// Only send a [`TnuaLedgeEvent`], and let the game code decide what to do.
+    Report,
+}
+
+/// Prevents a character from walking off ledges - e.g. patrolling enemies on platforms.
+///
+/// While the character stands on the ground and walks with [`TnuaBuiltinWalk`], the ground is
+/// probed ahead of it in the direction of the
+/// [`desired_velocity`](TnuaBuiltinWalk::desired_velocity). If there is no ground there within
+/// [`max_drop`](Self::max_drop) below the ground the character stands on (or if it is steeper than
+/// the walk's [`max_slope`](TnuaBuiltinWalk::max_slope)), the character is at a ledge, and the
+/// [`response`](Self::response) is applied.
+///
+/// The probe distance grows with the character's speed by the distance it needs for braking with
+/// the walk's [`acceleration`](TnuaBuiltinWalk::acceleration), so that fast characters still stop
+/// in time.
+///
+/// This adjusts the basis after the game code fed it, so the game code can keep feeding the
+/// character toward the ledge and rely on this to stop it.
+///
+/// Requires the plugin [`TnuaLedgeSafetyPlugin`].
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaLedgeSafety {
+    /// Drops higher than this are considered ledges. Set it to about the height of the stairs the
+    /// character may walk down.
+    pub max_drop: Float,
+
+    /// How far ahead of the character's center to probe the ground, when it is not moving. Should
+    /// be a bit more than the radius of the character's collider.
+    pub probe_distance: Float,
+
+    /// What to do when the character walks toward a ledge.
+    pub response: TnuaLedgeResponse,
+
+    at_ledge: bool,
+}
+
+impl Default for TnuaLedgeSafety {
+    fn default() -> Self {
+        Self {
+            max_drop: 0.5,
+            probe_distance: 0.6,
+            response: TnuaLedgeResponse::Stop,
+            at_ledge: false,
+        }
+    }
+}
+
+impl TnuaLedgeSafety {
+    /// Whether the character walked toward a ledge in the last frame.
+    pub fn is_at_ledge(&self) -> bool {
+        self.at_ledge
+    }
+}
+
+/// An event sent by [`TnuaLedgeSafetyPlugin`] when a character with [`TnuaLedgeSafety`] starts
+/// walking toward a ledge.
+///
+/// The event is sent once, and is sent again only after the character stops walking toward a
+/// ledge (e.g. turns away from it) and then walks toward a ledge again.
+#[derive(Event, Debug, Clone)]
+pub struct TnuaLedgeEvent {
+    /// The character entity that reached the ledge.
+    pub entity: Entity,
+
+    /// The direction the character wanted to walk in.
+    pub direction: Dir3,
+
+    /// The point, at the height of the ground the character stands on, where no standable ground
+    /// was found.
+    pub probe_point: Vector3,
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_ledge_safety<S: SystemParam + 'static>(
+    spatial_ext: StaticSystemParam<S>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaLedgeSafety,
+        &mut TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+        Option<&TnuaToggle>,
+    )>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    mut writer: EventWriter<TnuaLedgeEvent>,
+) where
+    for<'w, 's> S::Item<'w, 's>: TnuaSpatialExt,
+{
+    for (entity, mut ledge_safety, mut controller, tracker, sensor, tnua_toggle) in query.iter_mut()
+    {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly | TnuaToggle::Frozen => continue,
+            TnuaToggle::Enabled => {}
+        }
+
+        let up = -sensor.cast_direction;
+        let probe = (|| {
+            let (walk, walk_state) = controller.concrete_basis::<TnuaBuiltinWalk>()?;
+            walk_state.standing_on_entity()?;
+            let proximity = sensor.output.as_ref()?.proximity;
+            let desired_velocity = walk.desired_velocity.reject_from(up.adjust_precision());
+            let direction = Dir3::new(desired_velocity.f32()).ok()?;
+
+            let speed = tracker.velocity.dot(direction.adjust_precision()).max(0.0);
+            let braking_distance = if 0.0 < walk.acceleration {
+                speed.powi(2) / (2.0 * walk.acceleration)
+            } else {
+                0.0
+            };
+            let probe_point = tracker.translation - proximity * up.adjust_precision()
+                + (ledge_safety.probe_distance + braking_distance) * direction.adjust_precision();
+            let is_safe = spatial_ext
+                .standable_ground_at(
+                    entity,
+                    probe_point,
+                    up,
+                    walk.max_slope,
+                    ledge_safety.max_drop,
+                )
+                .is_some();
+            Some((direction, probe_point, is_safe))
+        })();
+
+        let Some((direction, probe_point, false)) = probe else {
+            ledge_safety.at_ledge = false;
+            continue;
+        };
+
+        if !ledge_safety.at_ledge {
+            writer.send(TnuaLedgeEvent {
+                entity,
+                direction,
+                probe_point,
+            });
+        }
+        ledge_safety.at_ledge = true;
+
+        if ledge_safety.response == TnuaLedgeResponse::Stop {
+            if let Some(walk) = controller.concrete_basis_input_mut::<TnuaBuiltinWalk>() {
+                let direction = direction.adjust_precision();
+                walk.desired_velocity -= walk.desired_velocity.dot(direction) * direction;
+            }
+        }
+    }
+}
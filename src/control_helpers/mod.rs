@@ -7,9 +7,51 @@
 //! be complex to use. This module provides helpers that allow using these features in an easier
 //! although less flexible way.
 mod air_actions_tracking;
+mod auxiliary_sensors;
+mod character_collision;
+mod commands;
+mod corner_nudge;
 mod crouch_enforcer;
+mod crowd_separation;
+mod first_person;
+mod footsteps;
+mod ground_samples;
+mod headroom;
+mod jump_link;
+mod ledge_safety;
+mod multi_jump;
+mod obstacle_radar_events;
+mod parameter_blend;
+mod path_follower;
+mod patrol;
+mod render_interpolation;
+mod sensor_hit_filter;
 mod simple_fall_through_platforms;
+mod standing_on;
+mod steering;
+mod wall_slide;
 
 pub use air_actions_tracking::*;
+pub use auxiliary_sensors::*;
+pub use character_collision::*;
+pub use commands::*;
+pub use corner_nudge::*;
 pub use crouch_enforcer::*;
+pub use crowd_separation::*;
+pub use first_person::*;
+pub use footsteps::*;
+pub use ground_samples::*;
+pub use headroom::*;
+pub use jump_link::*;
+pub use ledge_safety::*;
+pub use multi_jump::*;
+pub use obstacle_radar_events::*;
+pub use parameter_blend::*;
+pub use path_follower::*;
+pub use patrol::*;
+pub use render_interpolation::*;
+pub use sensor_hit_filter::*;
 pub use simple_fall_through_platforms::*;
+pub use standing_on::*;
+pub use steering::*;
+pub use wall_slide::*;
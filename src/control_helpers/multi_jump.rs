@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::Float;
+
+use crate::builtins::TnuaBuiltinJump;
+use crate::control_helpers::{TnuaAirActionsUpdate, TnuaSimpleAirActionsCounter};
+use crate::prelude::*;
+
+/// One of the jumps of a [`TnuaMultiJump`].
+#[derive(Clone)]
+pub struct TnuaMultiJumpConfig {
+    /// The jump action to perform.
+    ///
+    /// The [`allow_in_air`](TnuaBuiltinJump::allow_in_air) field is ignored for air jumps -
+    /// [`TnuaMultiJump`] sets it automatically.
+    pub jump: TnuaBuiltinJump,
+
+    /// The earliest time, in seconds, this jump can start after the previous air action (usually
+    /// the previous jump) started or after the character started falling.
+    ///
+    /// Ignored for the first jump, which is performed from the ground.
+    pub earliest: Float,
+
+    /// The latest time, in seconds, this jump can start after the previous air action (usually
+    /// the previous jump) started or after the character started falling.
+    ///
+    /// Ignored for the first jump, which is performed from the ground.
+    pub latest: Float,
+}
+
+impl TnuaMultiJumpConfig {
+    pub fn new(jump: TnuaBuiltinJump) -> Self {
+        Self {
+            jump,
+            earliest: 0.0,
+            latest: Float::INFINITY,
+        }
+    }
+
+    /// Only allow this jump between `earliest` and `latest` seconds after the previous air action
+    /// started.
+    pub fn with_window(self, earliest: Float, latest: Float) -> Self {
+        Self {
+            earliest,
+            latest,
+            ..self
+        }
+    }
+}
+
+/// A helper for a sequence of jumps - a jump from the ground followed by air jumps (double jump,
+/// triple jump...), each with its own parameters and timing window.
+///
+/// The character can perform as many jumps as there are in [`jumps`](Self::jumps). Air actions
+/// are counted with a [`TnuaSimpleAirActionsCounter`], so - just like with it - starting a free
+/// fall uses up the first jump, and other air actions (like air dashes) are counted together with
+/// the jumps. Use [`air_actions_counter`](Self::air_actions_counter) to check other air actions
+/// against the same count, and [`air_actions_counter_mut`](Self::air_actions_counter_mut) to
+/// restore air jumps.
+///
+/// [`update`](Self::update) must be called every frame - even when the jump button is not
+/// pressed. When it is pressed, feed the jump returned by [`jump`](Self::jump) to the controller:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaMultiJump;
+/// # use bevy_tnua::math::Float;
+/// # let mut controller = TnuaController::default();
+/// # let mut multi_jump = TnuaMultiJump::default();
+/// # let frame_duration: Float = 1.0 / 60.0;
+/// # let jump_pressed = true;
+/// multi_jump.update(&controller, frame_duration);
+/// if jump_pressed {
+///     if let Some(jump) = multi_jump.jump(&controller) {
+///         controller.action(jump);
+///     }
+/// }
+/// ```
+#[derive(Component, Default, Clone)]
+pub struct TnuaMultiJump {
+    /// The jumps, in order. The first one is the jump from the ground, the second one is the first
+    /// air jump, and so on.
+    pub jumps: Vec<TnuaMultiJumpConfig>,
+    air_actions_counter: TnuaSimpleAirActionsCounter,
+    time_since_air_action: Float,
+}
+
+impl TnuaMultiJump {
+    pub fn new(jumps: impl IntoIterator<Item = TnuaMultiJumpConfig>) -> Self {
+        Self {
+            jumps: jumps.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Call this every frame to track the jumps.
+    pub fn update(&mut self, controller: &TnuaController, frame_duration: Float) {
+        match self.air_actions_counter.update(controller) {
+            TnuaAirActionsUpdate::NoChange | TnuaAirActionsUpdate::ActionFinishedInAir => {
+                self.time_since_air_action += frame_duration;
+            }
+            TnuaAirActionsUpdate::FreeFallStarted
+            | TnuaAirActionsUpdate::AirActionStarted(_)
+            | TnuaAirActionsUpdate::JustLanded => {
+                self.time_since_air_action = 0.0;
+            }
+        }
+    }
+
+    /// The number of the jump that would be performed if the jump action is fed now - `0` for
+    /// the jump from the ground, `1` for the first air jump, and so on.
+    ///
+    /// Like [`TnuaSimpleAirActionsCounter::air_count_for`], this does not change while a jump
+    /// continues to be fed.
+    pub fn jump_number(&self) -> usize {
+        self.air_actions_counter
+            .air_count_for(TnuaBuiltinJump::NAME)
+    }
+
+    /// The jump action to feed to the controller, or `None` if the character cannot jump now -
+    /// because it used all of its jumps, or because the next jump is outside its timing window.
+    pub fn jump(&self, controller: &TnuaController) -> Option<TnuaBuiltinJump> {
+        let jump_number = self.jump_number();
+        let config = self.jumps.get(jump_number)?;
+        if jump_number == 0 {
+            return Some(config.jump.clone());
+        }
+        // A jump that already started should continue to be fed even after its window closes.
+        let ongoing = controller.action_name() == Some(TnuaBuiltinJump::NAME);
+        let in_window = (config.earliest..=config.latest).contains(&self.time_since_air_action);
+        if !(ongoing || in_window) {
+            return None;
+        }
+        Some(TnuaBuiltinJump {
+            allow_in_air: true,
+            ..config.jump.clone()
+        })
+    }
+
+    /// The counter used for counting the jumps and the other air actions.
+    pub fn air_actions_counter(&self) -> &TnuaSimpleAirActionsCounter {
+        &self.air_actions_counter
+    }
+
+    /// Obtain a mutable reference to the counter used for counting the jumps and the other air
+    /// actions - e.g. for restoring an air jump when the player picks up a floating token.
+    pub fn air_actions_counter_mut(&mut self) -> &mut TnuaSimpleAirActionsCounter {
+        &mut self.air_actions_counter
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+use crate::obstacle_radar::{TnuaObstacleRadar, TnuaObstacleRadarBlipInfo};
+use crate::schedules::TnuaScheduleFilter;
+use crate::{
+    TnuaGroundClass, TnuaGroundClassifier, TnuaPipelineStages, TnuaSurfaceMaterial, TnuaSystemSet,
+};
+
+pub struct TnuaObstacleRadarEventsPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaObstacleRadarEventsPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaObstacleRadarEventsPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for sending [`TnuaObstacleRadarEvent`]s.
+impl Plugin for TnuaObstacleRadarEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TnuaObstacleRadarEvent>();
+        app.add_systems(
+            self.schedule,
+            send_obstacle_radar_events
+                .in_set(TnuaSystemSet)
+                .after(TnuaPipelineStages::Sensors)
+                .before(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// An event sent by [`TnuaObstacleRadarEventsPlugin`] when an entity starts or stops being a
+/// blip of a [`TnuaObstacleRadar`].
+///
+/// Use it for AI and for interaction prompts, instead of checking the radar every frame:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::control_helpers::{TnuaObstacleRadarEvent, TnuaObstacleRadarEventKind};
+/// # use bevy_tnua::TnuaGroundClass;
+/// fn show_climb_prompts(mut events: EventReader<TnuaObstacleRadarEvent>) {
+///     for event in events.read() {
+///         match &event.kind {
+///             TnuaObstacleRadarEventKind::BlipAppeared {
+///                 ground_class: Some(TnuaGroundClass::Blocked),
+///                 info: Some(info),
+///             } if !info.extends_above => {
+///                 // show a "press E to climb" prompt for `event.blip`
+///             }
+///             TnuaObstacleRadarEventKind::BlipDisappeared => {
+///                 // hide the prompt for `event.blip`, if there is one
+///             }
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+///
+/// Requires the plugin [`TnuaObstacleRadarEventsPlugin`].
+#[derive(Event, Debug, Clone)]
+pub struct TnuaObstacleRadarEvent {
+    /// The entity of the radar (usually the character entity).
+    pub entity: Entity,
+
+    /// The entity that appeared on the radar or disappeared from it.
+    pub blip: Entity,
+
+    /// Whether the blip appeared or disappeared.
+    pub kind: TnuaObstacleRadarEventKind,
+}
+
+/// The kind of a [`TnuaObstacleRadarEvent`].
+#[derive(Debug, Clone)]
+pub enum TnuaObstacleRadarEventKind {
+    /// The radar started detecting the blip.
+    BlipAppeared {
+        /// How the surface of the blip that faces the radar is classified by the
+        /// [`TnuaGroundClassifier`] of the radar entity (and the [`TnuaSurfaceMaterial`] of the
+        /// blip).
+        ///
+        /// `None` unless the radar has [`collect_blip_info`](TnuaObstacleRadar::collect_blip_info)
+        /// set.
+        ground_class: Option<TnuaGroundClass>,
+
+        /// The geometry of the blip when it appeared.
+        ///
+        /// `None` unless the radar has [`collect_blip_info`](TnuaObstacleRadar::collect_blip_info)
+        /// set.
+        info: Option<TnuaObstacleRadarBlipInfo>,
+    },
+    /// The radar stopped detecting the blip.
+    BlipDisappeared,
+}
+
+fn send_obstacle_radar_events(
+    query: Query<(
+        Entity,
+        Ref<TnuaObstacleRadar>,
+        Option<&TnuaGroundClassifier>,
+    )>,
+    material_query: Query<&TnuaSurfaceMaterial>,
+    mut writer: EventWriter<TnuaObstacleRadarEvent>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    for (entity, radar, classifier) in query.iter() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        // The radar is only changed when the physics backend updates it.
+        if !radar.is_changed() {
+            continue;
+        }
+        for blip in radar.iter_appeared_blips() {
+            let info = radar.blip_info(blip).cloned();
+            let ground_class = info.as_ref().map(|info| {
+                classifier.copied().unwrap_or_default().classify(
+                    info.normal,
+                    radar.up_direction(),
+                    material_query.get(blip).ok(),
+                )
+            });
+            writer.send(TnuaObstacleRadarEvent {
+                entity,
+                blip,
+                kind: TnuaObstacleRadarEventKind::BlipAppeared { ground_class, info },
+            });
+        }
+        for blip in radar.iter_disappeared_blips() {
+            writer.send(TnuaObstacleRadarEvent {
+                entity,
+                blip,
+                kind: TnuaObstacleRadarEventKind::BlipDisappeared,
+            });
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+
+use crate::builtins::{TnuaBuiltinCrouch, TnuaBuiltinDash, TnuaBuiltinJump, TnuaBuiltinWalk};
+
+/// Parameters that can be blended - e.g. the configuration of a basis or an action.
+pub trait TnuaBlendable: Clone {
+    /// Interpolate between `self` (at `t = 0.0`) and `other` (at `t = 1.0`).
+    ///
+    /// Numeric parameters are interpolated linearly. Parameters that cannot be interpolated
+    /// (flags, infinite values, and input parameters like directions) are taken from `other`.
+    fn blend(&self, other: &Self, t: Float) -> Self;
+}
+
+fn lerp(from: Float, to: Float, t: Float) -> Float {
+    if from == to {
+        from
+    } else if from.is_infinite() || to.is_infinite() {
+        // Interpolating with infinity would result in NaN.
+        to
+    } else {
+        from + (to - from) * t
+    }
+}
+
+fn lerp_vec(from: Vector3, to: Vector3, t: Float) -> Vector3 {
+    from + (to - from) * t
+}
+
+fn lerp_option(from: Option<Float>, to: Option<Float>, t: Float) -> Option<Float> {
+    match (from, to) {
+        (Some(from), Some(to)) => Some(lerp(from, to, t)),
+        _ => to,
+    }
+}
+
+impl TnuaBlendable for TnuaBuiltinWalk {
+    fn blend(&self, other: &Self, t: Float) -> Self {
+        Self {
+            desired_velocity: other.desired_velocity,
+            desired_forward: other.desired_forward,
+            facing: other.facing,
+            float_height: lerp(self.float_height, other.float_height, t),
+            float_height_change_duration: lerp(
+                self.float_height_change_duration,
+                other.float_height_change_duration,
+                t,
+            ),
+            cling_distance: lerp(self.cling_distance, other.cling_distance, t),
+            spring_strengh: lerp(self.spring_strengh, other.spring_strengh, t),
+            spring_dampening: lerp(self.spring_dampening, other.spring_dampening, t),
+            acceleration: lerp(self.acceleration, other.acceleration, t),
+            acceleration_curve: other.acceleration_curve.clone(),
+            air_acceleration: lerp(self.air_acceleration, other.air_acceleration, t),
+            max_speed: lerp(self.max_speed, other.max_speed, t),
+            air_max_speed: lerp(self.air_max_speed, other.air_max_speed, t),
+            air_drag: lerp(self.air_drag, other.air_drag, t),
+            conserve_momentum: other.conserve_momentum,
+            momentum_drag: lerp(self.momentum_drag, other.momentum_drag, t),
+            coyote_time: lerp(self.coyote_time, other.coyote_time, t),
+            free_fall_extra_gravity: lerp(
+                self.free_fall_extra_gravity,
+                other.free_fall_extra_gravity,
+                t,
+            ),
+            tilt_offset_angvel: lerp(self.tilt_offset_angvel, other.tilt_offset_angvel, t),
+            tilt_offset_angacl: lerp(self.tilt_offset_angacl, other.tilt_offset_angacl, t),
+            ground_alignment: lerp(self.ground_alignment, other.ground_alignment, t),
+            max_ground_alignment_tilt: lerp(
+                self.max_ground_alignment_tilt,
+                other.max_ground_alignment_tilt,
+                t,
+            ),
+            turning_angvel: lerp(self.turning_angvel, other.turning_angvel, t),
+            turning_angacl: lerp(self.turning_angacl, other.turning_angacl, t),
+            max_slope: lerp(self.max_slope, other.max_slope, t),
+            slope_speed_curve: other.slope_speed_curve.clone(),
+            block_steep_slopes: other.block_steep_slopes,
+            ground_snap_distance: lerp(self.ground_snap_distance, other.ground_snap_distance, t),
+            ground_snap_max_speed: lerp(self.ground_snap_max_speed, other.ground_snap_max_speed, t),
+            ground_downforce: lerp(self.ground_downforce, other.ground_downforce, t),
+            input_conditioning: other.input_conditioning.clone(),
+        }
+    }
+}
+
+impl TnuaBlendable for TnuaBuiltinJump {
+    fn blend(&self, other: &Self, t: Float) -> Self {
+        Self {
+            height: lerp(self.height, other.height, t),
+            allow_in_air: other.allow_in_air,
+            upslope_extra_gravity: lerp(self.upslope_extra_gravity, other.upslope_extra_gravity, t),
+            takeoff_extra_gravity: lerp(self.takeoff_extra_gravity, other.takeoff_extra_gravity, t),
+            takeoff_above_velocity: lerp(
+                self.takeoff_above_velocity,
+                other.takeoff_above_velocity,
+                t,
+            ),
+            fall_extra_gravity: lerp(self.fall_extra_gravity, other.fall_extra_gravity, t),
+            shorten_extra_gravity: lerp(self.shorten_extra_gravity, other.shorten_extra_gravity, t),
+            release_curve: other.release_curve.clone(),
+            inherit_horizontal_platform_velocity: other.inherit_horizontal_platform_velocity,
+            inherit_vertical_platform_velocity: other.inherit_vertical_platform_velocity,
+            peak_prevention_at_upward_velocity: lerp(
+                self.peak_prevention_at_upward_velocity,
+                other.peak_prevention_at_upward_velocity,
+                t,
+            ),
+            peak_prevention_extra_gravity: lerp(
+                self.peak_prevention_extra_gravity,
+                other.peak_prevention_extra_gravity,
+                t,
+            ),
+            reschedule_cooldown: lerp_option(
+                self.reschedule_cooldown,
+                other.reschedule_cooldown,
+                t,
+            ),
+            input_buffer_time: lerp(self.input_buffer_time, other.input_buffer_time, t),
+        }
+    }
+}
+
+impl TnuaBlendable for TnuaBuiltinCrouch {
+    fn blend(&self, other: &Self, t: Float) -> Self {
+        Self {
+            float_offset: lerp(self.float_offset, other.float_offset, t),
+            height_change_impulse_for_duration: lerp(
+                self.height_change_impulse_for_duration,
+                other.height_change_impulse_for_duration,
+                t,
+            ),
+            height_change_impulse_limit: lerp(
+                self.height_change_impulse_limit,
+                other.height_change_impulse_limit,
+                t,
+            ),
+            uncancellable: other.uncancellable,
+        }
+    }
+}
+
+impl TnuaBlendable for TnuaBuiltinDash {
+    fn blend(&self, other: &Self, t: Float) -> Self {
+        Self {
+            displacement: lerp_vec(self.displacement, other.displacement, t),
+            desired_forward: other.desired_forward,
+            allow_in_air: other.allow_in_air,
+            speed: lerp(self.speed, other.speed, t),
+            brake_to_speed: lerp(self.brake_to_speed, other.brake_to_speed, t),
+            acceleration: lerp(self.acceleration, other.acceleration, t),
+            brake_acceleration: lerp(self.brake_acceleration, other.brake_acceleration, t),
+            input_buffer_time: lerp(self.input_buffer_time, other.input_buffer_time, t),
+            cooldown: lerp(self.cooldown, other.cooldown, t),
+        }
+    }
+}
+
+/// Smoothly transitions between sets of parameters over time.
+///
+/// Use this to change the feel of the character (e.g. when it becomes encumbered or powered-up)
+/// without a visible snap - changing the float height at once, for example, makes the character
+/// pop up or down.
+///
+/// Keep it in a component (or a resource), call [`update`](Self::update) every frame and use the
+/// value it returns (or [`current`](Self::current)) as the template for the basis or action:
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaParameterBlend;
+/// # let frame_duration = 1.0 / 60.0;
+/// let normal = TnuaBuiltinWalk {
+///     float_height: 1.5,
+///     ..Default::default()
+/// };
+/// let encumbered = TnuaBuiltinWalk {
+///     float_height: 1.3,
+///     acceleration: 20.0,
+///     ..normal.clone()
+/// };
+/// let mut blend = TnuaParameterBlend::new(normal);
+/// blend.transition_to(encumbered, 0.5);
+/// // Every frame:
+/// let walk = blend.update(frame_duration);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TnuaParameterBlend<T: TnuaBlendable> {
+    from: T,
+    to: T,
+    duration: Float,
+    elapsed: Float,
+}
+
+impl<T: TnuaBlendable> TnuaParameterBlend<T> {
+    /// Start with the given parameters, without transitioning.
+    pub fn new(initial: T) -> Self {
+        Self {
+            from: initial.clone(),
+            to: initial,
+            duration: 0.0,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Start transitioning to a new set of parameters over `duration` seconds.
+    ///
+    /// The transition starts from the current blended value, so it is safe to call this in the
+    /// middle of another transition.
+    pub fn transition_to(&mut self, target: T, duration: Float) {
+        self.from = self.current();
+        self.to = target;
+        self.duration = duration.max(0.0);
+        self.elapsed = 0.0;
+    }
+
+    /// Jump to a new set of parameters immediately.
+    pub fn set(&mut self, target: T) {
+        *self = Self::new(target);
+    }
+
+    /// Advance the transition and return the blended parameters.
+    pub fn update(&mut self, frame_duration: Float) -> T {
+        self.elapsed = (self.elapsed + frame_duration).min(self.duration);
+        self.current()
+    }
+
+    /// The parameters blended according to the progress of the transition.
+    ///
+    /// The blending is eased, so that the parameters start and finish changing gradually.
+    pub fn current(&self) -> T {
+        let t = self.progress();
+        if t < 1.0 {
+            let eased = t * t * (3.0 - 2.0 * t);
+            self.from.blend(&self.to, eased)
+        } else {
+            self.to.clone()
+        }
+    }
+
+    /// The parameters being transitioned to.
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+
+    /// Mutable access to the parameters being transitioned to, e.g. for setting input fields.
+    pub fn target_mut(&mut self) -> &mut T {
+        &mut self.to
+    }
+
+    /// The progress of the transition, from 0.0 to 1.0.
+    pub fn progress(&self) -> Float {
+        if 0.0 < self.duration {
+            self.elapsed / self.duration
+        } else {
+            1.0
+        }
+    }
+
+    /// Whether a transition is currently in progress.
+    pub fn is_transitioning(&self) -> bool {
+        self.progress() < 1.0
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use crate::builtins::{TnuaBuiltinJump, TnuaBuiltinWalk};
+use crate::controller::TnuaController;
+
+/// A point on a path followed by [`TnuaPathFollower`].
+#[derive(Debug, Clone)]
+pub struct TnuaWaypoint {
+    /// The position of the waypoint, in world space.
+    pub position: Vector3,
+
+    /// Whether the character needs to jump to reach this waypoint from the previous one (e.g. a
+    /// jump link in a navigation mesh).
+    pub jump: bool,
+}
+
+impl TnuaWaypoint {
+    /// A waypoint that can be reached by walking.
+    pub fn walk(position: Vector3) -> Self {
+        Self {
+            position,
+            jump: false,
+        }
+    }
+
+    /// A waypoint that needs a jump to be reached.
+    pub fn jump(position: Vector3) -> Self {
+        Self {
+            position,
+            jump: true,
+        }
+    }
+}
+
+impl From<Vector3> for TnuaWaypoint {
+    fn from(position: Vector3) -> Self {
+        Self::walk(position)
+    }
+}
+
+/// Helper for making a character follow a path - e.g. one generated by a navigation mesh.
+///
+/// Place this component on the character entity, set a path with [`set_path`](Self::set_path),
+/// and call [`update`](Self::update) every frame from the system that controls the character. The
+/// returned [`TnuaPathFollowerCommand`] can be fed to the controller with
+/// [`feed_to`](TnuaPathFollowerCommand::feed_to):
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaPathFollower;
+/// # use bevy_tnua::math::AdjustPrecision;
+/// fn npc_controls_system(
+///     mut query: Query<(&GlobalTransform, &mut TnuaController, &mut TnuaPathFollower)>,
+/// ) {
+///     for (transform, mut controller, mut path_follower) in query.iter_mut() {
+///         path_follower
+///             .update(transform.translation().adjust_precision(), Dir3::Y)
+///             .feed_to(
+///                 controller.as_mut(),
+///                 TnuaBuiltinWalk {
+///                     float_height: 1.5,
+///                     ..Default::default()
+///                 },
+///                 TnuaBuiltinJump {
+///                     height: 4.0,
+///                     ..Default::default()
+///                 },
+///             );
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct TnuaPathFollower {
+    waypoints: VecDeque<TnuaWaypoint>,
+
+    /// The speed to move at along the path.
+    pub speed: Float,
+
+    /// The horizontal distance from a waypoint at which it is considered reached.
+    pub reach_distance: Float,
+
+    /// The distance from the final waypoint at which the character starts slowing down.
+    ///
+    /// Set to 0.0 to reach the final waypoint at full speed.
+    pub slowdown_distance: Float,
+}
+
+impl Default for TnuaPathFollower {
+    fn default() -> Self {
+        Self {
+            waypoints: Default::default(),
+            speed: 10.0,
+            reach_distance: 0.5,
+            slowdown_distance: 2.0,
+        }
+    }
+}
+
+/// The result of [`TnuaPathFollower::update`].
+#[derive(Debug, Clone)]
+pub struct TnuaPathFollowerCommand {
+    /// The velocity to set as the walk basis'
+    /// [`desired_velocity`](TnuaBuiltinWalk::desired_velocity).
+    pub desired_velocity: Vector3,
+
+    /// The direction to set as the walk basis'
+    /// [`desired_forward`](TnuaBuiltinWalk::desired_forward).
+    pub desired_forward: Option<Dir3>,
+
+    /// Whether the jump action should be fed this frame.
+    pub jump: bool,
+}
+
+impl TnuaPathFollowerCommand {
+    /// Feed the walk basis (and, if needed, the jump action) to the controller.
+    ///
+    /// The `walk` and `jump` arguments are used as templates for the configuration. The
+    /// `desired_velocity` and `desired_forward` of the walk are overwritten.
+    pub fn feed_to(
+        &self,
+        controller: &mut TnuaController,
+        walk: TnuaBuiltinWalk,
+        jump: TnuaBuiltinJump,
+    ) {
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: self.desired_velocity,
+            desired_forward: self.desired_forward,
+            ..walk
+        });
+        if self.jump {
+            controller.action(jump);
+        }
+    }
+}
+
+impl TnuaPathFollower {
+    /// Replace the path the character follows.
+    pub fn set_path(&mut self, waypoints: impl IntoIterator<Item = impl Into<TnuaWaypoint>>) {
+        self.waypoints = waypoints.into_iter().map(Into::into).collect();
+    }
+
+    /// Stop following the path.
+    pub fn clear(&mut self) {
+        self.waypoints.clear();
+    }
+
+    /// The waypoints that were not yet reached.
+    pub fn remaining_waypoints(&self) -> impl Iterator<Item = &TnuaWaypoint> {
+        self.waypoints.iter()
+    }
+
+    /// Whether there are no more waypoints to follow.
+    pub fn is_finished(&self) -> bool {
+        self.waypoints.is_empty()
+    }
+
+    /// Call this every frame to advance along the path.
+    ///
+    /// The waypoint is reached when the character is within
+    /// [`reach_distance`](Self::reach_distance) from it on the plane perpendicular to `up`.
+    /// While heading to a waypoint that requires a jump, the jump is requested for as long as the
+    /// waypoint is above the character, so that the jump reaches its full height when needed.
+    pub fn update(&mut self, position: Vector3, up: Dir3) -> TnuaPathFollowerCommand {
+        let up = up.adjust_precision();
+        while let Some(waypoint) = self.waypoints.front() {
+            let offset = (waypoint.position - position).reject_from(up);
+            if self.reach_distance < offset.length() {
+                break;
+            }
+            self.waypoints.pop_front();
+        }
+
+        let Some(waypoint) = self.waypoints.front() else {
+            return TnuaPathFollowerCommand {
+                desired_velocity: Vector3::ZERO,
+                desired_forward: None,
+                jump: false,
+            };
+        };
+
+        let offset = waypoint.position - position;
+        let planar_offset = offset.reject_from(up);
+        let direction = planar_offset.normalize_or_zero();
+
+        let mut speed = self.speed;
+        if self.waypoints.len() == 1 && 0.0 < self.slowdown_distance {
+            speed *= (planar_offset.length() / self.slowdown_distance).min(1.0);
+        }
+
+        TnuaPathFollowerCommand {
+            desired_velocity: direction * speed,
+            desired_forward: Dir3::new(direction.f32()).ok(),
+            jump: waypoint.jump && 0.0 < offset.dot(up),
+        }
+    }
+}
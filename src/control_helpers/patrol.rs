@@ -0,0 +1,310 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::control_helpers::TnuaSteering;
+use crate::controller::TnuaController;
+use crate::schedules::TnuaScheduleFilter;
+use crate::{TnuaRigidBodyTracker, TnuaUserControlsSystemSet};
+
+pub struct TnuaPatrolPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaPatrolPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaPatrolPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaPatrol`] work.
+impl Plugin for TnuaPatrolPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaPatrol>();
+        app.add_systems(self.schedule, patrol.in_set(TnuaUserControlsSystemSet));
+    }
+}
+
+/// A point on the route of a [`TnuaPatrol`].
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaPatrolWaypoint {
+    /// The position of the waypoint, in world space. Only its offset on the plane perpendicular
+    /// to the up direction matters.
+    pub position: Vector3,
+
+    /// How long, in seconds, the character stands at the waypoint before moving to the next one.
+    pub wait_time: Float,
+}
+
+impl TnuaPatrolWaypoint {
+    /// A waypoint the character waits at for `wait_time` seconds.
+    pub fn new(position: Vector3, wait_time: Float) -> Self {
+        Self {
+            position,
+            wait_time,
+        }
+    }
+}
+
+impl From<Vector3> for TnuaPatrolWaypoint {
+    fn from(position: Vector3) -> Self {
+        Self::new(position, 0.0)
+    }
+}
+
+/// What a [`TnuaPatrol`] does after reaching the last waypoint.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TnuaPatrolMode {
+    /// Go back to the first waypoint.
+    #[default]
+    Loop,
+    /// Go through the waypoints in reverse order, and then forward again.
+    PingPong,
+    /// Stop at the last waypoint.
+    Once,
+}
+
+/// Makes a character walk between waypoints - e.g. an NPC guarding an area.
+///
+/// Place this component on the character entity instead of feeding the controller, and
+/// [`TnuaPatrolPlugin`] will feed the walk basis every frame:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::{TnuaPatrol, TnuaPatrolMode, TnuaPatrolWaypoint};
+/// # use bevy_tnua::math::Vector3;
+/// fn spawn_guard(mut commands: Commands) {
+///     commands.spawn((
+///         // ... the physics and the Tnua components ...
+///         TnuaPatrol::new(
+///             TnuaBuiltinWalk {
+///                 float_height: 1.5,
+///                 ..Default::default()
+///             },
+///             [
+///                 TnuaPatrolWaypoint::new(Vector3::new(-5.0, 0.0, 0.0), 2.0),
+///                 TnuaPatrolWaypoint::new(Vector3::new(5.0, 0.0, 0.0), 2.0),
+///             ],
+///         )
+///         .with_mode(TnuaPatrolMode::PingPong)
+///         .with_speed(3.0),
+///     ));
+/// }
+/// ```
+///
+/// Requires the plugin [`TnuaPatrolPlugin`].
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaPatrol {
+    /// The template for the walk basis. Its `desired_velocity` and `desired_forward` are
+    /// overwritten.
+    pub walk: TnuaBuiltinWalk,
+
+    /// The waypoints to walk between.
+    pub waypoints: Vec<TnuaPatrolWaypoint>,
+
+    /// What to do after reaching the last waypoint.
+    pub mode: TnuaPatrolMode,
+
+    /// The speed to walk at.
+    pub speed: Float,
+
+    /// The horizontal distance from a waypoint at which it is considered reached.
+    pub arrival_distance: Float,
+
+    /// The distance from a waypoint at which the character starts slowing down. Only used for
+    /// waypoints the character waits at (and for the last waypoint of a
+    /// [`TnuaPatrolMode::Once`] patrol).
+    pub slowdown_distance: Float,
+
+    /// While `true`, the character stands in place and the patrol does not advance.
+    pub paused: bool,
+
+    current: usize,
+    backwards: bool,
+    wait_remaining: Option<Float>,
+    finished: bool,
+    last_direction: Option<Dir3>,
+}
+
+impl TnuaPatrol {
+    /// Create a looping patrol through the given waypoints.
+    pub fn new(
+        walk: TnuaBuiltinWalk,
+        waypoints: impl IntoIterator<Item = impl Into<TnuaPatrolWaypoint>>,
+    ) -> Self {
+        Self {
+            walk,
+            waypoints: waypoints.into_iter().map(Into::into).collect(),
+            mode: TnuaPatrolMode::Loop,
+            speed: 5.0,
+            arrival_distance: 0.5,
+            slowdown_distance: 1.0,
+            paused: false,
+            current: 0,
+            backwards: false,
+            wait_remaining: None,
+            finished: false,
+            last_direction: None,
+        }
+    }
+
+    /// Set what to do after reaching the last waypoint.
+    pub fn with_mode(mut self, mode: TnuaPatrolMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the speed to walk at.
+    pub fn with_speed(mut self, speed: Float) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Set the wait time of all the waypoints.
+    pub fn with_wait_time(mut self, wait_time: Float) -> Self {
+        for waypoint in self.waypoints.iter_mut() {
+            waypoint.wait_time = wait_time;
+        }
+        self
+    }
+
+    /// The index of the waypoint the character is heading to (or waiting at).
+    pub fn current_waypoint(&self) -> usize {
+        self.current
+    }
+
+    /// Whether the character is waiting at a waypoint.
+    pub fn is_waiting(&self) -> bool {
+        self.wait_remaining.is_some()
+    }
+
+    /// Whether a [`TnuaPatrolMode::Once`] patrol has reached its last waypoint.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Start the patrol over from the first waypoint.
+    pub fn restart(&mut self) {
+        self.current = 0;
+        self.backwards = false;
+        self.wait_remaining = None;
+        self.finished = false;
+    }
+
+    fn advance(&mut self) {
+        let count = self.waypoints.len();
+        if count <= 1 {
+            self.finished = self.mode == TnuaPatrolMode::Once;
+            return;
+        }
+        match self.mode {
+            TnuaPatrolMode::Loop => {
+                self.current = (self.current + 1) % count;
+            }
+            TnuaPatrolMode::PingPong => {
+                if self.backwards && self.current == 0 {
+                    self.backwards = false;
+                } else if !self.backwards && self.current == count - 1 {
+                    self.backwards = true;
+                }
+                if self.backwards {
+                    self.current -= 1;
+                } else {
+                    self.current += 1;
+                }
+            }
+            TnuaPatrolMode::Once => {
+                if self.current + 1 < count {
+                    self.current += 1;
+                } else {
+                    self.finished = true;
+                }
+            }
+        }
+    }
+
+    /// Whether the character should slow down to stop at the current waypoint.
+    fn stops_at_current(&self) -> bool {
+        let Some(waypoint) = self.waypoints.get(self.current) else {
+            return true;
+        };
+        0.0 < waypoint.wait_time
+            || (self.mode == TnuaPatrolMode::Once && self.current + 1 == self.waypoints.len())
+    }
+}
+
+fn patrol(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaPatrol,
+        &mut TnuaController,
+        &TnuaRigidBodyTracker,
+    )>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    let frame_duration = time.delta_seconds().adjust_precision();
+    for (entity, mut patrol, mut controller, tracker) in query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        let patrol = patrol.as_mut();
+        let position = tracker.translation;
+        let up = Dir3::new(-tracker.gravity.f32()).unwrap_or(Dir3::Y);
+
+        let mut velocity = Vector3::ZERO;
+        if !patrol.paused && !patrol.finished {
+            if let Some(wait_remaining) = patrol.wait_remaining.as_mut() {
+                *wait_remaining -= frame_duration;
+                if *wait_remaining <= 0.0 {
+                    patrol.wait_remaining = None;
+                    patrol.advance();
+                }
+            } else if let Some(waypoint) = patrol.waypoints.get(patrol.current) {
+                let target = waypoint.position;
+                let offset = (target - position).reject_from(up.adjust_precision());
+                if offset.length() <= patrol.arrival_distance {
+                    if 0.0 < waypoint.wait_time {
+                        patrol.wait_remaining = Some(waypoint.wait_time);
+                    } else {
+                        patrol.advance();
+                    }
+                } else {
+                    let steering = TnuaSteering {
+                        max_speed: patrol.speed,
+                        slowdown_distance: patrol.slowdown_distance,
+                        ..TnuaSteering::new(up)
+                    };
+                    velocity = if patrol.stops_at_current() {
+                        steering.arrive(position, target)
+                    } else {
+                        steering.seek(position, target)
+                    };
+                }
+            }
+        }
+
+        if let Ok(direction) = Dir3::new(velocity.f32()) {
+            patrol.last_direction = Some(direction);
+        }
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: velocity,
+            desired_forward: patrol.last_direction.or(patrol.walk.desired_forward),
+            ..patrol.walk.clone()
+        });
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AsF32, Float, Quaternion, Vector3};
+
+use crate::{TnuaPipelineStages, TnuaRigidBodyTracker};
+
+pub struct TnuaRenderInterpolationPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaRenderInterpolationPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaRenderInterpolationPlugin {
+    fn default() -> Self {
+        Self::new(FixedUpdate)
+    }
+}
+
+/// A plugin required for making [`TnuaRenderInterpolation`] work.
+///
+/// Must be registered in the same schedule as the rest of the Tnua plugins - which is why its
+/// default is `FixedUpdate` rather than `Update`.
+impl Plugin for TnuaRenderInterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            record_render_interpolation.in_set(TnuaPipelineStages::SubservientSensors),
+        );
+    }
+}
+
+/// Keeps the character's position from the last two ticks, for smoothing its visuals when Tnua
+/// runs in a fixed schedule.
+///
+/// When the physics and Tnua run in `FixedUpdate`, rendering the rigid body's transform directly
+/// stutters because the number of fixed ticks per frame varies. Add this component to the
+/// character entity (and [`TnuaRenderInterpolationPlugin`] to the app), and use
+/// [`transform`](Self::transform) with the fixed time's overstep fraction to place a separate
+/// visual entity:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaRenderInterpolation;
+/// # use bevy_tnua::math::AdjustPrecision;
+/// # #[derive(Component)]
+/// # struct CharacterVisual {
+/// #     character: Entity,
+/// # }
+/// fn place_visuals(
+///     time: Res<Time<Fixed>>,
+///     characters_query: Query<&TnuaRenderInterpolation>,
+///     mut visuals_query: Query<(&CharacterVisual, &mut Transform)>,
+/// ) {
+///     let fraction = time.overstep_fraction().adjust_precision();
+///     for (visual, mut transform) in visuals_query.iter_mut() {
+///         if let Ok(interpolation) = characters_query.get(visual.character) {
+///             *transform = interpolation.transform(fraction);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Default, Debug, Clone)]
+pub struct TnuaRenderInterpolation {
+    previous: Option<(Vector3, Quaternion)>,
+    current: Option<(Vector3, Quaternion)>,
+}
+
+impl TnuaRenderInterpolation {
+    /// The translation between the last two ticks.
+    ///
+    /// `fraction` is how far (0.0 to 1.0) between the previous tick and the current tick to
+    /// interpolate - usually `Time<Fixed>::overstep_fraction`.
+    pub fn translation(&self, fraction: Float) -> Vector3 {
+        match (self.previous, self.current) {
+            (Some((previous, _)), Some((current, _))) => previous.lerp(current, fraction),
+            (_, Some((current, _))) => current,
+            _ => Vector3::ZERO,
+        }
+    }
+
+    /// The rotation between the last two ticks. See [`translation`](Self::translation).
+    pub fn rotation(&self, fraction: Float) -> Quaternion {
+        match (self.previous, self.current) {
+            (Some((_, previous)), Some((_, current))) => previous.slerp(current, fraction),
+            (_, Some((_, current))) => current,
+            _ => Quaternion::IDENTITY,
+        }
+    }
+
+    /// A transform with the interpolated translation and rotation. See
+    /// [`translation`](Self::translation).
+    pub fn transform(&self, fraction: Float) -> Transform {
+        Transform {
+            translation: self.translation(fraction).f32(),
+            rotation: self.rotation(fraction).f32(),
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+fn record_render_interpolation(
+    mut query: Query<(&TnuaRigidBodyTracker, &mut TnuaRenderInterpolation)>,
+) {
+    for (tracker, mut interpolation) in query.iter_mut() {
+        let interpolation = interpolation.as_mut();
+        interpolation.previous = interpolation.current;
+        interpolation.current = Some((tracker.translation, tracker.rotation));
+    }
+}
@@ -0,0 +1,128 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+use crate::controller::clear_sensor_computed_exclusions;
+use crate::{TnuaPaused, TnuaPipelineStages, TnuaSensorQueryFilter, TnuaSystemSet, TnuaToggle};
+
+/// A plugin required for making [`TnuaSensorHitFilter<C>`] work.
+///
+/// Must be added separately for each component type `C` used in a `TnuaSensorHitFilter`.
+pub struct TnuaSensorHitFilterPlugin<C: Component> {
+    schedule: InternedScheduleLabel,
+    _phantom: PhantomData<fn() -> C>,
+}
+
+impl<C: Component> TnuaSensorHitFilterPlugin<C> {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: Component> Default for TnuaSensorHitFilterPlugin<C> {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl<C: Component> Plugin for TnuaSensorHitFilterPlugin<C> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            apply_sensor_hit_filters::<C>
+                .in_set(TnuaSystemSet)
+                .after(clear_sensor_computed_exclusions)
+                .before(TnuaPipelineStages::Sensors),
+        );
+    }
+}
+
+/// Makes the proximity sensor decide which colliders it can detect based on a component of the
+/// collider's entity.
+///
+/// Add this component to the sensor entity (usually the character entity). Every frame, before
+/// the sensors are updated, the predicate is called for each entity that has a `C` component,
+/// and the entities for which it returns `false` are added to the
+/// [`computed_exclusions`](TnuaSensorQueryFilter::computed_exclusions) of the sensor's
+/// [`TnuaSensorQueryFilter`] (which is added automatically if missing). Colliders without a `C`
+/// component are not affected.
+///
+/// Requires the plugin [`TnuaSensorHitFilterPlugin<C>`].
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaSensorHitFilter;
+/// #[derive(Component, PartialEq)]
+/// struct Team(u8);
+///
+/// # let mut commands: Commands = panic!();
+/// # let player_entity: Entity = panic!();
+/// // Only stand on platforms owned by team 1:
+/// commands
+///     .entity(player_entity)
+///     .insert(TnuaSensorHitFilter::<Team>::new(|_, team| *team == Team(1)));
+/// ```
+///
+/// Note that the predicate runs for every pair of a sensor with this component and an entity with
+/// the `C` component, so `C` should be a component that only relevant colliders have.
+#[derive(Component)]
+pub struct TnuaSensorHitFilter<C: Component> {
+    #[allow(clippy::type_complexity)]
+    predicate: Arc<dyn Fn(Entity, &C) -> bool + Send + Sync>,
+}
+
+impl<C: Component> TnuaSensorHitFilter<C> {
+    /// The predicate receives the entity of the candidate collider and its `C` component, and
+    /// returns `true` if the sensor may detect it.
+    pub fn new(predicate: impl Fn(Entity, &C) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            predicate: Arc::new(predicate),
+        }
+    }
+}
+
+impl<C: Component> Clone for TnuaSensorHitFilter<C> {
+    fn clone(&self) -> Self {
+        Self {
+            predicate: self.predicate.clone(),
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_sensor_hit_filters<C: Component>(
+    mut query: Query<(
+        Entity,
+        &TnuaSensorHitFilter<C>,
+        Option<&mut TnuaSensorQueryFilter>,
+        Option<&TnuaToggle>,
+    )>,
+    candidates_query: Query<(Entity, &C)>,
+    paused: Option<Res<TnuaPaused>>,
+    mut commands: Commands,
+) {
+    for (entity, hit_filter, query_filter, tnua_toggle) in query.iter_mut() {
+        if matches!(
+            TnuaToggle::resolve(tnua_toggle, paused.as_deref()),
+            TnuaToggle::Disabled | TnuaToggle::Frozen
+        ) {
+            continue;
+        }
+        let excluded = candidates_query
+            .iter()
+            .filter(|(candidate, component)| !(hit_filter.predicate)(*candidate, component))
+            .map(|(candidate, _)| candidate);
+        if let Some(mut query_filter) = query_filter {
+            query_filter.computed_exclusions.extend(excluded);
+        } else {
+            let mut query_filter = TnuaSensorQueryFilter::default();
+            query_filter.computed_exclusions.extend(excluded);
+            commands.entity(entity).insert(query_filter);
+        }
+    }
+}
@@ -14,7 +14,7 @@ use crate::{TnuaGhostSensor, TnuaProximitySensor};
 /// system) use [`with`](Self::with) and call one of the methods of [the returned handle
 /// object](TnuaHandleForSimpleFallThroughPlatformsHelper) every frame. See the description of
 /// these methods to determine which one to call.
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone)]
 pub struct TnuaSimpleFallThroughPlatformsHelper {
     currently_falling_through: HashSet<Entity>,
 }
@@ -0,0 +1,220 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::controller::{apply_controller_system, TnuaController};
+use crate::schedules::TnuaScheduleFilter;
+use crate::{TnuaPipelineStages, TnuaProximitySensor};
+
+pub struct TnuaStandingOnPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaStandingOnPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaStandingOnPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaStandingOnIndex`], [`TnuaStandingOnEvent`] and
+/// [`TnuaPressurePlate`] work.
+impl Plugin for TnuaStandingOnPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaPressurePlate>();
+        app.init_resource::<TnuaStandingOnIndex>();
+        app.add_event::<TnuaStandingOnEvent>();
+        app.add_systems(
+            self.schedule,
+            (update_standing_on_index, update_pressure_plates)
+                .chain()
+                .in_set(TnuaPipelineStages::Logic)
+                .after(apply_controller_system),
+        );
+    }
+}
+
+/// Tracks which characters stand on which entities.
+///
+/// A character stands on the entity its proximity sensor detects while its controller is not
+/// airborne. Use this to find the characters standing on an entity without going over the sensors
+/// of all the controllers:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaStandingOnIndex;
+/// # #[derive(Component)]
+/// # struct Elevator;
+/// fn move_elevators(index: Res<TnuaStandingOnIndex>, query: Query<Entity, With<Elevator>>) {
+///     for elevator in query.iter() {
+///         if index.characters_standing_on(elevator).is_empty() {
+///             continue;
+///         }
+///         // move the elevator
+///     }
+/// }
+/// ```
+///
+/// Requires the plugin [`TnuaStandingOnPlugin`].
+#[derive(Resource, Default, Debug)]
+pub struct TnuaStandingOnIndex {
+    characters_by_ground: HashMap<Entity, Vec<Entity>>,
+    ground_by_character: HashMap<Entity, Entity>,
+}
+
+impl TnuaStandingOnIndex {
+    /// The characters that currently stand on the entity.
+    pub fn characters_standing_on(&self, ground: Entity) -> &[Entity] {
+        self.characters_by_ground
+            .get(&ground)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The entity the character currently stands on.
+    pub fn ground_of(&self, character: Entity) -> Option<Entity> {
+        self.ground_by_character.get(&character).copied()
+    }
+
+    /// All the entities that have characters standing on them.
+    pub fn stood_on_entities(&self) -> impl '_ + Iterator<Item = Entity> {
+        self.characters_by_ground.keys().copied()
+    }
+
+    fn set_ground(&mut self, character: Entity, ground: Option<Entity>) -> Option<Entity> {
+        let previous = match ground {
+            Some(ground) => self.ground_by_character.insert(character, ground),
+            None => self.ground_by_character.remove(&character),
+        };
+        if previous == ground {
+            return previous;
+        }
+        if let Some(previous) = previous {
+            if let Some(characters) = self.characters_by_ground.get_mut(&previous) {
+                characters.retain(|entity| *entity != character);
+                if characters.is_empty() {
+                    self.characters_by_ground.remove(&previous);
+                }
+            }
+        }
+        if let Some(ground) = ground {
+            self.characters_by_ground
+                .entry(ground)
+                .or_default()
+                .push(character);
+        }
+        previous
+    }
+}
+
+/// An event sent by [`TnuaStandingOnPlugin`] when a character starts or stops standing on an
+/// entity.
+#[derive(Event, Debug, Clone)]
+pub struct TnuaStandingOnEvent {
+    /// The character entity.
+    pub character: Entity,
+
+    /// The entity the character started or stopped standing on.
+    pub ground: Entity,
+
+    /// Whether the character started or stopped standing on the entity.
+    pub kind: TnuaStandingOnEventKind,
+}
+
+/// The kind of a [`TnuaStandingOnEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaStandingOnEventKind {
+    /// The character started standing on the entity.
+    Entered,
+    /// The character stopped standing on the entity - because it became airborne, moved to
+    /// another entity, or was despawned.
+    Left,
+}
+
+/// Keeps a list of the characters that stand on the entity it is added to.
+///
+/// The component is only changed (for Bevy's change detection) when the list changes, so it can
+/// be used with `Changed<TnuaPressurePlate>` for pressure plates, breaking bridges and the like.
+///
+/// Requires the plugin [`TnuaStandingOnPlugin`].
+#[derive(Component, Default, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaPressurePlate {
+    characters: Vec<Entity>,
+}
+
+impl TnuaPressurePlate {
+    /// The characters that currently stand on the entity.
+    pub fn characters(&self) -> &[Entity] {
+        &self.characters
+    }
+
+    /// Whether any character currently stands on the entity.
+    pub fn is_pressed(&self) -> bool {
+        !self.characters.is_empty()
+    }
+}
+
+fn update_standing_on_index(
+    query: Query<(Entity, &TnuaController, &TnuaProximitySensor)>,
+    mut removed: RemovedComponents<TnuaController>,
+    mut index: ResMut<TnuaStandingOnIndex>,
+    mut writer: EventWriter<TnuaStandingOnEvent>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    let mut send = |character, ground, kind| {
+        writer.send(TnuaStandingOnEvent {
+            character,
+            ground,
+            kind,
+        });
+    };
+    for character in removed.read() {
+        if index.ground_of(character).is_none() {
+            continue;
+        }
+        if let Some(ground) = index.set_ground(character, None) {
+            send(character, ground, TnuaStandingOnEventKind::Left);
+        }
+    }
+    for (character, controller, sensor) in query.iter() {
+        if !schedule_filter.drives(character) {
+            continue;
+        }
+        let ground = match controller.is_airborne() {
+            Ok(false) => sensor.output.as_ref().map(|output| output.entity),
+            Ok(true) | Err(_) => None,
+        };
+        // Avoid triggering change detection when nothing changed.
+        if index.ground_of(character) == ground {
+            continue;
+        }
+        let previous = index.set_ground(character, ground);
+        if let Some(previous) = previous {
+            send(character, previous, TnuaStandingOnEventKind::Left);
+        }
+        if let Some(ground) = ground {
+            send(character, ground, TnuaStandingOnEventKind::Entered);
+        }
+    }
+}
+
+fn update_pressure_plates(
+    mut query: Query<(Entity, &mut TnuaPressurePlate)>,
+    index: Res<TnuaStandingOnIndex>,
+) {
+    for (entity, mut pressure_plate) in query.iter_mut() {
+        let characters = index.characters_standing_on(entity);
+        if pressure_plate.characters != characters {
+            pressure_plate.characters = characters.to_vec();
+        }
+    }
+}
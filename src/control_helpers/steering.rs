@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{
+    float_consts, AdjustPrecision, AsF32, Float, Quaternion, Vector3,
+};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::obstacle_radar::TnuaObstacleRadar;
+
+/// Steering behaviors for NPC characters.
+///
+/// Each behavior returns a desired velocity on the plane perpendicular to the up direction.
+/// Behaviors can be combined by adding (and optionally weighting) their results, and the sum can
+/// be fed to the walk basis with [`walk`](Self::walk):
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::control_helpers::TnuaSteering;
+/// # use bevy_tnua::math::Vector3;
+/// # let mut controller = TnuaController::default();
+/// # let (position, target) = (Vector3::ZERO, Vector3::X);
+/// # let neighbors: Vec<Vector3> = Vec::new();
+/// let steering = TnuaSteering::new(Dir3::Y);
+/// let velocity = steering.arrive(position, target) + steering.separation(position, neighbors);
+/// controller.basis(steering.walk(
+///     velocity,
+///     TnuaBuiltinWalk {
+///         float_height: 1.5,
+///         ..Default::default()
+///     },
+/// ));
+/// ```
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaSteering {
+    /// The direction considered as "up". Steering ignores the offsets along it.
+    pub up: Dir3,
+
+    /// The speed the behaviors steer at.
+    pub max_speed: Float,
+
+    /// The distance from the target at which [`arrive`](Self::arrive) starts slowing down.
+    pub slowdown_distance: Float,
+
+    /// Neighbors closer than this distance will be pushed away by
+    /// [`separation`](Self::separation).
+    pub separation_distance: Float,
+}
+
+impl TnuaSteering {
+    pub fn new(up: Dir3) -> Self {
+        Self {
+            up,
+            max_speed: 10.0,
+            slowdown_distance: 2.0,
+            separation_distance: 1.5,
+        }
+    }
+
+    fn on_plane(&self, vector: Vector3) -> Vector3 {
+        vector.reject_from(self.up.adjust_precision())
+    }
+
+    /// Move toward the target at full speed.
+    pub fn seek(&self, position: Vector3, target: Vector3) -> Vector3 {
+        self.on_plane(target - position).normalize_or_zero() * self.max_speed
+    }
+
+    /// Move away from the target at full speed.
+    pub fn flee(&self, position: Vector3, target: Vector3) -> Vector3 {
+        -self.seek(position, target)
+    }
+
+    /// Move toward the target, slowing down near it so that it stops there.
+    pub fn arrive(&self, position: Vector3, target: Vector3) -> Vector3 {
+        let offset = self.on_plane(target - position);
+        let distance = offset.length();
+        let speed = if 0.0 < self.slowdown_distance {
+            self.max_speed * (distance / self.slowdown_distance).min(1.0)
+        } else {
+            self.max_speed
+        };
+        offset.normalize_or_zero() * speed
+    }
+
+    /// Wander around, turning randomly.
+    ///
+    /// `jitter` should be a random number between -1.0 and 1.0, generated by the game code each
+    /// frame. It determines how much (relative to `max_turn`, in radians) the wandering direction
+    /// turns this frame.
+    pub fn wander(
+        &self,
+        state: &mut TnuaSteeringWanderState,
+        jitter: Float,
+        max_turn: Float,
+    ) -> Vector3 {
+        let up = self.up.adjust_precision();
+        let current = self.on_plane(state.direction).normalize_or_zero();
+        let current = if current == Vector3::ZERO {
+            self.up.any_orthonormal_vector().adjust_precision()
+        } else {
+            current
+        };
+        let turn = jitter.clamp(-1.0, 1.0) * max_turn.clamp(0.0, float_consts::PI);
+        state.direction = Quaternion::from_axis_angle(up, turn) * current;
+        state.direction * self.max_speed
+    }
+
+    /// Move away from neighbors that are too close.
+    ///
+    /// The closer a neighbor is, the stronger it pushes the character away. The neighbors are
+    /// provided by the caller - usually from a [`TnuaObstacleRadar`], with
+    /// [`separation_from_radar`](Self::separation_from_radar).
+    pub fn separation(
+        &self,
+        position: Vector3,
+        neighbors: impl IntoIterator<Item = Vector3>,
+    ) -> Vector3 {
+        if self.separation_distance <= 0.0 {
+            return Vector3::ZERO;
+        }
+        let mut result = Vector3::ZERO;
+        for neighbor in neighbors {
+            let offset = self.on_plane(position - neighbor);
+            let distance = offset.length();
+            if self.separation_distance <= distance || distance == 0.0 {
+                continue;
+            }
+            let strength = 1.0 - distance / self.separation_distance;
+            result += offset / distance * strength;
+        }
+        result * self.max_speed
+    }
+
+    /// Like [`separation`](Self::separation), but with the blips of a [`TnuaObstacleRadar`] as the
+    /// neighbors.
+    ///
+    /// `neighbor_position` maps a blip to its position, and should return `None` for blips that
+    /// are not neighbors (e.g. the ground, walls, or the character itself).
+    pub fn separation_from_radar(
+        &self,
+        position: Vector3,
+        radar: &TnuaObstacleRadar,
+        neighbor_position: impl FnMut(Entity) -> Option<Vector3>,
+    ) -> Vector3 {
+        self.separation(position, radar.iter_blips().filter_map(neighbor_position))
+    }
+
+    /// Limit the velocity to [`max_speed`](Self::max_speed).
+    pub fn limit(&self, velocity: Vector3) -> Vector3 {
+        velocity.clamp_length_max(self.max_speed)
+    }
+
+    /// Create a walk basis that moves in the given velocity (limited to
+    /// [`max_speed`](Self::max_speed)) and faces its direction.
+    ///
+    /// The other fields of the basis are taken from `template`.
+    pub fn walk(&self, velocity: Vector3, template: TnuaBuiltinWalk) -> TnuaBuiltinWalk {
+        let velocity = self.limit(self.on_plane(velocity));
+        TnuaBuiltinWalk {
+            desired_velocity: velocity,
+            desired_forward: Dir3::new(velocity.f32()).ok().or(template.desired_forward),
+            ..template
+        }
+    }
+}
+
+/// State for [`TnuaSteering::wander`], to be kept between frames.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaSteeringWanderState {
+    /// The direction the character is currently wandering in.
+    pub direction: Vector3,
+}
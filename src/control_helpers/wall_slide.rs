@@ -0,0 +1,147 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{
+    float_consts, ops, AdjustPrecision, Float, Vector3,
+};
+
+use crate::controller::{apply_controller_system, apply_terminal_velocity_system};
+use crate::obstacle_radar::TnuaObstacleRadar;
+use crate::schedules::TnuaScheduleFilter;
+use crate::{
+    TnuaMotor, TnuaPaused, TnuaPipelineStages, TnuaRigidBodyTracker, TnuaToggle, TnuaVelChange,
+};
+
+pub struct TnuaWallSlidePlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaWallSlidePlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaWallSlidePlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+/// A plugin required for making [`TnuaWallSlide`] work.
+impl Plugin for TnuaWallSlidePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TnuaWallSlide>();
+        app.add_systems(
+            self.schedule,
+            apply_wall_slide
+                .in_set(TnuaPipelineStages::Logic)
+                .after(apply_controller_system)
+                .before(apply_terminal_velocity_system),
+        );
+    }
+}
+
+/// Makes a dynamic character slide along the walls it moves into, instead of pushing into them.
+///
+/// Without this, a character that walks diagonally into a wall keeps accelerating into it, and
+/// the physics solver keeps pushing it back out - which makes it jitter and lose speed. With this
+/// component, the part of the motor that would move the character into a nearby wall is removed
+/// after the basis and the action have set it, so only the part along the wall remains.
+///
+/// The walls are detected by the character's [`TnuaObstacleRadar`], which must have
+/// [`collect_blip_info`](TnuaObstacleRadar::collect_blip_info) set.
+///
+/// Requires the plugin [`TnuaWallSlidePlugin`].
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaWallSlide {
+    /// Walls closer than this (horizontally, from the character's position) are considered to be
+    /// in contact with the character.
+    ///
+    /// This should be a little larger than the radius of the character's collider.
+    pub contact_distance: Float,
+
+    /// The minimal angle, in radians, between a surface and the horizontal plane for the surface
+    /// to be considered a wall. Surfaces that are less steep than that are left for the walk basis
+    /// to deal with.
+    pub min_wall_angle: Float,
+
+    /// Whether the character was in contact with a wall in the last frame.
+    ///
+    /// This is updated by [`TnuaWallSlidePlugin`], and is used by
+    /// [`TnuaTerminalVelocity::wall_sliding`](crate::controller::TnuaTerminalVelocity::wall_sliding).
+    pub touching_wall: bool,
+}
+
+impl Default for TnuaWallSlide {
+    fn default() -> Self {
+        Self {
+            contact_distance: 0.6,
+            min_wall_angle: float_consts::FRAC_PI_4,
+            touching_wall: false,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_wall_slide(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaWallSlide,
+        &TnuaObstacleRadar,
+        &TnuaRigidBodyTracker,
+        &mut TnuaMotor,
+        Option<&TnuaToggle>,
+    )>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    let frame_duration = time.delta_seconds().adjust_precision();
+    for (entity, mut wall_slide, radar, tracker, mut motor, tnua_toggle) in query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+            TnuaToggle::Disabled | TnuaToggle::SenseOnly | TnuaToggle::Frozen => continue,
+            TnuaToggle::Enabled => {}
+        }
+        let up = radar.up_direction().adjust_precision();
+        let max_normal_up = ops::cos(wall_slide.min_wall_angle);
+        let position = radar.tracked_position();
+        let mut velocity = tracker.velocity + motor.lin.calc_boost(frame_duration);
+        let mut correction = Vector3::ZERO;
+        let mut touching_wall = false;
+        for (_, info) in radar.iter_blips_with_info() {
+            let Some(info) = info else {
+                continue;
+            };
+            let normal = info.normal.adjust_precision();
+            if max_normal_up < normal.dot(up) {
+                continue;
+            }
+            let offset = (position - info.closest_point).reject_from(up);
+            if wall_slide.contact_distance < offset.length() {
+                continue;
+            }
+            let Some(normal) = normal.reject_from(up).try_normalize() else {
+                continue;
+            };
+            touching_wall = true;
+            let into_wall = velocity.dot(normal);
+            if into_wall < 0.0 {
+                velocity -= into_wall * normal;
+                correction -= into_wall * normal;
+            }
+        }
+        if wall_slide.touching_wall != touching_wall {
+            wall_slide.touching_wall = touching_wall;
+        }
+        if correction != Vector3::ZERO {
+            motor.lin += TnuaVelChange::boost(correction);
+        }
+    }
+}
@@ -1,17 +1,37 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+
 use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
-use bevy::utils::{Entry, HashMap};
-use bevy_tnua_physics_integration_layer::math::{AsF32, Float};
+use bevy::utils::{Entry, HashMap, HashSet};
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
 
 use crate::basis_action_traits::{
     BoxableAction, BoxableBasis, DynamicAction, DynamicBasis, TnuaAction, TnuaActionContext,
     TnuaActionInitiationDirective, TnuaActionLifecycleDirective, TnuaActionLifecycleStatus,
-    TnuaBasisContext,
+    TnuaActionStage, TnuaBasisContext,
+};
+use crate::builtins::{
+    update_walk_animation_params, TnuaAnimationParams, TnuaBuiltinCrouch, TnuaBuiltinDash,
+    TnuaBuiltinJump, TnuaBuiltinKnockback, TnuaBuiltinWalk,
 };
+use crate::control_helpers::{TnuaAuxiliarySensors, TnuaWallSlide};
+#[cfg(feature = "prediction")]
+use crate::prediction::{
+    TnuaPredictionError, TnuaPredictionRegistry, TnuaPredictionSnapshot, TnuaSerializedActionSlot,
+    TnuaSerializedFedEntry, TnuaSerializedQueuedAction, TnuaSerializedQueuedActionStatus,
+    TnuaSerializedReportedState,
+};
+use crate::schedules::{TnuaScheduleFilter, TnuaSchedules};
+use crate::subservient_sensors::TnuaSubservientSensor;
 use crate::{
-    TnuaBasis, TnuaMotor, TnuaPipelineStages, TnuaProximitySensor, TnuaRigidBodyTracker,
-    TnuaSystemSet, TnuaToggle, TnuaUserControlsSystemSet,
+    TnuaBasis, TnuaBodyTargets, TnuaCharacterColliders, TnuaCharacterCollisionPolicy,
+    TnuaGhostPlatform, TnuaGhostSensor, TnuaGravityField, TnuaGroundClass, TnuaGroundClassifier,
+    TnuaMotor, TnuaMotorApplication, TnuaMotorLimits, TnuaPaused, TnuaPipelineStages,
+    TnuaPlaneLock, TnuaProximitySensor, TnuaRigidBodyTracker, TnuaSensorHitCache,
+    TnuaSensorQueryFilter, TnuaSensorSolidityProbe, TnuaSleepPolicy, TnuaSurfaceMaterial,
+    TnuaSystemSet, TnuaToggle, TnuaUpdateTier, TnuaUserControlsSystemSet,
 };
 
 /// The main for supporting Tnua character controller.
@@ -20,16 +40,54 @@ use crate::{
 /// `TnuaRapier3dPlugin`)
 pub struct TnuaControllerPlugin {
     schedule: InternedScheduleLabel,
+    unfed_tick_policy: TnuaUnfedTickPolicy,
 }
 
 impl TnuaControllerPlugin {
     pub fn new(schedule: impl ScheduleLabel) -> Self {
         Self {
             schedule: schedule.intern(),
+            unfed_tick_policy: Default::default(),
         }
     }
+
+    /// Set how the controller treats ticks in which nothing was fed to it.
+    ///
+    /// Use [`TnuaUnfedTickPolicy::RepeatLastInput`] when the controller runs in a fixed schedule
+    /// (e.g. `FixedUpdate`) but the player controls are fed in `Update`.
+    pub fn with_unfed_tick_policy(mut self, policy: TnuaUnfedTickPolicy) -> Self {
+        self.unfed_tick_policy = policy;
+        self
+    }
+}
+
+/// How the controller treats ticks in which nothing (neither a basis nor an action) was fed to it.
+///
+/// Set it with [`TnuaControllerPlugin::with_unfed_tick_policy`]. When the controller plugin is
+/// added in more than one schedule, each schedule has its own policy.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaUnfedTickPolicy {
+    /// Treat the actions as no longer fed. This is the right policy when the player controls are
+    /// fed in the same schedule the controller runs in, which should be the case when both run in
+    /// `Update`.
+    #[default]
+    ReleaseInput,
+    /// Treat the tick as if the same input as in the last tick was fed again.
+    ///
+    /// When the controller runs in a fixed schedule and the controls are fed in `Update`, the
+    /// fixed schedule may run several times between feeds. With this policy, a held jump button
+    /// will not be considered as released just because the controls system did not run between
+    /// two ticks.
+    ///
+    /// Note that with this policy, the game code must feed the basis every frame (even when there
+    /// is no input) - otherwise the last actions will be considered held indefinitely.
+    RepeatLastInput,
 }
 
+/// The [`TnuaUnfedTickPolicy`] of each schedule the controller plugin was added to.
+#[derive(Resource, Default)]
+pub(crate) struct TnuaUnfedTickPolicies(HashMap<InternedScheduleLabel, TnuaUnfedTickPolicy>);
+
 impl Default for TnuaControllerPlugin {
     fn default() -> Self {
         Self::new(Update)
@@ -38,6 +96,7 @@ impl Default for TnuaControllerPlugin {
 
 impl Plugin for TnuaControllerPlugin {
     fn build(&self, app: &mut App) {
+        TnuaSchedules::register(app, self.schedule);
         app.configure_sets(
             self.schedule,
             (
@@ -50,11 +109,72 @@ impl Plugin for TnuaControllerPlugin {
                 .chain()
                 .in_set(TnuaSystemSet),
         );
+        app.world_mut()
+            .get_resource_or_insert_with(TnuaUnfedTickPolicies::default)
+            .0
+            .insert(self.schedule, self.unfed_tick_policy);
+        app.register_type::<TnuaToggle>()
+            .register_type::<TnuaPaused>()
+            .register_type::<TnuaRigidBodyTracker>()
+            .register_type::<TnuaBodyTargets>()
+            .register_type::<TnuaCharacterColliders>()
+            .register_type::<TnuaCharacterCollisionPolicy>()
+            .register_type::<TnuaProximitySensor>()
+            .register_type::<TnuaMotor>()
+            .register_type::<TnuaMotorApplication>()
+            .register_type::<TnuaMotorLimits>()
+            .register_type::<TnuaPlaneLock>()
+            .register_type::<TnuaSleepPolicy>()
+            .register_type::<TnuaUpdateTier>()
+            .register_type::<TnuaGhostSensor>()
+            .register_type::<TnuaGhostPlatform>()
+            .register_type::<TnuaGravityField>()
+            .register_type::<TnuaGroundClass>()
+            .register_type::<TnuaGroundClassifier>()
+            .register_type::<TnuaSurfaceMaterial>()
+            .register_type::<TnuaSensorSolidityProbe>()
+            .register_type::<TnuaSensorHitCache>()
+            .register_type::<TnuaSubservientSensor>()
+            .register_type::<TnuaUpDirection>()
+            .register_type::<TnuaExternalFlow>()
+            .register_type::<TnuaTerminalVelocity>()
+            .register_type::<TnuaRootMotion>()
+            .register_type::<TnuaResourcePool>()
+            .register_type::<TnuaBuiltinWalk>()
+            .register_type::<TnuaBuiltinJump>()
+            .register_type::<TnuaBuiltinCrouch>()
+            .register_type::<TnuaBuiltinDash>()
+            .register_type::<TnuaBuiltinKnockback>()
+            .register_type::<TnuaAnimationParams>();
+        app.add_event::<TnuaControllerEvent>();
+        app.add_event::<TnuaLandedEvent>();
         app.add_systems(
             self.schedule,
-            apply_controller_system.in_set(TnuaPipelineStages::Logic),
+            (
+                apply_controller_system.in_set(TnuaPipelineStages::Logic),
+                apply_terminal_velocity_system
+                    .in_set(TnuaPipelineStages::Logic)
+                    .after(apply_controller_system),
+                send_controller_events
+                    .in_set(TnuaPipelineStages::Logic)
+                    .after(apply_controller_system),
+                update_walk_animation_params
+                    .in_set(TnuaPipelineStages::Logic)
+                    .after(apply_controller_system),
+                clear_sensor_computed_exclusions
+                    .in_set(TnuaSystemSet)
+                    .before(TnuaPipelineStages::Sensors),
+                classify_ground_system
+                    .in_set(TnuaSystemSet)
+                    .after(TnuaPipelineStages::Sensors)
+                    .before(TnuaPipelineStages::SubservientSensors),
+            ),
         );
     }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
 }
 
 /// All the Tnua components needed to run a floating character controller.
@@ -69,11 +189,90 @@ pub struct TnuaControllerBundle {
     pub proximity_sensor: TnuaProximitySensor,
 }
 
+#[derive(Clone)]
 struct FedEntry {
     fed_this_frame: bool,
     rescheduled_in: Option<Timer>,
 }
 
+pub(crate) type QueuedActionFeeder = fn(&dyn DynamicAction, &'static str, &mut TnuaController);
+type ActionQueueCondition = Arc<dyn Send + Sync + Fn(&TnuaController) -> bool>;
+
+struct QueuedAction {
+    name: &'static str,
+    action: Box<dyn DynamicAction>,
+    feed: QueuedActionFeeder,
+    condition: Option<ActionQueueCondition>,
+    status: QueuedActionStatus,
+}
+
+impl Clone for QueuedAction {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            action: self.action.clone_boxed(),
+            feed: self.feed,
+            condition: self.condition.clone(),
+            status: self.status,
+        }
+    }
+}
+
+/// Feeds a queued action of type `A` into the controller.
+pub(crate) fn queued_action_feeder<A: TnuaAction>() -> QueuedActionFeeder {
+    |action, name, controller| {
+        let Some(action) = action.as_any().downcast_ref::<BoxableAction<A>>() else {
+            panic!("Queued action {name:?} has the wrong type");
+        };
+        controller.feed_named_action(name, action.input.clone());
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum QueuedActionStatus {
+    Pending,
+    Fed,
+    Running,
+}
+
+#[derive(Default, Clone)]
+struct ReportedControllerState {
+    airborne: Option<bool>,
+    airborne_duration: Float,
+    highest_elevation: Float,
+    last_velocity: Vector3,
+    basis_name: Option<&'static str>,
+    action_name: Option<&'static str>,
+}
+
+#[derive(Default)]
+struct ActionSlot {
+    current_action: Option<(&'static str, Box<dyn DynamicAction>)>,
+    current_fed_this_frame: bool,
+    contender_action: Option<(&'static str, Box<dyn DynamicAction>, Stopwatch)>,
+    contender_fed_this_frame: bool,
+    conflicts_with: HashSet<&'static str>,
+}
+
+impl Clone for ActionSlot {
+    fn clone(&self) -> Self {
+        Self {
+            current_action: self
+                .current_action
+                .as_ref()
+                .map(|(name, action)| (*name, action.clone_boxed())),
+            current_fed_this_frame: self.current_fed_this_frame,
+            contender_action: self.contender_action.as_ref().map(
+                |(name, action, being_fed_for)| {
+                    (*name, action.clone_boxed(), being_fed_for.clone())
+                },
+            ),
+            contender_fed_this_frame: self.contender_fed_this_frame,
+            conflicts_with: self.conflicts_with.clone(),
+        }
+    }
+}
+
 /// The main component used for interaction with the controls and animation code.
 ///
 /// Every frame, the game code should feed input this component on every controlled entity. What
@@ -95,6 +294,10 @@ struct FedEntry {
 ///   Refer to the documentation of [the implementors of
 ///   `TnuaAction`](crate::TnuaAction#implementors) for more information.
 ///
+///   Actions that should run at the same time as the main action (e.g. an upper-body aim action
+///   during a dash) can be fed into their own slots with
+///   [`slot_action`](Self::slot_action).
+///
 /// Without [`TnuaControllerPlugin`] this component will not do anything.
 #[derive(Component, Default)]
 pub struct TnuaController {
@@ -102,7 +305,59 @@ pub struct TnuaController {
     actions_being_fed: HashMap<&'static str, FedEntry>,
     current_action: Option<(&'static str, Box<dyn DynamicAction>)>,
     contender_action: Option<(&'static str, Box<dyn DynamicAction>, Stopwatch)>,
+    contender_buffered_for: Option<Stopwatch>,
+    input_buffer_times: HashMap<&'static str, Float>,
+    action_priorities: HashMap<&'static str, TnuaActionPriority>,
+    current_action_running_for: Stopwatch,
+    cooldowns: HashMap<&'static str, Timer>,
+    action_queue: VecDeque<QueuedAction>,
+    action_slots: BTreeMap<&'static str, ActionSlot>,
     action_flow_status: TnuaActionFlowStatus,
+    reported_state: ReportedControllerState,
+    fed_since_last_tick: bool,
+    ground_history: TnuaGroundHistory,
+    #[cfg(feature = "replay")]
+    fed_actions_log: Option<Vec<(&'static str, Box<dyn DynamicAction>)>>,
+}
+
+/// Cloning the controller clones the state of the basis and the actions too, so the clone can
+/// be used as a snapshot of the controller for rollback networking (see [`rollback`](crate::rollback)).
+impl Clone for TnuaController {
+    fn clone(&self) -> Self {
+        Self {
+            current_basis: self
+                .current_basis
+                .as_ref()
+                .map(|(name, basis)| (*name, basis.clone_boxed())),
+            actions_being_fed: self.actions_being_fed.clone(),
+            current_action: self
+                .current_action
+                .as_ref()
+                .map(|(name, action)| (*name, action.clone_boxed())),
+            contender_action: self.contender_action.as_ref().map(
+                |(name, action, being_fed_for)| {
+                    (*name, action.clone_boxed(), being_fed_for.clone())
+                },
+            ),
+            contender_buffered_for: self.contender_buffered_for.clone(),
+            input_buffer_times: self.input_buffer_times.clone(),
+            action_priorities: self.action_priorities.clone(),
+            current_action_running_for: self.current_action_running_for.clone(),
+            cooldowns: self.cooldowns.clone(),
+            action_queue: self.action_queue.clone(),
+            action_slots: self.action_slots.clone(),
+            action_flow_status: self.action_flow_status.clone(),
+            reported_state: self.reported_state.clone(),
+            fed_since_last_tick: self.fed_since_last_tick,
+            ground_history: self.ground_history.clone(),
+            #[cfg(feature = "replay")]
+            fed_actions_log: self.fed_actions_log.as_ref().map(|log| {
+                log.iter()
+                    .map(|(name, action)| (*name, action.clone_boxed()))
+                    .collect()
+            }),
+        }
+    }
 }
 
 impl TnuaController {
@@ -117,6 +372,7 @@ impl TnuaController {
     /// allow, for example, different animations. Otherwise prefer to use the default name with
     /// [`basis`](Self::basis).
     pub fn named_basis<B: TnuaBasis>(&mut self, name: &'static str, basis: B) {
+        self.fed_since_last_tick = true;
         if let Some((existing_name, existing_basis)) =
             self.current_basis.as_mut().and_then(|(n, b)| {
                 let b = b.as_mut_any().downcast_mut::<BoxableBasis<B>>()?;
@@ -136,6 +392,7 @@ impl TnuaController {
     /// but generally it means that fields that typically come from a configuration will not be
     /// touched, and only fields that are typically set by user input get nullified.
     pub fn neutralize_basis(&mut self) {
+        self.fed_since_last_tick = true;
         if let Some((_, basis)) = self.current_basis.as_mut() {
             basis.neutralize();
         }
@@ -167,17 +424,174 @@ impl TnuaController {
         Some((&boxable_basis.input, &boxable_basis.state))
     }
 
+    /// Modify the input of the fed basis, without counting as feeding it.
+    ///
+    /// For helpers that adjust what the game code fed before the controller applies it.
+    pub(crate) fn concrete_basis_input_mut<B: TnuaBasis>(&mut self) -> Option<&mut B> {
+        let (_, basis) = self.current_basis.as_mut()?;
+        let boxable_basis: &mut BoxableBasis<B> = basis.as_mut_any().downcast_mut()?;
+        Some(&mut boxable_basis.input)
+    }
+
+    /// Buffer the input of the action with the given name.
+    ///
+    /// When an action is fed while it cannot start yet (its
+    /// [`initiation_decision`](TnuaAction::initiation_decision) returns
+    /// [`Delay`](crate::TnuaActionInitiationDirective::Delay) - e.g. a jump pressed slightly
+    /// before landing), it is normally forgotten as soon as it is no longer fed. With an input
+    /// buffer, it is remembered for up to `seconds` after it was last fed, and starts once it is
+    /// allowed to. Note that the action still gets to [reject](TnuaAction::initiation_decision)
+    /// the initiation - e.g. [`TnuaBuiltinJump`](crate::builtins::TnuaBuiltinJump) will only wait
+    /// for its own [`input_buffer_time`](crate::builtins::TnuaBuiltinJump::input_buffer_time).
+    ///
+    /// ```no_run
+    /// # use bevy_tnua::prelude::*;
+    /// TnuaController::default().with_input_buffer(TnuaBuiltinJump::NAME, 0.15);
+    /// ```
+    pub fn with_input_buffer(mut self, action_name: &'static str, seconds: Float) -> Self {
+        self.set_input_buffer(action_name, seconds);
+        self
+    }
+
+    /// Change the input buffer of an action. See [`with_input_buffer`](Self::with_input_buffer).
+    ///
+    /// Setting it to zero disables the input buffering for that action.
+    pub fn set_input_buffer(&mut self, action_name: &'static str, seconds: Float) {
+        if 0.0 < seconds {
+            self.input_buffer_times.insert(action_name, seconds);
+        } else {
+            self.input_buffer_times.remove(action_name);
+        }
+    }
+
+    /// Set the priority and interruption policy of an action.
+    ///
+    /// Actions without an explicit priority use [`TnuaActionPriority::default`], which keeps the
+    /// default behavior of letting any newly fed action cancel the current one (if its
+    /// [`initiation_decision`](TnuaAction::initiation_decision) allows it to start).
+    ///
+    /// ```no_run
+    /// # use bevy_tnua::prelude::*;
+    /// # use bevy_tnua::builtins::TnuaBuiltinDash;
+    /// # use bevy_tnua::controller::TnuaActionPriority;
+    /// TnuaController::default()
+    ///     .with_action_priority(
+    ///         TnuaBuiltinDash::NAME,
+    ///         TnuaActionPriority {
+    ///             level: 1,
+    ///             uninterruptible_for: 0.2,
+    ///             ..Default::default()
+    ///         },
+    ///     );
+    /// ```
+    pub fn with_action_priority(
+        mut self,
+        action_name: &'static str,
+        priority: TnuaActionPriority,
+    ) -> Self {
+        self.set_action_priority(action_name, priority);
+        self
+    }
+
+    /// Change the priority of an action. See [`with_action_priority`](Self::with_action_priority).
+    pub fn set_action_priority(&mut self, action_name: &'static str, priority: TnuaActionPriority) {
+        self.action_priorities.insert(action_name, priority);
+    }
+
+    /// The priority of an action, as set with [`with_action_priority`](Self::with_action_priority).
+    pub fn action_priority(&self, action_name: &'static str) -> TnuaActionPriority {
+        self.action_priorities
+            .get(action_name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn contender_may_interrupt(&self) -> bool {
+        let Some((current_name, _)) = &self.current_action else {
+            return true;
+        };
+        let Some((contender_name, ..)) = &self.contender_action else {
+            return true;
+        };
+        let current = self.action_priority(current_name);
+        let contender = self.action_priority(contender_name);
+        let elapsed = self
+            .current_action_running_for
+            .elapsed_secs()
+            .adjust_precision();
+        if elapsed < current.uninterruptible_for {
+            return false;
+        }
+        match contender.level.cmp(&current.level) {
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => true,
+            std::cmp::Ordering::Greater => {
+                contender.interrupts_lower && current.interruptible_by_higher
+            }
+        }
+    }
+
     /// Feed an action with [its default name](TnuaBasis::NAME).
     pub fn action<A: TnuaAction>(&mut self, action: A) {
         self.named_action(A::NAME, action);
     }
 
+    /// Feed an action, with [its default name](TnuaAction::NAME), scaled by an analog input.
+    ///
+    /// See [`named_action_with_strength`](Self::named_action_with_strength).
+    pub fn action_with_strength<A: TnuaAction>(&mut self, action: A, strength: Float) {
+        self.named_action_with_strength(A::NAME, action, strength);
+    }
+
+    /// Feed an action with a custom name, scaled by an analog input.
+    ///
+    /// `strength` (clamped to between 0.0 and 1.0) is passed to the action's
+    /// [`apply_strength`](TnuaAction::apply_strength) - e.g. the builtin jump scales its height by
+    /// it, and the builtin dash scales its distance.
+    ///
+    /// ```no_run
+    /// # use bevy_tnua::prelude::*;
+    /// # let mut controller = TnuaController::default();
+    /// # let trigger_pressure = 0.5;
+    /// controller.action_with_strength(
+    ///     TnuaBuiltinJump {
+    ///         height: 4.0,
+    ///         ..Default::default()
+    ///     },
+    ///     trigger_pressure,
+    /// );
+    /// ```
+    pub fn named_action_with_strength<A: TnuaAction>(
+        &mut self,
+        name: &'static str,
+        mut action: A,
+        strength: Float,
+    ) {
+        action.apply_strength(strength.clamp(0.0, 1.0));
+        self.named_action(name, action);
+    }
+
     /// Feed an action with a custom name.
     ///
     /// This should only be used if the same action type needs to be used with different names to
     /// allow, for example, different animations. Otherwise prefer to use the default name with
     /// [`action`](Self::action).
     pub fn named_action<A: TnuaAction>(&mut self, name: &'static str, action: A) {
+        self.fed_since_last_tick = true;
+        self.feed_named_action(name, action);
+    }
+
+    /// Feed an action without counting as input from the game code - for actions the controller
+    /// feeds itself (e.g. from the action queue), which should not prevent
+    /// [`TnuaUnfedTickPolicy::RepeatLastInput`] from repeating the last input.
+    fn feed_named_action<A: TnuaAction>(&mut self, name: &'static str, action: A) {
+        #[cfg(feature = "replay")]
+        if let Some(log) = self.fed_actions_log.as_mut() {
+            log.push((name, Box::new(BoxableAction::new(action.clone()))));
+        }
+        if matches!(&self.contender_action, Some((contender_name, ..)) if *contender_name == name) {
+            self.contender_buffered_for = None;
+        }
         match self.actions_being_fed.entry(name) {
             Entry::Occupied(mut entry) => {
                 entry.get_mut().fed_this_frame = true;
@@ -199,7 +613,7 @@ impl TnuaController {
                         .get()
                         .rescheduled_in
                         .as_ref()
-                        .map_or(false, |timer| timer.finished())
+                        .is_some_and(|timer| timer.finished())
                 {
                     // no action is running - but this action is rescheduled and there is no
                     // already-existing contender that would have taken priority
@@ -214,6 +628,15 @@ impl TnuaController {
                     fed_this_frame: true,
                     rescheduled_in: None,
                 });
+                if let Some((contender_name, ..)) = &self.contender_action {
+                    if *contender_name != name
+                        && self.action_priority(name).level
+                            < self.action_priority(contender_name).level
+                    {
+                        // A higher priority action was already fed this frame.
+                        return;
+                    }
+                }
                 if let Some(contender_action) = self.contender_action.as_mut().and_then(
                     |(contender_name, contender_action, _)| {
                         if *contender_name == name {
@@ -238,6 +661,132 @@ impl TnuaController {
         }
     }
 
+    /// Add an action, with [its default name](TnuaAction::NAME), to the end of the action queue.
+    ///
+    /// See [`queue_named_action_when`](Self::queue_named_action_when).
+    pub fn queue_action<A: TnuaAction>(&mut self, action: A) {
+        self.queue_named_action_when(A::NAME, action, None::<fn(&TnuaController) -> bool>);
+    }
+
+    /// Add an action, with [its default name](TnuaAction::NAME), to the end of the action queue,
+    /// to only be started if `condition` holds when its turn comes.
+    ///
+    /// See [`queue_named_action_when`](Self::queue_named_action_when).
+    pub fn queue_action_when<A: TnuaAction>(
+        &mut self,
+        action: A,
+        condition: impl 'static + Send + Sync + Fn(&TnuaController) -> bool,
+    ) {
+        self.queue_named_action_when(A::NAME, action, Some(condition));
+    }
+
+    /// Add an action to the end of the action queue.
+    ///
+    /// Queued actions are used for chaining actions (e.g. dash, then jump, then dive) without
+    /// having to feed each of them at the right time. The controller starts the first queued
+    /// action once no other action is running, and keeps feeding it until it finishes or is
+    /// cancelled. Then it starts the next queued action, and so on.
+    ///
+    /// When a queued action's turn comes and its `condition` (if there is one) does not hold, or
+    /// when the action refuses to start, the entire queue is cleared.
+    ///
+    /// ```no_run
+    /// # use bevy_tnua::prelude::*;
+    /// # use bevy_tnua::builtins::TnuaBuiltinDash;
+    /// # use bevy_tnua::math::Vector3;
+    /// # let mut controller = TnuaController::default();
+    /// controller.queue_action(TnuaBuiltinDash {
+    ///     displacement: Vector3::X * 5.0,
+    ///     ..Default::default()
+    /// });
+    /// controller.queue_action_when(
+    ///     TnuaBuiltinJump {
+    ///         height: 4.0,
+    ///         ..Default::default()
+    ///     },
+    ///     |controller| !controller.is_airborne().unwrap_or(true),
+    /// );
+    /// ```
+    pub fn queue_named_action_when<A: TnuaAction>(
+        &mut self,
+        name: &'static str,
+        action: A,
+        condition: Option<impl 'static + Send + Sync + Fn(&TnuaController) -> bool>,
+    ) {
+        self.action_queue.push_back(QueuedAction {
+            name,
+            action: Box::new(BoxableAction::new(action)),
+            feed: queued_action_feeder::<A>(),
+            condition: condition.map(|condition| Arc::new(condition) as ActionQueueCondition),
+            status: QueuedActionStatus::Pending,
+        });
+    }
+
+    /// The names of the actions in the action queue, including the one currently running from
+    /// it.
+    pub fn queued_actions(&self) -> impl '_ + Iterator<Item = &'static str> {
+        self.action_queue
+            .iter()
+            .map(|queued_action| queued_action.name)
+    }
+
+    /// Remove all the actions from the action queue.
+    ///
+    /// If an action from the queue is currently running, it will no longer be fed by the queue.
+    pub fn clear_action_queue(&mut self) {
+        self.action_queue.clear();
+    }
+
+    fn feed_queued_actions(&mut self) {
+        let mut queue = std::mem::take(&mut self.action_queue);
+        while let Some(queued_action) = queue.front_mut() {
+            let is_current = self.action_name() == Some(queued_action.name);
+            match queued_action.status {
+                QueuedActionStatus::Pending => {
+                    if self.current_action.is_some() {
+                        break;
+                    }
+                    if let Some(condition) = &queued_action.condition {
+                        if !condition(self) {
+                            queue.clear();
+                            break;
+                        }
+                    }
+                    (queued_action.feed)(queued_action.action.as_ref(), queued_action.name, self);
+                    queued_action.status = QueuedActionStatus::Fed;
+                    break;
+                }
+                QueuedActionStatus::Fed => {
+                    let is_contender = matches!(
+                        &self.contender_action,
+                        Some((contender_name, ..)) if *contender_name == queued_action.name,
+                    );
+                    if is_current {
+                        queued_action.status = QueuedActionStatus::Running;
+                    } else if !is_contender {
+                        // The action refused to start
+                        queue.clear();
+                        break;
+                    }
+                    (queued_action.feed)(queued_action.action.as_ref(), queued_action.name, self);
+                    break;
+                }
+                QueuedActionStatus::Running => {
+                    if is_current {
+                        (queued_action.feed)(
+                            queued_action.action.as_ref(),
+                            queued_action.name,
+                            self,
+                        );
+                        break;
+                    }
+                    queue.pop_front();
+                }
+            }
+        }
+        self.action_queue = queue;
+    }
+
     /// The name of the currently running action.
     ///
     /// When using an action with it's default name, prefer to match this against
@@ -264,6 +813,136 @@ impl TnuaController {
         Some((&boxable_action.input, &boxable_action.state))
     }
 
+    /// Feed an action, with [its default name](TnuaAction::NAME), into an action slot.
+    ///
+    /// See [`named_slot_action`](Self::named_slot_action).
+    pub fn slot_action<A: TnuaAction>(&mut self, slot: &'static str, action: A) {
+        self.named_slot_action(slot, A::NAME, action);
+    }
+
+    /// Feed an action with a custom name into an action slot.
+    ///
+    /// Each slot can run one action, independently of the main action (the one fed with
+    /// [`action`](Self::action)) and of the other slots. Slot actions are applied after the main
+    /// action, in the order of the slot names, so they should be designed to add to what the main
+    /// action does to the motor rather than override it.
+    ///
+    /// Slot actions are simpler than the main action:
+    ///
+    /// * A new action fed into an occupied slot waits (as long as it is fed) for the current
+    ///   action of the slot to finish - it does not cancel it.
+    /// * [`Reschedule`](TnuaActionLifecycleDirective::Reschedule) is treated like
+    ///   [`Finished`](TnuaActionLifecycleDirective::Finished).
+    /// * They are not reflected in [`action_flow_status`](Self::action_flow_status).
+    ///
+    /// Use [`set_slot_conflicts`](Self::set_slot_conflicts) to prevent incompatible actions from
+    /// overlapping.
+    pub fn named_slot_action<A: TnuaAction>(
+        &mut self,
+        slot: &'static str,
+        name: &'static str,
+        action: A,
+    ) {
+        self.fed_since_last_tick = true;
+        let slot = self.action_slots.entry(slot).or_default();
+        if let Some((current_name, current_action)) = slot.current_action.as_mut() {
+            if *current_name == name {
+                let Some(current_action) = current_action
+                    .as_mut_any()
+                    .downcast_mut::<BoxableAction<A>>()
+                else {
+                    panic!("Multiple action types registered with same name {name:?}");
+                };
+                current_action.input = action;
+                slot.current_fed_this_frame = true;
+                return;
+            }
+        }
+        if let Some((contender_name, contender_action, _)) = slot.contender_action.as_mut() {
+            if *contender_name == name {
+                let Some(contender_action) = contender_action
+                    .as_mut_any()
+                    .downcast_mut::<BoxableAction<A>>()
+                else {
+                    panic!("Multiple action types registered with same name {name:?}");
+                };
+                contender_action.input = action;
+                slot.contender_fed_this_frame = true;
+                return;
+            }
+        }
+        if !slot.contender_fed_this_frame {
+            slot.contender_action =
+                Some((name, Box::new(BoxableAction::new(action)), Stopwatch::new()));
+            slot.contender_fed_this_frame = true;
+        }
+    }
+
+    /// Declare the actions that cannot overlap with the actions of a slot.
+    ///
+    /// While an action with one of these names runs in the main action or in another slot, no
+    /// action will be started in this slot. If the main action switches to one of these actions,
+    /// the action of this slot will be cancelled (it gets
+    /// [`CancelledInto`](TnuaActionLifecycleStatus::CancelledInto) and is expected to finish).
+    /// The main action always takes priority over the slots, and a slot action that is already
+    /// running takes priority over one that wants to start in another slot.
+    ///
+    /// ```no_run
+    /// # use bevy_tnua::prelude::*;
+    /// # use bevy_tnua::builtins::TnuaBuiltinCrouch;
+    /// # let mut controller = TnuaController::default();
+    /// controller.set_slot_conflicts("upper-body", [TnuaBuiltinCrouch::NAME]);
+    /// ```
+    pub fn set_slot_conflicts(
+        &mut self,
+        slot: &'static str,
+        action_names: impl IntoIterator<Item = &'static str>,
+    ) {
+        self.action_slots.entry(slot).or_default().conflicts_with =
+            action_names.into_iter().collect();
+    }
+
+    /// The name of the action currently running in a slot.
+    pub fn slot_action_name(&self, slot: &'static str) -> Option<&'static str> {
+        let (name, _) = self.action_slots.get(slot)?.current_action.as_ref()?;
+        Some(*name)
+    }
+
+    /// The action currently running in a slot, together with its state.
+    pub fn concrete_slot_action<A: TnuaAction>(
+        &self,
+        slot: &'static str,
+    ) -> Option<(&A, &A::State)> {
+        let (_, action) = self.action_slots.get(slot)?.current_action.as_ref()?;
+        let boxable_action: &BoxableAction<A> = action.as_any().downcast_ref()?;
+        Some((&boxable_action.input, &boxable_action.state))
+    }
+
+    /// The time, in seconds, until an action with [its default name](TnuaAction::NAME) can start
+    /// again.
+    ///
+    /// See [`named_cooldown_remaining`](Self::named_cooldown_remaining).
+    pub fn cooldown_remaining<A: TnuaAction>(&self) -> Float {
+        self.named_cooldown_remaining(A::NAME)
+    }
+
+    /// The time, in seconds, until an action with the given name can start again.
+    ///
+    /// When an action ends, the controller puts its name on cooldown for the duration returned by
+    /// the action's [`cooldown`](TnuaAction::cooldown), and refuses to start actions with that name
+    /// until the cooldown is over. Returns 0.0 if the action is not on cooldown.
+    pub fn named_cooldown_remaining(&self, action_name: &'static str) -> Float {
+        self.cooldowns
+            .get(action_name)
+            .map(|timer| timer.remaining_secs().adjust_precision())
+            .unwrap_or(0.0)
+    }
+
+    /// Cancel the cooldown of an action, allowing it to start again immediately.
+    pub fn reset_cooldown(&mut self, action_name: &'static str) {
+        self.cooldowns.remove(action_name);
+    }
+
     /// Indicator for the state and flow of movement actions.
     ///
     /// Query this every frame to keep track of the actions. For air actions,
@@ -280,6 +959,327 @@ impl TnuaController {
         &self.action_flow_status
     }
 
+    /// A typed description of what the controller is currently doing.
+    ///
+    /// This is meant for animation state machines and debugging, and does not require
+    /// downcasting the basis or the action:
+    ///
+    /// ```no_run
+    /// # use bevy_tnua::prelude::*;
+    /// # use bevy_tnua::controller::TnuaLocomotionState;
+    /// # let controller = TnuaController::default();
+    /// let snapshot = controller.snapshot();
+    /// match (snapshot.locomotion, snapshot.action) {
+    ///     (_, Some(action)) => {
+    ///         // play the animation of `action.name`, in `action.stage.name`
+    ///     }
+    ///     (TnuaLocomotionState::Airborne, None) => {
+    ///         // play falling animation
+    ///     }
+    ///     (TnuaLocomotionState::Grounded | TnuaLocomotionState::NoBasis, None) => {
+    ///         // play idle or walk animation
+    ///     }
+    /// }
+    /// ```
+    pub fn snapshot(&self) -> TnuaControllerSnapshot {
+        TnuaControllerSnapshot {
+            basis_name: self.basis_name(),
+            locomotion: match self.is_airborne() {
+                Ok(false) => TnuaLocomotionState::Grounded,
+                Ok(true) => TnuaLocomotionState::Airborne,
+                Err(TnuaControllerHasNoBasis) => TnuaLocomotionState::NoBasis,
+            },
+            action: self
+                .current_action
+                .as_ref()
+                .map(|(name, action)| TnuaActionSnapshot {
+                    name,
+                    stage: action.stage(),
+                    still_fed: !matches!(
+                        self.action_flow_status,
+                        TnuaActionFlowStatus::ActionEnded(_)
+                    ),
+                }),
+        }
+    }
+
+    /// Capture the state of the controller, including the state of its basis and actions, in a
+    /// serializable form.
+    ///
+    /// See [the `prediction` module](crate::prediction).
+    #[cfg(feature = "prediction")]
+    pub fn prediction_snapshot(
+        &self,
+        registry: &TnuaPredictionRegistry,
+    ) -> Result<TnuaPredictionSnapshot, TnuaPredictionError> {
+        Ok(TnuaPredictionSnapshot {
+            basis: self
+                .current_basis
+                .as_ref()
+                .map(|(name, basis)| registry.save_basis(name, basis.as_ref()))
+                .transpose()?,
+            current_action: self
+                .current_action
+                .as_ref()
+                .map(|(name, action)| registry.save_action(name, action.as_ref()))
+                .transpose()?,
+            current_action_running_for: self.current_action_running_for.clone(),
+            contender_action: self
+                .contender_action
+                .as_ref()
+                .map(|(name, action, being_fed_for)| {
+                    Ok::<_, TnuaPredictionError>((
+                        registry.save_action(name, action.as_ref())?,
+                        being_fed_for.clone(),
+                    ))
+                })
+                .transpose()?,
+            contender_buffered_for: self.contender_buffered_for.clone(),
+            actions_being_fed: self
+                .actions_being_fed
+                .iter()
+                .map(|(name, fed_entry)| TnuaSerializedFedEntry {
+                    name: (*name).to_owned(),
+                    fed_this_frame: fed_entry.fed_this_frame,
+                    rescheduled_in: fed_entry.rescheduled_in.clone(),
+                })
+                .collect(),
+            cooldowns: self
+                .cooldowns
+                .iter()
+                .map(|(name, timer)| ((*name).to_owned(), timer.clone()))
+                .collect(),
+            action_queue: self
+                .action_queue
+                .iter()
+                .map(|queued_action| {
+                    Ok(TnuaSerializedQueuedAction {
+                        action: registry
+                            .save_action(queued_action.name, queued_action.action.as_ref())?,
+                        has_condition: queued_action.condition.is_some(),
+                        status: match queued_action.status {
+                            QueuedActionStatus::Pending => {
+                                TnuaSerializedQueuedActionStatus::Pending
+                            }
+                            QueuedActionStatus::Fed => TnuaSerializedQueuedActionStatus::Fed,
+                            QueuedActionStatus::Running => {
+                                TnuaSerializedQueuedActionStatus::Running
+                            }
+                        },
+                    })
+                })
+                .collect::<Result<_, TnuaPredictionError>>()?,
+            action_slots: self
+                .action_slots
+                .iter()
+                .map(|(slot_name, slot)| {
+                    Ok(TnuaSerializedActionSlot {
+                        name: (*slot_name).to_owned(),
+                        current_action: slot
+                            .current_action
+                            .as_ref()
+                            .map(|(name, action)| registry.save_action(name, action.as_ref()))
+                            .transpose()?,
+                        current_fed_this_frame: slot.current_fed_this_frame,
+                        contender_action: slot
+                            .contender_action
+                            .as_ref()
+                            .map(|(name, action, being_fed_for)| {
+                                Ok::<_, TnuaPredictionError>((
+                                    registry.save_action(name, action.as_ref())?,
+                                    being_fed_for.clone(),
+                                ))
+                            })
+                            .transpose()?,
+                        contender_fed_this_frame: slot.contender_fed_this_frame,
+                    })
+                })
+                .collect::<Result<_, TnuaPredictionError>>()?,
+            reported_state: TnuaSerializedReportedState {
+                airborne: self.reported_state.airborne,
+                airborne_duration: self.reported_state.airborne_duration,
+                highest_elevation: self.reported_state.highest_elevation,
+                last_velocity: self.reported_state.last_velocity,
+                basis_name: self.reported_state.basis_name.map(str::to_owned),
+                action_name: self.reported_state.action_name.map(str::to_owned),
+            },
+        })
+    }
+
+    /// Restore the state of the controller from a snapshot taken with
+    /// [`prediction_snapshot`](Self::prediction_snapshot).
+    ///
+    /// The configuration of the controller (e.g. action priorities and slot conflicts) is kept.
+    ///
+    /// The conditions of queued actions (see [`queue_action_when`](Self::queue_action_when)) are
+    /// closures and cannot be serialized. A restored queued action that had a condition takes the
+    /// condition of the first action with the same name in the controller's current queue, and
+    /// has no condition if there is no such action.
+    #[cfg(feature = "prediction")]
+    pub fn restore_prediction_snapshot(
+        &mut self,
+        snapshot: &TnuaPredictionSnapshot,
+        registry: &TnuaPredictionRegistry,
+    ) -> Result<(), TnuaPredictionError> {
+        let current_basis = snapshot
+            .basis
+            .as_ref()
+            .map(|entry| registry.load_basis(entry))
+            .transpose()?;
+        let current_action = snapshot
+            .current_action
+            .as_ref()
+            .map(|entry| registry.load_action(entry))
+            .transpose()?;
+        let contender_action = snapshot
+            .contender_action
+            .as_ref()
+            .map(|(entry, being_fed_for)| {
+                let (name, action) = registry.load_action(entry)?;
+                Ok::<_, TnuaPredictionError>((name, action, being_fed_for.clone()))
+            })
+            .transpose()?;
+        let actions_being_fed = snapshot
+            .actions_being_fed
+            .iter()
+            .map(|fed_entry| {
+                Ok((
+                    registry.resolve_name(&fed_entry.name)?,
+                    FedEntry {
+                        fed_this_frame: fed_entry.fed_this_frame,
+                        rescheduled_in: fed_entry.rescheduled_in.clone(),
+                    },
+                ))
+            })
+            .collect::<Result<_, TnuaPredictionError>>()?;
+        let cooldowns = snapshot
+            .cooldowns
+            .iter()
+            .map(|(name, timer)| Ok((registry.resolve_name(name)?, timer.clone())))
+            .collect::<Result<_, TnuaPredictionError>>()?;
+        let action_queue = snapshot
+            .action_queue
+            .iter()
+            .map(|queued_action| {
+                let (name, action) = registry.load_action(&queued_action.action)?;
+                let feed = registry.queued_action_feeder(name, action.as_ref())?;
+                let condition = if queued_action.has_condition {
+                    self.action_queue
+                        .iter()
+                        .find(|old_queued_action| old_queued_action.name == name)
+                        .and_then(|old_queued_action| old_queued_action.condition.clone())
+                } else {
+                    None
+                };
+                Ok(QueuedAction {
+                    name,
+                    action,
+                    feed,
+                    condition,
+                    status: match queued_action.status {
+                        TnuaSerializedQueuedActionStatus::Pending => QueuedActionStatus::Pending,
+                        TnuaSerializedQueuedActionStatus::Fed => QueuedActionStatus::Fed,
+                        TnuaSerializedQueuedActionStatus::Running => QueuedActionStatus::Running,
+                    },
+                })
+            })
+            .collect::<Result<_, TnuaPredictionError>>()?;
+        let action_slots = snapshot
+            .action_slots
+            .iter()
+            .map(|slot| {
+                let slot_name = match self.action_slots.keys().find(|name| **name == slot.name) {
+                    Some(slot_name) => *slot_name,
+                    None => registry.resolve_name(&slot.name)?,
+                };
+                let current_action = slot
+                    .current_action
+                    .as_ref()
+                    .map(|entry| registry.load_action(entry))
+                    .transpose()?;
+                let contender_action = slot
+                    .contender_action
+                    .as_ref()
+                    .map(|(entry, being_fed_for)| {
+                        let (name, action) = registry.load_action(entry)?;
+                        Ok::<_, TnuaPredictionError>((name, action, being_fed_for.clone()))
+                    })
+                    .transpose()?;
+                Ok((slot_name, slot, current_action, contender_action))
+            })
+            .collect::<Result<Vec<_>, TnuaPredictionError>>()?;
+        let resolve_name = |name: &Option<String>| {
+            name.as_deref()
+                .map(|name| registry.resolve_name(name))
+                .transpose()
+        };
+        let reported_state = ReportedControllerState {
+            airborne: snapshot.reported_state.airborne,
+            airborne_duration: snapshot.reported_state.airborne_duration,
+            highest_elevation: snapshot.reported_state.highest_elevation,
+            last_velocity: snapshot.reported_state.last_velocity,
+            basis_name: resolve_name(&snapshot.reported_state.basis_name)?,
+            action_name: resolve_name(&snapshot.reported_state.action_name)?,
+        };
+
+        self.action_flow_status = match &current_action {
+            Some((name, _)) => TnuaActionFlowStatus::ActionOngoing(name),
+            None => TnuaActionFlowStatus::NoAction,
+        };
+        self.reported_state = reported_state;
+        self.current_basis = current_basis;
+        self.current_action = current_action;
+        self.current_action_running_for = snapshot.current_action_running_for.clone();
+        self.contender_action = contender_action;
+        self.contender_buffered_for = snapshot.contender_buffered_for.clone();
+        self.actions_being_fed = actions_being_fed;
+        self.cooldowns = cooldowns;
+        self.action_queue = action_queue;
+        for slot in self.action_slots.values_mut() {
+            slot.current_action = None;
+            slot.current_fed_this_frame = false;
+            slot.contender_action = None;
+            slot.contender_fed_this_frame = false;
+        }
+        for (slot_name, serialized_slot, current_action, contender_action) in action_slots {
+            let slot = self.action_slots.entry(slot_name).or_default();
+            slot.current_action = current_action;
+            slot.current_fed_this_frame = serialized_slot.current_fed_this_frame;
+            slot.contender_action = contender_action;
+            slot.contender_fed_this_frame = serialized_slot.contender_fed_this_frame;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "replay")]
+    pub(crate) fn start_fed_actions_log(&mut self) {
+        self.fed_actions_log.get_or_insert_with(Vec::new);
+    }
+
+    /// Return the actions fed since the last call, if the log was started.
+    #[cfg(feature = "replay")]
+    pub(crate) fn take_fed_actions_log(&mut self) -> Vec<(&'static str, Box<dyn DynamicAction>)> {
+        self.fed_actions_log
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "replay")]
+    pub(crate) fn stop_fed_actions_log(&mut self) {
+        self.fed_actions_log = None;
+    }
+
+    /// What the proximity sensor detected in the last few ticks.
+    pub fn ground_history(&self) -> &TnuaGroundHistory {
+        &self.ground_history
+    }
+
+    /// Use this to change the [capacity](TnuaGroundHistory::set_capacity) of the ground history.
+    pub fn ground_history_mut(&mut self) -> &mut TnuaGroundHistory {
+        &mut self.ground_history
+    }
+
     /// Checks if the character is currently airborne.
     ///
     /// The check is done based on the basis, and is equivalent to getting the controller's
@@ -297,83 +1297,718 @@ impl TnuaController {
 #[error("The Tnua controller does not have any basis set")]
 pub struct TnuaControllerHasNoBasis;
 
-/// The result of [`TnuaController::action_flow_status()`].
-#[derive(Debug, Default, Clone)]
-pub enum TnuaActionFlowStatus {
-    /// No action is going on.
-    #[default]
-    NoAction,
-
-    /// An action just started.
-    ActionStarted(&'static str),
+/// A rolling record of what the proximity sensor detected in the last few ticks.
+///
+/// The controller records the output of the main proximity sensor every tick (before applying the
+/// basis and the actions), so that actions and gameplay code can tell what the character stood on
+/// before it was launched or fell off. Get it with [`TnuaController::ground_history`], or with
+/// [`TnuaActionContext::ground_history`] from inside an action.
+///
+/// Note that the proximity sensor may detect the ground even when the character is not standing on
+/// it (e.g. right after jumping), so check [`proximity`](TnuaGroundHistoryEntry::proximity) if it
+/// matters.
+#[derive(Debug, Clone)]
+pub struct TnuaGroundHistory {
+    entries: VecDeque<Option<TnuaGroundHistoryEntry>>,
+    capacity: usize,
+}
 
-    /// An action was fed in a past frame and is still ongoing.
-    ActionOngoing(&'static str),
+/// The output of the proximity sensor in a tick recorded in [`TnuaGroundHistory`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TnuaGroundHistoryEntry {
+    /// The entity the sensor detected.
+    pub entity: Entity,
 
-    /// An action has stopped being fed.
-    ///
-    /// Note that the action may still have a termination sequence after this happens.
-    ActionEnded(&'static str),
+    /// The normal of the detected surface.
+    pub normal: Dir3,
 
-    /// An action has just been canceled into another action.
-    Cancelled {
-        old: &'static str,
-        new: &'static str,
-    },
+    /// The distance from the sensor's cast origin to the detected surface.
+    pub proximity: Float,
 }
 
-impl TnuaActionFlowStatus {
-    /// The name of the ongoing action, if there is an ongoing action.
-    ///
-    /// Will also return a value if the action has just started.
-    pub fn ongoing(&self) -> Option<&'static str> {
-        match self {
-            TnuaActionFlowStatus::NoAction | TnuaActionFlowStatus::ActionEnded(_) => None,
-            TnuaActionFlowStatus::ActionStarted(action_name)
-            | TnuaActionFlowStatus::ActionOngoing(action_name)
-            | TnuaActionFlowStatus::Cancelled {
-                old: _,
-                new: action_name,
-            } => Some(action_name),
+impl Default for TnuaGroundHistory {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: Self::DEFAULT_CAPACITY,
         }
     }
+}
 
-    /// The name of the action that has just started this frame.
-    ///
-    /// Will return `None` if there is no action, or if the ongoing action has started in a past
-    /// frame.
-    pub fn just_starting(&self) -> Option<&'static str> {
-        match self {
-            TnuaActionFlowStatus::NoAction
-            | TnuaActionFlowStatus::ActionOngoing(_)
-            | TnuaActionFlowStatus::ActionEnded(_) => None,
-            TnuaActionFlowStatus::ActionStarted(action_name)
-            | TnuaActionFlowStatus::Cancelled {
-                old: _,
-                new: action_name,
-            } => Some(action_name),
-        }
+impl TnuaGroundHistory {
+    /// The number of ticks kept by default.
+    pub const DEFAULT_CAPACITY: usize = 16;
+
+    /// Change the number of ticks to keep.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.entries.truncate(capacity);
     }
-}
 
-#[allow(clippy::type_complexity)]
-fn apply_controller_system(
-    time: Res<Time>,
-    mut query: Query<(
-        &mut TnuaController,
-        &TnuaRigidBodyTracker,
-        &mut TnuaProximitySensor,
-        &mut TnuaMotor,
-        Option<&TnuaToggle>,
-    )>,
-) {
-    let frame_duration = time.delta().as_secs_f64() as Float;
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of recorded ticks.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The recorded ticks, from the newest to the oldest. `None` means that the sensor did not
+    /// detect anything in that tick.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = Option<&TnuaGroundHistoryEntry>> {
+        self.entries.iter().map(|entry| entry.as_ref())
+    }
+
+    /// The newest tick in which the sensor detected something, and how many ticks ago it was
+    /// (`0` being the current tick).
+    pub fn last_ground(&self) -> Option<(usize, &TnuaGroundHistoryEntry)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .find_map(|(ticks_ago, entry)| Some((ticks_ago, entry.as_ref()?)))
+    }
+
+    /// Forget all the recorded ticks.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn record(&mut self, sensor: &TnuaProximitySensor) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.truncate(self.capacity - 1);
+        self.entries
+            .push_front(sensor.output.as_ref().map(|output| TnuaGroundHistoryEntry {
+                entity: output.entity,
+                normal: output.normal,
+                proximity: output.proximity,
+            }));
+    }
+}
+
+/// The priority and interruption policy of an action.
+///
+/// Set it with [`TnuaController::with_action_priority`]. The controller uses it to decide which
+/// action wins when multiple actions are fed in the same frame, and whether a newly fed action may
+/// cancel the currently running one.
+#[derive(Debug, Clone, Copy, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaActionPriority {
+    /// Actions with higher levels win over actions with lower levels.
+    ///
+    /// When multiple actions start being fed in the same frame, the one with the highest level
+    /// becomes the contender (on a tie, the last one fed wins). A running action can never be
+    /// cancelled by an action with a lower level.
+    pub level: i32,
+
+    /// Whether this action can cancel a running action with a lower level.
+    pub interrupts_lower: bool,
+
+    /// Whether this action can be cancelled by an action with a higher level.
+    pub interruptible_by_higher: bool,
+
+    /// Time, in seconds, after the action starts during which no other action can cancel it.
+    pub uninterruptible_for: Float,
+}
+
+impl Default for TnuaActionPriority {
+    fn default() -> Self {
+        Self {
+            level: 0,
+            interrupts_lower: true,
+            interruptible_by_higher: true,
+            uninterruptible_for: 0.0,
+        }
+    }
+}
+
+/// An optional pool of a resource (e.g. stamina or energy) that actions spend when they start.
+///
+/// Add this component to a character entity and set the costs of the actions with
+/// [`with_action_cost`](Self::with_action_cost). The controller will refuse to start an action
+/// that costs more than the pool currently has, and will deduct the cost when the action starts.
+/// Actions without a cost are not affected.
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::builtins::TnuaBuiltinDash;
+/// # use bevy_tnua::controller::TnuaResourcePool;
+/// TnuaResourcePool::new(100.0)
+///     .with_action_cost(TnuaBuiltinDash::NAME, 30.0)
+///     .with_action_cost(TnuaBuiltinJump::NAME, 10.0);
+/// ```
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaResourcePool {
+    /// The amount currently available.
+    pub current: Float,
+
+    /// The maximum amount the pool can hold.
+    pub max: Float,
+
+    /// The amount regenerated per second while the character is on the ground.
+    pub ground_regeneration: Float,
+
+    /// The amount regenerated per second while the character is airborne.
+    pub air_regeneration: Float,
+
+    /// Time, in seconds, after spending during which the pool does not regenerate.
+    pub regeneration_delay: Float,
+
+    /// The costs of the actions, keyed by the action's name.
+    pub for_actions: HashMap<&'static str, Float>,
+
+    since_spent: Float,
+}
+
+impl TnuaResourcePool {
+    /// Create a full pool with the given capacity.
+    pub fn new(max: Float) -> Self {
+        Self {
+            current: max,
+            max,
+            ground_regeneration: 0.2 * max,
+            air_regeneration: 0.0,
+            regeneration_delay: 0.5,
+            for_actions: Default::default(),
+            since_spent: Float::INFINITY,
+        }
+    }
+
+    /// Set the cost of starting an action with the given name.
+    pub fn with_action_cost(mut self, action_name: &'static str, cost: Float) -> Self {
+        self.for_actions.insert(action_name, cost);
+        self
+    }
+
+    /// The cost of starting an action with the given name.
+    pub fn cost_of(&self, action_name: &'static str) -> Float {
+        self.for_actions.get(action_name).copied().unwrap_or(0.0)
+    }
+
+    /// Whether the pool has enough for starting an action with the given name.
+    pub fn can_afford(&self, action_name: &'static str) -> bool {
+        self.cost_of(action_name) <= self.current
+    }
+
+    /// Spend an amount from the pool, if it has enough.
+    ///
+    /// Returns `false` (and spends nothing) if there is not enough in the pool. The controller
+    /// does this automatically for actions with costs - use this for things that are not Tnua
+    /// actions (e.g. attacks).
+    pub fn spend(&mut self, amount: Float) -> bool {
+        if self.current < amount {
+            return false;
+        }
+        if 0.0 < amount {
+            self.current -= amount;
+            self.since_spent = 0.0;
+        }
+        true
+    }
+
+    /// The fraction of the pool that is currently available, between 0.0 and 1.0.
+    pub fn fraction(&self) -> Float {
+        if 0.0 < self.max {
+            (self.current / self.max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    fn spend_for(&mut self, action_name: &'static str) {
+        self.spend(self.cost_of(action_name));
+    }
+
+    fn regenerate(&mut self, airborne: bool, frame_duration: Float) {
+        self.since_spent += frame_duration;
+        if self.since_spent < self.regeneration_delay {
+            return;
+        }
+        let rate = if airborne {
+            self.air_regeneration
+        } else {
+            self.ground_regeneration
+        };
+        self.current = (self.current + rate * frame_duration).min(self.max);
+    }
+}
+
+/// A description of what a controller is doing. See [`TnuaController::snapshot`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TnuaControllerSnapshot {
+    /// The name of the current basis.
+    pub basis_name: Option<&'static str>,
+
+    /// Whether the character is on the ground or in the air, according to the basis.
+    pub locomotion: TnuaLocomotionState,
+
+    /// The currently running action, if there is one.
+    pub action: Option<TnuaActionSnapshot>,
+}
+
+/// The part of [`TnuaControllerSnapshot`] that is determined by the basis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaLocomotionState {
+    /// No basis was fed to the controller yet.
+    NoBasis,
+    /// The basis reports that the character is on the ground.
+    Grounded,
+    /// The basis reports that the character is airborne.
+    Airborne,
+}
+
+/// The part of [`TnuaControllerSnapshot`] that describes the current action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TnuaActionSnapshot {
+    /// The name of the action.
+    pub name: &'static str,
+
+    /// The stage the action is at, as reported by [`TnuaAction::stage`].
+    pub stage: TnuaActionStage,
+
+    /// `false` if the action is no longer fed and is only running its termination sequence.
+    pub still_fed: bool,
+}
+
+/// Controls the up direction the controller uses for a character.
+///
+/// By default, Tnua uses the reverse of the gravity direction as the up direction. Add this
+/// component to a character entity to either set a different up direction (e.g. for walking on
+/// walls) or to make the up direction turn gradually when the gravity changes (e.g. for walking
+/// around a spherical planet).
+///
+/// The basis and the actions (float spring, jump, facing rotation) all use the up direction
+/// calculated here, so limiting the turn rate makes all of them re-orient smoothly.
+///
+/// When the up direction needs to flip by 180 degrees in 2D games, it is rotated around the Z axis
+/// so that it stays on the XY plane.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaUpDirection {
+    /// The up direction to use. If `None`, the reverse of the gravity direction will be used.
+    pub target: Option<Dir3>,
+
+    /// The maximum speed, in radians per second, at which the up direction turns toward the
+    /// target.
+    ///
+    /// Set to [`Float::INFINITY`] to make it change immediately.
+    pub max_turn_rate: Float,
+
+    current: Option<Dir3>,
+}
+
+impl Default for TnuaUpDirection {
+    fn default() -> Self {
+        Self {
+            target: None,
+            max_turn_rate: Float::INFINITY,
+            current: None,
+        }
+    }
+}
+
+impl TnuaUpDirection {
+    /// Use a fixed up direction, regardless of gravity.
+    pub fn fixed(direction: Dir3) -> Self {
+        Self {
+            target: Some(direction),
+            ..Default::default()
+        }
+    }
+
+    /// Follow the gravity direction, but turn toward it at a limited rate.
+    pub fn smoothed(max_turn_rate: Float) -> Self {
+        Self {
+            max_turn_rate,
+            ..Default::default()
+        }
+    }
+
+    /// The up direction that was used in the last frame.
+    pub fn current(&self) -> Option<Dir3> {
+        self.current
+    }
+
+    fn update(&mut self, gravity_up: Dir3, frame_duration: Float) -> Dir3 {
+        let target = self.target.unwrap_or(gravity_up);
+        let new_up = match self.current {
+            Some(current) => rotate_direction_toward(
+                current,
+                target,
+                (self.max_turn_rate * frame_duration).f32(),
+            ),
+            None => target,
+        };
+        self.current = Some(new_up);
+        new_up
+    }
+}
+
+fn rotate_direction_toward(current: Dir3, target: Dir3, max_angle: f32) -> Dir3 {
+    let angle = current.angle_between(*target);
+    if angle <= max_angle {
+        return target;
+    }
+    let axis = Dir3::new(current.cross(*target)).unwrap_or_else(|_| {
+        if current.z.abs() < f32::EPSILON {
+            Dir3::Z
+        } else {
+            Dir3::new_unchecked(current.any_orthonormal_vector())
+        }
+    });
+    Quat::from_axis_angle(*axis, max_angle) * current
+}
+
+/// Caps the speed at which the character can fall.
+///
+/// Add this component to a character entity to limit its downward velocity (relative to the up
+/// direction). The cap is enforced on the [`TnuaMotor`] after the basis, the action and the
+/// control helpers that change the motor (like [`TnuaWallSlide`]) have set it, so it applies
+/// regardless of how much extra gravity they add.
+///
+/// Different caps can be set for sliding down walls and for specific actions, so that - for
+/// example - a wall slide or a glide can fall slower than a regular free fall:
+///
+/// ```no_run
+/// # use bevy_tnua::controller::TnuaTerminalVelocity;
+/// TnuaTerminalVelocity::falling(30.0)
+///     .with_wall_sliding_cap(4.0)
+///     .with_action_cap("Glide", 2.0);
+/// ```
+///
+/// Actions can also set their own cap with
+/// [`TnuaAction::terminal_velocity`](crate::TnuaAction::terminal_velocity), which is used even
+/// without this component.
+#[derive(Component, Default, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TnuaTerminalVelocity {
+    /// The maximum downward speed when none of the other caps apply.
+    ///
+    /// `None` means no cap.
+    pub falling: Option<Float>,
+
+    /// The maximum downward speed while the character is in contact with a wall.
+    ///
+    /// Requires the [`TnuaWallSlide`] component, which detects the contact with the wall. When
+    /// `None`, [`falling`](Self::falling) is used while sliding down walls as well.
+    pub wall_sliding: Option<Float>,
+
+    /// Caps for specific actions, keyed by the action's name.
+    ///
+    /// When the current action has an entry here, it is used instead of the action's own
+    /// [`TnuaAction::terminal_velocity`](crate::TnuaAction::terminal_velocity) and of all the
+    /// other caps.
+    pub for_actions: HashMap<&'static str, Float>,
+}
+
+impl TnuaTerminalVelocity {
+    /// Create a terminal velocity that only caps the regular fall speed.
+    pub fn falling(max_speed: Float) -> Self {
+        Self {
+            falling: Some(max_speed),
+            wall_sliding: None,
+            for_actions: Default::default(),
+        }
+    }
+
+    /// Set a different cap for when the character slides down a wall.
+    pub fn with_wall_sliding_cap(mut self, max_speed: Float) -> Self {
+        self.wall_sliding = Some(max_speed);
+        self
+    }
+
+    /// Set a different cap for when an action with the given name is active.
+    pub fn with_action_cap(mut self, action_name: &'static str, max_speed: Float) -> Self {
+        self.for_actions.insert(action_name, max_speed);
+        self
+    }
+
+    /// The cap that applies in the given situation.
+    ///
+    /// `action_cap` is the [`TnuaAction::terminal_velocity`](crate::TnuaAction::terminal_velocity)
+    /// of the current action, and `wall_sliding` is whether the character is in contact with a
+    /// wall.
+    pub fn cap_for(
+        &self,
+        action_name: Option<&'static str>,
+        action_cap: Option<Float>,
+        wall_sliding: bool,
+    ) -> Option<Float> {
+        action_name
+            .and_then(|action_name| self.for_actions.get(action_name).copied())
+            .or(action_cap)
+            .or(self.wall_sliding.filter(|_| wall_sliding))
+            .or(self.falling)
+    }
+}
+
+/// Change the motor so that the velocity it will result in does not exceed the cap.
+///
+/// Gravity is taken into account, since the physics backend will apply it on top of the motor.
+fn enforce_terminal_velocity(
+    max_speed: Float,
+    tracker: &TnuaRigidBodyTracker,
+    up_direction: Dir3,
+    frame_duration: Float,
+    motor: &mut TnuaMotor,
+) {
+    let up = up_direction.adjust_precision();
+    let expected_velocity =
+        tracker.velocity + motor.lin.calc_boost(frame_duration) + tracker.gravity * frame_duration;
+    let downward_speed = -expected_velocity.dot(up);
+    let excess = downward_speed - max_speed;
+    if 0.0 < excess {
+        motor.lin.boost += excess * up;
+    }
+}
+
+/// A moving medium - like wind or a water current - that the character is immersed in.
+///
+/// Add this component to a character entity (or update it from a system that tracks which wind or
+/// current zone the character is in) to make the medium drag the character toward its velocity.
+/// The drag is added to the [`TnuaMotor`] after the basis and the action have set it, so it
+/// affects every basis and action - e.g. wind pushes airborne characters and currents push
+/// swimmers.
+///
+/// Bases can resist the drag by reading it from
+/// [`TnuaBasisContext::external_flow`](crate::TnuaBasisContext::external_flow).
+/// [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk) does so up to its acceleration limit, so
+/// a grounded character holds its position in a mild wind but gets pushed around by a strong one -
+/// and is pushed much more easily while in the air.
+#[derive(Component, Default, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaExternalFlow {
+    /// The velocity of the medium.
+    pub velocity: Vector3,
+
+    /// How strongly the medium drags the character toward its velocity.
+    ///
+    /// The drag acceleration is the difference between the medium's velocity and the character's
+    /// velocity, multiplied by this coefficient.
+    pub coupling: Float,
+}
+
+impl TnuaExternalFlow {
+    /// The acceleration the medium applies to a character moving at the given velocity.
+    ///
+    /// The acceleration is limited so that it will never overshoot the medium's velocity within a
+    /// single frame.
+    pub fn acceleration_on(&self, velocity: Vector3, frame_duration: Float) -> Vector3 {
+        if frame_duration <= 0.0 {
+            return Vector3::ZERO;
+        }
+        let coupling = self.coupling.clamp(0.0, 1.0 / frame_duration);
+        (self.velocity - velocity) * coupling
+    }
+}
+
+/// Root motion fed from an animation system.
+///
+/// Add this component to a character entity to let its animations drive its movement. Each frame,
+/// the animation system should [`feed`](Self::feed) the translation the animation's root bone made
+/// and the animation time it took to make it. The controller converts the fed motion into a
+/// velocity and passes it to the basis via
+/// [`TnuaBasisContext::root_motion`](crate::TnuaBasisContext::root_motion).
+/// [`TnuaBuiltinWalk`](crate::builtins::TnuaBuiltinWalk) uses it instead of its
+/// [`desired_velocity`](crate::builtins::TnuaBuiltinWalk::desired_velocity), so the character
+/// still floats, turns and collides like a regular Tnua character while the animation decides its
+/// speed.
+///
+/// If the controller runs more often than the animation system feeds root motion (e.g. when it
+/// runs in `FixedUpdate`), the last calculated velocity is kept. Use [`clear`](Self::clear) when
+/// the animation no longer provides root motion.
+#[derive(Component, Default, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaRootMotion {
+    pending_translation: Vector3,
+    pending_duration: Float,
+    velocity: Option<Vector3>,
+}
+
+impl TnuaRootMotion {
+    /// Add root motion from the animation.
+    ///
+    /// The `translation` must be in world space - so it should be rotated by the character's
+    /// rotation (and scaled by its scale) if the animation provides it in the character's local
+    /// space. The `duration` is the animation time, in seconds, over which this translation was
+    /// made.
+    pub fn feed(&mut self, translation: Vector3, duration: Float) {
+        self.pending_translation += translation;
+        self.pending_duration += duration;
+    }
+
+    /// Stop driving the character with root motion, until new root motion is fed.
+    pub fn clear(&mut self) {
+        *self = Default::default();
+    }
+
+    /// The velocity calculated from the root motion fed so far.
+    pub fn velocity(&self) -> Option<Vector3> {
+        self.velocity
+    }
+
+    fn consume(&mut self) -> Option<Vector3> {
+        if 0.0 < self.pending_duration {
+            self.velocity = Some(self.pending_translation / self.pending_duration);
+            self.pending_translation = Vector3::ZERO;
+            self.pending_duration = 0.0;
+        }
+        self.velocity
+    }
+}
+
+/// The result of [`TnuaController::action_flow_status()`].
+#[derive(Debug, Default, Clone)]
+pub enum TnuaActionFlowStatus {
+    /// No action is going on.
+    #[default]
+    NoAction,
+
+    /// An action just started.
+    ActionStarted(&'static str),
+
+    /// An action was fed in a past frame and is still ongoing.
+    ActionOngoing(&'static str),
+
+    /// An action has stopped being fed.
+    ///
+    /// Note that the action may still have a termination sequence after this happens.
+    ActionEnded(&'static str),
+
+    /// An action has just been canceled into another action.
+    Cancelled {
+        old: &'static str,
+        new: &'static str,
+    },
+}
+
+impl TnuaActionFlowStatus {
+    /// The name of the ongoing action, if there is an ongoing action.
+    ///
+    /// Will also return a value if the action has just started.
+    pub fn ongoing(&self) -> Option<&'static str> {
+        match self {
+            TnuaActionFlowStatus::NoAction | TnuaActionFlowStatus::ActionEnded(_) => None,
+            TnuaActionFlowStatus::ActionStarted(action_name)
+            | TnuaActionFlowStatus::ActionOngoing(action_name)
+            | TnuaActionFlowStatus::Cancelled {
+                old: _,
+                new: action_name,
+            } => Some(action_name),
+        }
+    }
+
+    /// The name of the action that has just started this frame.
+    ///
+    /// Will return `None` if there is no action, or if the ongoing action has started in a past
+    /// frame.
+    pub fn just_starting(&self) -> Option<&'static str> {
+        match self {
+            TnuaActionFlowStatus::NoAction
+            | TnuaActionFlowStatus::ActionOngoing(_)
+            | TnuaActionFlowStatus::ActionEnded(_) => None,
+            TnuaActionFlowStatus::ActionStarted(action_name)
+            | TnuaActionFlowStatus::Cancelled {
+                old: _,
+                new: action_name,
+            } => Some(action_name),
+        }
+    }
+}
+
+/// The parts of [`TnuaActionContext`] that stay the same throughout a frame of a character.
+#[derive(Clone, Copy)]
+struct ActionContextBase<'a> {
+    frame_duration: Float,
+    tracker: &'a TnuaRigidBodyTracker,
+    proximity_sensor: &'a TnuaProximitySensor,
+    up_direction: Dir3,
+    external_flow: TnuaExternalFlow,
+    root_motion: Option<Vector3>,
+    auxiliary_sensors: Option<&'a TnuaAuxiliarySensors>,
+}
+
+impl<'a> ActionContextBase<'a> {
+    fn make<'b>(
+        &self,
+        basis: &'b dyn DynamicBasis,
+        ground_history: &'b TnuaGroundHistory,
+    ) -> TnuaActionContext<'b>
+    where
+        'a: 'b,
+    {
+        TnuaActionContext {
+            frame_duration: self.frame_duration,
+            tracker: self.tracker,
+            proximity_sensor: self.proximity_sensor,
+            basis,
+            auxiliary_sensors: self.auxiliary_sensors,
+            ground_history,
+            up_direction: self.up_direction,
+            external_flow: self.external_flow,
+            root_motion: self.root_motion,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_controller_system(
+    time: Res<Time>,
+    unfed_tick_policies: Res<TnuaUnfedTickPolicies>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    mut query: Query<(
+        Entity,
+        &mut TnuaController,
+        &TnuaRigidBodyTracker,
+        &mut TnuaProximitySensor,
+        &mut TnuaMotor,
+        Option<&TnuaToggle>,
+        Option<&mut TnuaUpDirection>,
+        Option<&TnuaExternalFlow>,
+        Option<&mut TnuaRootMotion>,
+        Option<&mut TnuaResourcePool>,
+        Option<&TnuaAuxiliarySensors>,
+    )>,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    let unfed_tick_policy = schedule_filter
+        .current_schedule()
+        .and_then(|schedule| unfed_tick_policies.0.get(&schedule))
+        .copied()
+        .unwrap_or_default();
     if frame_duration == 0.0 {
         return;
     }
-    for (mut controller, tracker, mut sensor, mut motor, tnua_toggle) in query.iter_mut() {
-        match tnua_toggle.copied().unwrap_or_default() {
-            TnuaToggle::Disabled => continue,
+    for (
+        entity,
+        mut controller,
+        tracker,
+        mut sensor,
+        mut motor,
+        tnua_toggle,
+        up_direction_control,
+        external_flow,
+        root_motion,
+        mut resource_pool,
+        auxiliary_sensors,
+    ) in query.iter_mut()
+    {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+            TnuaToggle::Disabled | TnuaToggle::Frozen => continue,
             TnuaToggle::SenseOnly => {}
             TnuaToggle::Enabled => {}
         }
@@ -394,8 +2029,26 @@ fn apply_controller_system(
             }
         }
 
+        let repeat_last_input = !std::mem::take(&mut controller.fed_since_last_tick)
+            && unfed_tick_policy == TnuaUnfedTickPolicy::RepeatLastInput;
+
+        controller.feed_queued_actions();
+        controller.current_action_running_for.tick(time.delta());
+        controller
+            .cooldowns
+            .retain(|_, cooldown| !cooldown.tick(time.delta()).finished());
+        let contender_may_interrupt = controller.contender_may_interrupt();
+        controller.ground_history.record(sensor.as_ref());
+
         if let Some((_, basis)) = controller.current_basis.as_mut() {
-            let up_direction = Dir3::new(-tracker.gravity.f32()).unwrap_or(Dir3::Y);
+            let gravity_up_direction = Dir3::new(-tracker.gravity.f32()).unwrap_or(Dir3::Y);
+            let up_direction = if let Some(mut up_direction_control) = up_direction_control {
+                up_direction_control.update(gravity_up_direction, frame_duration)
+            } else {
+                gravity_up_direction
+            };
+            let external_flow = external_flow.copied().unwrap_or_default();
+            let root_motion = root_motion.and_then(|mut root_motion| root_motion.consume());
             let basis = basis.as_mut();
             basis.apply(
                 TnuaBasisContext {
@@ -403,40 +2056,52 @@ fn apply_controller_system(
                     tracker,
                     proximity_sensor: sensor.as_ref(),
                     up_direction,
+                    external_flow,
+                    root_motion,
                 },
                 motor.as_mut(),
             );
             let sensor_cast_range_for_basis = basis.proximity_sensor_cast_range();
 
-            // To streamline TnuaActionContext creation
-            let proximity_sensor = sensor.as_ref();
-
-            let has_valid_contender = if let Some((_, contender_action, being_fed_for)) =
-                &mut controller.contender_action
-            {
-                let initiation_decision = contender_action.initiation_decision(
-                    TnuaActionContext {
-                        frame_duration,
-                        tracker,
-                        proximity_sensor,
-                        basis,
-                        up_direction,
-                    },
-                    being_fed_for,
-                );
-                being_fed_for.tick(time.delta());
-                match initiation_decision {
-                    TnuaActionInitiationDirective::Reject => {
-                        controller.contender_action = None;
-                        false
-                    }
-                    TnuaActionInitiationDirective::Delay => false,
-                    TnuaActionInitiationDirective::Allow => true,
-                }
-            } else {
-                false
+            let action_context_base = ActionContextBase {
+                frame_duration,
+                tracker,
+                proximity_sensor: sensor.as_ref(),
+                up_direction,
+                external_flow,
+                root_motion,
+                auxiliary_sensors,
             };
 
+            let has_valid_contender =
+                if let Some((contender_name, contender_action, being_fed_for)) =
+                    &mut controller.contender_action
+                {
+                    let initiation_decision = if controller.cooldowns.contains_key(contender_name)
+                        || resource_pool
+                            .as_ref()
+                            .is_some_and(|pool| !pool.can_afford(contender_name))
+                    {
+                        TnuaActionInitiationDirective::Reject
+                    } else {
+                        contender_action.initiation_decision(
+                            action_context_base.make(basis, &controller.ground_history),
+                            being_fed_for,
+                        )
+                    };
+                    being_fed_for.tick(time.delta());
+                    match initiation_decision {
+                        TnuaActionInitiationDirective::Reject => {
+                            controller.contender_action = None;
+                            false
+                        }
+                        TnuaActionInitiationDirective::Delay => false,
+                        TnuaActionInitiationDirective::Allow => contender_may_interrupt,
+                    }
+                } else {
+                    false
+                };
+
             if let Some((name, current_action)) = controller.current_action.as_mut() {
                 let lifecycle_status = if has_valid_contender {
                     TnuaActionLifecycleStatus::CancelledInto
@@ -452,13 +2117,7 @@ fn apply_controller_system(
                 };
 
                 let directive = current_action.apply(
-                    TnuaActionContext {
-                        frame_duration,
-                        tracker,
-                        proximity_sensor,
-                        basis,
-                        up_direction,
-                    },
+                    action_context_base.make(basis, &controller.ground_history),
                     lifecycle_status,
                     motor.as_mut(),
                 );
@@ -486,6 +2145,7 @@ fn apply_controller_system(
                     }
                     TnuaActionLifecycleDirective::Finished
                     | TnuaActionLifecycleDirective::Reschedule { .. } => {
+                        start_cooldown(&mut controller.cooldowns, name, current_action.as_ref());
                         if let TnuaActionLifecycleDirective::Reschedule { after_seconds } =
                             directive
                         {
@@ -498,14 +2158,11 @@ fn apply_controller_system(
                             {
                                 contender_fed_entry.rescheduled_in = None;
                             }
+                            if let Some(resource_pool) = resource_pool.as_mut() {
+                                resource_pool.spend_for(contender_name);
+                            }
                             let contender_directive = contender_action.apply(
-                                TnuaActionContext {
-                                    frame_duration,
-                                    tracker,
-                                    proximity_sensor,
-                                    basis,
-                                    up_direction,
-                                },
+                                action_context_base.make(basis, &controller.ground_history),
                                 TnuaActionLifecycleStatus::CancelledFrom,
                                 motor.as_mut(),
                             );
@@ -527,6 +2184,7 @@ fn apply_controller_system(
                                         controller.action_flow_status =
                                             TnuaActionFlowStatus::ActionStarted(contender_name);
                                     }
+                                    controller.current_action_running_for.reset();
                                     Some((contender_name, contender_action))
                                 }
                                 TnuaActionLifecycleDirective::Finished => {
@@ -565,14 +2223,11 @@ fn apply_controller_system(
                     .contender_action
                     .take()
                     .expect("has_valid_contender can only be true if contender_action is Some");
+                if let Some(resource_pool) = resource_pool.as_mut() {
+                    resource_pool.spend_for(contender_name);
+                }
                 contender_action.apply(
-                    TnuaActionContext {
-                        frame_duration,
-                        tracker,
-                        proximity_sensor,
-                        basis,
-                        up_direction,
-                    },
+                    action_context_base.make(basis, &controller.ground_history),
                     TnuaActionLifecycleStatus::Initiated,
                     motor.as_mut(),
                 );
@@ -580,9 +2235,117 @@ fn apply_controller_system(
                     basis.violate_coyote_time();
                 }
                 controller.action_flow_status = TnuaActionFlowStatus::ActionStarted(contender_name);
+                controller.current_action_running_for.reset();
                 controller.current_action = Some((contender_name, contender_action));
             }
 
+            let main_action_name = controller
+                .current_action
+                .as_ref()
+                .map(|(action_name, _)| *action_name);
+            let mut sensor_cast_range_for_slots: Float = 0.0;
+            let slot_names: Vec<&'static str> = controller.action_slots.keys().copied().collect();
+            for slot_name in slot_names {
+                let running_elsewhere: Vec<&'static str> = main_action_name
+                    .into_iter()
+                    .chain(controller.action_slots.iter().filter_map(|(name, slot)| {
+                        if *name == slot_name {
+                            return None;
+                        }
+                        Some(slot.current_action.as_ref()?.0)
+                    }))
+                    .collect();
+                let slot = controller
+                    .action_slots
+                    .get_mut(slot_name)
+                    .expect("slot names were just taken from the map");
+                let blocked = running_elsewhere
+                    .iter()
+                    .any(|name| slot.conflicts_with.contains(name));
+                let preempted =
+                    main_action_name.is_some_and(|name| slot.conflicts_with.contains(name));
+
+                if let Some((name, current_action)) = slot.current_action.as_mut() {
+                    let lifecycle_status = if preempted {
+                        TnuaActionLifecycleStatus::CancelledInto
+                    } else if slot.current_fed_this_frame {
+                        TnuaActionLifecycleStatus::StillFed
+                    } else {
+                        TnuaActionLifecycleStatus::NoLongerFed
+                    };
+                    let directive = current_action.apply(
+                        action_context_base.make(basis, &controller.ground_history),
+                        lifecycle_status,
+                        motor.as_mut(),
+                    );
+                    if current_action.violates_coyote_time() {
+                        basis.violate_coyote_time();
+                    }
+                    if !matches!(directive, TnuaActionLifecycleDirective::StillActive) {
+                        start_cooldown(&mut controller.cooldowns, name, current_action.as_ref());
+                        slot.current_action = None;
+                    }
+                } else if let (false, Some((contender_name, contender_action, being_fed_for))) =
+                    (blocked, slot.contender_action.as_mut())
+                {
+                    let initiation_decision = if controller.cooldowns.contains_key(contender_name)
+                        || resource_pool
+                            .as_ref()
+                            .is_some_and(|pool| !pool.can_afford(contender_name))
+                    {
+                        TnuaActionInitiationDirective::Reject
+                    } else {
+                        contender_action.initiation_decision(
+                            action_context_base.make(basis, &controller.ground_history),
+                            being_fed_for,
+                        )
+                    };
+                    being_fed_for.tick(time.delta());
+                    match initiation_decision {
+                        TnuaActionInitiationDirective::Reject => {
+                            slot.contender_action = None;
+                        }
+                        TnuaActionInitiationDirective::Delay => {}
+                        TnuaActionInitiationDirective::Allow => {
+                            let (contender_name, mut contender_action, _) = slot
+                                .contender_action
+                                .take()
+                                .expect("contender_action was just matched as Some");
+                            if let Some(resource_pool) = resource_pool.as_mut() {
+                                resource_pool.spend_for(contender_name);
+                            }
+                            let directive = contender_action.apply(
+                                action_context_base.make(basis, &controller.ground_history),
+                                TnuaActionLifecycleStatus::Initiated,
+                                motor.as_mut(),
+                            );
+                            if contender_action.violates_coyote_time() {
+                                basis.violate_coyote_time();
+                            }
+                            if matches!(directive, TnuaActionLifecycleDirective::StillActive) {
+                                slot.current_action = Some((contender_name, contender_action));
+                            }
+                        }
+                    }
+                }
+
+                if let Some((_, current_action)) = &slot.current_action {
+                    sensor_cast_range_for_slots = sensor_cast_range_for_slots
+                        .max(current_action.proximity_sensor_cast_range());
+                }
+                if !repeat_last_input {
+                    slot.current_fed_this_frame = false;
+                    if !slot.contender_fed_this_frame {
+                        slot.contender_action = None;
+                    }
+                    slot.contender_fed_this_frame = false;
+                }
+            }
+
+            if let Some(resource_pool) = resource_pool.as_mut() {
+                resource_pool.regenerate(basis.is_airborne(), frame_duration);
+            }
+
             let sensor_case_range_for_action =
                 if let Some((_, current_action)) = &controller.current_action {
                     current_action.proximity_sensor_cast_range()
@@ -590,8 +2353,22 @@ fn apply_controller_system(
                     0.0
                 };
 
-            sensor.cast_range = sensor_cast_range_for_basis.max(sensor_case_range_for_action);
+            sensor.cast_range = sensor_cast_range_for_basis
+                .max(sensor_case_range_for_action)
+                .max(sensor_cast_range_for_slots);
             sensor.cast_direction = -up_direction;
+
+            motor.lin.acceleration +=
+                external_flow.acceleration_on(tracker.velocity, frame_duration);
+        }
+
+        if repeat_last_input {
+            for fed_entry in controller.actions_being_fed.values_mut() {
+                if let Some(rescheduled_in) = &mut fed_entry.rescheduled_in {
+                    rescheduled_in.tick(time.delta());
+                }
+            }
+            continue;
         }
 
         // Cycle actions_being_fed
@@ -609,7 +2386,314 @@ fn apply_controller_system(
 
         if let Some((contender_name, ..)) = controller.contender_action {
             if !controller.actions_being_fed.contains_key(contender_name) {
-                controller.contender_action = None;
+                let buffer_time = controller
+                    .input_buffer_times
+                    .get(contender_name)
+                    .copied()
+                    .unwrap_or(0.0);
+                let buffered_for = controller
+                    .contender_buffered_for
+                    .get_or_insert_with(Stopwatch::new);
+                buffered_for.tick(time.delta());
+                if buffer_time < buffered_for.elapsed_secs().adjust_precision() {
+                    controller.contender_action = None;
+                    controller.contender_buffered_for = None;
+                }
+            }
+        } else {
+            controller.contender_buffered_for = None;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_terminal_velocity_system(
+    time: Res<Time>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+    mut query: Query<(
+        Entity,
+        &TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+        &mut TnuaMotor,
+        Option<&TnuaTerminalVelocity>,
+        Option<&TnuaWallSlide>,
+        Option<&TnuaToggle>,
+    )>,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    if frame_duration == 0.0 {
+        return;
+    }
+    for (
+        entity,
+        controller,
+        tracker,
+        sensor,
+        mut motor,
+        terminal_velocity,
+        wall_slide,
+        tnua_toggle,
+    ) in query.iter_mut()
+    {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+            TnuaToggle::Disabled | TnuaToggle::Frozen => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        if controller.current_basis.is_none() {
+            continue;
+        }
+        let action_cap = controller
+            .current_action
+            .as_ref()
+            .and_then(|(_, action)| action.terminal_velocity());
+        let max_speed = if let Some(terminal_velocity) = terminal_velocity {
+            terminal_velocity.cap_for(
+                controller.action_name(),
+                action_cap,
+                wall_slide.is_some_and(|wall_slide| wall_slide.touching_wall),
+            )
+        } else {
+            action_cap
+        };
+        let Some(max_speed) = max_speed else {
+            continue;
+        };
+        // The controller points the sensor against the up direction it used for this frame.
+        enforce_terminal_velocity(
+            max_speed,
+            tracker,
+            -sensor.cast_direction,
+            frame_duration,
+            motor.as_mut(),
+        );
+    }
+}
+
+fn start_cooldown(
+    cooldowns: &mut HashMap<&'static str, Timer>,
+    action_name: &'static str,
+    action: &dyn DynamicAction,
+) {
+    let cooldown = action.cooldown();
+    if 0.0 < cooldown {
+        cooldowns.insert(
+            action_name,
+            Timer::from_seconds(cooldown.f32(), TimerMode::Once),
+        );
+    }
+}
+
+/// An event sent by [`TnuaControllerPlugin`] when the state of a controller changes.
+///
+/// Use it to trigger sounds, particles and other effects without having to keep track of the
+/// previous state of the controller:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::controller::{TnuaControllerEvent, TnuaControllerEventKind};
+/// fn play_sounds(mut events: EventReader<TnuaControllerEvent>) {
+///     for event in events.read() {
+///         match event.kind {
+///             TnuaControllerEventKind::Landed => {
+///                 // play landing sound for `event.entity`
+///             }
+///             TnuaControllerEventKind::ActionStarted(action_name) => {
+///                 // play a sound according to `action_name`
+///             }
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+#[derive(Event, Debug, Clone)]
+pub struct TnuaControllerEvent {
+    /// The character entity whose controller changed.
+    pub entity: Entity,
+
+    /// What changed.
+    pub kind: TnuaControllerEventKind,
+}
+
+/// The kind of a [`TnuaControllerEvent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TnuaControllerEventKind {
+    /// The character left the ground.
+    BecameAirborne,
+    /// The character touched the ground after being airborne.
+    Landed,
+    /// An action started while no other action was running.
+    ActionStarted(&'static str),
+    /// An action ended without being cancelled by another action.
+    ActionFinished(&'static str),
+    /// An action was cancelled by another action (possibly of the same name).
+    ActionCancelled {
+        old: &'static str,
+        new: &'static str,
+    },
+    /// The basis was replaced by a basis with a different name.
+    BasisSwitched {
+        old: Option<&'static str>,
+        new: &'static str,
+    },
+}
+
+/// An event sent by [`TnuaControllerPlugin`] when a character lands after being airborne.
+///
+/// Use it for fall damage, landing dust effects and heavy landing animations. It is sent together
+/// with the [`Landed`](TnuaControllerEventKind::Landed) [`TnuaControllerEvent`].
+#[derive(Event, Debug, Clone)]
+pub struct TnuaLandedEvent {
+    /// The character entity that landed.
+    pub entity: Entity,
+
+    /// The entity the character landed on, if the proximity sensor detected it.
+    pub ground_entity: Option<Entity>,
+
+    /// The height difference between the highest point of the airborne period and the landing
+    /// point.
+    pub fall_distance: Float,
+
+    /// The downward speed (along the up direction) of the character right before it landed.
+    pub impact_speed: Float,
+
+    /// The time, in seconds, the character was airborne.
+    pub airborne_duration: Float,
+}
+
+fn send_controller_events(
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaController,
+        &TnuaRigidBodyTracker,
+        &TnuaProximitySensor,
+    )>,
+    mut writer: EventWriter<TnuaControllerEvent>,
+    mut landed_writer: EventWriter<TnuaLandedEvent>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    let frame_duration = time.delta().as_secs_f64() as Float;
+    for (entity, mut controller, tracker, sensor) in query.iter_mut() {
+        if !schedule_filter.drives(entity) {
+            continue;
+        }
+        // Avoid triggering change detection when nothing changed.
+        let controller = controller.bypass_change_detection();
+        let mut send = |kind| writer.send(TnuaControllerEvent { entity, kind });
+
+        let basis_name = controller.basis_name();
+        if let Some(new) = basis_name {
+            if controller.reported_state.basis_name != Some(new) {
+                send(TnuaControllerEventKind::BasisSwitched {
+                    old: controller.reported_state.basis_name,
+                    new,
+                });
+            }
+        }
+        controller.reported_state.basis_name = basis_name;
+
+        let airborne = controller.is_airborne().ok();
+        let up = -sensor.cast_direction.adjust_precision();
+        let elevation = tracker.translation.dot(up);
+        let reported_state = &mut controller.reported_state;
+        match (reported_state.airborne, airborne) {
+            (Some(false), Some(true)) => {
+                send(TnuaControllerEventKind::BecameAirborne);
+                reported_state.airborne_duration = 0.0;
+                reported_state.highest_elevation = elevation;
+            }
+            (Some(true), Some(false)) => {
+                send(TnuaControllerEventKind::Landed);
+                landed_writer.send(TnuaLandedEvent {
+                    entity,
+                    ground_entity: sensor.output.as_ref().map(|output| output.entity),
+                    fall_distance: (reported_state.highest_elevation - elevation).max(0.0),
+                    impact_speed: (-reported_state.last_velocity.dot(up)).max(0.0),
+                    airborne_duration: reported_state.airborne_duration,
+                });
+            }
+            _ => {}
+        }
+        if airborne == Some(true) {
+            reported_state.airborne_duration += frame_duration;
+            reported_state.highest_elevation = reported_state.highest_elevation.max(elevation);
+        }
+        reported_state.airborne = airborne;
+        reported_state.last_velocity = tracker.velocity;
+
+        let previous_action = controller.reported_state.action_name;
+        let action_name = controller.action_name();
+        match controller.action_flow_status {
+            TnuaActionFlowStatus::Cancelled { old, new } => {
+                send(TnuaControllerEventKind::ActionCancelled { old, new });
+            }
+            TnuaActionFlowStatus::ActionStarted(new) => {
+                if let Some(old) = previous_action {
+                    send(TnuaControllerEventKind::ActionFinished(old));
+                }
+                send(TnuaControllerEventKind::ActionStarted(new));
+            }
+            _ => {
+                if let (Some(old), None) = (previous_action, action_name) {
+                    send(TnuaControllerEventKind::ActionFinished(old));
+                }
+            }
+        }
+        controller.reported_state.action_name = action_name;
+    }
+}
+
+pub(crate) fn clear_sensor_computed_exclusions(mut query: Query<&mut TnuaSensorQueryFilter>) {
+    for mut query_filter in query.iter_mut() {
+        // Avoid triggering change detection when there is nothing to clear.
+        if !query_filter.computed_exclusions.is_empty() {
+            query_filter.computed_exclusions.clear();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn classify_ground_system(
+    mut query: Query<(
+        Entity,
+        &mut TnuaProximitySensor,
+        Option<&TnuaGroundClassifier>,
+        Option<&TnuaSubservientSensor>,
+        Option<&TnuaToggle>,
+    )>,
+    material_query: Query<&TnuaSurfaceMaterial>,
+    paused: Option<Res<TnuaPaused>>,
+    schedule_filter: TnuaScheduleFilter,
+) {
+    for (entity, mut sensor, classifier, subservient, tnua_toggle) in query.iter_mut() {
+        match TnuaToggle::resolve(tnua_toggle, paused.as_deref()) {
+            TnuaToggle::Disabled | TnuaToggle::Frozen => continue,
+            TnuaToggle::SenseOnly => {}
+            TnuaToggle::Enabled => {}
+        }
+        let owner_entity = subservient.map_or(entity, |subservient| subservient.owner_entity);
+        if !schedule_filter.drives(owner_entity) {
+            continue;
+        }
+        let Some(output) = sensor.output.as_ref() else {
+            continue;
+        };
+        let ground_class = classifier.copied().unwrap_or_default().classify(
+            output.normal,
+            -sensor.cast_direction,
+            material_query.get(output.entity).ok(),
+        );
+        // Only touch the sensor when the class changes, so that sensors the backend did not
+        // update this frame are not marked as changed.
+        if output.ground_class != ground_class {
+            if let Some(output) = sensor.output.as_mut() {
+                output.ground_class = ground_class;
             }
         }
     }
@@ -0,0 +1,141 @@
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::utils::Instant;
+use bevy_tnua_physics_integration_layer::math::Float;
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::{TnuaPaused, TnuaPipelineStages, TnuaSensorCastCounter, TnuaSystemSet, TnuaToggle};
+
+/// A plugin that registers Tnua's diagnostics with Bevy's `DiagnosticsStore`.
+///
+/// The diagnostics can be viewed with any tool that reads the store - e.g. Bevy's
+/// `LogDiagnosticsPlugin`. See the `TnuaDiagnosticsPlugin::*` constants for the recorded
+/// diagnostics.
+///
+/// Must be registered in the same schedule as the rest of the Tnua plugins.
+pub struct TnuaDiagnosticsPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaDiagnosticsPlugin {
+    /// Time spent in [`TnuaPipelineStages::Sensors`], in milliseconds.
+    pub const SENSORS_TIME: DiagnosticPath = DiagnosticPath::const_new("tnua/sensors_time");
+    /// Time spent in [`TnuaPipelineStages::Motors`], in milliseconds.
+    pub const MOTORS_TIME: DiagnosticPath = DiagnosticPath::const_new("tnua/motors_time");
+    /// Number of casts the physics backend does for the proximity sensors in a frame (see
+    /// [`TnuaSensorCastCounter`]).
+    pub const SENSOR_CASTS: DiagnosticPath = DiagnosticPath::const_new("tnua/sensor_casts");
+    /// Number of controllers that have a basis and are not disabled.
+    pub const ACTIVE_CONTROLLERS: DiagnosticPath =
+        DiagnosticPath::const_new("tnua/active_controllers");
+    /// Average distance of the characters walking on the ground from their float height.
+    pub const FLOAT_HEIGHT_ERROR: DiagnosticPath =
+        DiagnosticPath::const_new("tnua/float_height_error");
+
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaDiagnosticsPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl Plugin for TnuaDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(Self::SENSORS_TIME).with_suffix("ms"));
+        app.register_diagnostic(Diagnostic::new(Self::MOTORS_TIME).with_suffix("ms"));
+        app.register_diagnostic(Diagnostic::new(Self::SENSOR_CASTS));
+        app.register_diagnostic(Diagnostic::new(Self::ACTIVE_CONTROLLERS));
+        app.register_diagnostic(Diagnostic::new(Self::FLOAT_HEIGHT_ERROR));
+        app.init_resource::<StageTimings>();
+        app.init_resource::<TnuaSensorCastCounter>();
+        app.add_systems(
+            self.schedule,
+            (
+                start_stage_timer(|timings| &mut timings.sensors)
+                    .before(TnuaPipelineStages::Sensors),
+                finish_stage_timer(|timings| &mut timings.sensors, Self::SENSORS_TIME)
+                    .after(TnuaPipelineStages::Sensors)
+                    .before(TnuaPipelineStages::SubservientSensors),
+                start_stage_timer(|timings| &mut timings.motors)
+                    .after(TnuaPipelineStages::Logic)
+                    .before(TnuaPipelineStages::Motors),
+                finish_stage_timer(|timings| &mut timings.motors, Self::MOTORS_TIME)
+                    .after(TnuaPipelineStages::Motors),
+                record_controller_diagnostics.after(TnuaPipelineStages::Logic),
+            )
+                .in_set(TnuaSystemSet),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+struct StageTimings {
+    sensors: Option<Instant>,
+    motors: Option<Instant>,
+}
+
+fn start_stage_timer(
+    field: fn(&mut StageTimings) -> &mut Option<Instant>,
+) -> impl FnMut(ResMut<StageTimings>) {
+    move |mut timings| {
+        *field(&mut timings) = Some(Instant::now());
+    }
+}
+
+fn finish_stage_timer(
+    field: fn(&mut StageTimings) -> &mut Option<Instant>,
+    path: DiagnosticPath,
+) -> impl FnMut(ResMut<StageTimings>, Diagnostics) {
+    move |mut timings, mut diagnostics| {
+        if let Some(started) = field(&mut timings).take() {
+            diagnostics.add_measurement(&path, || started.elapsed().as_secs_f64() * 1000.0);
+        }
+    }
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn record_controller_diagnostics(
+    mut diagnostics: Diagnostics,
+    mut cast_counter: ResMut<TnuaSensorCastCounter>,
+    controllers_query: Query<(&TnuaController, Option<&TnuaToggle>)>,
+    paused: Option<Res<TnuaPaused>>,
+) {
+    let is_active = |toggle: Option<&TnuaToggle>| {
+        !matches!(
+            TnuaToggle::resolve(toggle, paused.as_deref()),
+            TnuaToggle::Disabled | TnuaToggle::Frozen
+        )
+    };
+    let sensor_casts = cast_counter.take();
+    diagnostics.add_measurement(&TnuaDiagnosticsPlugin::SENSOR_CASTS, || sensor_casts as f64);
+    let active_controllers = || {
+        controllers_query
+            .iter()
+            .filter(|(controller, toggle)| controller.basis_name().is_some() && is_active(*toggle))
+            .map(|(controller, _)| controller)
+    };
+    diagnostics.add_measurement(&TnuaDiagnosticsPlugin::ACTIVE_CONTROLLERS, || {
+        active_controllers().count() as f64
+    });
+    diagnostics.add_measurement(&TnuaDiagnosticsPlugin::FLOAT_HEIGHT_ERROR, || {
+        let (sum, count) = active_controllers()
+            .filter(|controller| !controller.is_airborne().unwrap_or(true))
+            .filter_map(|controller| controller.concrete_basis::<TnuaBuiltinWalk>())
+            .fold((0.0 as Float, 0), |(sum, count), (_, state)| {
+                (sum + state.standing_offset.length(), count + 1)
+            });
+        if count == 0 {
+            0.0
+        } else {
+            (sum / count as Float) as f64
+        }
+    });
+}
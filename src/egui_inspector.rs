@@ -0,0 +1,241 @@
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+#[cfg(feature = "config-asset")]
+use bevy_tnua_physics_integration_layer::math::{float_consts, Float};
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, Vector3};
+
+use crate::builtins::TnuaBuiltinWalk;
+#[cfg(feature = "config-asset")]
+use crate::config_asset::TnuaConfigured;
+use crate::controller::{TnuaController, TnuaLocomotionState};
+use crate::{TnuaMotor, TnuaProximitySensor, TnuaUserControlsSystemSet};
+
+/// A plugin that shows an egui window with the state of a controller.
+///
+/// The window shows the controller of the entity marked with [`TnuaInspected`] (or the first
+/// controller, if no entity is marked): its basis, its actions, the output of its proximity
+/// sensor and its motor.
+///
+/// If the entity has a [`TnuaConfigured`] component (requires the `config-asset` feature), the
+/// window also has sliders for editing the config live. Note that the edits are lost when the
+/// config asset is reloaded.
+///
+/// This plugin does not add `EguiPlugin` - the game must add it.
+pub struct TnuaEguiInspectorPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaEguiInspectorPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaEguiInspectorPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl Plugin for TnuaEguiInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            inspector_window_system.after(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+/// Marks the entity whose controller is shown by [`TnuaEguiInspectorPlugin`].
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct TnuaInspected;
+
+#[cfg(feature = "config-asset")]
+type ConfiguredQuery = Option<&'static mut TnuaConfigured>;
+#[cfg(not(feature = "config-asset"))]
+type ConfiguredQuery = ();
+
+fn format_vector(vector: Vector3) -> String {
+    format!("({:.2}, {:.2}, {:.2})", vector.x, vector.y, vector.z)
+}
+
+#[allow(clippy::type_complexity)]
+#[cfg_attr(not(feature = "config-asset"), allow(unused_variables))]
+fn inspector_window_system(
+    mut egui_contexts: EguiContexts,
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &TnuaController,
+        &TnuaProximitySensor,
+        &TnuaMotor,
+        Has<TnuaInspected>,
+        ConfiguredQuery,
+    )>,
+) {
+    let Some(item) = query
+        .iter_mut()
+        .max_by_key(|(_, _, _, _, inspected, ..)| *inspected)
+    else {
+        return;
+    };
+    let (entity, controller, sensor, motor, _, configured) = item;
+
+    egui::Window::new("Tnua Inspector").show(egui_contexts.ctx_mut(), |ui| {
+        ui.label(format!("Entity: {entity:?}"));
+        ui.label(format!("Frame duration: {:.4}", time.delta_seconds()));
+
+        let snapshot = controller.snapshot();
+        egui::CollapsingHeader::new("Basis")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.label(format!("Name: {}", snapshot.basis_name.unwrap_or("None")));
+                ui.label(format!(
+                    "Locomotion: {}",
+                    match snapshot.locomotion {
+                        TnuaLocomotionState::NoBasis => "No basis",
+                        TnuaLocomotionState::Grounded => "Grounded",
+                        TnuaLocomotionState::Airborne => "Airborne",
+                    }
+                ));
+                if let Some((_, state)) = controller.concrete_basis::<TnuaBuiltinWalk>() {
+                    ui.label(format!(
+                        "Standing offset: {}",
+                        format_vector(state.standing_offset)
+                    ));
+                    ui.label(format!(
+                        "Running velocity: {}",
+                        format_vector(state.running_velocity)
+                    ));
+                    if let Some(coyote_time) = state.coyote_time_remaining() {
+                        ui.label(format!("Coyote time remaining: {coyote_time:.2}"));
+                    }
+                }
+            });
+
+        egui::CollapsingHeader::new("Actions")
+            .default_open(true)
+            .show(ui, |ui| {
+                if let Some(action) = &snapshot.action {
+                    ui.label(format!("Current: {}", action.name));
+                    match action.stage.progress {
+                        Some(progress) => ui.label(format!(
+                            "Stage: {} ({:.0}%)",
+                            action.stage.name,
+                            progress * 100.0
+                        )),
+                        None => ui.label(format!("Stage: {}", action.stage.name)),
+                    };
+                    ui.label(format!("Still fed: {}", action.still_fed));
+                    let cooldown = controller.named_cooldown_remaining(action.name);
+                    if 0.0 < cooldown {
+                        ui.label(format!("Cooldown remaining: {cooldown:.2}"));
+                    }
+                } else {
+                    ui.label("Current: None");
+                }
+                let queued = controller.queued_actions().collect::<Vec<_>>();
+                if !queued.is_empty() {
+                    ui.label(format!("Queued: {}", queued.join(", ")));
+                }
+            });
+
+        egui::CollapsingHeader::new("Proximity Sensor")
+            .default_open(true)
+            .show(ui, |ui| {
+                if let Some(output) = &sensor.output {
+                    ui.label(format!("Entity: {:?}", output.entity));
+                    ui.label(format!("Proximity: {:.3}", output.proximity));
+                    ui.label(format!(
+                        "Normal: {}",
+                        format_vector(output.normal.adjust_precision())
+                    ));
+                    ui.label(format!(
+                        "Entity velocity: {}",
+                        format_vector(output.entity_linvel)
+                    ));
+                } else {
+                    ui.label("Nothing detected");
+                }
+            });
+
+        egui::CollapsingHeader::new("Motor")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.label(format!(
+                    "Linear acceleration: {}",
+                    format_vector(motor.lin.acceleration)
+                ));
+                ui.label(format!("Linear boost: {}", format_vector(motor.lin.boost)));
+                ui.label(format!(
+                    "Angular acceleration: {}",
+                    format_vector(motor.ang.acceleration)
+                ));
+                ui.label(format!("Angular boost: {}", format_vector(motor.ang.boost)));
+            });
+
+        #[cfg(feature = "config-asset")]
+        if let Some(mut configured) = configured {
+            if let Some(config) = configured.config_mut() {
+                egui::CollapsingHeader::new("Config").show(ui, |ui| {
+                    let walk = config.walk.get_or_insert_with(Default::default);
+                    ui.add(
+                        egui::Slider::new(&mut walk.float_height, 0.0..=10.0).text("Float Height"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut walk.cling_distance, 0.0..=10.0)
+                            .text("Cling Distance"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut walk.spring_strengh, 0.0..=4000.0)
+                            .text("Spring Strengh"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut walk.spring_dampening, 0.0..=1.9)
+                            .text("Spring Dampening"),
+                    );
+                    finite_slider(ui, &mut walk.acceleration, 0.0..=200.0, "Acceleration");
+                    finite_slider(
+                        ui,
+                        &mut walk.air_acceleration,
+                        0.0..=200.0,
+                        "Air Acceleration",
+                    );
+                    ui.add(egui::Slider::new(&mut walk.coyote_time, 0.0..=1.0).text("Coyote Time"));
+                    ui.add(
+                        egui::Slider::new(&mut walk.max_slope, 0.0..=float_consts::FRAC_PI_2)
+                            .text("Max Slope (in radians)"),
+                    );
+                    let jump = config.jump.get_or_insert_with(Default::default);
+                    ui.add(egui::Slider::new(&mut jump.height, 0.0..=10.0).text("Jump Height"));
+                    ui.add(
+                        egui::Slider::new(&mut jump.fall_extra_gravity, 0.0..=50.0)
+                            .text("Jump Fall Extra Gravity"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut jump.shorten_extra_gravity, 0.0..=100.0)
+                            .text("Jump Shorten Extra Gravity"),
+                    );
+                });
+            }
+        }
+    });
+}
+
+/// A slider for a value that may be infinite. Infinite values are not editable.
+#[cfg(feature = "config-asset")]
+fn finite_slider(
+    ui: &mut egui::Ui,
+    value: &mut Float,
+    range: std::ops::RangeInclusive<Float>,
+    caption: &str,
+) {
+    if value.is_finite() {
+        ui.add(egui::Slider::new(value, range).text(caption));
+    } else {
+        ui.label(format!("{caption}: \u{221e}"));
+    }
+}
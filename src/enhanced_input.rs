@@ -0,0 +1,191 @@
+//! Feeding the controller from [bevy_enhanced_input](bevy_enhanced_input).
+//!
+//! Add [`TnuaEnhancedInputPlugin`] for the input context component, and a
+//! [`TnuaEnhancedInputBindings`] component next to the context and the [`TnuaController`] of the
+//! character:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_enhanced_input::prelude::*;
+//! # use bevy_tnua::prelude::*;
+//! # use bevy_tnua::builtins::TnuaBuiltinDash;
+//! # use bevy_tnua::enhanced_input::{TnuaEnhancedInputBindings, TnuaEnhancedInputPlugin};
+//! # use bevy_tnua::input_bindings::TnuaInputTrigger;
+//! # use bevy_tnua::math::Vector3;
+//! #[derive(Debug, InputAction)]
+//! #[input_action(output = Vec2)]
+//! struct Move;
+//!
+//! #[derive(Debug, InputAction)]
+//! #[input_action(output = bool)]
+//! struct Jump;
+//!
+//! #[derive(Debug, InputAction)]
+//! #[input_action(output = bool)]
+//! struct Dash;
+//!
+//! #[derive(Component)]
+//! struct OnFoot;
+//!
+//! impl InputContext for OnFoot {
+//!     fn context_instance(_world: &World, _entity: Entity) -> ContextInstance {
+//!         let mut ctx = ContextInstance::default();
+//!         ctx.bind::<Move>()
+//!             .to((GamepadStick::Left, Cardinal::wasd_keys()));
+//!         ctx.bind::<Jump>()
+//!             .to((KeyCode::Space, GamepadButtonType::South));
+//!         ctx.bind::<Dash>()
+//!             .to((KeyCode::ShiftLeft, GamepadButtonType::East));
+//!         ctx
+//!     }
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_input_context::<OnFoot>();
+//! app.add_plugins(TnuaEnhancedInputPlugin::<OnFoot>::default());
+//!
+//! fn spawn_player(mut commands: Commands) {
+//!     commands.spawn((
+//!         // ... the physics and the Tnua components ...
+//!         OnFoot,
+//!         TnuaEnhancedInputBindings::<OnFoot>::new(
+//!             TnuaBuiltinWalk {
+//!                 float_height: 1.5,
+//!                 ..Default::default()
+//!             },
+//!             10.0,
+//!         )
+//!         .with_movement::<Move>()
+//!         .with_action::<Jump>(
+//!             TnuaInputTrigger::Hold,
+//!             TnuaBuiltinJump {
+//!                 height: 4.0,
+//!                 ..Default::default()
+//!             },
+//!         )
+//!         .with_action::<Dash>(
+//!             TnuaInputTrigger::Press,
+//!             TnuaBuiltinDash {
+//!                 displacement: 10.0 * Vector3::NEG_Z,
+//!                 ..Default::default()
+//!             },
+//!         ),
+//!     ));
+//! }
+//! ```
+use std::marker::PhantomData;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use bevy_tnua_physics_integration_layer::math::AdjustPrecision;
+
+use crate::controller::TnuaController;
+use crate::input_bindings::{TnuaInputBindings, TnuaInputSource, TnuaInputTrigger};
+use crate::{TnuaAction, TnuaUserControlsSystemSet};
+
+/// A plugin that feeds the [`TnuaController`] of every entity with a
+/// [`TnuaEnhancedInputBindings`] component, based on the state of its `C` input context.
+///
+/// The input context must be registered separately, with `add_input_context`.
+pub struct TnuaEnhancedInputPlugin<C> {
+    schedule: InternedScheduleLabel,
+    _phantom: PhantomData<fn() -> C>,
+}
+
+impl<C> TnuaEnhancedInputPlugin<C> {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C> Default for TnuaEnhancedInputPlugin<C> {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl<C: InputContext> Plugin for TnuaEnhancedInputPlugin<C> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            feed_controller_from_enhanced_input::<C>.in_set(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+type ActionDataGetter = for<'a> fn(&'a ContextInstance) -> Option<&'a ActionData>;
+
+/// The [`TnuaInputSource`] of bevy_enhanced_input, where the inputs are the input actions of the
+/// `C` input context.
+pub struct TnuaEnhancedInput<C>(PhantomData<fn() -> C>);
+
+impl<C: InputContext> TnuaInputSource for TnuaEnhancedInput<C> {
+    type Input = ActionDataGetter;
+}
+
+/// Declares how the input actions of the `C` input context feed the [`TnuaController`] on the
+/// same entity. Fed by [`TnuaEnhancedInputPlugin`].
+///
+/// An input is considered active while its input action is fired.
+///
+/// See [`TnuaInputBindings`].
+pub type TnuaEnhancedInputBindings<C> = TnuaInputBindings<TnuaEnhancedInput<C>>;
+
+impl<C: InputContext> TnuaEnhancedInputBindings<C> {
+    /// Set the input action that moves the character. Its value is read as a 2D axis.
+    ///
+    /// The length of the value is clamped to 1, so a stick that is not fully tilted moves the
+    /// character slower.
+    pub fn with_movement<A: InputAction>(mut self) -> Self {
+        self.set_movement_input(ContextInstance::action::<A>);
+        self
+    }
+
+    /// Bind an input action to a Tnua action.
+    ///
+    /// With [`TnuaInputTrigger::Hold`], the magnitude of the input action's value is passed as
+    /// the strength of the action.
+    pub fn with_action<A: InputAction>(
+        mut self,
+        trigger: TnuaInputTrigger,
+        action: impl TnuaAction,
+    ) -> Self {
+        self.add_action_input(ContextInstance::action::<A>, trigger, action);
+        self
+    }
+}
+
+fn feed_controller_from_enhanced_input<C: InputContext>(
+    instances: Res<ContextInstances>,
+    mut query: Query<(
+        Entity,
+        &mut TnuaEnhancedInputBindings<C>,
+        &mut TnuaController,
+    )>,
+) {
+    for (entity, mut bindings, mut controller) in query.iter_mut() {
+        let Some(ctx) = instances.get::<C>(entity) else {
+            continue;
+        };
+        let fired = |input: &ActionDataGetter| {
+            input(ctx).filter(|action_data| action_data.state() == ActionState::Fired)
+        };
+        bindings.feed(
+            controller.as_mut(),
+            |movement| Some(fired(movement)?.value().as_axis2d().adjust_precision()),
+            |input| {
+                Some(
+                    fired(input)?
+                        .value()
+                        .as_axis3d()
+                        .length()
+                        .adjust_precision(),
+                )
+            },
+        );
+    }
+}
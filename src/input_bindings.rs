@@ -0,0 +1,193 @@
+//! The part of the input library integrations that does not depend on the input library.
+//!
+//! [`TnuaInputBindings`] holds the basis to feed and the inputs bound to the movement and to the
+//! actions. Each input library integration provides a [`TnuaInputSource`] that decides what
+//! identifies an input, a plugin that reads the inputs, and methods for binding them:
+//!
+//! * The `leafwing` feature - the `leafwing` module, for leafwing-input-manager.
+//! * The `enhanced-input` feature - the `enhanced_input` module, for bevy_enhanced_input.
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::{AsF32, Float, Vector2, Vector3};
+
+use crate::builtins::TnuaBuiltinWalk;
+use crate::controller::TnuaController;
+use crate::TnuaAction;
+
+/// An input library that [`TnuaInputBindings`] can read the inputs from.
+pub trait TnuaInputSource: 'static {
+    /// What identifies an input of the library.
+    type Input: 'static + Send + Sync;
+}
+
+/// When to feed an action bound to an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaInputTrigger {
+    /// Feed the action every frame the input is active.
+    ///
+    /// Releasing the input stops feeding the action - which is what makes the height of
+    /// [`TnuaBuiltinJump`](crate::builtins::TnuaBuiltinJump) depend on how long the button is
+    /// held, and what makes [`TnuaBuiltinCrouch`](crate::builtins::TnuaBuiltinCrouch) stand up.
+    ///
+    /// The value of the input is passed as the strength of the action (see
+    /// [`TnuaController::action_with_strength`]), so analog triggers can control it.
+    Hold,
+
+    /// [Queue](TnuaController::queue_action) the action on the frame the input becomes active, so
+    /// that it runs to its end even if the input is released right away.
+    Press,
+}
+
+type ActionFeeder = Box<dyn Send + Sync + Fn(&mut TnuaController, Float)>;
+
+struct ActionBinding<I> {
+    input: I,
+    trigger: TnuaInputTrigger,
+    feed: ActionFeeder,
+    was_active: bool,
+}
+
+/// Declares how the inputs of an input library feed the [`TnuaController`] on the same entity.
+///
+/// Every frame, the plugin of the input library feeds the walk basis given to [`new`](Self::new),
+/// with its [`desired_velocity`](TnuaBuiltinWalk::desired_velocity) (and optionally its
+/// [`desired_forward`](TnuaBuiltinWalk::desired_forward)) set from the movement input, and feeds
+/// the bound actions according to their inputs.
+///
+/// The methods for binding the inputs are provided by the integration of the input library.
+#[derive(Component)]
+pub struct TnuaInputBindings<S: TnuaInputSource> {
+    basis: TnuaBuiltinWalk,
+    speed: Float,
+    movement: Option<S::Input>,
+    movement_axes: [Vector3; 2],
+    face_movement: bool,
+    actions: Vec<ActionBinding<S::Input>>,
+    _phantom: PhantomData<fn() -> S>,
+}
+
+impl<S: TnuaInputSource> TnuaInputBindings<S> {
+    /// Create bindings that feed `basis`, moving the character at up to `speed`.
+    pub fn new(basis: TnuaBuiltinWalk, speed: Float) -> Self {
+        Self {
+            basis,
+            speed,
+            movement: None,
+            movement_axes: [Vector3::X, Vector3::NEG_Z],
+            face_movement: true,
+            actions: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Set the world directions the `X` and `Y` axes of the movement input move the character at.
+    ///
+    /// The default is `X` for `X` and `-Z` for `Y` (up on the stick moves the character away from
+    /// a default camera). For 2D platformers, use `Vector3::ZERO` as the direction of `Y`.
+    ///
+    /// To move relative to a camera, update the axes every frame with
+    /// [`set_movement_axes`](Self::set_movement_axes) (e.g. with the results of
+    /// [`TnuaCameraRelativeInput`](crate::util::TnuaCameraRelativeInput)).
+    pub fn with_movement_axes(mut self, x: Vector3, y: Vector3) -> Self {
+        self.set_movement_axes(x, y);
+        self
+    }
+
+    /// See [`with_movement_axes`](Self::with_movement_axes).
+    pub fn set_movement_axes(&mut self, x: Vector3, y: Vector3) {
+        self.movement_axes = [x, y];
+    }
+
+    /// Set whether the character should turn to face the direction it moves at. Defaults to
+    /// `true`.
+    pub fn facing_movement(mut self, face_movement: bool) -> Self {
+        self.face_movement = face_movement;
+        self
+    }
+
+    /// Mutable access to the basis that gets fed, for changing its parameters at runtime.
+    pub fn basis_mut(&mut self) -> &mut TnuaBuiltinWalk {
+        &mut self.basis
+    }
+
+    /// Set the maximum speed the movement input moves the character at.
+    pub fn set_speed(&mut self, speed: Float) {
+        self.speed = speed;
+    }
+
+    pub(crate) fn set_movement_input(&mut self, input: S::Input) {
+        self.movement = Some(input);
+    }
+
+    pub(crate) fn add_action_input(
+        &mut self,
+        input: S::Input,
+        trigger: TnuaInputTrigger,
+        action: impl TnuaAction,
+    ) {
+        let feed: ActionFeeder = match trigger {
+            TnuaInputTrigger::Hold => Box::new(move |controller, strength| {
+                controller.action_with_strength(action.clone(), strength);
+            }),
+            TnuaInputTrigger::Press => Box::new(move |controller, _| {
+                controller.queue_action(action.clone());
+            }),
+        };
+        self.actions.push(ActionBinding {
+            input,
+            trigger,
+            feed,
+            was_active: false,
+        });
+    }
+
+    /// Feed the controller.
+    ///
+    /// `movement` reads the movement input as a 2D axis, and `action_value` reads the value of an
+    /// action input - `None` when the input is not active.
+    pub(crate) fn feed(
+        &mut self,
+        controller: &mut TnuaController,
+        movement: impl FnOnce(&S::Input) -> Option<Vector2>,
+        mut action_value: impl FnMut(&S::Input) -> Option<Float>,
+    ) {
+        let direction = if let Some(input) = self.movement.as_ref().and_then(movement) {
+            let input = input.clamp_length_max(1.0);
+            let [x_axis, y_axis] = self.movement_axes;
+            input.x * x_axis + input.y * y_axis
+        } else {
+            Vector3::ZERO
+        };
+        controller.basis(TnuaBuiltinWalk {
+            desired_velocity: self.speed * direction,
+            desired_forward: if self.face_movement {
+                Dir3::new(direction.f32()).ok()
+            } else {
+                self.basis.desired_forward
+            },
+            ..self.basis.clone()
+        });
+
+        for binding in self.actions.iter_mut() {
+            let value = action_value(&binding.input);
+            // Input libraries differ in whether they can tell that an input just became active,
+            // so track it here.
+            let just_activated = value.is_some() && !binding.was_active;
+            binding.was_active = value.is_some();
+            match binding.trigger {
+                TnuaInputTrigger::Hold => {
+                    if let Some(value) = value {
+                        let strength = if 0.0 < value { value.min(1.0) } else { 1.0 };
+                        (binding.feed)(controller, strength);
+                    }
+                }
+                TnuaInputTrigger::Press => {
+                    if just_activated {
+                        (binding.feed)(controller, 1.0);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Driving controllers from inputs received over the network.
+//!
+//! A [`TnuaInputCommand`] is everything that was fed into a controller in one tick - the basis and
+//! the actions - in a serializable form. Clients create commands instead of (or in addition to)
+//! feeding their controller directly, and send them to the server. The server pushes the
+//! commands it receives into the [`TnuaInputCommandBuffer`] of the player's entity, and
+//! [`TnuaInputCommandPlugin`] feeds them into the controller one command per tick.
+use std::collections::VecDeque;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::controller::TnuaController;
+use crate::prediction::{TnuaPredictionError, TnuaPredictionRegistry, TnuaSerializedEntry};
+use crate::{TnuaAction, TnuaBasis, TnuaUserControlsSystemSet};
+
+/// A plugin required for making [`TnuaInputCommandBuffer`] work.
+///
+/// Must be registered in the same schedule as the rest of the Tnua plugins - usually the fixed
+/// schedule the physics runs in, so that exactly one command is consumed per physics tick.
+pub struct TnuaInputCommandPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaInputCommandPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaInputCommandPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl Plugin for TnuaInputCommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TnuaPredictionRegistry>();
+        app.add_systems(
+            self.schedule,
+            apply_input_commands.in_set(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+/// The basis and the actions fed into a controller in a single tick.
+///
+/// The types of the basis and the actions must be registered in the [`TnuaPredictionRegistry`].
+///
+/// ```no_run
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::input_command::TnuaInputCommand;
+/// # use bevy_tnua::prediction::TnuaPredictionRegistry;
+/// # use bevy_tnua::math::Vector3;
+/// # let registry = TnuaPredictionRegistry::default();
+/// # let (tick, jump_pressed) = (0, true);
+/// let mut command = TnuaInputCommand::new(tick);
+/// command.basis(&registry, &TnuaBuiltinWalk {
+///     desired_velocity: Vector3::X,
+///     float_height: 1.5,
+///     ..Default::default()
+/// }).unwrap();
+/// if jump_pressed {
+///     command.action(&registry, &TnuaBuiltinJump {
+///         height: 4.0,
+///         ..Default::default()
+///     }).unwrap();
+/// }
+/// // Send `command` to the server
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TnuaInputCommand {
+    /// The tick the command was created for. Commands are consumed in the order of their ticks.
+    pub tick: u64,
+    pub basis: Option<TnuaSerializedEntry>,
+    pub actions: Vec<TnuaSerializedEntry>,
+}
+
+impl TnuaInputCommand {
+    /// An empty command for the given tick.
+    pub fn new(tick: u64) -> Self {
+        Self {
+            tick,
+            basis: None,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Set the basis, with [its default name](TnuaBasis::NAME).
+    pub fn basis<B: TnuaBasis + Serialize>(
+        &mut self,
+        registry: &TnuaPredictionRegistry,
+        basis: &B,
+    ) -> Result<&mut Self, TnuaPredictionError> {
+        self.named_basis(registry, B::NAME, basis)
+    }
+
+    /// Set the basis with a custom name. The name must be registered in the registry.
+    pub fn named_basis<B: TnuaBasis + Serialize>(
+        &mut self,
+        registry: &TnuaPredictionRegistry,
+        name: &'static str,
+        basis: &B,
+    ) -> Result<&mut Self, TnuaPredictionError> {
+        self.basis = Some(registry.serialize_basis_input(name, basis)?);
+        Ok(self)
+    }
+
+    /// Add an action, with [its default name](TnuaAction::NAME).
+    pub fn action<A: TnuaAction + Serialize>(
+        &mut self,
+        registry: &TnuaPredictionRegistry,
+        action: &A,
+    ) -> Result<&mut Self, TnuaPredictionError> {
+        self.named_action(registry, A::NAME, action)
+    }
+
+    /// Add an action with a custom name. The name must be registered in the registry.
+    pub fn named_action<A: TnuaAction + Serialize>(
+        &mut self,
+        registry: &TnuaPredictionRegistry,
+        name: &'static str,
+        action: &A,
+    ) -> Result<&mut Self, TnuaPredictionError> {
+        self.actions
+            .push(registry.serialize_action_input(name, action)?);
+        Ok(self)
+    }
+
+    /// Feed the basis and the actions of the command into a controller.
+    pub fn apply(
+        &self,
+        registry: &TnuaPredictionRegistry,
+        controller: &mut TnuaController,
+    ) -> Result<(), TnuaPredictionError> {
+        self.apply_basis(registry, controller)?;
+        for action in self.actions.iter() {
+            registry.feed_action(action, controller)?;
+        }
+        Ok(())
+    }
+
+    /// Feed only the basis of the command into a controller.
+    pub fn apply_basis(
+        &self,
+        registry: &TnuaPredictionRegistry,
+        controller: &mut TnuaController,
+    ) -> Result<(), TnuaPredictionError> {
+        if let Some(basis) = &self.basis {
+            registry.feed_basis(basis, controller)?;
+        }
+        Ok(())
+    }
+}
+
+/// Queues [`TnuaInputCommand`]s for a character, to be fed into its controller one per tick.
+///
+/// Without [`TnuaInputCommandPlugin`] this component will not do anything.
+#[derive(Component, Debug, Clone)]
+pub struct TnuaInputCommandBuffer {
+    commands: VecDeque<TnuaInputCommand>,
+    last_applied: Option<TnuaInputCommand>,
+    /// The maximum number of commands to keep. When more commands are pushed, the oldest ones are
+    /// dropped.
+    pub max_len: usize,
+    /// Whether to apply the basis of the last command again when there is no new command - so
+    /// that a late or lost packet does not make the character stop.
+    ///
+    /// The actions of the last command are not repeated, because feeding them again could start
+    /// them again (e.g. make the character jump twice).
+    pub repeat_last_when_empty: bool,
+}
+
+impl Default for TnuaInputCommandBuffer {
+    fn default() -> Self {
+        Self {
+            commands: VecDeque::new(),
+            last_applied: None,
+            max_len: 32,
+            repeat_last_when_empty: true,
+        }
+    }
+}
+
+impl TnuaInputCommandBuffer {
+    /// Add a command to the buffer.
+    ///
+    /// Commands are kept sorted by their tick. Commands for ticks that were already applied are
+    /// ignored, and a command for a tick that is already in the buffer replaces it.
+    pub fn push(&mut self, command: TnuaInputCommand) {
+        if let Some(last_applied) = &self.last_applied {
+            if command.tick <= last_applied.tick {
+                return;
+            }
+        }
+        match self
+            .commands
+            .binary_search_by_key(&command.tick, |existing| existing.tick)
+        {
+            Ok(index) => self.commands[index] = command,
+            Err(index) => self.commands.insert(index, command),
+        }
+        while self.max_len < self.commands.len() {
+            self.commands.pop_front();
+        }
+    }
+
+    /// The number of commands waiting to be applied.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether there are no commands waiting to be applied.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// The tick of the last command that was applied to the controller.
+    pub fn last_applied_tick(&self) -> Option<u64> {
+        Some(self.last_applied.as_ref()?.tick)
+    }
+
+    /// The command to apply in this tick, and whether its actions should be applied too.
+    fn next_command(&mut self) -> Option<(&TnuaInputCommand, bool)> {
+        if let Some(command) = self.commands.pop_front() {
+            Some((self.last_applied.insert(command), true))
+        } else if self.repeat_last_when_empty {
+            Some((self.last_applied.as_ref()?, false))
+        } else {
+            None
+        }
+    }
+}
+
+fn apply_input_commands(
+    registry: Res<TnuaPredictionRegistry>,
+    mut query: Query<(&mut TnuaInputCommandBuffer, &mut TnuaController)>,
+) {
+    for (mut buffer, mut controller) in query.iter_mut() {
+        let Some((command, with_actions)) = buffer.next_command() else {
+            continue;
+        };
+        let result = if with_actions {
+            command.apply(&registry, controller.as_mut())
+        } else {
+            command.apply_basis(&registry, controller.as_mut())
+        };
+        if let Err(err) = result {
+            error!("Cannot apply input command: {err}");
+        }
+    }
+}
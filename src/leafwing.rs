@@ -0,0 +1,160 @@
+//! Feeding the controller from [leafwing-input-manager](leafwing_input_manager).
+//!
+//! Add [`TnuaLeafwingPlugin`] for the game's `Actionlike` type, and a [`TnuaLeafwingBindings`]
+//! component next to the `ActionState` and the [`TnuaController`] of the character:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_tnua::prelude::*;
+//! # use bevy_tnua::builtins::{TnuaBuiltinCrouch, TnuaBuiltinDash};
+//! # use bevy_tnua::input_bindings::TnuaInputTrigger;
+//! # use bevy_tnua::leafwing::{TnuaLeafwingBindings, TnuaLeafwingPlugin};
+//! # use bevy_tnua::math::Vector3;
+//! # use leafwing_input_manager::prelude::*;
+//! #[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+//! enum PlayerAction {
+//!     #[actionlike(DualAxis)]
+//!     Move,
+//!     Jump,
+//!     Crouch,
+//!     Dash,
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugins(TnuaLeafwingPlugin::<PlayerAction>::default());
+//!
+//! fn spawn_player(mut commands: Commands) {
+//!     commands.spawn((
+//!         // ... the physics and the Tnua components, and the `InputManagerBundle` ...
+//!         TnuaLeafwingBindings::new(
+//!             TnuaBuiltinWalk {
+//!                 float_height: 1.5,
+//!                 ..Default::default()
+//!             },
+//!             10.0,
+//!         )
+//!         .with_movement(PlayerAction::Move)
+//!         .with_action(
+//!             PlayerAction::Jump,
+//!             TnuaInputTrigger::Hold,
+//!             TnuaBuiltinJump {
+//!                 height: 4.0,
+//!                 ..Default::default()
+//!             },
+//!         )
+//!         .with_action(
+//!             PlayerAction::Crouch,
+//!             TnuaInputTrigger::Hold,
+//!             TnuaBuiltinCrouch {
+//!                 float_offset: -0.9,
+//!                 ..Default::default()
+//!             },
+//!         )
+//!         .with_action(
+//!             PlayerAction::Dash,
+//!             TnuaInputTrigger::Press,
+//!             TnuaBuiltinDash {
+//!                 displacement: 10.0 * Vector3::NEG_Z,
+//!                 ..Default::default()
+//!             },
+//!         ),
+//!     ));
+//! }
+//! ```
+use std::marker::PhantomData;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy_tnua_physics_integration_layer::math::AdjustPrecision;
+use leafwing_input_manager::prelude::*;
+
+use crate::controller::TnuaController;
+use crate::input_bindings::{TnuaInputBindings, TnuaInputSource, TnuaInputTrigger};
+use crate::{TnuaAction, TnuaUserControlsSystemSet};
+
+/// A plugin that feeds the [`TnuaController`] of every entity with a [`TnuaLeafwingBindings`]
+/// component, based on its `ActionState`.
+pub struct TnuaLeafwingPlugin<A> {
+    schedule: InternedScheduleLabel,
+    _phantom: PhantomData<fn() -> A>,
+}
+
+impl<A> TnuaLeafwingPlugin<A> {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<A> Default for TnuaLeafwingPlugin<A> {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl<A: Actionlike> Plugin for TnuaLeafwingPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            feed_controller_from_leafwing::<A>.in_set(TnuaUserControlsSystemSet),
+        );
+    }
+}
+
+/// The [`TnuaInputSource`] of leafwing-input-manager, where the inputs are the variants of the
+/// game's `Actionlike` type.
+pub struct TnuaLeafwingInput<A>(PhantomData<fn() -> A>);
+
+impl<A: Actionlike> TnuaInputSource for TnuaLeafwingInput<A> {
+    type Input = A;
+}
+
+/// Declares how the inputs of a leafwing-input-manager `ActionState` feed the [`TnuaController`]
+/// on the same entity. Fed by [`TnuaLeafwingPlugin`].
+///
+/// See [`TnuaInputBindings`].
+pub type TnuaLeafwingBindings<A> = TnuaInputBindings<TnuaLeafwingInput<A>>;
+
+impl<A: Actionlike> TnuaLeafwingBindings<A> {
+    /// Set the dual-axis input that moves the character.
+    ///
+    /// The length of the input is clamped to 1, so a stick that is not fully tilted moves the
+    /// character slower.
+    pub fn with_movement(mut self, input: A) -> Self {
+        self.set_movement_input(input);
+        self
+    }
+
+    /// Bind an input to an action.
+    pub fn with_action(
+        mut self,
+        input: A,
+        trigger: TnuaInputTrigger,
+        action: impl TnuaAction,
+    ) -> Self {
+        self.add_action_input(input, trigger, action);
+        self
+    }
+}
+
+fn feed_controller_from_leafwing<A: Actionlike>(
+    mut query: Query<(
+        &ActionState<A>,
+        &mut TnuaLeafwingBindings<A>,
+        &mut TnuaController,
+    )>,
+) {
+    for (action_state, mut bindings, mut controller) in query.iter_mut() {
+        bindings.feed(
+            controller.as_mut(),
+            |movement| Some(action_state.axis_pair(movement).adjust_precision()),
+            |input| {
+                action_state
+                    .pressed(input)
+                    .then(|| action_state.value(input).adjust_precision())
+            },
+        );
+    }
+}
@@ -33,6 +33,32 @@
 //! systems must also be registered under that same schedule (instead of under `Update`, which is
 //! where it should usually be registered)
 //!
+//! If the player controls systems must stay in `Update` (e.g. because they read input events),
+//! register `TnuaControllerPlugin` with
+//! [`TnuaUnfedTickPolicy::RepeatLastInput`](controller::TnuaUnfedTickPolicy::RepeatLastInput) so
+//! that held actions are not released in ticks that run without the controls being fed:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_tnua::prelude::*;
+//! # use bevy_tnua::controller::TnuaUnfedTickPolicy;
+//! # let mut app = App::new();
+//! app.add_plugins(
+//!     TnuaControllerPlugin::new(FixedUpdate)
+//!         .with_unfed_tick_policy(TnuaUnfedTickPolicy::RepeatLastInput),
+//! );
+//! ```
+//!
+//! Since the fixed schedule does not run every frame, the visuals of the characters may stutter.
+//! [`TnuaRenderInterpolation`](control_helpers::TnuaRenderInterpolation) can be used for
+//! interpolating them between ticks.
+//!
+//! The physics integration plugin and `TnuaControllerPlugin` can also be registered in more than
+//! one schedule at once - e.g. `FixedUpdate` for the player and `Update` for cosmetic NPCs. Add a
+//! [`TnuaSchedule`](schedules::TnuaSchedule) component to choose the schedule that drives each
+//! character. Characters without it are driven by the schedule of the first plugin that was
+//! registered.
+//!
 //! A Tnua controlled character must have a dynamic rigid body, everything from
 //! `Tnua<physics-backend>IOBundle` (e.g. - for Rapier 3D, use `TnuaRapier3dIOBundle`), and
 //! everything from [`TnuaControllerBundle`](prelude::TnuaControllerBundle):
@@ -126,16 +152,40 @@
 //!
 //! [`TnuaController`](crate::prelude::TnuaController) can also be used to retreive data that can
 //! be used to decide which animation to play. A useful helper for that is [`TnuaAnimatingState`].
+//! For blending animations instead of switching between them, use [`TnuaAnimationBlender`].
 mod animating_helper;
+mod animation_blending;
 mod basis_action_traits;
 pub mod builtins;
+#[cfg(feature = "config-asset")]
+pub mod config_asset;
 pub mod control_helpers;
 pub mod controller;
+pub mod diagnostics;
+#[cfg(feature = "egui")]
+pub mod egui_inspector;
+#[cfg(feature = "enhanced-input")]
+pub mod enhanced_input;
+#[cfg(any(feature = "leafwing", feature = "enhanced-input"))]
+pub mod input_bindings;
+#[cfg(feature = "prediction")]
+pub mod input_command;
+#[cfg(feature = "leafwing")]
+pub mod leafwing;
+#[cfg(feature = "prediction")]
+pub mod prediction;
+#[cfg(feature = "replay")]
+pub mod replay;
+pub mod rollback;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod util;
 pub use animating_helper::{TnuaAnimatingState, TnuaAnimatingStateDirective};
+pub use animation_blending::{TnuaAnimationBlend, TnuaAnimationBlender, TnuaBlendSpace1d};
 pub use basis_action_traits::{
     DynamicAction, DynamicBasis, TnuaAction, TnuaActionContext, TnuaActionInitiationDirective,
-    TnuaActionLifecycleDirective, TnuaActionLifecycleStatus, TnuaBasis, TnuaBasisContext,
+    TnuaActionLifecycleDirective, TnuaActionLifecycleStatus, TnuaActionStage, TnuaBasis,
+    TnuaBasisContext,
 };
 
 pub mod prelude {
@@ -0,0 +1,470 @@
+//! Support for client-side prediction and reconciliation.
+//!
+//! Networking libraries that use prediction (like `lightyear` or `bevy_replicon`) need to:
+//!
+//! 1. Capture the state of the character when the server confirms it. Use
+//!    [`TnuaController::prediction_snapshot`] to get a [`TnuaPredictionSnapshot`], which can be
+//!    serialized and sent over the network.
+//! 2. When a correction arrives, restore that state with
+//!    [`TnuaController::restore_prediction_snapshot`] (together with the state of the physics
+//!    backend), and then fast-forward by resimulating the ticks since that state using the
+//!    recorded inputs - see [`tnua_resimulate`].
+//!
+//! The basis and the actions are stored in the controller with dynamic dispatch, so in order to
+//! (de)serialize them their types must first be registered in a [`TnuaPredictionRegistry`].
+//!
+//! The snapshot includes the action queue and the action slots. It does not include the
+//! configuration of the controller (action priorities, input buffer times and slot conflicts),
+//! which is expected to already be set on the controller the snapshot is restored into. Names of
+//! action slots must be registered with [`register_name`](TnuaPredictionRegistry::register_name)
+//! unless the controller the snapshot is restored into already has these slots.
+use std::any::{Any, TypeId};
+
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use bevy::time::{Stopwatch, Timer};
+use bevy::utils::HashMap;
+use bevy_tnua_physics_integration_layer::math::{Float, Vector3};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::basis_action_traits::{BoxableAction, BoxableBasis, DynamicAction, DynamicBasis};
+use crate::builtins::{
+    TnuaBuiltinCrouch, TnuaBuiltinDash, TnuaBuiltinJump, TnuaBuiltinKnockback, TnuaBuiltinWalk,
+};
+use crate::controller::{queued_action_feeder, QueuedActionFeeder, TnuaController};
+use crate::{TnuaAction, TnuaBasis};
+
+/// The error of capturing or restoring a [`TnuaPredictionSnapshot`].
+#[derive(thiserror::Error, Debug)]
+pub enum TnuaPredictionError {
+    #[error("Type {0} is not registered in the TnuaPredictionRegistry")]
+    UnregisteredType(String),
+    #[error("Name {0:?} is not registered in the TnuaPredictionRegistry")]
+    UnregisteredName(String),
+    #[error("Could not serialize the state: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("Could not deserialize the state: {0}")]
+    Deserialize(#[from] ron::error::SpannedError),
+}
+
+type SaveFn<D> = fn(&D) -> Result<String, TnuaPredictionError>;
+type LoadFn<D> = fn(&str) -> Result<Box<D>, TnuaPredictionError>;
+type FeedFn = fn(&'static str, &str, &mut TnuaController) -> Result<(), TnuaPredictionError>;
+
+struct RegisteredType<D: ?Sized> {
+    key: &'static str,
+    save: SaveFn<D>,
+    save_input: SaveFn<D>,
+    load: LoadFn<D>,
+    feed: FeedFn,
+}
+
+/// The basis and action types (and names) that can be stored in a [`TnuaPredictionSnapshot`].
+///
+/// [`Default`] registers all the builtin basis and actions. Custom basis and actions must be
+/// registered with [`register_basis`](Self::register_basis) and
+/// [`register_action`](Self::register_action). Names other than [the default
+/// names](TnuaAction::NAME) (e.g. ones used with [`named_action`](crate::controller::TnuaController::named_action))
+/// must be registered with [`register_name`](Self::register_name).
+#[derive(Resource)]
+pub struct TnuaPredictionRegistry {
+    names: HashMap<String, &'static str>,
+    bases: HashMap<TypeId, RegisteredType<dyn DynamicBasis>>,
+    bases_by_key: HashMap<&'static str, TypeId>,
+    actions: HashMap<TypeId, RegisteredType<dyn DynamicAction>>,
+    actions_by_key: HashMap<&'static str, TypeId>,
+    queued_action_feeders: HashMap<TypeId, QueuedActionFeeder>,
+}
+
+impl Default for TnuaPredictionRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register_basis::<TnuaBuiltinWalk>();
+        registry.register_action::<TnuaBuiltinJump>();
+        registry.register_action::<TnuaBuiltinCrouch>();
+        registry.register_action::<TnuaBuiltinDash>();
+        registry.register_action::<TnuaBuiltinKnockback>();
+        registry
+    }
+}
+
+impl TnuaPredictionRegistry {
+    /// A registry without the builtin basis and actions.
+    pub fn empty() -> Self {
+        Self {
+            names: Default::default(),
+            bases: Default::default(),
+            bases_by_key: Default::default(),
+            actions: Default::default(),
+            actions_by_key: Default::default(),
+            queued_action_feeders: Default::default(),
+        }
+    }
+
+    /// Register a name, so that it can be restored from a snapshot.
+    pub fn register_name(&mut self, name: &'static str) -> &mut Self {
+        self.names.insert(name.to_owned(), name);
+        self
+    }
+
+    /// Register a basis type (and [its default name](TnuaBasis::NAME)).
+    pub fn register_basis<B>(&mut self) -> &mut Self
+    where
+        B: TnuaBasis + Serialize + DeserializeOwned,
+        B::State: Serialize + DeserializeOwned,
+    {
+        let key = std::any::type_name::<B>();
+        let type_id = TypeId::of::<BoxableBasis<B>>();
+        self.bases.insert(
+            type_id,
+            RegisteredType {
+                key,
+                save: |basis| {
+                    let basis: &BoxableBasis<B> = downcast(basis.as_any())?;
+                    Ok(ron::to_string(&(&basis.input, &basis.state))?)
+                },
+                save_input: |basis| {
+                    let basis: &BoxableBasis<B> = downcast(basis.as_any())?;
+                    Ok(ron::to_string(&basis.input)?)
+                },
+                load: |data| {
+                    let (input, state): (B, B::State) = ron::from_str(data)?;
+                    Ok(Box::new(BoxableBasis { input, state }))
+                },
+                feed: |name, data, controller| {
+                    let input: B = ron::from_str(data)?;
+                    controller.named_basis(name, input);
+                    Ok(())
+                },
+            },
+        );
+        self.bases_by_key.insert(key, type_id);
+        self.register_name(B::NAME)
+    }
+
+    /// Register an action type (and [its default name](TnuaAction::NAME)).
+    pub fn register_action<A>(&mut self) -> &mut Self
+    where
+        A: TnuaAction + Serialize + DeserializeOwned,
+        A::State: Serialize + DeserializeOwned,
+    {
+        let key = std::any::type_name::<A>();
+        let type_id = TypeId::of::<BoxableAction<A>>();
+        self.actions.insert(
+            type_id,
+            RegisteredType {
+                key,
+                save: |action| {
+                    let action: &BoxableAction<A> = downcast(action.as_any())?;
+                    Ok(ron::to_string(&(&action.input, &action.state))?)
+                },
+                save_input: |action| {
+                    let action: &BoxableAction<A> = downcast(action.as_any())?;
+                    Ok(ron::to_string(&action.input)?)
+                },
+                load: |data| {
+                    let (input, state): (A, A::State) = ron::from_str(data)?;
+                    Ok(Box::new(BoxableAction { input, state }))
+                },
+                feed: |name, data, controller| {
+                    let input: A = ron::from_str(data)?;
+                    controller.named_action(name, input);
+                    Ok(())
+                },
+            },
+        );
+        self.actions_by_key.insert(key, type_id);
+        self.queued_action_feeders
+            .insert(type_id, queued_action_feeder::<A>());
+        self.register_name(A::NAME)
+    }
+
+    /// Serialize the input of a basis, without its state.
+    pub(crate) fn serialize_basis_input<B: TnuaBasis + Serialize>(
+        &self,
+        name: &'static str,
+        basis: &B,
+    ) -> Result<TnuaSerializedEntry, TnuaPredictionError> {
+        let registered = self
+            .bases
+            .get(&TypeId::of::<BoxableBasis<B>>())
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(name.to_owned()))?;
+        Ok(TnuaSerializedEntry {
+            name: name.to_owned(),
+            type_key: registered.key.to_owned(),
+            data: ron::to_string(basis)?,
+        })
+    }
+
+    /// Serialize the input of an action, without its state.
+    pub(crate) fn serialize_action_input<A: TnuaAction + Serialize>(
+        &self,
+        name: &'static str,
+        action: &A,
+    ) -> Result<TnuaSerializedEntry, TnuaPredictionError> {
+        let registered = self
+            .actions
+            .get(&TypeId::of::<BoxableAction<A>>())
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(name.to_owned()))?;
+        Ok(TnuaSerializedEntry {
+            name: name.to_owned(),
+            type_key: registered.key.to_owned(),
+            data: ron::to_string(action)?,
+        })
+    }
+
+    /// Serialize the input of a basis stored in a controller (e.g. the one returned by
+    /// [`TnuaController::dynamic_basis`]), without its state.
+    pub fn serialize_dynamic_basis_input(
+        &self,
+        name: &'static str,
+        basis: &dyn DynamicBasis,
+    ) -> Result<TnuaSerializedEntry, TnuaPredictionError> {
+        let registered = self
+            .bases
+            .get(&Any::type_id(basis.as_any()))
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(name.to_owned()))?;
+        Ok(TnuaSerializedEntry {
+            name: name.to_owned(),
+            type_key: registered.key.to_owned(),
+            data: (registered.save_input)(basis)?,
+        })
+    }
+
+    /// Serialize the input of a type-erased action (e.g. the one returned by
+    /// [`TnuaController::dynamic_action`]), without its state.
+    pub fn serialize_dynamic_action_input(
+        &self,
+        name: &'static str,
+        action: &dyn DynamicAction,
+    ) -> Result<TnuaSerializedEntry, TnuaPredictionError> {
+        let registered = self
+            .actions
+            .get(&Any::type_id(action.as_any()))
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(name.to_owned()))?;
+        Ok(TnuaSerializedEntry {
+            name: name.to_owned(),
+            type_key: registered.key.to_owned(),
+            data: (registered.save_input)(action)?,
+        })
+    }
+
+    /// Feed a basis serialized with [`serialize_basis_input`](Self::serialize_basis_input).
+    pub(crate) fn feed_basis(
+        &self,
+        entry: &TnuaSerializedEntry,
+        controller: &mut TnuaController,
+    ) -> Result<(), TnuaPredictionError> {
+        let registered = self
+            .bases_by_key
+            .get(entry.type_key.as_str())
+            .and_then(|type_id| self.bases.get(type_id))
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(entry.type_key.clone()))?;
+        (registered.feed)(self.resolve_name(&entry.name)?, &entry.data, controller)
+    }
+
+    /// Feed an action serialized with [`serialize_action_input`](Self::serialize_action_input).
+    pub(crate) fn feed_action(
+        &self,
+        entry: &TnuaSerializedEntry,
+        controller: &mut TnuaController,
+    ) -> Result<(), TnuaPredictionError> {
+        let registered = self
+            .actions_by_key
+            .get(entry.type_key.as_str())
+            .and_then(|type_id| self.actions.get(type_id))
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(entry.type_key.clone()))?;
+        (registered.feed)(self.resolve_name(&entry.name)?, &entry.data, controller)
+    }
+
+    pub(crate) fn queued_action_feeder(
+        &self,
+        name: &'static str,
+        action: &dyn DynamicAction,
+    ) -> Result<QueuedActionFeeder, TnuaPredictionError> {
+        self.queued_action_feeders
+            .get(&Any::type_id(action.as_any()))
+            .copied()
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(name.to_owned()))
+    }
+
+    pub(crate) fn resolve_name(&self, name: &str) -> Result<&'static str, TnuaPredictionError> {
+        self.names
+            .get(name)
+            .copied()
+            .ok_or_else(|| TnuaPredictionError::UnregisteredName(name.to_owned()))
+    }
+
+    pub(crate) fn save_basis(
+        &self,
+        name: &'static str,
+        basis: &dyn DynamicBasis,
+    ) -> Result<TnuaSerializedEntry, TnuaPredictionError> {
+        let registered = self
+            .bases
+            .get(&Any::type_id(basis.as_any()))
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(name.to_owned()))?;
+        Ok(TnuaSerializedEntry {
+            name: name.to_owned(),
+            type_key: registered.key.to_owned(),
+            data: (registered.save)(basis)?,
+        })
+    }
+
+    pub(crate) fn load_basis(
+        &self,
+        entry: &TnuaSerializedEntry,
+    ) -> Result<(&'static str, Box<dyn DynamicBasis>), TnuaPredictionError> {
+        let registered = self
+            .bases_by_key
+            .get(entry.type_key.as_str())
+            .and_then(|type_id| self.bases.get(type_id))
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(entry.type_key.clone()))?;
+        Ok((
+            self.resolve_name(&entry.name)?,
+            (registered.load)(&entry.data)?,
+        ))
+    }
+
+    pub(crate) fn save_action(
+        &self,
+        name: &'static str,
+        action: &dyn DynamicAction,
+    ) -> Result<TnuaSerializedEntry, TnuaPredictionError> {
+        let registered = self
+            .actions
+            .get(&Any::type_id(action.as_any()))
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(name.to_owned()))?;
+        Ok(TnuaSerializedEntry {
+            name: name.to_owned(),
+            type_key: registered.key.to_owned(),
+            data: (registered.save)(action)?,
+        })
+    }
+
+    pub(crate) fn load_action(
+        &self,
+        entry: &TnuaSerializedEntry,
+    ) -> Result<(&'static str, Box<dyn DynamicAction>), TnuaPredictionError> {
+        let registered = self
+            .actions_by_key
+            .get(entry.type_key.as_str())
+            .and_then(|type_id| self.actions.get(type_id))
+            .ok_or_else(|| TnuaPredictionError::UnregisteredType(entry.type_key.clone()))?;
+        Ok((
+            self.resolve_name(&entry.name)?,
+            (registered.load)(&entry.data)?,
+        ))
+    }
+}
+
+fn downcast<T: 'static>(value: &dyn Any) -> Result<&T, TnuaPredictionError> {
+    value
+        .downcast_ref()
+        .ok_or_else(|| TnuaPredictionError::UnregisteredType(std::any::type_name::<T>().to_owned()))
+}
+
+/// A serialized basis or action, including its state.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TnuaSerializedEntry {
+    /// The name the basis or action was fed with.
+    pub name: String,
+    /// The key of the type in the [`TnuaPredictionRegistry`].
+    pub type_key: String,
+    /// Serialized as RON - the input and the state in a [`TnuaPredictionSnapshot`], only the
+    /// input in a [`TnuaInputCommand`](crate::input_command::TnuaInputCommand).
+    pub data: String,
+}
+
+/// An action that was being fed when the snapshot was taken.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TnuaSerializedFedEntry {
+    pub name: String,
+    pub fed_this_frame: bool,
+    pub rescheduled_in: Option<Timer>,
+}
+
+/// The state of a [`TnuaController`](crate::controller::TnuaController), including the state of
+/// its basis and its actions.
+///
+/// See [the module documentation](self).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TnuaPredictionSnapshot {
+    pub basis: Option<TnuaSerializedEntry>,
+    pub current_action: Option<TnuaSerializedEntry>,
+    pub current_action_running_for: Stopwatch,
+    pub contender_action: Option<(TnuaSerializedEntry, Stopwatch)>,
+    pub contender_buffered_for: Option<Stopwatch>,
+    pub actions_being_fed: Vec<TnuaSerializedFedEntry>,
+    pub cooldowns: Vec<(String, Timer)>,
+    pub action_queue: Vec<TnuaSerializedQueuedAction>,
+    pub action_slots: Vec<TnuaSerializedActionSlot>,
+    pub reported_state: TnuaSerializedReportedState,
+}
+
+/// An action in the action queue when the snapshot was taken.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TnuaSerializedQueuedAction {
+    pub action: TnuaSerializedEntry,
+    /// Whether the action was queued with a condition. The condition itself cannot be serialized
+    /// - see [`TnuaController::restore_prediction_snapshot`].
+    pub has_condition: bool,
+    pub status: TnuaSerializedQueuedActionStatus,
+}
+
+/// How far the controller got with a [`TnuaSerializedQueuedAction`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaSerializedQueuedActionStatus {
+    /// Waiting for its turn.
+    Pending,
+    /// Fed to the controller, but not started yet.
+    Fed,
+    /// Currently running.
+    Running,
+}
+
+/// The actions in an action slot when the snapshot was taken.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TnuaSerializedActionSlot {
+    pub name: String,
+    pub current_action: Option<TnuaSerializedEntry>,
+    pub current_fed_this_frame: bool,
+    pub contender_action: Option<(TnuaSerializedEntry, Stopwatch)>,
+    pub contender_fed_this_frame: bool,
+}
+
+/// What the controller last reported in its events (e.g. whether it was airborne), which it
+/// compares against to decide which events to send.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TnuaSerializedReportedState {
+    pub airborne: Option<bool>,
+    pub airborne_duration: Float,
+    pub highest_elevation: Float,
+    pub last_velocity: Vector3,
+    pub basis_name: Option<String>,
+    pub action_name: Option<String>,
+}
+
+/// Fast-forward the simulation by running a schedule once for each recorded input.
+///
+/// Call this after restoring a [`TnuaPredictionSnapshot`] (and the state of the physics backend)
+/// to resimulate the ticks that passed since the snapshot was taken. `apply_input` should feed the
+/// recorded input of the tick - usually by writing it where the game's control system reads it
+/// from.
+///
+/// `schedule` must contain Tnua, the physics backend, and the game's control system. If it is
+/// `FixedUpdate`, make sure the `Time` resource advances in fixed steps while resimulating (e.g.
+/// by running this from a system inside `FixedUpdate`'s parent schedule, where `Time` is set to
+/// `Time<Fixed>`), so that the resimulated ticks have the same duration as the original ones.
+pub fn tnua_resimulate<I>(
+    world: &mut World,
+    schedule: impl ScheduleLabel + Clone,
+    inputs: impl IntoIterator<Item = I>,
+    mut apply_input: impl FnMut(&mut World, I),
+) {
+    for input in inputs {
+        apply_input(world, input);
+        world.run_schedule(schedule.clone());
+    }
+}
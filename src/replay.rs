@@ -0,0 +1,255 @@
+//! Recording the input fed into a controller, and playing it back.
+//!
+//! Add [`TnuaRecorder`] to a character to record the basis and actions fed into its controller
+//! every tick. [`finish`](TnuaRecorder::finish) turns the recording into a [`TnuaReplay`], which
+//! can be saved as a `.tnua-replay.ron` file and loaded back as an asset. Add [`TnuaPlayback`] to
+//! a character to feed its controller from a replay instead of from the game's control system.
+//!
+//! The basis and action types must be registered in the [`TnuaPredictionRegistry`]. Actions fed
+//! into [action slots](TnuaController::slot_action) are not recorded, and actions started from
+//! the [action queue](TnuaController::queue_action) are recorded as if they were fed directly.
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::controller::TnuaController;
+use crate::input_command::TnuaInputCommand;
+use crate::prediction::{TnuaPredictionError, TnuaPredictionRegistry};
+use crate::{TnuaPipelineStages, TnuaSystemSet, TnuaUserControlsSystemSet};
+
+/// A plugin required for making [`TnuaRecorder`] and [`TnuaPlayback`] work.
+///
+/// Must be registered in the same schedule as the rest of the Tnua plugins.
+pub struct TnuaReplayPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl TnuaReplayPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for TnuaReplayPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
+impl Plugin for TnuaReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TnuaPredictionRegistry>();
+        app.init_asset::<TnuaReplay>();
+        app.register_asset_loader(TnuaReplayLoader);
+        app.add_systems(
+            self.schedule,
+            (
+                play_replays.in_set(TnuaUserControlsSystemSet),
+                record_fed_inputs
+                    .after(TnuaUserControlsSystemSet)
+                    .before(TnuaPipelineStages::Logic)
+                    .in_set(TnuaSystemSet),
+            ),
+        );
+        app.observe(start_recording_on_insertion);
+        app.observe(stop_recording_on_removal);
+    }
+}
+
+/// The input fed into a controller over a number of ticks.
+#[derive(Asset, TypePath, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TnuaReplay {
+    /// One command per tick, starting from tick 0.
+    pub commands: Vec<TnuaInputCommand>,
+}
+
+impl TnuaReplay {
+    /// Serialize the replay, for saving it to a `.tnua-replay.ron` file.
+    pub fn to_ron_string(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, Default::default())
+    }
+}
+
+/// The error of loading a [`TnuaReplay`].
+#[derive(thiserror::Error, Debug)]
+pub enum TnuaReplayLoaderError {
+    #[error("Could not read the replay file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse the replay file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+pub struct TnuaReplayLoader;
+
+impl AssetLoader for TnuaReplayLoader {
+    type Asset = TnuaReplay;
+    type Settings = ();
+    type Error = TnuaReplayLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<TnuaReplay, TnuaReplayLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tnua-replay.ron"]
+    }
+}
+
+/// Records the input fed into the controller of the entity. See [the module
+/// documentation](self).
+///
+/// Without [`TnuaReplayPlugin`] this component will not do anything.
+#[derive(Component, Default, Debug)]
+pub struct TnuaRecorder {
+    commands: Vec<TnuaInputCommand>,
+    errors: Vec<TnuaPredictionError>,
+}
+
+impl TnuaRecorder {
+    /// The number of ticks recorded so far.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether no ticks were recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Errors encountered while recording - usually basis or actions whose types are not
+    /// registered in the [`TnuaPredictionRegistry`]. Such basis and actions are left out of the
+    /// recording.
+    pub fn errors(&self) -> &[TnuaPredictionError] {
+        &self.errors
+    }
+
+    /// Take the recording so far (and keep recording from this point).
+    pub fn finish(&mut self) -> TnuaReplay {
+        TnuaReplay {
+            commands: std::mem::take(&mut self.commands),
+        }
+    }
+}
+
+/// Feeds the controller of the entity from a [`TnuaReplay`]. See [the module
+/// documentation](self).
+///
+/// Without [`TnuaReplayPlugin`] this component will not do anything.
+#[derive(Component, Debug)]
+pub struct TnuaPlayback {
+    pub replay: Handle<TnuaReplay>,
+    /// Start over when the replay ends.
+    pub looping: bool,
+    position: usize,
+    finished: bool,
+}
+
+impl TnuaPlayback {
+    pub fn new(replay: Handle<TnuaReplay>) -> Self {
+        Self {
+            replay,
+            looping: false,
+            position: 0,
+            finished: false,
+        }
+    }
+
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// The number of ticks played so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Whether the entire replay was played (never `true` for a looping playback).
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+fn record_fed_inputs(
+    registry: Res<TnuaPredictionRegistry>,
+    mut query: Query<(&mut TnuaController, &mut TnuaRecorder)>,
+) {
+    for (mut controller, mut recorder) in query.iter_mut() {
+        // In case the controller was added after the recorder:
+        controller.start_fed_actions_log();
+        let fed_actions = controller.take_fed_actions_log();
+        let recorder = recorder.as_mut();
+        let mut command = TnuaInputCommand::new(recorder.commands.len() as u64);
+        if let (Some(name), Some(basis)) = (controller.basis_name(), controller.dynamic_basis()) {
+            match registry.serialize_dynamic_basis_input(name, basis) {
+                Ok(entry) => command.basis = Some(entry),
+                Err(err) => recorder.errors.push(err),
+            }
+        }
+        for (name, action) in fed_actions {
+            match registry.serialize_dynamic_action_input(name, action.as_ref()) {
+                Ok(entry) => command.actions.push(entry),
+                Err(err) => recorder.errors.push(err),
+            }
+        }
+        recorder.commands.push(command);
+    }
+}
+
+fn start_recording_on_insertion(
+    trigger: Trigger<OnAdd, TnuaRecorder>,
+    mut query: Query<&mut TnuaController>,
+) {
+    if let Ok(mut controller) = query.get_mut(trigger.entity()) {
+        controller.start_fed_actions_log();
+    }
+}
+
+fn stop_recording_on_removal(
+    trigger: Trigger<OnRemove, TnuaRecorder>,
+    mut query: Query<&mut TnuaController>,
+) {
+    if let Ok(mut controller) = query.get_mut(trigger.entity()) {
+        controller.stop_fed_actions_log();
+    }
+}
+
+fn play_replays(
+    registry: Res<TnuaPredictionRegistry>,
+    replays: Res<Assets<TnuaReplay>>,
+    mut query: Query<(&mut TnuaController, &mut TnuaPlayback)>,
+) {
+    for (mut controller, mut playback) in query.iter_mut() {
+        if playback.finished {
+            continue;
+        }
+        let Some(replay) = replays.get(&playback.replay) else {
+            continue;
+        };
+        if replay.commands.len() <= playback.position {
+            if playback.looping && !replay.commands.is_empty() {
+                playback.position = 0;
+            } else {
+                playback.finished = true;
+                continue;
+            }
+        }
+        let command = &replay.commands[playback.position];
+        playback.position += 1;
+        if let Err(err) = command.apply(&registry, controller.as_mut()) {
+            error!("Cannot play replay command: {err}");
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Support for rollback networking.
+//!
+//! All the components Tnua keeps its state in are [`Clone`] - including [`TnuaController`],
+//! which clones the state of its basis and actions. With a rollback library that snapshots
+//! components by cloning them (like `bevy_ggrs`), register these components:
+//!
+//! * The controller and the data the physics backends exchange with it: [`TnuaController`],
+//!   [`TnuaMotor`], [`TnuaMotorLimits`] (which holds the clamped part of the last motor),
+//!   [`TnuaRigidBodyTracker`], [`TnuaProximitySensor`], [`TnuaGhostSensor`],
+//!   [`TnuaSensorHitCache`], [`TnuaSleepPolicy`] and [`TnuaObstacleRadar`].
+//! * Components of the controller that have state: [`TnuaResourcePool`], [`TnuaUpDirection`]
+//!   (which holds the up direction while it turns toward its target) and [`TnuaRootMotion`]
+//!   (which holds the root motion fed since the controller last consumed it).
+//! * Components of control helpers that have state: [`TnuaSimpleAirActionsCounter`],
+//!   [`TnuaMultiJump`], [`TnuaSimpleFallThroughPlatformsHelper`], [`TnuaFootsteps`],
+//!   [`TnuaCornerNudge`], [`TnuaLedgeSafety`], [`TnuaWallSlide`], [`TnuaPressurePlate`],
+//!   [`TnuaCommands`], [`TnuaPatrol`], [`TnuaPathFollower`], [`TnuaJumpLinkExecutor`] and
+//!   [`TnuaSteeringWanderState`].
+//!
+//! With the `tnua-ggrs` feature, `TnuaGgrsPlugin` registers all of them with `bevy_ggrs`.
+//! Otherwise the registration has to be done by the game:
+//!
+//! ```ignore
+//! app.rollback_component_with_clone::<TnuaController>()
+//!     .rollback_component_with_clone::<TnuaMotor>()
+//!     // ... and the rest of the components listed above
+//!     .rollback_component_with_clone::<TnuaSteeringWanderState>();
+//! ```
+//!
+//! [`TnuaCrouchEnforcer`](crate::control_helpers::TnuaCrouchEnforcer),
+//! [`TnuaGroundSamples`](crate::control_helpers::TnuaGroundSamples) and
+//! [`TnuaAuxiliarySensors`](crate::control_helpers::TnuaAuxiliarySensors) hold closures, so they
+//! cannot be cloned - but the sensor entities they create have their own [`TnuaProximitySensor`],
+//! which is rolled back. Helpers that only affect the presentation (like
+//! [`TnuaRenderInterpolation`](crate::control_helpers::TnuaRenderInterpolation) and
+//! [`TnuaEyeAnchor`](crate::control_helpers::TnuaEyeAnchor)) should not be rolled back.
+//!
+//! Note that Tnua must run in the rollback schedule, together with the physics backend (which
+//! must also be registered for rollback).
+//!
+//! For libraries that need explicit save and load hooks, [`TnuaRollbackSnapshot`] captures the
+//! state of all of these components at once.
+use bevy::prelude::*;
+
+use crate::control_helpers::{
+    TnuaCommands, TnuaCornerNudge, TnuaFootsteps, TnuaJumpLinkExecutor, TnuaLedgeSafety,
+    TnuaMultiJump, TnuaPathFollower, TnuaPatrol, TnuaPressurePlate, TnuaSimpleAirActionsCounter,
+    TnuaSimpleFallThroughPlatformsHelper, TnuaSteeringWanderState, TnuaWallSlide,
+};
+use crate::controller::{TnuaController, TnuaResourcePool, TnuaRootMotion, TnuaUpDirection};
+use crate::obstacle_radar::TnuaObstacleRadar;
+use crate::{
+    TnuaGhostSensor, TnuaMotor, TnuaMotorLimits, TnuaProximitySensor, TnuaRigidBodyTracker,
+    TnuaSensorHitCache, TnuaSleepPolicy,
+};
+
+/// Defines [`TnuaRollbackSnapshot`] and `TnuaGgrsPlugin` from a single list, so that they
+/// always cover the same components.
+macro_rules! rollback_components {
+    ($($field:ident: $component:ty,)*) => {
+        /// A snapshot of the state of all the Tnua components of a character entity.
+        ///
+        /// ```no_run
+        /// # use bevy::prelude::*;
+        /// # use bevy_tnua::rollback::TnuaRollbackSnapshot;
+        /// # let mut world = World::new();
+        /// # let character = world.spawn_empty().id();
+        /// let snapshot = TnuaRollbackSnapshot::save(world.entity(character));
+        /// // ... advance the simulation ...
+        /// snapshot.restore(&mut world.entity_mut(character));
+        /// ```
+        #[derive(Clone, Default)]
+        pub struct TnuaRollbackSnapshot {
+            $(pub $field: Option<$component>,)*
+        }
+
+        impl TnuaRollbackSnapshot {
+            /// Capture the Tnua components of an entity.
+            pub fn save(entity: EntityRef) -> Self {
+                Self {
+                    $($field: entity.get().cloned(),)*
+                }
+            }
+
+            /// Write the captured components back into an entity.
+            ///
+            /// Components that the entity did not have when the snapshot was taken are left
+            /// untouched.
+            pub fn restore(&self, entity: &mut EntityWorldMut) {
+                $(
+                    if let Some($field) = &self.$field {
+                        entity.insert($field.clone());
+                    }
+                )*
+            }
+        }
+
+        /// Registers all the stateful Tnua components for rollback with `bevy_ggrs`.
+        ///
+        /// Only available with the `tnua-ggrs` feature. `TnuaControllerPlugin` (and the plugins
+        /// of the control helpers and the physics backend) should be added to `GgrsSchedule`.
+        #[cfg(feature = "tnua-ggrs")]
+        pub struct TnuaGgrsPlugin;
+
+        #[cfg(feature = "tnua-ggrs")]
+        impl Plugin for TnuaGgrsPlugin {
+            fn build(&self, app: &mut App) {
+                use bevy_ggrs::GgrsApp;
+                $(app.rollback_component_with_clone::<$component>();)*
+            }
+        }
+    };
+}
+
+rollback_components! {
+    controller: TnuaController,
+    motor: TnuaMotor,
+    motor_limits: TnuaMotorLimits,
+    rigid_body_tracker: TnuaRigidBodyTracker,
+    proximity_sensor: TnuaProximitySensor,
+    ghost_sensor: TnuaGhostSensor,
+    sensor_hit_cache: TnuaSensorHitCache,
+    sleep_policy: TnuaSleepPolicy,
+    obstacle_radar: TnuaObstacleRadar,
+    resource_pool: TnuaResourcePool,
+    up_direction: TnuaUpDirection,
+    root_motion: TnuaRootMotion,
+    air_actions_counter: TnuaSimpleAirActionsCounter,
+    multi_jump: TnuaMultiJump,
+    fall_through_platforms: TnuaSimpleFallThroughPlatformsHelper,
+    footsteps: TnuaFootsteps,
+    corner_nudge: TnuaCornerNudge,
+    ledge_safety: TnuaLedgeSafety,
+    wall_slide: TnuaWallSlide,
+    pressure_plate: TnuaPressurePlate,
+    commands: TnuaCommands,
+    patrol: TnuaPatrol,
+    path_follower: TnuaPathFollower,
+    jump_link_executor: TnuaJumpLinkExecutor,
+    steering_wander_state: TnuaSteeringWanderState,
+}
@@ -0,0 +1,462 @@
+//! Helpers for testing character movement without rendering - e.g. for regression-testing the
+//! movement tuning of a game in CI.
+//!
+//! [`TnuaTestHarness`] builds a headless app (with `MinimalPlugins`) that advances by a fixed
+//! duration on every tick, spawns characters, feeds scripted input into their controllers and
+//! reads back where they ended up:
+//!
+//! ```
+//! # use avian3d::prelude::*;
+//! # use bevy::prelude::*;
+//! # use bevy_tnua::prelude::*;
+//! # use bevy_tnua::math::Vector3;
+//! # use bevy_tnua::testing::TnuaTestHarness;
+//! # use bevy_tnua_avian3d::TnuaAvian3dPlugin;
+//! let mut harness = TnuaTestHarness::new(|app| {
+//!     // Avian needs the mesh and scene assets when Bevy's `bevy_render` and `bevy_scene`
+//!     // features are enabled, even when running headless.
+//!     app.add_plugins((AssetPlugin::default(), bevy::scene::ScenePlugin));
+//!     app.init_asset::<Mesh>();
+//!     app.add_plugins((PhysicsPlugins::default(), TnuaAvian3dPlugin::default()));
+//! });
+//! harness.spawn((
+//!     TransformBundle::from_transform(Transform::from_xyz(0.0, -0.5, 0.0)),
+//!     RigidBody::Static,
+//!     Collider::cuboid(100.0, 1.0, 100.0),
+//! ));
+//! let character = harness.spawn_character((
+//!     TransformBundle::from_transform(Transform::from_xyz(0.0, 2.0, 0.0)),
+//!     RigidBody::Dynamic,
+//!     Collider::capsule(0.5, 1.0),
+//!     LockedAxes::ROTATION_LOCKED,
+//! ));
+//! harness.run_ticks(character, 120, |_tick, controller| {
+//!     controller.basis(TnuaBuiltinWalk {
+//!         desired_velocity: Vector3::X,
+//!         float_height: 1.5,
+//!         ..Default::default()
+//!     });
+//! });
+//! harness.assert_position_near(
+//!     character,
+//!     Vector3::new(1.9, 1.5, 0.0),
+//!     Vector3::new(0.2, 0.05, 0.05),
+//! );
+//! ```
+//!
+//! To catch changes in the feel of the movement, use [`TnuaScenario`] to record a [`TnuaTrace`] of
+//! a canonical movement and compare it with a golden trace stored in a file (see
+//! [`assert_matches_golden_file`](TnuaTrace::assert_matches_golden_file)).
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_tnua_physics_integration_layer::math::{AdjustPrecision, AsF32, Float, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::builtins::{TnuaBuiltinJump, TnuaBuiltinWalk};
+use crate::controller::{TnuaController, TnuaControllerBundle, TnuaControllerPlugin};
+use crate::TnuaRigidBodyTracker;
+
+/// A headless app for running characters with scripted input. See [the module
+/// documentation](self).
+pub struct TnuaTestHarness {
+    pub app: App,
+    tick: usize,
+}
+
+impl TnuaTestHarness {
+    /// The duration of each tick, unless changed with [`with_tick_duration`](Self::with_tick_duration).
+    pub const DEFAULT_TICK_DURATION: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+    /// Create a headless app with Tnua in it.
+    ///
+    /// `add_physics` should add the physics engine and its Tnua backend plugin.
+    pub fn new(add_physics: impl FnOnce(&mut App)) -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            Self::DEFAULT_TICK_DURATION,
+        ));
+        add_physics(&mut app);
+        app.add_plugins(TnuaControllerPlugin::default());
+        Self { app, tick: 0 }
+    }
+
+    /// Change the duration each tick advances the time by.
+    pub fn with_tick_duration(mut self, tick_duration: Duration) -> Self {
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(tick_duration));
+        self
+    }
+
+    /// Spawn an entity, like the floor or an obstacle.
+    pub fn spawn(&mut self, bundle: impl Bundle) -> Entity {
+        self.app.world_mut().spawn(bundle).id()
+    }
+
+    /// Spawn a character.
+    ///
+    /// The Tnua components are added automatically - `bundle` should contain the transform and
+    /// the components that the physics engine needs to make it a dynamic rigid body.
+    pub fn spawn_character(&mut self, bundle: impl Bundle) -> Entity {
+        self.app
+            .world_mut()
+            .spawn((TnuaControllerBundle::default(), bundle))
+            .id()
+    }
+
+    /// The number of ticks that were run so far.
+    pub fn tick(&self) -> usize {
+        self.tick
+    }
+
+    /// Run the app for one tick, with nothing fed into the controllers.
+    pub fn run_tick(&mut self) {
+        self.app.update();
+        self.tick += 1;
+    }
+
+    /// Run the app for `ticks` ticks, calling `script` before each tick to feed the controller of
+    /// `character`.
+    ///
+    /// The first argument of `script` is the tick number (counting from the creation of the
+    /// harness).
+    pub fn run_ticks(
+        &mut self,
+        character: Entity,
+        ticks: usize,
+        mut script: impl FnMut(usize, &mut TnuaController),
+    ) {
+        for _ in 0..ticks {
+            let mut controller = self
+                .app
+                .world_mut()
+                .get_mut::<TnuaController>(character)
+                .expect("character must have a TnuaController");
+            script(self.tick, controller.as_mut());
+            self.run_tick();
+        }
+    }
+
+    /// The controller of a character.
+    pub fn controller(&self, character: Entity) -> &TnuaController {
+        self.app
+            .world()
+            .get(character)
+            .expect("character must have a TnuaController")
+    }
+
+    /// The position of an entity.
+    pub fn position(&self, entity: Entity) -> Vector3 {
+        self.app
+            .world()
+            .get::<GlobalTransform>(entity)
+            .expect("entity must have a GlobalTransform")
+            .translation()
+            .adjust_precision()
+    }
+
+    /// The velocity of a character, as last reported by the physics backend.
+    pub fn velocity(&self, character: Entity) -> Vector3 {
+        self.app
+            .world()
+            .get::<TnuaRigidBodyTracker>(character)
+            .expect("character must have a TnuaRigidBodyTracker")
+            .velocity
+    }
+
+    /// Like [`run_ticks`](Self::run_ticks), but record the position and velocity of the character
+    /// after every tick.
+    pub fn run_ticks_traced(
+        &mut self,
+        character: Entity,
+        ticks: usize,
+        script: impl FnMut(usize, &mut TnuaController),
+    ) -> TnuaTrace {
+        let mut trace = TnuaTrace::default();
+        let mut script = script;
+        for _ in 0..ticks {
+            self.run_ticks(character, 1, &mut script);
+            trace.samples.push(self.sample(character));
+        }
+        trace
+    }
+
+    /// The current position and velocity of a character.
+    pub fn sample(&self, character: Entity) -> TnuaTraceSample {
+        TnuaTraceSample {
+            tick: self.tick,
+            position: self.position(character),
+            velocity: self.velocity(character),
+        }
+    }
+
+    /// Panic if the position of an entity is further than `tolerance` (on each axis) from
+    /// `expected`.
+    #[track_caller]
+    pub fn assert_position_near(&self, entity: Entity, expected: Vector3, tolerance: Vector3) {
+        let actual = self.position(entity);
+        assert!(
+            is_near(actual, expected, tolerance),
+            "At tick {}, position of {entity:?} is {actual}, expected {expected} ± {tolerance}",
+            self.tick,
+        );
+    }
+
+    /// Panic if the velocity of a character is further than `tolerance` (on each axis) from
+    /// `expected`.
+    #[track_caller]
+    pub fn assert_velocity_near(&self, character: Entity, expected: Vector3, tolerance: Vector3) {
+        let actual = self.velocity(character);
+        assert!(
+            is_near(actual, expected, tolerance),
+            "At tick {}, velocity of {character:?} is {actual}, expected {expected} ± {tolerance}",
+            self.tick,
+        );
+    }
+}
+
+fn is_near(actual: Vector3, expected: Vector3, tolerance: Vector3) -> bool {
+    (actual - expected).abs().cmple(tolerance).all()
+}
+
+/// The position and velocity of a character at a specific tick.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TnuaTraceSample {
+    pub tick: usize,
+    pub position: Vector3,
+    pub velocity: Vector3,
+}
+
+/// The positions and velocities of a character over time.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TnuaTrace {
+    pub samples: Vec<TnuaTraceSample>,
+}
+
+/// The first difference found when comparing two [`TnuaTrace`]s.
+#[derive(thiserror::Error, Debug)]
+pub enum TnuaTraceMismatch {
+    #[error("Trace has {actual} samples, expected {expected}")]
+    Length { actual: usize, expected: usize },
+    #[error("At tick {}, position is {}, expected {} (tolerance {tolerance})", .actual.tick, .actual.position, .expected.position)]
+    Position {
+        actual: TnuaTraceSample,
+        expected: TnuaTraceSample,
+        tolerance: Float,
+    },
+    #[error("At tick {}, velocity is {}, expected {} (tolerance {tolerance})", .actual.tick, .actual.velocity, .expected.velocity)]
+    Velocity {
+        actual: TnuaTraceSample,
+        expected: TnuaTraceSample,
+        tolerance: Float,
+    },
+}
+
+impl TnuaTrace {
+    /// The environment variable that makes
+    /// [`assert_matches_golden_file`](Self::assert_matches_golden_file) write the golden files
+    /// instead of comparing with them.
+    pub const BLESS_ENV_VAR: &'static str = "TNUA_BLESS";
+
+    /// Compare with another trace, allowing each sample's position and velocity to differ by
+    /// the given distances.
+    pub fn compare(
+        &self,
+        expected: &TnuaTrace,
+        position_tolerance: Float,
+        velocity_tolerance: Float,
+    ) -> Result<(), TnuaTraceMismatch> {
+        if self.samples.len() != expected.samples.len() {
+            return Err(TnuaTraceMismatch::Length {
+                actual: self.samples.len(),
+                expected: expected.samples.len(),
+            });
+        }
+        for (actual, expected) in self.samples.iter().zip(expected.samples.iter()) {
+            let position_distance = actual.position.distance(expected.position);
+            if position_distance.is_nan() || position_tolerance < position_distance {
+                return Err(TnuaTraceMismatch::Position {
+                    actual: actual.clone(),
+                    expected: expected.clone(),
+                    tolerance: position_tolerance,
+                });
+            }
+            let velocity_distance = actual.velocity.distance(expected.velocity);
+            if velocity_distance.is_nan() || velocity_tolerance < velocity_distance {
+                return Err(TnuaTraceMismatch::Velocity {
+                    actual: actual.clone(),
+                    expected: expected.clone(),
+                    tolerance: velocity_tolerance,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Panic if the trace does not match the golden trace stored in a RON file.
+    ///
+    /// When the `TNUA_BLESS` environment variable is set (e.g. `TNUA_BLESS=1 cargo test`), the
+    /// trace is written to the file instead - use this to create the golden files, and to update
+    /// them after intentional changes to the movement.
+    #[track_caller]
+    pub fn assert_matches_golden_file(
+        &self,
+        path: impl AsRef<Path>,
+        position_tolerance: Float,
+        velocity_tolerance: Float,
+    ) {
+        let path = path.as_ref();
+        if std::env::var_os(Self::BLESS_ENV_VAR).is_some() {
+            let serialized = ron::ser::to_string_pretty(self, Default::default())
+                .expect("trace must be serializable");
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("cannot create golden file directory");
+            }
+            std::fs::write(path, serialized).expect("cannot write golden file");
+            return;
+        }
+        let golden = std::fs::read_to_string(path).unwrap_or_else(|err| {
+            panic!(
+                "Cannot read golden file {}: {err} (set {} to create it)",
+                path.display(),
+                Self::BLESS_ENV_VAR,
+            )
+        });
+        let golden: TnuaTrace = ron::from_str(&golden)
+            .unwrap_or_else(|err| panic!("Cannot parse golden file {}: {err}", path.display()));
+        if let Err(mismatch) = self.compare(&golden, position_tolerance, velocity_tolerance) {
+            panic!("Trace does not match {}: {mismatch}", path.display());
+        }
+    }
+}
+
+/// Spawns the physics-engine specific entities of the [`TnuaScenario`]s.
+pub trait TnuaScenarioSetup {
+    /// Spawn a static box with the given half extents.
+    fn spawn_static_box(
+        &self,
+        harness: &mut TnuaTestHarness,
+        transform: Transform,
+        half_extents: Vector3,
+    ) -> Entity;
+
+    /// Spawn a kinematic box with the given half extents, moving at a constant velocity.
+    fn spawn_moving_box(
+        &self,
+        harness: &mut TnuaTestHarness,
+        transform: Transform,
+        half_extents: Vector3,
+        velocity: Vector3,
+    ) -> Entity;
+
+    /// Spawn a character (using [`TnuaTestHarness::spawn_character`]) with a capsule collider
+    /// of radius 0.5 and total height 2.0, with its rotation locked.
+    fn spawn_character(&self, harness: &mut TnuaTestHarness, transform: Transform) -> Entity;
+}
+
+/// Canonical movements for recording [`TnuaTrace`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnuaScenario {
+    /// Running on flat ground, starting from a standstill.
+    FlatRun,
+    /// Running up a 20 degrees slope.
+    SlopeClimb,
+    /// Jumping in place, from the takeoff to the landing.
+    JumpArc,
+    /// Standing on a platform that moves horizontally.
+    MovingPlatformRide,
+}
+
+impl TnuaScenario {
+    pub const ALL: [TnuaScenario; 4] = [
+        Self::FlatRun,
+        Self::SlopeClimb,
+        Self::JumpArc,
+        Self::MovingPlatformRide,
+    ];
+
+    /// A name suitable for a golden file name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FlatRun => "flat_run",
+            Self::SlopeClimb => "slope_climb",
+            Self::JumpArc => "jump_arc",
+            Self::MovingPlatformRide => "moving_platform_ride",
+        }
+    }
+
+    /// Run the scenario in a harness (which should not have anything spawned in it yet) and return
+    /// the trace of the character.
+    ///
+    /// `walk` and `jump` are templates for the basis and action the scenario feeds - the scenario
+    /// only sets their input fields.
+    pub fn run(
+        &self,
+        harness: &mut TnuaTestHarness,
+        setup: &impl TnuaScenarioSetup,
+        walk: &TnuaBuiltinWalk,
+        jump: &TnuaBuiltinJump,
+    ) -> TnuaTrace {
+        let standing = Transform::from_xyz(0.0, walk.float_height.f32(), 0.0);
+        let character = match self {
+            Self::FlatRun | Self::JumpArc => {
+                setup.spawn_static_box(
+                    harness,
+                    Transform::from_xyz(0.0, -0.5, 0.0),
+                    Vector3::new(50.0, 0.5, 50.0),
+                );
+                setup.spawn_character(harness, standing)
+            }
+            Self::SlopeClimb => {
+                let angle = (20.0 as Float).to_radians();
+                setup.spawn_static_box(
+                    harness,
+                    Transform::from_xyz(0.0, -0.5, 0.0),
+                    Vector3::new(4.0, 0.5, 50.0),
+                );
+                let slope_rotation = Quat::from_rotation_z(angle.f32());
+                let slope_start = Vec3::new(4.0, 0.0, 0.0);
+                let slope_center = slope_start + slope_rotation * Vec3::new(20.0, -0.5, 0.0);
+                setup.spawn_static_box(
+                    harness,
+                    Transform::from_translation(slope_center).with_rotation(slope_rotation),
+                    Vector3::new(20.0, 0.5, 50.0),
+                );
+                setup.spawn_character(harness, standing)
+            }
+            Self::MovingPlatformRide => {
+                setup.spawn_moving_box(
+                    harness,
+                    Transform::from_xyz(0.0, -0.5, 0.0),
+                    Vector3::new(5.0, 0.5, 5.0),
+                    Vector3::X * 2.0,
+                );
+                setup.spawn_character(harness, standing)
+            }
+        };
+
+        // Let the character settle before the scenario starts.
+        harness.run_ticks(character, 30, |_, controller| {
+            controller.basis(walk.clone());
+        });
+
+        let first_tick = harness.tick();
+        harness.run_ticks_traced(character, 120, |tick, controller| {
+            let tick = tick - first_tick;
+            let desired_velocity = match self {
+                Self::FlatRun | Self::SlopeClimb => Vector3::X * 5.0,
+                Self::JumpArc | Self::MovingPlatformRide => Vector3::ZERO,
+            };
+            controller.basis(TnuaBuiltinWalk {
+                desired_velocity,
+                ..walk.clone()
+            });
+            if *self == Self::JumpArc && (10..40).contains(&tick) {
+                controller.action(jump.clone());
+            }
+        })
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use bevy_tnua_physics_integration_layer::math::{
-    AdjustPrecision, Float, Quaternion, Vector2, Vector3,
+    AdjustPrecision, AsF32, Float, Quaternion, Vector2, Vector3,
 };
 
 /// Calculate the kinetic energy required to jump to a certain height when different gravity is
@@ -148,6 +148,175 @@ pub fn rotation_arc_around_axis(
     Some(rotation_to_set_forward.xyz().z)
 }
 
+/// Calculate the signed angle, in radians, of the shortest rotation around `around_axis` that
+/// rotates the character from `current_forward` to `desired_forward`.
+///
+/// Unlike [`rotation_arc_around_axis`], the result is an actual angle in the `[-PI, PI]` range.
+pub fn signed_angle_around_axis(
+    around_axis: Dir3,
+    current_forward: Vector3,
+    desired_forward: Vector3,
+) -> Option<Float> {
+    let around_axis: Vector3 = around_axis.adjust_precision();
+    let rotation_plane_x = current_forward.reject_from(around_axis).try_normalize()?;
+    let rotation_plane_y = around_axis.cross(rotation_plane_x);
+    let desired_forward_in_plane_coords = Vector2::new(
+        rotation_plane_x.dot(desired_forward),
+        rotation_plane_y.dot(desired_forward),
+    )
+    .try_normalize()?;
+    Some(desired_forward_in_plane_coords.to_angle())
+}
+
+/// Converts 2D movement input (e.g. from a stick or WASD) to movement on the character's ground
+/// plane, relative to the direction the camera is looking at.
+///
+/// The input's `Y` axis is mapped to the camera's forward direction and its `X` axis to the
+/// camera's right, both projected on the plane perpendicular to the up direction - so this works
+/// with any up direction, not just Bevy's `Y`. When the camera looks straight down (or straight
+/// up) its up vector is used as the forward direction instead, so top-down cameras work too.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tnua::prelude::*;
+/// # use bevy_tnua::util::TnuaCameraRelativeInput;
+/// # use bevy_tnua::math::{AdjustPrecision, Vector2};
+/// # use bevy_tnua::TnuaProximitySensor;
+/// # #[derive(Component)]
+/// # struct PlayerCamera;
+/// fn apply_controls(
+///     camera_query: Query<&GlobalTransform, With<PlayerCamera>>,
+///     mut query: Query<(&mut TnuaController, &TnuaProximitySensor)>,
+/// ) {
+///     # let stick = Vector2::ZERO; // read from the gamepad
+///     let Ok(camera_transform) = camera_query.get_single() else {
+///         return;
+///     };
+///     for (mut controller, sensor) in query.iter_mut() {
+///         let Some(input) = TnuaCameraRelativeInput::from_camera_transform(
+///             camera_transform,
+///             -sensor.cast_direction,
+///         ) else {
+///             continue;
+///         };
+///         controller.basis(TnuaBuiltinWalk {
+///             desired_velocity: input.desired_velocity(stick, 10.0),
+///             desired_forward: input.desired_forward(stick),
+///             float_height: 1.5,
+///             ..Default::default()
+///         });
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TnuaCameraRelativeInput {
+    forward: Vector3,
+    right: Vector3,
+}
+
+impl TnuaCameraRelativeInput {
+    /// Create the conversion from the rotation of the camera and the up direction of the
+    /// character.
+    ///
+    /// Returns `None` if the camera's forward and up vectors are both parallel to the up direction,
+    /// which can only happen with a degenerate rotation.
+    pub fn new(camera_rotation: Quaternion, up: Dir3) -> Option<Self> {
+        let up: Vector3 = up.adjust_precision();
+        let forward = (camera_rotation * Vector3::NEG_Z)
+            .reject_from(up)
+            .try_normalize()
+            .or_else(|| {
+                (camera_rotation * Vector3::Y)
+                    .reject_from(up)
+                    .try_normalize()
+            })?;
+        Some(Self {
+            forward,
+            right: forward.cross(up),
+        })
+    }
+
+    /// Create the conversion from the global transform of the camera and the up direction of the
+    /// character. See [`new`](Self::new).
+    pub fn from_camera_transform(camera_transform: &GlobalTransform, up: Dir3) -> Option<Self> {
+        let (_, rotation, _) = camera_transform.to_scale_rotation_translation();
+        Self::new(rotation.adjust_precision(), up)
+    }
+
+    /// The camera's forward direction, projected on the ground plane.
+    pub fn forward(&self) -> Vector3 {
+        self.forward
+    }
+
+    /// The camera's right direction, projected on the ground plane.
+    pub fn right(&self) -> Vector3 {
+        self.right
+    }
+
+    /// Convert the input to a direction on the ground plane.
+    ///
+    /// The length of the input is preserved, but clamped to 1 - so that diagonal keyboard input
+    /// does not move the character faster than straight input.
+    pub fn direction(&self, input: Vector2) -> Vector3 {
+        let input = input.clamp_length_max(1.0);
+        input.x * self.right + input.y * self.forward
+    }
+
+    /// Convert the input to a value for
+    /// [`TnuaBuiltinWalk::desired_velocity`](crate::builtins::TnuaBuiltinWalk::desired_velocity).
+    pub fn desired_velocity(&self, input: Vector2, speed: Float) -> Vector3 {
+        speed * self.direction(input)
+    }
+
+    /// Convert the input to a value for
+    /// [`TnuaBuiltinWalk::desired_forward`](crate::builtins::TnuaBuiltinWalk::desired_forward) -
+    /// so that the character faces the direction it moves at.
+    ///
+    /// Returns `None` when there is no input, so that the character keeps its current facing.
+    pub fn desired_forward(&self, input: Vector2) -> Option<Dir3> {
+        Dir3::new(self.direction(input).f32()).ok()
+    }
+}
+
+/// A piecewise linear function, for parameters that designers may want to shape freely.
+///
+/// The curve is defined by points, sorted by their X coordinate, and is flat before the first
+/// point and after the last point.
+#[derive(Debug, Default, Clone, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TnuaCurve {
+    /// The `(x, y)` points of the curve. Must be sorted by `x` - use [`TnuaCurve::new`] to sort
+    /// them automatically.
+    pub points: Vec<(Float, Float)>,
+}
+
+impl TnuaCurve {
+    pub fn new(points: impl IntoIterator<Item = (Float, Float)>) -> Self {
+        let mut points: Vec<(Float, Float)> = points.into_iter().collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { points }
+    }
+
+    /// Calculate the value of the curve at `x`. An empty curve is always `0.0`.
+    pub fn sample(&self, x: Float) -> Float {
+        let index = self.points.partition_point(|(point_x, _)| *point_x < x);
+        match (
+            index.checked_sub(1).map(|i| self.points[i]),
+            self.points.get(index).copied(),
+        ) {
+            (None, None) => 0.0,
+            (Some((_, y)), None) | (None, Some((_, y))) => y,
+            (Some((x1, y1)), Some((x2, y2))) => {
+                if x2 <= x1 {
+                    y2
+                } else {
+                    y1 + (y2 - y1) * (x - x1) / (x2 - x1)
+                }
+            }
+        }
+    }
+}
+
 /// Temporary until we get an official release of the physics integration layer crate with
 /// `calc_boost` in it.
 pub(crate) fn calc_boost(
@@ -0,0 +1,95 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_tnua::builtins::{TnuaBuiltinJump, TnuaBuiltinWalk};
+use bevy_tnua::math::Vector3;
+use bevy_tnua::testing::{TnuaScenario, TnuaScenarioSetup, TnuaTestHarness};
+use bevy_tnua_avian3d::TnuaAvian3dPlugin;
+
+struct Avian3dSetup;
+
+impl TnuaScenarioSetup for Avian3dSetup {
+    fn spawn_static_box(
+        &self,
+        harness: &mut TnuaTestHarness,
+        transform: Transform,
+        half_extents: Vector3,
+    ) -> Entity {
+        let size = 2.0 * half_extents;
+        harness.spawn((
+            TransformBundle::from_transform(transform),
+            RigidBody::Static,
+            Collider::cuboid(size.x, size.y, size.z),
+        ))
+    }
+
+    fn spawn_moving_box(
+        &self,
+        harness: &mut TnuaTestHarness,
+        transform: Transform,
+        half_extents: Vector3,
+        velocity: Vector3,
+    ) -> Entity {
+        let size = 2.0 * half_extents;
+        harness.spawn((
+            TransformBundle::from_transform(transform),
+            RigidBody::Kinematic,
+            LinearVelocity(velocity),
+            Collider::cuboid(size.x, size.y, size.z),
+        ))
+    }
+
+    fn spawn_character(&self, harness: &mut TnuaTestHarness, transform: Transform) -> Entity {
+        harness.spawn_character((
+            TransformBundle::from_transform(transform),
+            RigidBody::Dynamic,
+            Collider::capsule(0.5, 1.0),
+            LockedAxes::ROTATION_LOCKED,
+        ))
+    }
+}
+
+fn check_scenario(scenario: TnuaScenario) {
+    let mut harness = TnuaTestHarness::new(|app| {
+        app.add_plugins((AssetPlugin::default(), bevy::scene::ScenePlugin));
+        app.init_asset::<Mesh>();
+        app.add_plugins((PhysicsPlugins::default(), TnuaAvian3dPlugin::default()));
+    });
+    let walk = TnuaBuiltinWalk {
+        float_height: 1.5,
+        ..Default::default()
+    };
+    let jump = TnuaBuiltinJump {
+        height: 4.0,
+        ..Default::default()
+    };
+    let trace = scenario.run(&mut harness, &Avian3dSetup, &walk, &jump);
+    trace.assert_matches_golden_file(
+        format!(
+            "{}/tests/golden/{}.ron",
+            env!("CARGO_MANIFEST_DIR"),
+            scenario.name()
+        ),
+        0.05,
+        0.1,
+    );
+}
+
+#[test]
+fn flat_run() {
+    check_scenario(TnuaScenario::FlatRun);
+}
+
+#[test]
+fn slope_climb() {
+    check_scenario(TnuaScenario::SlopeClimb);
+}
+
+#[test]
+fn jump_arc() {
+    check_scenario(TnuaScenario::JumpArc);
+}
+
+#[test]
+fn moving_platform_ride() {
+    check_scenario(TnuaScenario::MovingPlatformRide);
+}